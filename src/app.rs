@@ -1,40 +1,218 @@
 use std::process::Command;
 use std::{
+    fs,
     io,
-    time::Duration,
+    mem,
+    path::Path,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use ratatui::crossterm::event::{self, Event};
 
-use crate::ui::{Tui, render_frame};
-use crate::model::Model;
-use crate::controller::{update, messages::{Message, handle_key_event}};
-use crate::parsing::convert_to_cli;
+use crate::ui::{self, Tui, render_frame};
+use crate::model::{AppCommand, Model};
+use crate::controller::{update, messages::{handle_key_event, handle_mouse_event}};
+use crate::cli::{self, AppArgs, SubcommandDiscovery};
+use crate::fixup;
+use crate::output::{OutputLine, OutputPane, RunningCommand};
+use crate::pager::Pager;
+use crate::pathcheck::PathChecker;
+use crate::parsing::{convert_to_cli, convert_to_dry_run_cli};
+use crate::quoting;
+use crate::stats;
+use crate::storage::{HistoryEntry, Storage};
 
-fn handle_event(model: &Model) -> Option<Message>{
-    if event::poll(Duration::from_millis(250)).unwrap() {
-        if let Event::Key(key) = event::read().unwrap() {
-            if key.kind == event::KeyEventKind::Press {
-                return handle_key_event(key, model);
-            }
+/// Time spent on waiting events before the screen is drawn again, one frame at 60 Hz
+const BATCH_BUDGET: Duration = Duration::from_millis(16);
+
+/// Time waited for a key before the screen is drawn again anyway, to show new output and finished path checks
+const TICK: Duration = Duration::from_millis(250);
+
+/// Tick with reduced animation, every redraw of streaming output costs bandwidth on a slow link
+const REDUCED_TICK: Duration = Duration::from_secs(1);
+
+/// Wait for an event and handle it together with the events that are already waiting, e.g. the keys of a paste,
+/// without drawing in between, until the budget is spent or a message requested a command
+fn handle_events(model: &mut Model) -> io::Result<()> {
+    let started = Instant::now();
+    let mut timeout = if model.reduced_animation { REDUCED_TICK } else { TICK };
+    while event::poll(timeout)? {
+        let message = match event::read()? {
+            Event::Key(key) => handle_key_event(key, model),
+            Event::Mouse(mouse) => handle_mouse_event(mouse, model),
+            _ => None,
+        };
+        if let Some(message) = message {
+            update(model, message);
+        }
+        if !model.commands.is_empty() || started.elapsed() >= BATCH_BUDGET {
+            break;
         }
+        timeout = Duration::ZERO;
     }
-    None
+    Ok(())
 }
 
-pub fn run(terminal: &mut Tui, model: &mut Model) -> io::Result<Option<Command>> {
-    while !model.exit {
-        terminal.draw(|frame| render_frame(frame, model))?;
-        
-        let message = handle_event(model);
+/// Select the parameter the failed command complained about, so going back shows the error next to it
+fn point_at_failed_parameter(model: &mut Model) {
+    let Some(output) = &model.output else {
+        return;
+    };
+    if output.status.is_some_and(|status| status.success()) {
+        return;
+    }
+    let error = output.error_output();
+    if fixup::find_failed_key(&error).is_some_and(|key| model.select_key(&key)) {
+        model.error = Some(fixup::summarize_error(&error));
+    }
+}
 
-        if let Some(message) = message {
-            update(model, message);
+/// File name of the wrapped program, the tool of its history entries
+fn tool_name(args: &AppArgs) -> String {
+    Path::new(&args.command[0]).file_name().map_or_else(|| args.command[0].clone(), |name| name.to_string_lossy().to_string())
+}
+
+/// Add the finished command to the history, a failure is shown as error instead of stopping clitui
+fn record_history(model: &mut Model, args: &AppArgs, storage: &mut dyn Storage) {
+    let Some(output) = &model.output else {
+        return;
+    };
+    let entry = HistoryEntry {
+        tool: tool_name(args),
+        command_line: output.command_line.clone(),
+        started: output.started.duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs()),
+        duration_ms: output.started.elapsed().map_or(0, |duration| duration.as_millis() as u64),
+        exit_code: output.status.and_then(|status| status.code()),
+        fingerprint: output.fingerprint.clone(),
+    };
+    if let Err(error) = storage.append_history(&entry) {
+        model.error = Some(format!("Cannot save history: {error}"));
+    }
+}
+
+/// Show a new output pane for the command and start it
+/// A run with a fingerprint is estimated from earlier runs with the same one, without an estimate if the history cannot be read
+fn start_output(model: &mut Model, cli_command: Command, fingerprint: Option<String>, args: &AppArgs, storage: &dyn Storage) -> io::Result<RunningCommand> {
+    model.error = None;
+    let mut output = OutputPane::new(quoting::format_command(&cli_command, args.quote_options));
+    output.estimate = fingerprint.as_deref().and_then(|fingerprint| stats::estimate_duration(&storage.tool_history(&tool_name(args)).ok()?, &tool_name(args), fingerprint));
+    output.fingerprint = fingerprint;
+    model.output = Some(output);
+    RunningCommand::spawn(cli_command)
+}
+
+/// Suspend the TUI and hand the terminal over to a command that prompts for input,
+/// clitui comes back with its exit status once it has finished and Enter is pressed
+fn run_in_terminal(terminal: &mut Tui, model: &mut Model, mut cli_command: Command, args: &AppArgs) -> io::Result<()> {
+    model.error = None;
+    let mut output = OutputPane::new(quoting::format_command(&cli_command, args.quote_options));
+    ui::restore()?;
+    println!("{}", output.command_line);
+    let status = cli_command.status();
+    println!("Press Enter to return to clitui");
+    io::stdin().read_line(&mut String::new())?;
+    *terminal = ui::init()?;
+    match status {
+        Ok(status) => {
+            output.push(OutputLine::Stdout(String::from("The command ran in the terminal, its output was not captured")));
+            output.status = Some(status);
+            model.output = Some(output);
+        },
+        Err(error) => model.error = Some(format!("Cannot run {}: {error}", output.command_line)),
+    }
+    Ok(())
+}
+
+/// Suspend the TUI while the output is shown in `$PAGER`,
+/// the built-in pager is opened instead if it cannot be started
+fn open_external_pager(terminal: &mut Tui, model: &mut Model) -> io::Result<()> {
+    let Some(output) = &model.output else {
+        return Ok(());
+    };
+    ui::restore()?;
+    let result = cli::run_pager(&output.text());
+    *terminal = ui::init()?;
+    if result.is_err() {
+        model.pager = Some(Pager::default());
+    }
+    Ok(())
+}
+
+/// Render the screen again and save it as `clitui-<seconds since epoch>` in the working directory
+fn save_screenshot(terminal: &mut Tui, model: &mut Model, args: &AppArgs) -> io::Result<()> {
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+    let path = format!("clitui-{seconds}.{}", args.screenshot_format.extension());
+    let frame = terminal.draw(|frame| render_frame(frame, model))?;
+    match fs::write(&path, args.screenshot_format.export(frame.buffer)) {
+        Ok(()) => model.notice = Some(format!("Screenshot saved to {path}")),
+        Err(error) => model.error = Some(format!("Cannot save screenshot {path}: {error}")),
+    }
+    Ok(())
+}
+
+/// Compute the usage statistics from the history, which stays on this machine
+fn show_stats(model: &mut Model, storage: &dyn Storage) {
+    match storage.history() {
+        Ok(history) => model.stats = Some(stats::tool_stats(&history)),
+        Err(error) => model.error = Some(format!("Cannot read history: {error}")),
+    }
+}
+
+/// Runs the form until it is quit, the subcommands are attached once the discovery has finished
+/// Returns the command if it should be run detached from clitui, other commands run inside the output pane
+pub fn run(terminal: &mut Tui, model: &mut Model, args: &AppArgs, storage: &mut dyn Storage, mut discovery: Option<SubcommandDiscovery>) -> io::Result<Option<Command>> {
+    let mut running_command: Option<RunningCommand> = None;
+    let mut path_checker = PathChecker::spawn();
+    loop {
+        if let Some(parameters) = discovery.as_ref().and_then(SubcommandDiscovery::poll) {
+            model.attach_discovered(parameters);
+            discovery = None;
         }
+        if let (Some(command), Some(output)) = (running_command.as_mut(), model.output.as_mut()) {
+            command.poll(output)?;
+            if !output.is_running() {
+                running_command = None;
+                record_history(model, args, storage);
+                point_at_failed_parameter(model);
+                // the command may have created or removed the files
+                path_checker.forget(&mut model.path_states);
+            }
+        }
+        path_checker.poll(&mut model.path_states);
+        path_checker.request(model.path_values().into_iter());
 
-        if model.run {
-            return Ok(Some(convert_to_cli(&model.parameters)));
+        terminal.draw(|frame| render_frame(frame, model))?;
+        handle_events(model)?;
+
+        // the queue is drained completely before a requested run leaves the loop
+        let mut detached_command = None;
+        for command in mem::take(&mut model.commands) {
+            match command {
+                AppCommand::Run | AppCommand::RunInTerminal if args.detach => detached_command = Some(convert_to_cli(&model.parameters)),
+                AppCommand::Run => {
+                    let cli_command = convert_to_cli(&model.parameters);
+                    let fingerprint = model.parameters.fingerprint();
+                    running_command = Some(start_output(model, cli_command, Some(fingerprint), args, storage)?);
+                },
+                AppCommand::RunInTerminal => {
+                    run_in_terminal(terminal, model, convert_to_cli(&model.parameters), args)?;
+                    record_history(model, args, storage);
+                    path_checker.forget(&mut model.path_states);
+                },
+                AppCommand::TestRun => {
+                    if let Some(cli_command) = convert_to_dry_run_cli(&mut model.parameters) {
+                        // test runs take less time than real runs and are left out of the estimates
+                        running_command = Some(start_output(model, cli_command, None, args, storage)?);
+                    }
+                },
+                AppCommand::ExternalPager => open_external_pager(terminal, model)?,
+                AppCommand::Screenshot => save_screenshot(terminal, model, args)?,
+                AppCommand::ShowStats => show_stats(model, storage),
+                AppCommand::Quit => return Ok(None),
+            }
+        }
+        if detached_command.is_some() {
+            return Ok(detached_command);
         }
     }
-    Ok(None)
-}
\ No newline at end of file
+}
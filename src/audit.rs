@@ -0,0 +1,432 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    fs,
+    io,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use regex::Regex;
+
+use crate::parsing::{find_unparsed_lines, parse_help_string, parse_help_string_with_parsers, CLILib, CLIParameters, Guess, HelpParser, HelpProbe, ParseError};
+
+/// Value names that say nothing about the value, the type of the parameter cannot be inferred from them
+const GENERIC_PLACEHOLDERS: [&str; 5] = ["VALUE", "VAL", "ARG", "STRING", "STR"];
+
+/// Result of parsing a single help text fixture
+#[derive(Debug, PartialEq)]
+pub struct AuditReport {
+    pub file: PathBuf,
+    pub parser: Option<CLILib>,
+    pub arguments: usize,
+    pub positionals: usize,
+    pub options: usize,
+    pub flags: usize,
+    pub unparsed_lines: Vec<String>,
+    /// Why each parser rejected the help text, if all of them did
+    pub errors: Vec<ParseError>,
+}
+
+impl AuditReport {
+    pub fn failed(&self) -> bool {
+        self.parser.is_none()
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let file = self.file.display();
+        let Some(parser) = &self.parser else {
+            write!(f, "{file}: FAILED, no parser accepted the help text")?;
+            for error in &self.errors {
+                write!(f, "\n    {error}")?;
+            }
+            return Ok(());
+        };
+        write!(
+            f,
+            "{file}: {parser:?}, {} arguments, {} positionals, {} options, {} flags, {} unparsed lines",
+            self.arguments,
+            self.positionals,
+            self.options,
+            self.flags,
+            self.unparsed_lines.len(),
+        )?;
+        for line in &self.unparsed_lines {
+            write!(f, "\n    {line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a help text and summarize the result
+pub fn audit_help_string(file: PathBuf, help_string: &str) -> AuditReport {
+    let parameters = match parse_help_string(help_string) {
+        Ok(parameters) => parameters,
+        Err(errors) => return AuditReport {
+            file,
+            parser: None,
+            arguments: 0,
+            positionals: 0,
+            options: 0,
+            flags: 0,
+            unparsed_lines: Vec::new(),
+            errors,
+        },
+    };
+    AuditReport {
+        file,
+        parser: Some(parameters.cli_lib),
+        arguments: parameters.arguments.len(),
+        positionals: parameters.positionals.len(),
+        options: parameters.options.len(),
+        flags: parameters.flags.len(),
+        unparsed_lines: find_unparsed_lines(help_string, &parameters),
+        errors: Vec::new(),
+    }
+}
+
+/// Parse every file of a directory as help text, sorted by file name
+pub fn audit_directory(directory: &Path) -> io::Result<Vec<AuditReport>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+    files.retain(|file| file.is_file());
+    files.sort();
+    files
+        .into_iter()
+        .map(|file| {
+            let content = fs::read(&file)?;
+            let help_string = String::from_utf8_lossy(&content).to_string();
+            Ok(audit_help_string(file, &help_string))
+        })
+        .collect()
+}
+
+/// Something in a help text clitui cannot read or has to guess, with how the author of the tool could fix it
+#[derive(Debug, PartialEq)]
+pub struct LintFinding {
+    /// Number and text of the line it was found in, None for the help text as a whole
+    pub line: Option<(usize, String)>,
+    pub message: String,
+    pub suggestion: String,
+}
+
+impl fmt::Display for LintFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((number, line)) = &self.line {
+            write!(f, "line {number}: {line}\n    ")?;
+        }
+        write!(f, "{}\n    {}", self.message, self.suggestion)
+    }
+}
+
+/// Number and trimmed text of the first line the predicate holds for
+fn find_line(help_string: &str, predicate: impl Fn(&str) -> bool) -> Option<(usize, String)> {
+    help_string
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .find(|(_, line)| predicate(line))
+        .map(|(index, line)| (index + 1, line.to_string()))
+}
+
+/// Whether the word of a parameter row is part of its keys and value name, e.g. `-n,`, `<NAME>` or `NAME`
+fn is_key_word(word: &str) -> bool {
+    let word = word.trim_end_matches(',');
+    word.starts_with(['-', '<', '[', '{', '='])
+        || word.chars().all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ch == '_' || ch == '.')
+}
+
+/// Rows of parameters whose description is not where the other rows put it
+/// A description separated by a single space cannot be told apart from the value name,
+/// a description left of the common column could have been aligned, unlike one pushed right by long keys
+fn lint_parameter_rows(help_string: &str, skipped: &[usize]) -> Vec<LintFinding> {
+    static ROW_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<keys>\s*-\S.*?)\s{2,}\S").unwrap());
+    let rows: Vec<(usize, &str)> = help_string
+        .lines()
+        .enumerate()
+        .filter(|(index, line)| line.trim_start().starts_with('-') && !skipped.contains(&(index + 1)))
+        .collect();
+    let columns: Vec<(usize, &str, usize, usize)> = rows
+        .iter()
+        .filter_map(|(index, line)| {
+            let keys = ROW_PATTERN.captures(line)?.name("keys")?;
+            let column = line[keys.end()..].find(|ch: char| !ch.is_whitespace())? + keys.end();
+            Some((index + 1, *line, keys.as_str().chars().count(), line[..column].chars().count()))
+        })
+        .collect();
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    columns.iter().for_each(|(_, _, _, column)| *counts.entry(*column).or_default() += 1);
+    let common = counts.iter().max_by_key(|(column, count)| (**count, std::cmp::Reverse(**column))).map(|(column, _)| *column);
+    let mut findings = Vec::new();
+    for (number, line) in &rows {
+        let number = number + 1;
+        let aligned = columns.iter().find(|(row, _, _, _)| *row == number);
+        if aligned.is_none() && !line.split_whitespace().all(is_key_word) {
+            findings.push(LintFinding {
+                line: Some((number, line.trim().to_string())),
+                message: String::from("A single space separates the description from the keys, where the value name ends is a guess"),
+                suggestion: String::from("Separate the description by at least two spaces, e.g. `-n, --name <NAME>  Name to greet`"),
+            });
+        }
+        if let (Some((_, _, keys_width, column)), Some(common)) = (aligned, common) {
+            if *column != common && keys_width + 2 <= common {
+                findings.push(LintFinding {
+                    line: Some((number, line.trim().to_string())),
+                    message: format!("The description starts in column {}, most rows start it in column {}", column + 1, common + 1),
+                    suggestion: String::from("Align the descriptions in one column, wrapped description lines are then told apart from new rows"),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Value names that tell nothing about the value, and flags whose description mentions a default as if they took a value
+fn lint_placeholders(help_string: &str, parameters: &CLIParameters) -> Vec<LintFinding> {
+    let mut findings = Vec::new();
+    for argument in parameters.arguments.iter().chain(&parameters.options).filter(|argument| argument.choices.is_empty()) {
+        let name = argument.name.trim_matches(['<', '>', '[', ']']);
+        if GENERIC_PLACEHOLDERS.contains(&name.to_uppercase().as_str()) {
+            findings.push(LintFinding {
+                line: find_line(help_string, |line| line.starts_with('-') && line.contains(&argument.key) && line.contains(name)),
+                message: format!("The value name {name} of {} does not tell what the value is", argument.key),
+                suggestion: String::from("Name the value after what it holds, e.g. <FILE>, <DIR>, <COUNT> or <TIMEOUT>, so the form can offer a file picker or check numbers"),
+            });
+        }
+    }
+    for flag in &parameters.flags {
+        let mentions_default = flag.description.as_deref().is_some_and(|description| description.to_lowercase().contains("default:"));
+        if mentions_default {
+            findings.push(LintFinding {
+                line: find_line(help_string, |line| line.starts_with('-') && line.split([' ', ',', '=']).any(|word| flag.has_key(word))),
+                message: format!("{} is read as a flag without value, but its description mentions a default", flag.key),
+                suggestion: format!("Name the value after the key if it takes one, e.g. `{} <VALUE>`", flag.key),
+            });
+        }
+    }
+    findings
+}
+
+/// What clitui cannot read or has to guess in the help text of a tool, for its author
+/// The help is read by the parsers of the config like the form reads it, without an accepted parser each tells what it expected instead
+/// Types guessed from value names are left out, naming values after their type is what the findings suggest
+pub fn lint_help_string(help_string: &str, cli_libs: &[CLILib]) -> Vec<LintFinding> {
+    let parsers: Vec<&dyn HelpParser> = cli_libs.iter().map(|cli_lib| cli_lib as &dyn HelpParser).collect();
+    let parameters = match parse_help_string_with_parsers(help_string, &parsers) {
+        Ok(parameters) => parameters,
+        Err(errors) => {
+            return errors
+                .into_iter()
+                .map(|error| LintFinding {
+                    line: error.line.clone(),
+                    message: format!("The {} parser does not accept the help: {}", error.parser, error.message),
+                    suggestion: error.suggestion.unwrap_or_default(),
+                })
+                .collect();
+        },
+    };
+    let mut findings = Vec::new();
+    let mut skipped = Vec::new();
+    for guess in parameters.find_guess_kinds(help_string) {
+        match guess {
+            Guess::UnparsedLine(unparsed) => {
+                let line = find_line(help_string, |line| line == unparsed);
+                skipped.extend(line.as_ref().map(|(number, _)| *number));
+                findings.push(LintFinding {
+                    line,
+                    message: String::from("The row looks like a parameter but is not read as one"),
+                    suggestion: String::from("Start the row with the keys and the value name, e.g. `-n, --name <NAME>`, or write alternatives like `--[no-]color` as two rows"),
+                });
+            },
+            Guess::ValueType { .. } => (),
+            Guess::Requiredness(cli_lib) => findings.push(LintFinding {
+                line: None,
+                message: format!("The help is read by the {cli_lib:?} parser, which cannot tell required options"),
+                suggestion: String::from("List required options without brackets in a usage line like clap does, e.g. `Usage: tool [OPTIONS] --name <NAME>`"),
+            }),
+        }
+    }
+    findings.extend(lint_parameter_rows(help_string, &skipped));
+    findings.extend(lint_placeholders(help_string, &parameters));
+    findings.sort_by_key(|finding| finding.line.as_ref().map_or(usize::MAX, |(number, _)| *number));
+    findings
+}
+
+/// Parameters listed in only one of `-h` and `--help`, once the parameters of both are merged
+/// Parameters only in `--help` point at their row in the help text, the help of `-h` is not kept
+pub fn lint_probes(help_string: &str, parameters: &CLIParameters) -> Vec<LintFinding> {
+    parameters.probes
+        .iter()
+        .filter_map(|(key, probe)| {
+            let (line, probe_flag) = match probe {
+                HelpProbe::Long => (find_line(help_string, |line| line.split([' ', ',', '=', '[']).any(|word| word == key)), "--help"),
+                HelpProbe::Short => (None, "-h"),
+                HelpProbe::Both => return None,
+            };
+            Some(LintFinding {
+                line,
+                message: format!("{key} is only listed in the help of {probe_flag}"),
+                suggestion: String::from("List every parameter in the help of both -h and --help, -h may describe it tersely"),
+            })
+        })
+        .collect()
+}
+
+// Unit tests
+
+#[test]
+fn test_audit_help_string() {
+    let help_string = "Usage: greeter.exe [OPTIONS] --name <NAME>
+
+Options:
+    -n, --name <NAME>    Name of the person to greet
+    -c, --count <COUNT>  Number of times to greet [default: 1]
+    -h, --help           Print help";
+
+    let report = audit_help_string(PathBuf::from("greeter.txt"), help_string);
+
+    assert_eq!(
+        report,
+        AuditReport {
+            file: PathBuf::from("greeter.txt"),
+            parser: Some(CLILib::Clap),
+            arguments: 1,
+            positionals: 0,
+            options: 1,
+            flags: 1,
+            unparsed_lines: Vec::new(),
+            errors: Vec::new(),
+        }
+    );
+    assert_eq!(
+        report.to_string(),
+        "greeter.txt: Clap, 1 arguments, 0 positionals, 1 options, 1 flags, 0 unparsed lines",
+    );
+}
+
+#[test]
+fn test_audit_help_string_unparsed_lines() {
+    let help_string = "Usage: greeter.exe [OPTIONS]
+
+Options:
+    -n, --name <NAME>    Name of the person to greet
+    --[no-]color         Toggle colored output";
+
+    let report = audit_help_string(PathBuf::from("greeter.txt"), help_string);
+
+    assert_eq!(
+        report.unparsed_lines,
+        vec![String::from("--[no-]color         Toggle colored output")],
+    );
+}
+
+#[test]
+fn test_audit_help_string_failed() {
+    let report = audit_help_string(PathBuf::from("empty.txt"), "Nothing to see here");
+
+    assert!(report.failed());
+    assert_eq!(
+        report.to_string(),
+        "empty.txt: FAILED, no parser accepted the help text
+    argparse: no usage: line, line 1 is \"Nothing to see here\"
+    cobra: no Usage: section, line 1 is \"Nothing to see here\"
+    docopt: no Usage: patterns, line 1 is \"Nothing to see here\"
+    clap: no Usage: line with an Options: section, line 1 is \"Nothing to see here\"
+    gnu: no Usage: line, line 1 is \"Nothing to see here\"
+    windows: no /OPTION rows, line 1 is \"Nothing to see here\"",
+    );
+}
+
+#[test]
+fn test_audit_directory() {
+    let directory = std::env::temp_dir().join(format!("clitui-audit-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("b.txt"), "Nothing to see here").unwrap();
+    fs::write(directory.join("a.txt"), "Usage: tool [OPTIONS]\n\nOptions:\n  -h, --help  Print help").unwrap();
+
+    let reports = audit_directory(&directory).unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    let results: Vec<(String, bool)> = reports
+        .iter()
+        .map(|report| (report.file.file_name().unwrap().to_string_lossy().to_string(), report.failed()))
+        .collect();
+    assert_eq!(
+        results,
+        vec![(String::from("a.txt"), false), (String::from("b.txt"), true)],
+    );
+}
+
+#[test]
+fn test_lint_help_string() {
+    let help_string = "Usage: greeter.exe [OPTIONS] --name <NAME>
+
+Options:
+    -n, --name <NAME>    Name of the person to greet
+    -c, --count <COUNT> Number of times to greet
+    -f, --fmt <VALUE>      Format of the greeting
+    -j, --jobs           Number of parallel greetings (default: 4)
+    --[no-]color         Toggle colored output
+    -h, --help           Print help";
+
+    let findings = lint_help_string(help_string, &CLILib::ALL);
+
+    let lines: Vec<(Option<usize>, &str)> = findings
+        .iter()
+        .map(|finding| (finding.line.as_ref().map(|(number, _)| *number), finding.message.split(',').next().unwrap()))
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            (Some(5), "A single space separates the description from the keys"),
+            (Some(6), "The description starts in column 28"),
+            (Some(6), "The value name VALUE of --fmt does not tell what the value is"),
+            (Some(7), "--jobs is read as a flag without value"),
+            (Some(8), "The row looks like a parameter but is not read as one"),
+        ],
+    );
+    assert_eq!(
+        findings[0].to_string(),
+        "line 5: -c, --count <COUNT> Number of times to greet
+    A single space separates the description from the keys, where the value name ends is a guess
+    Separate the description by at least two spaces, e.g. `-n, --name <NAME>  Name to greet`",
+    );
+}
+
+#[test]
+fn test_lint_help_string_without_parser() {
+    let findings = lint_help_string("Nothing to see here", &CLILib::ALL);
+
+    assert_eq!(findings.len(), CLILib::ALL.len());
+    assert_eq!(findings[0].message, "The argparse parser does not accept the help: no usage: line");
+    assert_eq!(findings[3].message, "The clap parser does not accept the help: no Usage: line with an Options: section");
+    assert_eq!(findings[3].suggestion, "clap prints `Usage: tool [OPTIONS]` and an `Options:` heading");
+}
+
+#[test]
+fn test_lint_help_string_with_configured_parsers() {
+    let help_string = "Usage: greeter [OPTION]...\n  -n, --name NAME  Name to greet\n";
+
+    let findings = lint_help_string(help_string, &[CLILib::Gnu]);
+
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].message, "The help is read by the Gnu parser, which cannot tell required options");
+    assert_eq!(lint_help_string(help_string, &[CLILib::Cobra]).len(), 1);
+    assert!(lint_help_string(help_string, &[CLILib::Cobra])[0].message.starts_with("The cobra parser does not accept the help"));
+}
+
+#[test]
+fn test_lint_probes() {
+    let help_string = "Usage: greeter [OPTIONS]\n\nOptions:\n  -n, --name <NAME>  Name to greet\n  -c, --caps  Greet in capitals\n";
+    let mut parameters = parse_help_string(help_string).unwrap();
+    let short_parameters = parse_help_string("Usage: greeter [OPTIONS]\n\nOptions:\n  -n, --name <NAME>  Name\n  -q, --quiet  Greet silently\n").unwrap();
+    parameters.merge_short_help(short_parameters);
+
+    let findings = lint_probes(help_string, &parameters);
+
+    let lines: Vec<(Option<usize>, &str)> = findings.iter().map(|finding| (finding.line.as_ref().map(|(number, _)| *number), finding.message.as_str())).collect();
+    assert_eq!(lines, [(Some(5), "--caps is only listed in the help of --help"), (None, "--quiet is only listed in the help of -h")]);
+}
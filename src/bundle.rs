@@ -0,0 +1,244 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config, parsing::split_shell_words, quoting::{self, QuoteOptions, Shell}, storage::Storage};
+
+/// Words of key names that mark a value as secret, compared ignoring case
+const SECRET_KEY_WORDS: [&str; 4] = ["password", "secret", "token", "apikey"];
+/// Adjacent words of key names that mark a value as secret together, e.g. `api-key`
+const SECRET_KEY_PAIRS: [(&str, &str); 1] = [("api", "key")];
+
+/// Config and saved documents of one setup in a single TOML file, to share it with a team or a dotfiles repository
+/// The history and the parsed help are left out, they belong to one machine
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bundle {
+    /// Content of `config.toml` with the keymap and the per-tool rules, None if there is no config file
+    pub config: Option<String>,
+    /// Saved documents like profiles by name
+    pub documents: BTreeMap<String, String>,
+}
+
+/// Whether a word of the key or a pair of adjacent words is a secret, the words are split on `-` and `_`
+/// `--api-key` and `api_token` are secrets, `--max-tokens` and `tokenizer` are not
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_lowercase();
+    let words: Vec<&str> = key.split(['-', '_']).filter(|word| !word.is_empty()).collect();
+    words.iter().any(|word| SECRET_KEY_WORDS.contains(word))
+        || words.windows(2).any(|pair| SECRET_KEY_PAIRS.contains(&(pair[0], pair[1])))
+}
+
+fn remove_secrets(table: &mut toml::Table) {
+    table.retain(|key, _| !is_secret_key(key));
+    for (_, value) in table.iter_mut() {
+        match value {
+            toml::Value::Table(table) => remove_secrets(table),
+            toml::Value::Array(values) => values.iter_mut().filter_map(toml::Value::as_table_mut).for_each(remove_secrets),
+            _ => {},
+        }
+    }
+}
+
+/// The document without values whose keys look like secrets, e.g. `api_token`
+/// Documents that are not TOML are kept as they are
+pub fn strip_secrets(document: &str) -> String {
+    let Ok(mut table) = document.parse::<toml::Table>() else {
+        return document.to_string();
+    };
+    let before = table.clone();
+    remove_secrets(&mut table);
+    if table == before {
+        return document.to_string();
+    }
+    toml::to_string(&table).unwrap_or_else(|_| document.to_string())
+}
+
+/// The command line without the values of options that look like secrets, e.g. `--token abc` or `--password=abc`
+/// The options are dropped with their values, the form asks for them when the alias is opened
+fn strip_command_secrets(command_line: &str) -> String {
+    let words = split_shell_words(command_line);
+    let mut kept = Vec::new();
+    let mut iter = words.iter().peekable();
+    while let Some(word) = iter.next() {
+        let (key, value) = word.split_once('=').map_or((word.as_str(), None), |(key, value)| (key, Some(value)));
+        if !key.starts_with('-') || !is_secret_key(key) {
+            kept.push(word.clone());
+            continue;
+        }
+        if value.is_none() && iter.peek().is_some_and(|next| !next.starts_with('-')) {
+            iter.next();
+        }
+    }
+    if kept.len() == words.len() {
+        return command_line.to_string();
+    }
+    let options = QuoteOptions { shell: Shell::Posix, ..Default::default() };
+    kept.iter().map(|word| quoting::quote(word, options)).collect::<Vec<_>>().join(" ")
+}
+
+/// The config without values whose keys look like secrets and without secret options in the command lines of aliases
+pub fn strip_config_secrets(config: &str) -> String {
+    let Ok(mut table) = config.parse::<toml::Table>() else {
+        return config.to_string();
+    };
+    let before = table.clone();
+    remove_secrets(&mut table);
+    if let Some(toml::Value::Table(aliases)) = table.get_mut("aliases") {
+        for (_, value) in aliases.iter_mut() {
+            if let toml::Value::String(command_line) = value {
+                *command_line = strip_command_secrets(command_line);
+            }
+        }
+    }
+    if table == before {
+        return config.to_string();
+    }
+    toml::to_string(&table).unwrap_or_else(|_| config.to_string())
+}
+
+/// Bundle of the config file and the documents of the storage, secrets are stripped from both
+pub fn export(config_file: Option<&Path>, storage: &dyn Storage) -> Result<Bundle, String> {
+    let config = match config_file.map(fs::read_to_string) {
+        Some(Ok(content)) => Some(strip_config_secrets(&content)),
+        Some(Err(error)) if error.kind() != io::ErrorKind::NotFound => return Err(format!("Cannot read config: {error}")),
+        _ => None,
+    };
+    let mut documents = BTreeMap::new();
+    for name in storage.document_names().map_err(|error| format!("Cannot read documents: {error}"))? {
+        if let Some(content) = storage.read_document(&name).map_err(|error| format!("Cannot read document {name}: {error}"))? {
+            documents.insert(name, strip_secrets(&content));
+        }
+    }
+    Ok(Bundle { config, documents })
+}
+
+/// Write the config of the bundle to the config file and its documents into the storage
+/// An invalid config is refused before anything is written, documents with the same name are overwritten
+/// A different existing config is kept next to it as `config.toml.bak`, its path is returned
+pub fn import(bundle: &Bundle, config_file: Option<&Path>, storage: &mut dyn Storage) -> Result<Option<PathBuf>, String> {
+    let mut backup = None;
+    if let Some(content) = &bundle.config {
+        config::parse_config(content)?;
+        let path = config_file.ok_or("No config directory to import the config into")?;
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|error| format!("Cannot create {}: {error}", directory.display()))?;
+        }
+        match fs::read_to_string(path) {
+            Ok(existing) if existing != *content => {
+                let backup_path = path.with_extension("toml.bak");
+                fs::write(&backup_path, existing).map_err(|error| format!("Cannot back up config to {}: {error}", backup_path.display()))?;
+                backup = Some(backup_path);
+            },
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(format!("Cannot read config: {error}")),
+            _ => {},
+        }
+        fs::write(path, content).map_err(|error| format!("Cannot write config {}: {error}", path.display()))?;
+    }
+    for (name, content) in &bundle.documents {
+        storage.write_document(name, content).map_err(|error| format!("Cannot write document {name}: {error}"))?;
+    }
+    Ok(backup)
+}
+
+pub fn write_bundle(path: &Path, bundle: &Bundle) -> Result<(), String> {
+    let content = toml::to_string(bundle).map_err(|error| format!("Cannot serialize bundle: {error}"))?;
+    fs::write(path, content).map_err(|error| format!("Cannot write bundle {}: {error}", path.display()))
+}
+
+pub fn read_bundle(path: &Path) -> Result<Bundle, String> {
+    let content = fs::read_to_string(path).map_err(|error| format!("Cannot read bundle {}: {error}", path.display()))?;
+    toml::from_str(&content).map_err(|error| format!("Invalid bundle: {error}"))
+}
+
+// Unit tests
+
+#[test]
+fn test_is_secret_key() {
+    assert!(is_secret_key("--api-key"));
+    assert!(is_secret_key("api_key"));
+    assert!(is_secret_key("--apikey"));
+    assert!(is_secret_key("API_TOKEN"));
+    assert!(is_secret_key("--db-password"));
+    assert!(!is_secret_key("--max-tokens"));
+    assert!(!is_secret_key("tokenizer"));
+    assert!(!is_secret_key("--key"));
+}
+
+#[test]
+fn test_strip_secrets() {
+    let document = "keys = [\"--release\"]\napi_token = \"abc\"\n\n[values]\n\"--name\" = \"Ferris\"\nPassword = \"hunter2\"\n";
+
+    let stripped = strip_secrets(document);
+
+    assert!(!stripped.contains("abc"));
+    assert!(!stripped.contains("hunter2"));
+    assert!(stripped.contains("Ferris"));
+    assert_eq!(strip_secrets("keys = [\"--release\"]"), "keys = [\"--release\"]");
+    assert_eq!(strip_secrets("not toml token=abc ="), "not toml token=abc =");
+}
+
+#[test]
+fn test_export_and_import_bundle() {
+    let directory = std::env::temp_dir().join(format!("clitui-bundle-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    let config_file = directory.join("from").join("config.toml");
+    fs::create_dir_all(config_file.parent().unwrap()).unwrap();
+    fs::write(&config_file, "[keymap]\nquit = [\"Ctrl+X\"]\n").unwrap();
+    let mut from = crate::storage::FileStorage { directory: directory.join("from-data") };
+    from.write_document("release.toml", "keys = [\"--release\"]\ntoken = \"abc\"\n").unwrap();
+    let bundle_file = directory.join("bundle.toml");
+
+    write_bundle(&bundle_file, &export(Some(&config_file), &from).unwrap()).unwrap();
+    let imported_config = directory.join("to").join("config.toml");
+    let mut to = crate::storage::FileStorage { directory: directory.join("to-data") };
+    import(&read_bundle(&bundle_file).unwrap(), Some(&imported_config), &mut to).unwrap();
+
+    assert_eq!(fs::read_to_string(&imported_config).unwrap(), "[keymap]\nquit = [\"Ctrl+X\"]\n");
+    assert_eq!(to.read_document("release.toml").unwrap().as_deref(), Some("keys = [\"--release\"]\n"));
+}
+
+#[test]
+fn test_strip_config_secrets() {
+    let config = "[aliases]\ndeploy = \"kubectl apply --token abc -f 'my dir'\"\nlogin = \"tool login --password=hunter2 --user me\"\nask = \"llm --api-key=abc --max-tokens 100\"\nchat = \"llm --api-key abc\"\n\n[parsers.tool]\napi_key = \"abc\"\napi-key = \"abc\"\n";
+
+    let stripped = strip_config_secrets(config);
+
+    assert!(!stripped.contains("abc"));
+    assert!(!stripped.contains("hunter2"));
+    assert!(stripped.contains("deploy = \"kubectl apply -f 'my dir'\""));
+    assert!(stripped.contains("login = \"tool login --user me\""));
+    assert!(stripped.contains("ask = \"llm --max-tokens 100\""));
+    assert!(stripped.contains("chat = \"llm\""));
+    assert_eq!(strip_config_secrets("[aliases]\nls = \"ls -la\"\n"), "[aliases]\nls = \"ls -la\"\n");
+}
+
+#[test]
+fn test_import_backs_up_config() {
+    let directory = std::env::temp_dir().join(format!("clitui-bundle-backup-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    let config_file = directory.join("config.toml");
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(&config_file, "monochrome = true\n").unwrap();
+    let bundle = Bundle { config: Some(String::from("[keymap]\nquit = [\"Ctrl+X\"]\n")), ..Default::default() };
+    let mut storage = crate::storage::FileStorage { directory: directory.join("data") };
+
+    let backup = import(&bundle, Some(&config_file), &mut storage).unwrap();
+
+    assert_eq!(backup, Some(directory.join("config.toml.bak")));
+    assert_eq!(fs::read_to_string(directory.join("config.toml.bak")).unwrap(), "monochrome = true\n");
+    assert_eq!(import(&bundle, Some(&config_file), &mut storage).unwrap(), None);
+}
+
+#[test]
+fn test_import_invalid_config() {
+    let bundle = Bundle { config: Some(String::from("[keymap]\nquit = 1")), ..Default::default() };
+    let mut storage = crate::storage::FileStorage { directory: std::env::temp_dir().join("clitui-bundle-invalid") };
+
+    assert!(import(&bundle, None, &mut storage).unwrap_err().starts_with("Invalid config:"));
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::parsing::CLIParameters;
+
+/// Parameters parsed from the help of a command, valid as long as its program and scripts are unchanged
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry<P> {
+    command: Vec<String>,
+    /// Path of the program the command runs
+    binary: PathBuf,
+    /// Latest modification of the program or a file among the arguments, in seconds since the Unix epoch
+    modified: u64,
+    /// Size of the program in bytes, package managers may keep the modification time on upgrades
+    size: u64,
+    parameters: P,
+}
+
+/// Parsed parameters of commands, one TOML file per command
+#[derive(Debug, PartialEq)]
+pub struct HelpCache {
+    pub directory: PathBuf,
+}
+
+/// Path of a program as found by the shell, searching `$PATH` unless it contains a directory
+fn find_binary(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+    if path.components().count() > 1 {
+        return Some(path.to_path_buf()).filter(|path| path.is_file());
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|directory| [directory.join(program), directory.join(format!("{program}{}", env::consts::EXE_SUFFIX))])
+        .find(|candidate| candidate.is_file())
+}
+
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+    modified.duration_since(UNIX_EPOCH).ok().map(|duration| duration.as_secs())
+}
+
+/// Binary of the command, the latest modification of it or of a file it is given, e.g. the script of `python greeter.py`,
+/// and the size of the binary
+fn command_state(command: &[String]) -> Option<(PathBuf, u64, u64)> {
+    let binary = find_binary(command.first()?)?;
+    let modified = command[1..]
+        .iter()
+        .filter_map(|arg| modified_secs(Path::new(arg)))
+        .chain(modified_secs(&binary))
+        .max()?;
+    let size = fs::metadata(&binary).ok()?.len();
+    Some((binary, modified, size))
+}
+
+impl HelpCache {
+    /// The file name hashes the command, the options that change how its help is parsed
+    /// and the version of clitui, whose parsers may read the same help differently after an upgrade
+    fn entry_path(&self, command: &[String], options: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        (command, options, env!("CARGO_PKG_VERSION")).hash(&mut hasher);
+        self.directory.join(format!("{:016x}.toml", hasher.finish()))
+    }
+
+    /// Cached parameters of the command, None if there are none or its program changed since
+    pub fn load(&self, command: &[String], options: &str) -> Option<CLIParameters> {
+        let (binary, modified, size) = command_state(command)?;
+        let content = fs::read_to_string(self.entry_path(command, options)).ok()?;
+        let entry: CacheEntry<CLIParameters> = toml::from_str(&content).ok()?;
+        if entry.command != command || entry.binary != binary || entry.modified != modified || entry.size != size {
+            return None;
+        }
+        Some(entry.parameters)
+    }
+
+    /// Does nothing for commands whose program cannot be found
+    pub fn store(&self, command: &[String], options: &str, parameters: &CLIParameters) -> io::Result<()> {
+        let Some((binary, modified, size)) = command_state(command) else {
+            return Ok(());
+        };
+        let entry = CacheEntry { command: command.to_vec(), binary, modified, size, parameters };
+        let content = toml::to_string(&entry).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.entry_path(command, options), content)
+    }
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_cache(name: &str) -> (HelpCache, Vec<String>) {
+    let directory = env::temp_dir().join(format!("clitui-cache-{name}"));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    let binary = directory.join("tool");
+    fs::write(&binary, "").unwrap();
+    (HelpCache { directory: directory.join("cache") }, vec![binary.to_string_lossy().to_string()])
+}
+
+#[allow(dead_code)]
+fn create_test_parameters() -> CLIParameters {
+    use crate::parsing::{CLIFlag, CLISubcommand};
+    CLIParameters {
+        cli_name: String::from("tool"),
+        flags: vec![CLIFlag { key: String::from("--verbose"), short_key: Some(String::from("-v")), multiple: true, ..Default::default() }],
+        subcommands: vec![CLISubcommand {
+            name: String::from("build"),
+            parameters: Some(CLIParameters { cli_name: String::from("tool"), command_path: vec![String::from("build")], ..Default::default() }),
+            ..Default::default()
+        }],
+        exclusive_groups: vec![vec![String::from("--json"), String::from("--yaml")]],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_help_cache_round_trip() {
+    let (cache, command) = create_test_cache("round-trip");
+
+    cache.store(&command, "clap", &create_test_parameters()).unwrap();
+
+    assert_eq!(cache.load(&command, "clap"), Some(create_test_parameters()));
+    assert_eq!(cache.load(&command, "gnu"), None);
+}
+
+#[test]
+fn test_help_cache_stale_after_binary_changed() {
+    let (cache, command) = create_test_cache("stale");
+    cache.store(&command, "", &create_test_parameters()).unwrap();
+
+    let binary = fs::File::options().write(true).open(&command[0]).unwrap();
+    binary.set_modified(UNIX_EPOCH).unwrap();
+
+    assert_eq!(cache.load(&command, ""), None);
+}
+
+#[test]
+fn test_help_cache_stale_after_binary_resized() {
+    let (cache, command) = create_test_cache("resized");
+    cache.store(&command, "", &create_test_parameters()).unwrap();
+    let modified = fs::metadata(&command[0]).unwrap().modified().unwrap();
+
+    let binary = fs::File::options().write(true).open(&command[0]).unwrap();
+    binary.set_len(64).unwrap();
+    binary.set_modified(modified).unwrap();
+
+    assert_eq!(cache.load(&command, ""), None);
+}
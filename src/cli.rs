@@ -1,45 +1,571 @@
-use std::process::Command;
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
 
-pub fn build_help_command(args: Vec<String>) -> Command {
-    let command_to_run = &args[0];
-    let mut command_args: Vec<&str> = args.iter().skip(1).map(|arg| arg.as_str()).collect();
-    command_args.push("--help");
+use crate::{
+    model::Section,
+    parsing::{parse_completion_script, parse_help_string_with, parse_help_string_with_parsers, parse_json_help, HelpParser, ParseError, parse_spec, parse_version, CLILib, CLIParameters, KeyStyle},
+    paths::{self, PathKind},
+    quoting::{QuoteOptions, QuoteStyle, Shell},
+    screenshot::ScreenshotFormat,
+};
 
-        // Example: Running a command with the collected arguments
-    let mut output = Command::new(command_to_run);
-    output.args(command_args);
-    output
+/// Maximum depth of nested subcommands whose help is probed
+const SUBCOMMAND_DEPTH: usize = 3;
+
+/// Time a probe of the help or the version may take before the tool is killed, e.g. a REPL waiting for input
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Options of clitui itself, followed by the command that should be wrapped
+#[derive(Debug, Default, PartialEq)]
+pub struct AppArgs {
+    pub command: Vec<String>,
+    pub quote_options: QuoteOptions,
+    pub detach: bool,
+    pub log_file: Option<PathBuf>,
+    pub sort: bool,
+    pub audit: Option<PathBuf>,
+    pub no_subcommands: bool,
+    /// Share code whose values are filled into the form
+    pub import_code: Option<String>,
+    /// Config file used instead of the default one
+    pub config_file: Option<PathBuf>,
+    /// Whether the help of `-h` is merged into the help of `--help`
+    pub merge_help: bool,
+    /// Form of the keys passed to the command, overrides the config
+    pub key_style: Option<KeyStyle>,
+    pub screenshot_format: ScreenshotFormat,
+    /// Sections left out of the form by `--only` and `--hide`
+    pub hidden_sections: Vec<Section>,
+    /// Spec file declaring the parameters, used instead of the help of the command
+    pub spec_file: Option<PathBuf>,
+    /// Storage directory whose history and documents are copied into the default one
+    pub import_storage: Option<PathBuf>,
+    /// Whether the help is parsed again instead of using the cached parameters
+    pub refresh_cache: bool,
+    /// Whether the bundled greeter is wrapped instead of a command
+    pub demo: bool,
+    /// Whether any guess made while reading the help refuses to open the form
+    pub strict: bool,
+    /// File the config and saved documents are exported to
+    pub export_bundle: Option<PathBuf>,
+    /// File whose config and saved documents replace the current ones
+    pub import_bundle: Option<PathBuf>,
+    /// Whether the screen is drawn without colors, like `monochrome` in the config
+    pub monochrome: bool,
+    /// Whether the help of the command is checked for what clitui cannot read instead of opening the form
+    pub lint_help: bool,
 }
 
-pub fn run_help_command(mut command: Command) -> std::io::Result<String> {
-    let command = command.output()?;
-    if command.status.success() {
-        let output = String::from_utf8_lossy(&command.stdout).to_string();
-        return Ok(output);
-    } else {
-        let error = String::from_utf8_lossy(&command.stderr);
-        panic!("Failed to retrieve help description: {error}");
+fn next_value(args: &mut impl Iterator<Item = String>, option: &str) -> Result<String, String> {
+    args.next().ok_or(format!("Missing value for {option}"))
+}
+
+/// Comma separated section names, e.g. `args,flags`
+fn parse_sections(value: &str) -> Result<Vec<Section>, String> {
+    value.split(',').map(|name| Section::from_name(name.trim()).ok_or(format!("Unknown section: {name}"))).collect()
+}
+
+/// Split the clitui options from the command that should be wrapped, e.g.
+/// clitui --quote double greeter.exe
+/// Everything after the first unknown argument (or after `--`) belongs to the wrapped command
+pub fn parse_app_args(args: Vec<String>) -> Result<AppArgs, String> {
+    let mut app_args = AppArgs::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--quote" => {
+                let value = next_value(&mut args, "--quote")?;
+                app_args.quote_options.style = QuoteStyle::from_name(&value).ok_or(format!("Unknown quote style: {value}"))?;
+            },
+            "--shell" => {
+                let value = next_value(&mut args, "--shell")?;
+                app_args.quote_options.shell = Shell::from_name(&value).ok_or(format!("Unknown shell: {value}"))?;
+            },
+            "--keys" => {
+                let value = next_value(&mut args, "--keys")?;
+                app_args.key_style = Some(KeyStyle::from_name(&value).ok_or(format!("Unknown key style: {value}"))?);
+            },
+            "--screenshot-format" => {
+                let value = next_value(&mut args, "--screenshot-format")?;
+                app_args.screenshot_format = ScreenshotFormat::from_name(&value).ok_or(format!("Unknown screenshot format: {value}"))?;
+            },
+            "--only" => {
+                let shown = parse_sections(&next_value(&mut args, "--only")?)?;
+                app_args.hidden_sections.extend(Section::ALL.into_iter().filter(|section| !shown.contains(section)));
+            },
+            "--hide" => app_args.hidden_sections.extend(parse_sections(&next_value(&mut args, "--hide")?)?),
+            "--detach" => app_args.detach = true,
+            "--sort" => app_args.sort = true,
+            "--no-subcommands" => app_args.no_subcommands = true,
+            "--merge-help" => app_args.merge_help = true,
+            "--refresh-cache" => app_args.refresh_cache = true,
+            "--demo" => app_args.demo = true,
+            "--strict" => app_args.strict = true,
+            "--monochrome" => app_args.monochrome = true,
+            "--lint-help" => app_args.lint_help = true,
+            "--audit" => app_args.audit = Some(PathBuf::from(next_value(&mut args, "--audit")?)),
+            "--log" => app_args.log_file = Some(PathBuf::from(next_value(&mut args, "--log")?)),
+            "--spec" => app_args.spec_file = Some(PathBuf::from(next_value(&mut args, "--spec")?)),
+            "--config" => app_args.config_file = Some(PathBuf::from(next_value(&mut args, "--config")?)),
+            "--import-code" => app_args.import_code = Some(next_value(&mut args, "--import-code")?),
+            "--import-storage" => app_args.import_storage = Some(PathBuf::from(next_value(&mut args, "--import-storage")?)),
+            "--export-bundle" => app_args.export_bundle = Some(PathBuf::from(next_value(&mut args, "--export-bundle")?)),
+            "--import-bundle" => app_args.import_bundle = Some(PathBuf::from(next_value(&mut args, "--import-bundle")?)),
+            "--" => {
+                app_args.command.extend(args.by_ref());
+            },
+            _ => {
+                app_args.command.push(arg);
+                app_args.command.extend(args.by_ref());
+            },
+        }
+    }
+    let without_command = app_args.audit.is_some()
+        || app_args.import_storage.is_some()
+        || app_args.export_bundle.is_some()
+        || app_args.import_bundle.is_some()
+        || app_args.spec_file.is_some()
+        || app_args.demo;
+    if app_args.command.is_empty() && !without_command {
+        return Err(String::from("No arguments provided"));
+    }
+    // only a detached command writes its output to a file, the others show it in the output pane
+    if app_args.log_file.is_some() && !app_args.detach {
+        return Err(String::from("--log requires --detach"));
     }
+    Ok(app_args)
 }
 
-pub fn run_external_command(mut command: Command) -> std::io::Result<()> {
-    let command = command.output()?;
-    if command.status.success() {
-        let output = String::from_utf8_lossy(&command.stdout);
-        println!("{output}");
-    } else {
-        let error = String::from_utf8_lossy(&command.stderr);
-        eprintln!("Command failed: {}", error);
+/// Ways to ask a program for its help, tried in this order: `--help`, `-h`, a `help` subcommand, `/?` and no arguments at all
+const HELP_INVOCATIONS: [&[&str]; 5] = [&["--help"], &["-h"], &["help"], &["/?"], &[]];
+
+/// Help invocations for this platform, `/?` of classic Windows tools could be taken for a path elsewhere
+/// Running the tool without arguments does whatever the tool does, so it is only tried if `bare_help` is set in its parser rule
+fn help_invocations(bare_help: bool) -> impl Iterator<Item = &'static [&'static str]> {
+    HELP_INVOCATIONS
+        .into_iter()
+        .filter(|help_args| cfg!(windows) || *help_args != ["/?"])
+        .filter(move |help_args| bare_help || !help_args.is_empty())
+}
+
+/// The help invocations for error messages, e.g. `--help, -h, help and no arguments`
+fn describe_help_invocations(bare_help: bool) -> String {
+    let mut names: Vec<String> = help_invocations(bare_help)
+        .map(|help_args| if help_args.is_empty() { String::from("no arguments") } else { help_args.join(" ") })
+        .collect();
+    let last = names.pop().unwrap_or_default();
+    format!("{} and {last}", names.join(", "))
+}
+
+pub fn build_help_command_with_flag(args: Vec<String>, help_flag: &str) -> Command {
+    build_help_command_with_args(&args, &[help_flag])
+}
+
+fn build_help_command_with_args(args: &[String], help_args: &[&str]) -> Command {
+    let mut command = Command::new(&args[0]);
+    command.args(&args[1..]).args(help_args).stdin(Stdio::null());
+    command
+}
+
+/// Help text of the first help invocation that prints anything, see `HELP_INVOCATIONS`
+pub fn retrieve_help_string(args: &[String], bare_help: bool) -> Option<String> {
+    help_invocations(bare_help)
+        .filter_map(|help_args| help_output(build_help_command_with_args(args, help_args)))
+        .find(|help_string| !help_string.trim().is_empty())
+}
+
+/// Read the stream on a thread of its own, a full pipe would block the probed tool
+fn read_in_background(mut stream: impl Read + Send + 'static) -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = stream.read_to_end(&mut bytes);
+        let _ = sender.send(bytes);
+    });
+    receiver
+}
+
+/// Output of a probe of the tool, None if it cannot be started or is killed after `PROBE_TIMEOUT`
+fn probe_output(mut command: Command) -> Option<Output> {
+    let mut child = command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn().ok()?;
+    let stdout = read_in_background(child.stdout.take()?);
+    let stderr = read_in_background(child.stderr.take()?);
+    let deadline = Instant::now() + PROBE_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            },
+        }
+    };
+    // a process started by the tool may keep the pipes open after it exited
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    let stdout = stdout.recv_timeout(remaining()).ok()?;
+    let stderr = stderr.recv_timeout(remaining()).ok()?;
+    Some(Output { status, stdout, stderr })
+}
+
+/// Standard output of the command, or standard error if nothing was written to standard output
+/// The exit status is ignored, many programs print their usage and fail when asked for help in an unknown way
+fn help_output(command: Command) -> Option<String> {
+    let output = probe_output(command)?;
+    let stream = if output.stdout.iter().all(u8::is_ascii_whitespace) { output.stderr } else { output.stdout };
+    Some(String::from_utf8_lossy(&stream).to_string())
+}
+
+/// Directories of installed completion scripts, zsh functions are named `_tool` and bash scripts like the tool
+/// The zsh directories come first, their specs describe values and exclusions as well
+const COMPLETION_DIRECTORIES: [&str; 8] = [
+    "/usr/share/zsh/site-functions",
+    "/usr/share/zsh/vendor-completions",
+    "/usr/local/share/zsh/site-functions",
+    "/opt/homebrew/share/zsh/site-functions",
+    "/usr/share/bash-completion/completions",
+    "/usr/local/share/bash-completion/completions",
+    "/etc/bash_completion.d",
+    "/opt/homebrew/etc/bash_completion.d",
+];
+
+/// Directories of the completion files of fish, named `tool.fish`
+const FISH_COMPLETION_DIRECTORIES: [&str; 5] = [
+    "/etc/fish/completions",
+    "/usr/share/fish/vendor_completions.d",
+    "/usr/local/share/fish/vendor_completions.d",
+    "/usr/share/fish/completions",
+    "/opt/homebrew/share/fish/vendor_completions.d",
+];
+
+/// Completion directories of the user, e.g. `~/.local/share/bash-completion/completions`, followed by the system ones
+fn completion_directories() -> Vec<PathBuf> {
+    paths::xdg_directory(PathKind::Data)
+        .map(|directory| directory.join("bash-completion").join("completions"))
+        .into_iter()
+        .chain(COMPLETION_DIRECTORIES.iter().map(PathBuf::from))
+        .collect()
+}
+
+/// Fish completion directories of the user, e.g. `~/.config/fish/completions`, followed by the system ones
+fn fish_completion_directories() -> Vec<PathBuf> {
+    let user_directories = [
+        paths::xdg_directory(PathKind::Config).map(|directory| directory.join("fish").join("completions")),
+        paths::xdg_directory(PathKind::Data).map(|directory| directory.join("fish").join("vendor_completions.d")),
+    ];
+    user_directories.into_iter().flatten().chain(FISH_COMPLETION_DIRECTORIES.iter().map(PathBuf::from)).collect()
+}
+
+/// Completion script of the program in the first directory that has one
+fn find_completion_script(program: &str, directories: &[PathBuf]) -> Option<String> {
+    let name = Path::new(program).file_name()?.to_str()?;
+    let file_names = [format!("_{name}"), name.to_string(), format!("{name}.bash"), format!("{name}.fish")];
+    directories
+        .iter()
+        .flat_map(|directory| file_names.iter().map(move |file_name| directory.join(file_name)))
+        .find_map(|path| fs::read_to_string(path).ok())
+}
+
+/// Parameters from the installed completion script of a program run without arguments,
+/// the completion of an interpreter or a subcommand does not describe the invoked command
+fn completion_parameters(args: &[String], directories: &[PathBuf]) -> Option<CLIParameters> {
+    let [program] = args else {
+        return None;
+    };
+    let name = Path::new(program).file_name()?.to_str()?;
+    parse_completion_script(&find_completion_script(program, directories)?, name)
+}
+
+/// Flag of tools that dump their command model as JSON, preferred over the help text when the help lists it
+const DUMP_HELP_FLAG: &str = "--dump-help-json";
+
+/// Flags printing the version of a tool, the first one the help lists is used
+const VERSION_FLAGS: [&str; 2] = ["--version", "-V"];
+
+/// Warning if the help names another program than the invoked one, e.g. `greeter.exe` for `python greeter.py`
+/// Names are compared without directory and extension, so `./target/debug/greeter` matches `greeter.exe`
+pub fn program_name_warning(command: &[String], cli_name: &str) -> Option<String> {
+    let stem = |word: &str| Path::new(word).file_stem().map(|stem| stem.to_string_lossy().to_lowercase());
+    let name = stem(cli_name)?;
+    if command.iter().any(|word| stem(word).as_ref() == Some(&name)) {
+        return None;
+    }
+    Some(format!("The help names the command {cli_name}, running {} as invoked", command.join(" ")))
+}
+
+/// Version of the tool if its help lists a version flag, see `VERSION_FLAGS`
+pub fn retrieve_version(args: &[String], parameters: &CLIParameters) -> Option<String> {
+    let version_flag = VERSION_FLAGS.into_iter().find(|key| parameters.flags.iter().any(|flag| flag.has_key(key)))?;
+    let output = probe_output(build_help_command_with_flag(args.to_vec(), version_flag))?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the help of the first invocation whose output any of the parsers accepts
+/// The version is asked for alongside the help
+pub fn retrieve_help(args: &[String], cli_libs: &[CLILib], bare_help: bool) -> Result<CLIParameters, String> {
+    let mut parameters = retrieve_help_text(args, cli_libs, bare_help)?;
+    parameters.version = retrieve_version(args, &parameters);
+    Ok(parameters)
+}
+
+/// Why the help of a tool could not be parsed, with the errors of the first invocation that printed anything
+fn describe_parse_errors(program: &str, help_args: &[&str], errors: &[ParseError], bare_help: bool) -> String {
+    let invocation = if help_args.is_empty() { String::from("without arguments") } else { help_args.join(" ") };
+    let mut description = format!("Cannot parse the help of {program}, tried {}\nThe output of {invocation} was rejected by every parser:", describe_help_invocations(bare_help));
+    for error in errors {
+        description.push_str(&format!("\n  {error}"));
+        if let Some(suggestion) = &error.suggestion {
+            description.push_str(&format!("\n    {suggestion}"));
+        }
+    }
+    description.push_str("\nForce a parser with `[parsers.<tool>]` in the config or describe the tool in a --spec file");
+    description
+}
+
+fn retrieve_help_text(args: &[String], cli_libs: &[CLILib], bare_help: bool) -> Result<CLIParameters, String> {
+    let parsers: Vec<&dyn HelpParser> = cli_libs.iter().map(|cli_lib| cli_lib as &dyn HelpParser).collect();
+    let mut first_errors = None;
+    let mut parsed = None;
+    for help_args in help_invocations(bare_help) {
+        let Some(help_string) = help_output(build_help_command_with_args(args, help_args)) else {
+            continue;
+        };
+        match parse_help_string_with_parsers(&help_string, &parsers) {
+            Ok(mut parameters) => {
+                parameters.guesses = parameters.find_guesses(&help_string);
+                parsed = Some(parameters);
+                break;
+            },
+            Err(errors) if first_errors.is_none() && !help_string.trim().is_empty() => first_errors = Some((help_args, errors)),
+            Err(_) => (),
+        }
+    }
+    // fish completions map onto the fields almost one to one and add to whatever the help described
+    let mut fish_completion = completion_parameters(args, &fish_completion_directories());
+    // completion scripts describe tools whose help is missing or yields no fields
+    let completion = parsed
+        .as_ref()
+        .is_none_or(CLIParameters::has_only_help_flags)
+        .then(|| completion_parameters(args, &completion_directories()).or_else(|| fish_completion.take()))
+        .flatten();
+    let mut parameters = match (parsed, first_errors, completion) {
+        (_, _, Some(mut completion)) => {
+            completion.guesses.push(String::from("Read from a completion script instead of the help, required options are unknown"));
+            completion
+        },
+        (Some(parameters), _, None) => parameters,
+        (None, Some((help_args, errors)), None) => return Err(describe_parse_errors(&args[0], help_args, &errors, bare_help)),
+        (None, None, None) if bare_help => return Err(format!("Cannot retrieve the help of {}, tried {}", args[0], describe_help_invocations(bare_help))),
+        (None, None, None) => {
+            return Err(format!(
+                "Cannot retrieve the help of {}, tried {}\nSet `bare_help = true` in `[parsers.<tool>]` of the config to run it without arguments as well",
+                args[0],
+                describe_help_invocations(bare_help),
+            ))
+        },
+    };
+    if let Some(fish_completion) = fish_completion {
+        parameters.merge_completion(fish_completion);
+    }
+    if parameters.flags.iter().any(|flag| flag.has_key(DUMP_HELP_FLAG)) {
+        let dump = probe_output(build_help_command_with_flag(args.to_vec(), DUMP_HELP_FLAG)).filter(|output| output.status.success());
+        if let Some(dumped_parameters) = dump.and_then(|output| parse_json_help(&String::from_utf8_lossy(&output.stdout)).ok()) {
+            return Ok(dumped_parameters);
+        }
+    }
+    Ok(parameters)
+}
+
+/// Read the parameters from a spec file instead of the help of the command
+pub fn load_spec(spec_file: &Path) -> Result<CLIParameters, String> {
+    let content = fs::read_to_string(spec_file).map_err(|error| format!("Cannot read spec {}: {error}", spec_file.display()))?;
+    parse_spec(&content)
+}
+
+/// Parse the short help of `-h`, None if the command does not support it
+pub fn probe_short_help(args: Vec<String>, cli_libs: &[CLILib]) -> Option<CLIParameters> {
+    let output = probe_output(build_help_command_with_flag(args, "-h"))?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_help_output(&output.stdout, cli_libs)
+}
+
+/// Parse the help printed by a command, noting the guesses it was parsed with
+fn parse_help_output(stdout: &[u8], cli_libs: &[CLILib]) -> Option<CLIParameters> {
+    let help_string = String::from_utf8_lossy(stdout);
+    let mut parameters = parse_help_string_with(&help_string, cli_libs)?;
+    parameters.guesses = parameters.find_guesses(&help_string);
+    Some(parameters)
+}
+
+/// How the help of a subcommand is requested, set per tool in the config
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum SubcommandHelp {
+    /// `tool sub --help`
+    Flag,
+    /// `tool help sub`, e.g. for some Go tools
+    HelpSubcommand,
+    /// Try `tool sub --help` and then `tool help sub`, using whichever parses
+    #[default]
+    Auto,
+}
+
+impl SubcommandHelp {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<SubcommandHelp> {
+        match name {
+            "flag" => return Some(SubcommandHelp::Flag),
+            "help" => return Some(SubcommandHelp::HelpSubcommand),
+            "auto" => return Some(SubcommandHelp::Auto),
+            _ => return None,
+        }
+    }
+
+    /// Commands asking for the help of the subcommand at the path, in the order they are tried
+    fn build_commands(&self, args: &[String], command_path: &[String]) -> Vec<Command> {
+        let mut flag_args = command_path.to_vec();
+        flag_args.push(String::from("--help"));
+        let mut help_args = vec![String::from("help")];
+        help_args.extend_from_slice(command_path);
+        let orders = match self {
+            SubcommandHelp::Flag => vec![flag_args],
+            SubcommandHelp::HelpSubcommand => vec![help_args],
+            SubcommandHelp::Auto => vec![flag_args, help_args],
+        };
+        orders
+            .iter()
+            .map(|order| build_help_command_with_args(args, &order.iter().map(String::as_str).collect::<Vec<&str>>()))
+            .collect()
+    }
+}
+
+/// Probe the help of every subcommand and attach the parsed parameters, recursively
+/// Subcommands whose help cannot be retrieved or parsed are left without parameters
+pub fn discover_subcommands(args: &[String], parameters: &mut CLIParameters, cli_libs: &[CLILib], subcommand_help: SubcommandHelp) {
+    discover_subcommands_up_to(args, parameters, cli_libs, subcommand_help, SUBCOMMAND_DEPTH);
+}
+
+fn discover_subcommands_up_to(args: &[String], parameters: &mut CLIParameters, cli_libs: &[CLILib], subcommand_help: SubcommandHelp, depth: usize) {
+    if depth == 0 {
+        return;
+    }
+    // subcommands of a command dump already have their parameters
+    for subcommand in parameters.subcommands.iter_mut().filter(|subcommand| subcommand.parameters.is_none()) {
+        let mut command_path = parameters.command_path.clone();
+        command_path.push(subcommand.name.clone());
+        let subcommand_parameters = subcommand_help
+            .build_commands(args, &command_path)
+            .into_iter()
+            .filter_map(probe_output)
+            .filter(|output| output.status.success())
+            .find_map(|output| parse_help_output(&output.stdout, cli_libs));
+        let Some(mut subcommand_parameters) = subcommand_parameters else {
+            continue;
+        };
+        subcommand_parameters.cli_name = parameters.cli_name.clone();
+        subcommand_parameters.command_path = command_path;
+        discover_subcommands_up_to(args, &mut subcommand_parameters, cli_libs, subcommand_help, depth - 1);
+        subcommand.parameters = Some(subcommand_parameters);
+    }
+}
+
+/// Discovers the subcommands on a thread of its own, the form of the command is shown meanwhile
+#[derive(Debug)]
+pub struct SubcommandDiscovery {
+    results: Receiver<CLIParameters>,
+}
+
+impl SubcommandDiscovery {
+    /// Discover the subcommands of the parameters, `finish` prepares the discovered ones like the form was prepared
+    pub fn spawn(args: Vec<String>, mut parameters: CLIParameters, cli_libs: Vec<CLILib>, subcommand_help: SubcommandHelp, finish: impl FnOnce(&mut CLIParameters) + Send + 'static) -> SubcommandDiscovery {
+        let (sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            discover_subcommands(&args, &mut parameters, &cli_libs, subcommand_help);
+            finish(&mut parameters);
+            let _ = sender.send(parameters);
+        });
+        SubcommandDiscovery { results }
     }
 
+    /// The parameters with the discovered subcommands, once the thread has finished
+    pub fn poll(&self) -> Option<CLIParameters> {
+        self.results.try_recv().ok()
+    }
+}
+
+/// Show the text in the pager of the user, `$PAGER` or `less` if it is not set
+/// Waits until the pager is closed, quitting it before reading everything is not an error
+pub fn run_pager(text: &str) -> std::io::Result<()> {
+    let pager = env::var("PAGER").ok().filter(|pager| !pager.trim().is_empty()).unwrap_or(String::from("less"));
+    let mut words = pager.split_whitespace();
+    let mut command = Command::new(words.next().unwrap_or_default());
+    command.args(words).stdin(Stdio::piped());
+    let mut child = command.spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
     Ok(())
 }
 
+/// Launch the command detached from the terminal so it keeps running once clitui quits or the terminal is closed and Ctrl+C does not reach it.
+/// On Unix it is started in a new session, on Windows in a new process group without a console.
+/// Output goes to the log file if one is given and is discarded otherwise.
+/// Returns the process id of the launched command.
+pub fn run_detached_command(mut command: Command, log_file: Option<&Path>) -> std::io::Result<u32> {
+    command.stdin(Stdio::null());
+    if let Some(log_file) = log_file {
+        let log = File::create(log_file)?;
+        command.stdout(log.try_clone()?).stderr(log);
+    } else {
+        command.stdout(Stdio::null()).stderr(Stdio::null());
+    }
+    detach(&mut command);
+    let child = command.spawn()?;
+    Ok(child.id())
+}
+
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+    // SAFETY: setsid is async-signal-safe and touches no memory of the parent
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+    const DETACHED_PROCESS: u32 = 0x00000008;
+    const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+    command.creation_flags(DETACHED_PROCESS | CREATE_NEW_PROCESS_GROUP);
+}
+
 #[test]
 fn test_build_help_command() {
     let args = vec![String::from("greeter.exe")];
 
-    let help_command = build_help_command(args);
+    let help_command = build_help_command_with_flag(args, "--help");
 
     let mut expected_help_command = Command::new("greeter.exe");
     expected_help_command.arg("--help");
@@ -53,7 +579,7 @@ fn test_build_help_command() {
 fn test_build_help_command_from_multiple_args() {
     let args = vec![String::from("python"), String::from("greeter.py")];
 
-    let help_command = build_help_command(args);
+    let help_command = build_help_command_with_flag(args, "--help");
 
     let mut expected_help_command = Command::new("python");
     expected_help_command.args(vec![String::from("greeter.py"), String::from("--help")]);
@@ -61,4 +587,492 @@ fn test_build_help_command_from_multiple_args() {
         format!("{:?}", help_command),
         format!("{:?}", expected_help_command),
     )
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_parse_app_args_without_options() {
+    let args = vec![String::from("python"), String::from("greeter.py")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(
+        app_args,
+        AppArgs {
+            command: vec![String::from("python"), String::from("greeter.py")],
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_parse_app_args_quote_options() {
+    let args = vec![
+        String::from("--quote"),
+        String::from("double"),
+        String::from("--shell"),
+        String::from("cmd"),
+        String::from("greeter.exe"),
+        String::from("--quote"),
+    ];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(
+        app_args,
+        AppArgs {
+            command: vec![String::from("greeter.exe"), String::from("--quote")],
+            quote_options: QuoteOptions {
+                shell: Shell::Cmd,
+                style: QuoteStyle::Double,
+            },
+            ..Default::default()
+        },
+    )
+}
+
+#[test]
+fn test_parse_app_args_key_style() {
+    let args = vec![String::from("--keys"), String::from("short"), String::from("greeter.exe")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(app_args.key_style, Some(KeyStyle::Short));
+    assert_eq!(
+        parse_app_args(vec![String::from("--keys"), String::from("medium"), String::from("greeter.exe")]),
+        Err(String::from("Unknown key style: medium")),
+    );
+}
+
+#[test]
+fn test_parse_app_args_strict() {
+    let app_args = parse_app_args(vec![String::from("--strict"), String::from("rm"), String::from("--strict")]).unwrap();
+
+    assert!(app_args.strict);
+    assert_eq!(app_args.command, vec![String::from("rm"), String::from("--strict")]);
+}
+
+#[test]
+fn test_parse_app_args_monochrome() {
+    let app_args = parse_app_args(vec![String::from("--monochrome"), String::from("greeter.exe")]).unwrap();
+
+    assert!(app_args.monochrome);
+    assert_eq!(app_args.command, vec![String::from("greeter.exe")]);
+}
+
+#[test]
+fn test_parse_app_args_lint_help() {
+    let app_args = parse_app_args(vec![String::from("--lint-help"), String::from("greeter.exe")]).unwrap();
+
+    assert!(app_args.lint_help);
+    assert_eq!(app_args.command, vec![String::from("greeter.exe")]);
+}
+
+#[test]
+fn test_parse_app_args_screenshot_format() {
+    let args = vec![String::from("--screenshot-format"), String::from("html"), String::from("greeter.exe")];
+
+    assert_eq!(parse_app_args(args).unwrap().screenshot_format, ScreenshotFormat::Html);
+    assert_eq!(
+        parse_app_args(vec![String::from("--screenshot-format"), String::from("png"), String::from("greeter.exe")]),
+        Err(String::from("Unknown screenshot format: png")),
+    );
+}
+
+#[test]
+fn test_parse_app_args_sections() {
+    let args = vec![String::from("--only"), String::from("args,flags"), String::from("--hide"), String::from("flags"), String::from("greeter.exe")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(app_args.hidden_sections, vec![Section::Positionals, Section::Options, Section::Flags]);
+    assert_eq!(
+        parse_app_args(vec![String::from("--hide"), String::from("flags,extras"), String::from("greeter.exe")]),
+        Err(String::from("Unknown section: extras")),
+    );
+}
+
+#[test]
+fn test_parse_app_args_spec_without_command() {
+    let app_args = parse_app_args(vec![String::from("--spec"), String::from("mytool.toml")]).unwrap();
+
+    assert_eq!(app_args.spec_file, Some(PathBuf::from("mytool.toml")));
+    assert!(app_args.command.is_empty());
+}
+
+#[test]
+fn test_parse_app_args_demo_without_command() {
+    let app_args = parse_app_args(vec![String::from("--demo")]).unwrap();
+
+    assert!(app_args.demo);
+    assert!(app_args.command.is_empty());
+}
+
+#[test]
+fn test_parse_app_args_bundle() {
+    let args = vec![String::from("--export-bundle"), String::from("team.toml")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(app_args.export_bundle, Some(PathBuf::from("team.toml")));
+    assert_eq!(parse_app_args(vec![String::from("--import-bundle")]).unwrap_err(), "Missing value for --import-bundle");
+}
+
+#[test]
+fn test_parse_app_args_import_storage() {
+    let args = vec![String::from("--import-storage"), String::from("/backup/clitui")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(app_args.import_storage, Some(PathBuf::from("/backup/clitui")));
+    assert!(app_args.command.is_empty());
+}
+
+#[test]
+fn test_parse_app_args_separator() {
+    let args = vec![String::from("--"), String::from("--quote")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(
+        app_args.command,
+        vec![String::from("--quote")],
+    )
+}
+
+#[test]
+fn test_parse_app_args_unknown_quote_style() {
+    let args = vec![String::from("--quote"), String::from("backtick"), String::from("greeter.exe")];
+
+    assert_eq!(
+        parse_app_args(args),
+        Err(String::from("Unknown quote style: backtick")),
+    )
+}
+
+#[test]
+fn test_parse_app_args_without_command() {
+    assert_eq!(
+        parse_app_args(Vec::new()),
+        Err(String::from("No arguments provided")),
+    )
+}
+
+#[test]
+fn test_parse_app_args_detach() {
+    let args = vec![
+        String::from("--detach"),
+        String::from("--log"),
+        String::from("server.log"),
+        String::from("server"),
+    ];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert!(app_args.detach);
+    assert_eq!(
+        app_args.log_file,
+        Some(PathBuf::from("server.log")),
+    )
+}
+
+#[test]
+fn test_parse_app_args_log_without_detach() {
+    assert_eq!(
+        parse_app_args(vec![String::from("--log"), String::from("server.log"), String::from("server")]),
+        Err(String::from("--log requires --detach")),
+    )
+}
+
+#[test]
+fn test_parse_app_args_refresh_cache() {
+    let args = parse_app_args(vec![String::from("--refresh-cache"), String::from("cargo")]).unwrap();
+
+    assert!(args.refresh_cache);
+    assert_eq!(args.command, vec![String::from("cargo")]);
+}
+
+#[test]
+fn test_parse_app_args_sort() {
+    let args = vec![String::from("--sort"), String::from("greeter.exe")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert!(app_args.sort);
+}
+
+#[test]
+fn test_parse_app_args_import_code() {
+    let args = vec![String::from("--import-code"), String::from("MQBmAC0tY2Fwcw"), String::from("greeter.exe")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(app_args.import_code.as_deref(), Some("MQBmAC0tY2Fwcw"));
+    assert_eq!(app_args.command, vec![String::from("greeter.exe")]);
+}
+
+#[test]
+fn test_build_short_help_command() {
+    let args = vec![String::from("greeter.exe")];
+
+    let help_command = build_help_command_with_flag(args, "-h");
+
+    let mut expected_help_command = Command::new("greeter.exe");
+    expected_help_command.arg("-h");
+    assert_eq!(
+        format!("{:?}", help_command),
+        format!("{:?}", expected_help_command),
+    )
+}
+
+#[test]
+fn test_parse_app_args_audit_without_command() {
+    let args = vec![String::from("--audit"), String::from("fixtures")];
+
+    let app_args = parse_app_args(args).unwrap();
+
+    assert_eq!(
+        app_args.audit,
+        Some(PathBuf::from("fixtures")),
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_discover_subcommands() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script = std::env::temp_dir().join(format!("clitui-subcommands-{}.sh", std::process::id()));
+    std::fs::write(&script, r#"#!/bin/sh
+if [ "$1" = "build" ]; then
+    printf 'Usage: tool build [OPTIONS]\n\nOptions:\n      --release  Build in release mode\n'
+else
+    printf 'Usage: tool [OPTIONS] [COMMAND]\n\nCommands:\n  build  Compile\n  broken  Fails\n\nOptions:\n  -h, --help  Print help\n'
+fi
+[ "$1" != "broken" ]
+"#).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let args = vec![script.to_string_lossy().to_string()];
+    let mut parameters = retrieve_help(&args, &CLILib::ALL, false).unwrap();
+
+    discover_subcommands(&args, &mut parameters, &CLILib::ALL, SubcommandHelp::Flag);
+    let discovery = SubcommandDiscovery::spawn(args.clone(), retrieve_help(&args, &CLILib::ALL, false).unwrap(), CLILib::ALL.to_vec(), SubcommandHelp::Flag, CLIParameters::mark_globals);
+    let discovered_parameters = (0..250).find_map(|_| discovery.poll().or_else(|| {
+        thread::sleep(Duration::from_millis(20));
+        None
+    }));
+    std::fs::remove_file(&script).unwrap();
+
+    assert_eq!(discovered_parameters.as_ref(), Some(&parameters));
+
+    let build_parameters = parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(build_parameters.command_path, vec![String::from("build")]);
+    assert_eq!(build_parameters.flags[0].key, "--release");
+    assert_eq!(parameters.subcommands[1].parameters, None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_detached_command_writes_log() {
+    let log_file = std::env::temp_dir().join(format!("clitui-detached-{}.log", std::process::id()));
+    let mut command = Command::new("sh");
+    command.args(["-c", "echo detached"]);
+
+    let pid = run_detached_command(command, Some(&log_file)).unwrap();
+
+    assert!(pid > 0);
+    let mut output = String::new();
+    for _ in 0..50 {
+        output = std::fs::read_to_string(&log_file).unwrap();
+        if !output.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    std::fs::remove_file(&log_file).unwrap();
+    assert_eq!(output, "detached\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_run_detached_command_starts_new_session() {
+    let mut command = Command::new("sleep");
+    command.arg("1");
+
+    let pid = run_detached_command(command, None).unwrap();
+
+    let session = unsafe { libc::getsid(pid as libc::pid_t) };
+    let own_session = unsafe { libc::getsid(0) };
+    unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) };
+    assert_eq!(session, pid as libc::pid_t);
+    assert_ne!(session, own_session);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retrieve_help_falls_back_to_help_subcommand() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script = std::env::temp_dir().join(format!("clitui-help-subcommand-{}.sh", std::process::id()));
+    std::fs::write(&script, r#"#!/bin/sh
+if [ "$1" = "help" ]; then
+    printf 'Usage: tool [OPTIONS]\n\nOptions:\n      --verbose  Print more\n'
+else
+    echo "unknown option $1" >&2
+    exit 2
+fi
+"#).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let args = vec![script.to_string_lossy().to_string()];
+
+    let parameters = retrieve_help(&args, &CLILib::ALL, false);
+    std::fs::remove_file(&script).unwrap();
+
+    assert_eq!(parameters.unwrap().flags[0].key, "--verbose");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retrieve_help_with_version() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script = std::env::temp_dir().join(format!("clitui-version-{}.sh", std::process::id()));
+    std::fs::write(&script, r#"#!/bin/sh
+if [ "$1" = "--version" ]; then
+    echo "tool 2.1.0"
+else
+    printf 'Usage: tool [OPTIONS]\n\nOptions:\n  -V, --version  Print version\n'
+fi
+"#).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let args = vec![script.to_string_lossy().to_string()];
+
+    let parameters = retrieve_help(&args, &CLILib::ALL, false);
+    std::fs::remove_file(&script).unwrap();
+
+    assert_eq!(parameters.unwrap().version.as_deref(), Some("2.1.0"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retrieve_help_with_parse_errors() {
+    let args = vec![String::from("sh"), String::from("-c"), String::from("echo 'error: unknown argument'"), String::from("sh")];
+
+    let error = retrieve_help(&args, &[CLILib::Clap, CLILib::Gnu], false).unwrap_err();
+
+    assert_eq!(
+        error,
+        "Cannot parse the help of sh, tried --help, -h and help
+The output of --help was rejected by every parser:
+  clap: no Usage: line with an Options: section, line 1 is \"error: unknown argument\"
+    clap prints `Usage: tool [OPTIONS]` and an `Options:` heading
+  gnu: no Usage: line, line 1 is \"error: unknown argument\"
+    GNU style help has a `Usage: tool [OPTION]...` line and `-x, --xxx` option rows
+Force a parser with `[parsers.<tool>]` in the config or describe the tool in a --spec file",
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retrieve_help_without_help() {
+    let args = vec![String::from("sh"), String::from("-c"), String::from(":")];
+
+    assert_eq!(
+        retrieve_help(&args, &CLILib::ALL, false),
+        Err(String::from("Cannot retrieve the help of sh, tried --help, -h and help
+Set `bare_help = true` in `[parsers.<tool>]` of the config to run it without arguments as well")),
+    );
+    assert_eq!(
+        retrieve_help(&args, &CLILib::ALL, true),
+        Err(String::from("Cannot retrieve the help of sh, tried --help, -h, help and no arguments")),
+    )
+}
+
+#[cfg(unix)]
+#[test]
+fn test_retrieve_help_kills_hanging_tool() {
+    let args = vec![String::from("sh"), String::from("-c"), String::from("sleep 30"), String::from("sh")];
+    let started = Instant::now();
+
+    assert_eq!(help_output(build_help_command_with_args(&args, &["--help"])), None);
+    assert!(started.elapsed() < PROBE_TIMEOUT * 2);
+}
+
+#[test]
+fn test_completion_parameters() {
+    let directory = std::env::temp_dir().join(format!("clitui-completions-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("_mytool"), "#compdef mytool\n_arguments '--all[Show all]' '--color=[When]:when:(auto never)'\n").unwrap();
+    let directories = [directory.join("missing"), directory.clone()];
+
+    let parameters = completion_parameters(&[String::from("/usr/bin/mytool")], &directories).unwrap();
+
+    assert_eq!(parameters.cli_name, "mytool");
+    assert_eq!(parameters.flags[0].key, "--all");
+    assert_eq!(parameters.options[0].choices, ["auto", "never"]);
+    assert_eq!(completion_parameters(&[String::from("python"), String::from("mytool")], &directories), None);
+    assert_eq!(completion_parameters(&[String::from("othertool")], &directories), None);
+}
+
+#[test]
+fn test_fish_completion_parameters() {
+    let directory = std::env::temp_dir().join(format!("clitui-fish-completions-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("mytool.fish"), "complete -c mytool -s a -l all -d 'Show all'\n").unwrap();
+
+    let parameters = completion_parameters(&[String::from("mytool")], &[directory]).unwrap();
+
+    assert_eq!(parameters.flags[0].key, "--all");
+    assert_eq!(parameters.flags[0].short_key.as_deref(), Some("-a"));
+}
+
+#[test]
+fn test_build_subcommand_help_commands() {
+    let args = vec![String::from("tool")];
+    let command_path = vec![String::from("mod"), String::from("tidy")];
+
+    let commands = SubcommandHelp::Auto.build_commands(&args, &command_path);
+
+    let arguments: Vec<Vec<&std::ffi::OsStr>> = commands.iter().map(|command| command.get_args().collect()).collect();
+    assert_eq!(arguments, [vec!["mod", "tidy", "--help"], vec!["help", "mod", "tidy"]]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_discover_subcommands_with_help_subcommand() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let script = std::env::temp_dir().join(format!("clitui-help-order-{}.sh", std::process::id()));
+    std::fs::write(&script, r#"#!/bin/sh
+if [ "$1 $2" = "help build" ]; then
+    printf 'Usage: tool build [OPTIONS]\n\nOptions:\n      --release  Build in release mode\n'
+elif [ "$1" = "build" ]; then
+    echo "unknown flag: --help" >&2
+    exit 2
+else
+    printf 'Usage: tool [OPTIONS] [COMMAND]\n\nCommands:\n  build  Compile\n\nOptions:\n  -h, --help  Print help\n'
+fi
+"#).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let args = vec![script.to_string_lossy().to_string()];
+    let mut parameters = retrieve_help(&args, &CLILib::ALL, false).unwrap();
+
+    discover_subcommands(&args, &mut parameters, &CLILib::ALL, SubcommandHelp::Auto);
+    std::fs::remove_file(&script).unwrap();
+
+    let build_parameters = parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(build_parameters.flags[0].key, "--release");
+}
+
+#[test]
+fn test_program_name_warning() {
+    let command = |words: &[&str]| words.iter().map(|word| word.to_string()).collect::<Vec<String>>();
+
+    assert_eq!(program_name_warning(&command(&["python", "greeter.py"]), "greeter.py"), None);
+    assert_eq!(program_name_warning(&command(&["./target/debug/greeter"]), "greeter.exe"), None);
+    assert_eq!(
+        program_name_warning(&command(&["./hello-wrapper"]), "greeter.exe"),
+        Some(String::from("The help names the command greeter.exe, running ./hello-wrapper as invoked")),
+    );
+}
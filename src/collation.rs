@@ -0,0 +1,42 @@
+use std::cmp::Ordering;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Key that compares equal for strings differing only in case or diacritics,
+/// e.g. "Größe" and "grosse" or "Élément" and "element"
+pub fn collation_key(value: &str) -> String {
+    value
+        .nfkd()
+        .filter(|ch| !is_combining_mark(*ch))
+        .flat_map(char::to_lowercase)
+        .map(|ch| if ch == 'ß' { String::from("ss") } else { ch.to_string() })
+        .collect()
+}
+
+/// Compare two strings by their collation key, falling back to the raw strings on ties
+pub fn compare(a: &str, b: &str) -> Ordering {
+    collation_key(a)
+        .cmp(&collation_key(b))
+        .then_with(|| a.cmp(b))
+}
+
+// Unit tests
+
+#[test]
+fn test_collation_key_ignores_case_and_diacritics() {
+    assert_eq!(collation_key("Élément"), "element");
+}
+
+#[test]
+fn test_collation_key_expands_sharp_s() {
+    assert_eq!(collation_key("Größe"), "grosse");
+}
+
+#[test]
+fn test_compare_sorts_accented_next_to_plain() {
+    let mut names = vec!["zeta", "Écrire", "alpha", "ecrire"];
+
+    names.sort_by(|a, b| compare(a, b));
+
+    assert_eq!(names, vec!["alpha", "ecrire", "Écrire", "zeta"]);
+}
@@ -0,0 +1,386 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io,
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use crate::{cli::SubcommandHelp, parsing::{split_shell_words, CLILib, KeyStyle, PathCheck}, paths, storage::StorageBackend, ui::{Animation, Theme}};
+
+/// User configuration read from `config.toml`, every section is optional
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Keys per action replacing its default keys, e.g. `quit = ["Ctrl+X"]`
+    pub keymap: BTreeMap<String, Vec<String>>,
+    /// Parsers per tool, keyed by the file name of the program or script, e.g. `[parsers.mytool]`
+    pub parsers: BTreeMap<String, ParserRule>,
+    /// Form of the keys passed to the command, `long` or `short`, e.g. `keys = "short"`
+    pub keys: Option<String>,
+    /// How parameters are listed, `inline` or `columns`, e.g. `list_style = "columns"`
+    pub list_style: Option<String>,
+    /// Refresh of the screen, `full`, `reduced` or `auto` to reduce it over SSH and on dumb terminals, e.g. `animation = "reduced"`
+    pub animation: Option<String>,
+    /// Whether the screen is drawn without colors or reverse video, e.g. `monochrome = true`
+    pub monochrome: bool,
+    /// Built-in colors, `dark`, `light` or `high-contrast`, e.g. `theme = "light"`
+    pub theme: Option<String>,
+    /// Colors per role replacing those of the theme, e.g. `[colors]` with `selected = "bold black on yellow"`
+    pub colors: BTreeMap<String, String>,
+    /// Templates of options computed from other fields per tool, e.g. `[derived.mytool]` with `"--output" = "{name}-{date}.log"`
+    pub derived: BTreeMap<String, BTreeMap<String, String>>,
+    /// Keys whose values are passed as `--key=value` per tool, e.g. `[joined]` with `mytool = ["--name"]`
+    pub joined: BTreeMap<String, Vec<String>>,
+    /// Help and version flags share codes and aliases set per tool, only set by hand otherwise, e.g. `[meta_flags]` with `mytool = ["-V"]`
+    pub meta_flags: BTreeMap<String, Vec<String>>,
+    /// Checks of path values per tool, `exist`, `absent` or `any`, e.g. `[path_checks.mytool]` with `"--input" = "exist"`
+    pub path_checks: BTreeMap<String, BTreeMap<String, String>>,
+    /// Command lines opened by name, e.g. `deploy = "kubectl apply -f manifests/ --context prod"` for `clitui deploy`
+    pub aliases: BTreeMap<String, String>,
+    /// Tools that prompt for input and always run in the terminal instead of the output pane, e.g. `interactive = ["ssh"]`
+    pub interactive: Vec<String>,
+    /// Backend of the history and the documents, `files` or `sqlite`, e.g. `storage = "sqlite"`
+    pub storage: Option<String>,
+}
+
+/// Overrides the detection of the help format of a tool
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ParserRule {
+    /// The only parser that is tried, e.g. `force = "gnu"`
+    pub force: Option<String>,
+    /// Parsers that are never tried, e.g. `forbid = ["docopt"]`
+    pub forbid: Vec<String>,
+    /// How the help of subcommands is requested, `flag`, `help` or `auto`, e.g. `subcommand_help = "help"`
+    pub subcommand_help: Option<String>,
+    /// Whether the help is also read by running the tool without arguments, e.g. `bare_help = true`
+    pub bare_help: bool,
+}
+
+/// How parameters are shown in their section, set by `list_style`
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum ListStyle {
+    /// `NAME: value`
+    #[default]
+    Inline,
+    /// Names aligned on the left and values on the right of the section
+    Columns,
+}
+
+impl ListStyle {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<ListStyle> {
+        match name {
+            "inline" => return Some(ListStyle::Inline),
+            "columns" => return Some(ListStyle::Columns),
+            _ => return None,
+        }
+    }
+}
+
+/// Entry of the first word of the wrapped command that has one, by the file name of the program or script
+fn entry_for<'a, T>(entries: &'a BTreeMap<String, T>, command: &[String]) -> Option<&'a T> {
+    command
+        .iter()
+        .filter_map(|word| Path::new(word).file_name()?.to_str())
+        .find_map(|name| entries.get(name))
+}
+
+impl Config {
+    /// Rule of the first word of the wrapped command that has one
+    fn rule_for(&self, command: &[String]) -> Option<&ParserRule> {
+        entry_for(&self.parsers, command)
+    }
+
+    /// Parsers to try for the wrapped command, restricted by its rule
+    pub fn parsers_for(&self, command: &[String]) -> Result<Vec<CLILib>, String> {
+        let Some(rule) = self.rule_for(command) else {
+            return Ok(CLILib::ALL.to_vec());
+        };
+        let parse_name = |name: &String| CLILib::from_name(name).ok_or(format!("Unknown parser in config: {name}"));
+        if let Some(force) = &rule.force {
+            return Ok(vec![parse_name(force)?]);
+        }
+        let forbidden = rule.forbid.iter().map(parse_name).collect::<Result<Vec<CLILib>, String>>()?;
+        Ok(CLILib::ALL.into_iter().filter(|cli_lib| !forbidden.contains(cli_lib)).collect())
+    }
+
+    /// Whether the wrapped command may be run without arguments to read its help, never unless its rule allows it
+    pub fn bare_help_for(&self, command: &[String]) -> bool {
+        self.rule_for(command).is_some_and(|rule| rule.bare_help)
+    }
+
+    /// Keys and templates of the derived options of the wrapped command, matched like its parser rule
+    pub fn derived_for(&self, command: &[String]) -> Vec<(String, String)> {
+        entry_for(&self.derived, command)
+            .map(|templates| templates.iter().map(|(key, template)| (key.clone(), template.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    /// Keys of the wrapped command whose values are passed as `--key=value`, matched like its parser rule
+    pub fn joined_for(&self, command: &[String]) -> &[String] {
+        entry_for(&self.joined, command).map_or(&[], Vec::as_slice)
+    }
+
+    /// Keys of the help and version flags of the wrapped command that are passed once set, matched like its parser rule
+    pub fn meta_flags_for(&self, command: &[String]) -> &[String] {
+        entry_for(&self.meta_flags, command).map_or(&[], Vec::as_slice)
+    }
+
+    /// Keys or positional names and the checks of their path values for the wrapped command, matched like its parser rule
+    pub fn path_checks_for(&self, command: &[String]) -> Result<Vec<(String, PathCheck)>, String> {
+        entry_for(&self.path_checks, command)
+            .into_iter()
+            .flatten()
+            .map(|(key, name)| Ok((key.clone(), PathCheck::from_name(name).ok_or(format!("Unknown path check in config: {name}"))?)))
+            .collect()
+    }
+
+    /// Words of the command line of an alias, the program first
+    pub fn alias(&self, name: &str) -> Option<Vec<String>> {
+        self.aliases.get(name).map(|command_line| split_shell_words(command_line)).filter(|words| !words.is_empty())
+    }
+
+    /// Whether the wrapped command is marked as interactive, matched like its parser rule
+    pub fn is_interactive(&self, command: &[String]) -> bool {
+        command
+            .iter()
+            .filter_map(|word| Path::new(word).file_name()?.to_str())
+            .any(|name| self.interactive.iter().any(|tool| tool == name))
+    }
+
+    /// Form of the keys passed to the command, long keys by default
+    #[allow(clippy::needless_return)]
+    pub fn key_style(&self) -> Result<KeyStyle, String> {
+        match &self.keys {
+            Some(name) => return KeyStyle::from_name(name).ok_or(format!("Unknown key style in config: {name}")),
+            None => return Ok(KeyStyle::Long),
+        }
+    }
+
+    /// How parameters are listed in the form, `NAME: value` by default
+    #[allow(clippy::needless_return)]
+    pub fn list_style(&self) -> Result<ListStyle, String> {
+        match &self.list_style {
+            Some(name) => return ListStyle::from_name(name).ok_or(format!("Unknown list style in config: {name}")),
+            None => return Ok(ListStyle::Inline),
+        }
+    }
+
+    /// Where the history and the documents are kept, files by default
+    #[allow(clippy::needless_return)]
+    pub fn storage_backend(&self) -> Result<StorageBackend, String> {
+        match &self.storage {
+            Some(name) => return StorageBackend::from_name(name).ok_or(format!("Unknown storage in config: {name}")),
+            None => return Ok(StorageBackend::Files),
+        }
+    }
+
+    /// How often the screen is refreshed, reduced on slow links by default
+    #[allow(clippy::needless_return)]
+    pub fn animation(&self) -> Result<Animation, String> {
+        match &self.animation {
+            Some(name) => return Animation::from_name(name).ok_or(format!("Unknown animation in config: {name}")),
+            None => return Ok(Animation::Auto),
+        }
+    }
+
+    /// Colors of the screen, the dark theme by default, with the colors of the config applied
+    pub fn theme(&self) -> Result<Theme, String> {
+        let mut theme = match &self.theme {
+            Some(name) => Theme::from_name(name).ok_or(format!("Unknown theme in config: {name}"))?,
+            None => Theme::default(),
+        };
+        for (role, color) in &self.colors {
+            theme.set_color(role, color)?;
+        }
+        Ok(theme)
+    }
+
+    /// How the help of subcommands of the wrapped command is requested, detected automatically by default
+    #[allow(clippy::needless_return)]
+    pub fn subcommand_help_for(&self, command: &[String]) -> Result<SubcommandHelp, String> {
+        match self.rule_for(command).and_then(|rule| rule.subcommand_help.as_ref()) {
+            Some(name) => return SubcommandHelp::from_name(name).ok_or(format!("Unknown subcommand help in config: {name}")),
+            None => return Ok(SubcommandHelp::Auto),
+        }
+    }
+}
+
+pub fn parse_config(content: &str) -> Result<Config, String> {
+    toml::from_str(content).map_err(|error| format!("Invalid config: {error}"))
+}
+
+/// Read the given config file or the default one
+/// A missing default config file results in the default config
+#[allow(clippy::needless_return)]
+pub fn load_config(config_file: Option<&Path>) -> Result<Config, String> {
+    let path = match config_file {
+        Some(config_file) => config_file.to_path_buf(),
+        None => match paths::config_file() {
+            Some(path) => path,
+            None => return Ok(Config::default()),
+        },
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => return parse_config(&content),
+        Err(error) if error.kind() == io::ErrorKind::NotFound && config_file.is_none() => return Ok(Config::default()),
+        Err(error) => return Err(format!("Cannot read config {}: {error}", path.display())),
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_parse_config_keymap() {
+    let config = parse_config("[keymap]\nquit = [\"Ctrl+X\"]\nhelp = [\"F2\", \"h\"]").unwrap();
+
+    assert_eq!(config.keymap["quit"], vec![String::from("Ctrl+X")]);
+    assert_eq!(config.keymap["help"], vec![String::from("F2"), String::from("h")]);
+}
+
+#[test]
+fn test_parse_empty_config() {
+    assert_eq!(parse_config(""), Ok(Config::default()));
+}
+
+#[test]
+fn test_parse_invalid_config() {
+    assert!(parse_config("[keymap]\nquit = 1").unwrap_err().starts_with("Invalid config:"));
+}
+
+#[test]
+fn test_parsers_for_forced_tool() {
+    let config = parse_config("[parsers.mytool]\nforce = \"gnu\"").unwrap();
+
+    assert_eq!(config.parsers_for(&[String::from("/usr/bin/mytool")]), Ok(vec![CLILib::Gnu]));
+    assert_eq!(config.parsers_for(&[String::from("othertool")]), Ok(CLILib::ALL.to_vec()));
+}
+
+#[test]
+fn test_parsers_for_forbidden_parsers() {
+    let config = parse_config("[parsers.\"greeter.py\"]\nforbid = [\"clap\", \"docopt\"]").unwrap();
+
+    assert_eq!(
+        config.parsers_for(&[String::from("python"), String::from("greeter.py")]),
+        Ok(vec![CLILib::Argparse, CLILib::Cobra, CLILib::Gnu, CLILib::WindowsSlash]),
+    );
+}
+
+#[test]
+fn test_derived_for() {
+    let config = parse_config("[derived.mytool]\n\"--output\" = \"{name}-{date}.log\"").unwrap();
+
+    assert_eq!(config.derived_for(&[String::from("./mytool")]), vec![(String::from("--output"), String::from("{name}-{date}.log"))]);
+    assert_eq!(config.derived_for(&[String::from("othertool")]), Vec::new());
+}
+
+#[test]
+fn test_joined_for() {
+    let config = parse_config("[joined]\n\"greeter.py\" = [\"--name\"]").unwrap();
+
+    assert_eq!(config.joined_for(&[String::from("python"), String::from("greeter.py")]), [String::from("--name")]);
+    assert!(config.joined_for(&[String::from("othertool")]).is_empty());
+}
+
+#[test]
+fn test_meta_flags_for() {
+    let config = parse_config("[meta_flags]\nmytool = [\"-V\"]").unwrap();
+
+    assert_eq!(config.meta_flags_for(&[String::from("/usr/bin/mytool")]), [String::from("-V")]);
+    assert!(config.meta_flags_for(&[String::from("df")]).is_empty());
+}
+
+#[test]
+fn test_path_checks_for() {
+    let config = parse_config("[path_checks.mytool]\n\"--input\" = \"exist\"\nOUTPUT = \"absent\"").unwrap();
+
+    assert_eq!(
+        config.path_checks_for(&[String::from("mytool")]),
+        Ok(vec![(String::from("--input"), PathCheck::MustExist), (String::from("OUTPUT"), PathCheck::MustNotExist)]),
+    );
+    assert_eq!(config.path_checks_for(&[String::from("othertool")]), Ok(Vec::new()));
+    let config = parse_config("[path_checks.mytool]\n\"--input\" = \"there\"").unwrap();
+    assert_eq!(config.path_checks_for(&[String::from("mytool")]), Err(String::from("Unknown path check in config: there")));
+}
+
+#[test]
+fn test_is_interactive() {
+    let config = parse_config("interactive = [\"ssh\"]").unwrap();
+
+    assert!(config.is_interactive(&[String::from("/usr/bin/ssh"), String::from("host")]));
+    assert!(!config.is_interactive(&[String::from("scp")]));
+}
+
+#[test]
+fn test_parsers_for_unknown_parser() {
+    let config = parse_config("[parsers.mytool]\nforce = \"pascal\"").unwrap();
+
+    assert_eq!(config.parsers_for(&[String::from("mytool")]), Err(String::from("Unknown parser in config: pascal")));
+}
+
+#[test]
+fn test_subcommand_help_for() {
+    let config = parse_config("[parsers.gotool]\nsubcommand_help = \"help\"\n\n[parsers.other]\nsubcommand_help = \"manual\"").unwrap();
+
+    assert_eq!(config.subcommand_help_for(&[String::from("gotool")]), Ok(SubcommandHelp::HelpSubcommand));
+    assert_eq!(config.subcommand_help_for(&[String::from("mytool")]), Ok(SubcommandHelp::Auto));
+    assert_eq!(config.subcommand_help_for(&[String::from("other")]), Err(String::from("Unknown subcommand help in config: manual")));
+}
+
+#[test]
+fn test_key_style() {
+    assert_eq!(parse_config("keys = \"short\"").unwrap().key_style(), Ok(KeyStyle::Short));
+    assert_eq!(parse_config("").unwrap().key_style(), Ok(KeyStyle::Long));
+    assert_eq!(parse_config("keys = \"tiny\"").unwrap().key_style(), Err(String::from("Unknown key style in config: tiny")));
+}
+
+#[test]
+fn test_list_style() {
+    assert_eq!(parse_config("list_style = \"columns\"").unwrap().list_style(), Ok(ListStyle::Columns));
+    assert_eq!(parse_config("").unwrap().list_style(), Ok(ListStyle::Inline));
+    assert_eq!(parse_config("list_style = \"grid\"").unwrap().list_style(), Err(String::from("Unknown list style in config: grid")));
+}
+
+#[test]
+fn test_theme() {
+    use ratatui::style::{Style, Stylize};
+
+    let config = parse_config("theme = \"high-contrast\"\n[colors]\nkey = \"magenta\"\n").unwrap();
+
+    assert_eq!(config.theme().unwrap().key, Style::new().magenta());
+    assert_eq!(config.theme().unwrap().error, Theme::from_name("high-contrast").unwrap().error);
+    assert_eq!(parse_config("").unwrap().theme(), Ok(Theme::default()));
+    assert_eq!(parse_config("theme = \"neon\"").unwrap().theme(), Err(String::from("Unknown theme in config: neon")));
+}
+
+#[test]
+fn test_animation() {
+    assert_eq!(parse_config("animation = \"reduced\"").unwrap().animation(), Ok(Animation::Reduced));
+    assert_eq!(parse_config("").unwrap().animation(), Ok(Animation::Auto));
+    assert_eq!(parse_config("animation = \"none\"").unwrap().animation(), Err(String::from("Unknown animation in config: none")));
+}
+
+#[test]
+fn test_monochrome() {
+    assert!(parse_config("monochrome = true").unwrap().monochrome);
+    assert!(!parse_config("").unwrap().monochrome);
+}
+
+#[test]
+fn test_alias() {
+    let config = parse_config("[aliases]\ndeploy = \"kubectl apply -f manifests/ --context 'prod eu'\"\nempty = \"\"").unwrap();
+
+    assert_eq!(config.alias("deploy").unwrap(), ["kubectl", "apply", "-f", "manifests/", "--context", "prod eu"]);
+    assert_eq!(config.alias("empty"), None);
+    assert_eq!(config.alias("kubectl"), None);
+}
+
+#[test]
+fn test_load_missing_config_file() {
+    let path = std::env::temp_dir().join("clitui-missing-config.toml");
+
+    assert!(load_config(Some(&path)).unwrap_err().starts_with("Cannot read config"));
+}
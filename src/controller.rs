@@ -1,45 +1,175 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use messages::{
+    CursorMove,
     Message,
     Direction,
 };
 
 use crate::{
-    model::{Model, Section},
+    editor::{byte_index, ValueEditor},
+    expression,
+    filepicker::{self, FilePicker},
+    finder::Finder,
+    keymap::Action,
+    model::{AppCommand, Dropdown, FieldId, Model, Popup, Section, TextCursor},
+    pager::Pager,
+    share::SharedForm,
     parsing::{
-        CLIArgument, CLIFlag, CLILib, CLIParameters
+        convert_to_cli, CLIArgument, CLIFlag, CLILib, CLIParameters, CLIPositional, CLISubcommand, ValueType
     }
 };
 
 pub mod messages;
 
 pub fn update(model: &mut Model, message: Message) {
+    model.notice = None;
     match message {
         Message::Move(direction) => move_selected_index(model, direction),
-        Message::TextEdit(ch) => edit_text(model, ch),
-        Message::RemoveText => remove_text(model),
-        Message::Toggle => toggle_flag(model),
-        Message::Run => run(model),
+        Message::TextEdit(field, ch) if in_value_editor(model, &field) => edit_in_value_editor(model, |editor| editor.insert(ch)),
+        Message::RemoveText(field) if in_value_editor(model, &field) => edit_in_value_editor(model, ValueEditor::remove_before),
+        Message::RemoveNextText(field) if in_value_editor(model, &field) => edit_in_value_editor(model, ValueEditor::remove_after),
+        Message::MoveCursor(field, movement) if in_value_editor(model, &field) => edit_in_value_editor(model, |editor| move_in_value_editor(editor, movement)),
+        Message::RemoveText(field) if model.choices(&field).is_some() => unset_choice(model, &field),
+        Message::TextEdit(field, ch) => edit_text(model, &field, ch),
+        Message::RemoveText(field) => remove_text(model, &field),
+        Message::RemoveNextText(field) => remove_next_text(model, &field),
+        Message::MoveCursor(field, movement) => move_cursor(model, &field, movement),
+        Message::AddValue(field) => add_value(model, &field),
+        Message::ToggleGlob(field) => toggle_glob(model, &field),
+        Message::Expand(field) => open_value_editor(model, &field),
+        Message::PickFile(field) => open_file_picker(model, &field),
+        Message::OpenDropdown(field) => open_dropdown(model, &field),
+        Message::Toggle(field) => toggle_field(model, &field),
+        Message::SearchText(ch) => edit_search(model, ch),
+        Message::RemoveSearchText => remove_search_text(model),
+        Message::Select if model.dropdown.is_some() => confirm_dropdown(model),
+        Message::Select if model.file_picker.is_some() => confirm_file_picker(model),
+        Message::Select if model.value_editor.is_some() => confirm_value_editor(model),
+        Message::Select if model.pager.is_some() => confirm_search(model),
+        Message::Select if model.output.is_some() => confirm_output_filter(model),
+        Message::Select if model.finder.is_some() => confirm_finder(model),
+        Message::Select if model.filter.is_some() => confirm_filter(model),
+        Message::Select => select_subcommand(model),
+        Message::Follow => follow_output(model),
+        Message::Pager => open_pager(model),
+        Message::ExternalPager => model.commands.push(AppCommand::ExternalPager),
+        Message::Search => start_search(model),
+        Message::Jump => open_finder(model),
+        Message::Click(field) => click_field(model, &field),
+        Message::ClickSubcommand(index) => click_subcommand(model, index),
+        Message::NextMatch => jump_to_next_match(model),
+        Message::Top if model.pager.is_some() => jump_pager(model, Direction::Up),
+        Message::Bottom if model.pager.is_some() => jump_pager(model, Direction::Down),
+        Message::Top => jump_in_section(model, Direction::Up),
+        Message::Bottom => jump_in_section(model, Direction::Down),
+        Message::PageUp if model.pager.is_some() => page_pager(model, Direction::Up),
+        Message::PageDown if model.pager.is_some() => page_pager(model, Direction::Down),
+        Message::PageUp => page_in_section(model, Direction::Up),
+        Message::PageDown => page_in_section(model, Direction::Down),
+        Message::Back => back(model),
+        Message::Increment(field) if model.choices(&field).is_some() => cycle_choice(model, &field, true),
+        Message::Decrement(field) if model.choices(&field).is_some() => cycle_choice(model, &field, false),
+        Message::Increment(field) => count_flag(model, &field, true),
+        Message::Decrement(field) => count_flag(model, &field, false),
+        Message::NextField => move_to_field(model, true),
+        Message::PreviousField => move_to_field(model, false),
+        Message::Share => share(model),
+        Message::Screenshot => model.commands.push(AppCommand::Screenshot),
+        Message::TogglePopup(Popup::Argv) => toggle_argv(model),
+        Message::TogglePopup(Popup::Stats) => toggle_stats(model),
+        Message::TogglePopup(Popup::Help) => toggle_help(model),
+        Message::Run if model.run_in_terminal || model.parameters.needs_terminal() => run(model, AppCommand::RunInTerminal),
+        Message::Run => run(model, AppCommand::Run),
+        Message::RunInTerminal => run(model, AppCommand::RunInTerminal),
+        Message::TestRun => run(model, AppCommand::TestRun),
         Message::Quit => quit(model),
     }
+    model.parameters.fill_derived_values(&template_builtin);
+    // editing ends once another parameter is selected
+    if model.text_cursor.as_ref().is_some_and(|cursor| Some(&cursor.field) != model.selected_field().as_ref()) {
+        model.text_cursor = None;
+    }
+}
+
+/// Placeholders of templates that are not fields, `{date}` as `2024-05-01` and `{time}` as `13-45-00`, both in UTC
+#[allow(clippy::needless_return)]
+pub fn template_builtin(name: &str) -> Option<String> {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let (days, secs_of_day) = (secs / 86400, secs % 86400);
+    match name {
+        "date" => {
+            let (year, month, day) = civil_from_days(days as i64);
+            return Some(format!("{year:04}-{month:02}-{day:02}"));
+        },
+        "time" => return Some(format!("{:02}-{:02}-{:02}", secs_of_day / 3600, secs_of_day % 3600 / 60, secs_of_day % 60)),
+        _ => return None,
+    }
+}
+
+/// Year, month and day of a number of days since 1970-01-01 in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Order in which Tab visits the sections, like filling a form from top to bottom
+const FILL_ORDER: [Section; 4] = [Section::Arguments, Section::Positionals, Section::Options, Section::Flags];
+
+/// Select the next or previous field in fill order, independent of the arrow keys
+/// Moving past the last field wraps around to the first one
+fn move_to_field(model: &mut Model, forward: bool) {
+    if model.output.is_some() || model.selecting_subcommand || !evaluate_selected_expressions(model) {
+        return;
+    }
+    let fields: Vec<(Section, usize)> = FILL_ORDER
+        .into_iter()
+        .filter(|section| model.section_is_available(*section))
+        .flat_map(|section| (0..model.get_parameter_len(section)).map(move |index| (section, index)))
+        .collect();
+    let Some(position) = fields.iter().position(|field| *field == (model.current_section, model.current_key_index)) else {
+        return;
+    };
+    let position = if forward {
+        (position + 1) % fields.len()
+    } else {
+        (position + fields.len() - 1) % fields.len()
+    };
+    (model.current_section, model.current_key_index) = fields[position];
 }
 
+#[allow(clippy::needless_return)]
 fn get_next_section(section: Section) -> Section {
     match section {
-        Section::Arguments => return Section::Flags,
+        Section::Arguments => return Section::Positionals,
+        Section::Positionals => return Section::Flags,
         Section::Flags => return Section::Options,
         Section::Options => return Section::Arguments,
     }
 }
 
+#[allow(clippy::needless_return)]
 fn get_previous_section(section: Section) -> Section {
     match section {
         Section::Arguments => return Section::Options,
-        Section::Flags => return Section::Arguments,
+        Section::Positionals => return Section::Arguments,
+        Section::Flags => return Section::Positionals,
         Section::Options => return Section::Flags,
     }
 }
 
 fn set_next_section(model: &mut Model) {
+    if model.get_selected_parameter_len() == 0 {
+        return;
+    }
     let mut possible_next_section = get_next_section(model.current_section);
     loop {
         if model.section_is_available(possible_next_section) {
@@ -53,6 +183,9 @@ fn set_next_section(model: &mut Model) {
 }
 
 fn set_previous_section(model: &mut Model) {
+    if model.get_selected_parameter_len() == 0 {
+        return;
+    }
     let mut possible_previous_section = get_previous_section(model.current_section);
     loop {
         if model.section_is_available(possible_previous_section) {
@@ -65,7 +198,237 @@ fn set_previous_section(model: &mut Model) {
     }
 }
 
+fn move_selected_subcommand_index(model: &mut Model, direction: Direction) {
+    match direction {
+        Direction::Down => model.subcommand_index = (model.subcommand_index + 1) % model.get_subcommand_len(),
+        Direction::Up => model.subcommand_index = (model.subcommand_index + model.get_subcommand_len() - 1) % model.get_subcommand_len(),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Scroll the keybinding help, which has one line per action
+fn scroll_help(model: &mut Model, direction: Direction) {
+    match direction {
+        Direction::Down => model.help_scroll = (model.help_scroll + 1).min(Action::ALL.len() - 1),
+        Direction::Up => model.help_scroll = model.help_scroll.saturating_sub(1),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Scroll the output of the command, scrolling up stops following it
+fn scroll_output(model: &mut Model, direction: Direction) {
+    let Some(output) = &mut model.output else {
+        return;
+    };
+    match direction {
+        Direction::Up => output.scroll_up(),
+        Direction::Down => output.scroll_down(),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+fn follow_output(model: &mut Model) {
+    if let Some(output) = &mut model.output {
+        output.follow();
+    }
+}
+
+/// Open the pager on the output of a finished command
+fn open_pager(model: &mut Model) {
+    if model.output.as_ref().is_some_and(|output| !output.is_running()) {
+        model.pager = Some(Pager::default());
+    }
+}
+
+fn scroll_pager(model: &mut Model, direction: Direction) {
+    let (Some(pager), Some(output)) = (&mut model.pager, &model.output) else {
+        return;
+    };
+    match direction {
+        Direction::Up => pager.scroll_up(),
+        Direction::Down => pager.scroll_down(output.lines.len()),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Jump to the first line for up and the last page for down
+fn jump_pager(model: &mut Model, direction: Direction) {
+    let (Some(pager), Some(output)) = (&mut model.pager, &model.output) else {
+        return;
+    };
+    match direction {
+        Direction::Up => pager.jump_to_top(),
+        Direction::Down => pager.jump_to_bottom(output.lines.len()),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Scroll the pager up or down by a page
+fn page_pager(model: &mut Model, direction: Direction) {
+    let (Some(pager), Some(output)) = (&mut model.pager, &model.output) else {
+        return;
+    };
+    match direction {
+        Direction::Up => pager.page_up(),
+        Direction::Down => pager.page_down(output.lines.len()),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Select the first or last parameter of the section, unlike moving this does not wrap around
+fn jump_in_section(model: &mut Model, direction: Direction) {
+    let len = model.get_selected_parameter_len();
+    if len == 0 || !evaluate_selected_expressions(model) {
+        return;
+    }
+    match direction {
+        Direction::Up => model.current_key_index = 0,
+        Direction::Down => model.current_key_index = len - 1,
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Move the selection by the number of parameters the section showed when it was last rendered
+fn page_in_section(model: &mut Model, direction: Direction) {
+    let len = model.get_selected_parameter_len();
+    if len == 0 || !evaluate_selected_expressions(model) {
+        return;
+    }
+    let page_height = model.section_page_height.get().max(1);
+    match direction {
+        Direction::Up => model.current_key_index = model.current_key_index.saturating_sub(page_height),
+        Direction::Down => model.current_key_index = (model.current_key_index + page_height).min(len - 1),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Start typing a new search query in the pager, or filter the output or the form once the selected value is valid
+fn start_search(model: &mut Model) {
+    if let Some(pager) = &mut model.pager {
+        pager.query.clear();
+        pager.searching = true;
+    } else if let Some(output) = &mut model.output {
+        output.filtering = true;
+    } else if model.output.is_none() && !model.selecting_subcommand && evaluate_selected_expressions(model) {
+        model.filter = Some(String::new());
+    }
+}
+
+/// Open the finder over the form once the selected value is valid, it replaces the filter
+fn open_finder(model: &mut Model) {
+    if model.output.is_none() && !model.selecting_subcommand && evaluate_selected_expressions(model) {
+        model.filter = None;
+        model.finder = Some(Finder::default());
+    }
+}
+
+/// Select the highlighted match of the finder and close it
+fn confirm_finder(model: &mut Model) {
+    let Some(finder) = model.finder.take() else {
+        return;
+    };
+    if let Some(found) = finder.matches(model).get(finder.selected) {
+        (model.current_section, model.current_key_index) = (found.section, found.index);
+    }
+}
+
+/// Highlight the previous or next match of the finder, wrapping around like the lists of the form
+fn move_in_finder(model: &mut Model, direction: Direction) {
+    let Some(finder) = &model.finder else {
+        return;
+    };
+    let len = finder.matches(model).len();
+    let Some(finder) = model.finder.as_mut().filter(|_| len > 0) else {
+        return;
+    };
+    match direction {
+        Direction::Down => finder.selected = (finder.selected + 1) % len,
+        Direction::Up => finder.selected = (finder.selected + len - 1) % len,
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Stop filtering and select the first match, the selection stays if nothing matches
+fn confirm_filter(model: &mut Model) {
+    if let Some((section, index)) = model.first_filter_match() {
+        (model.current_section, model.current_key_index) = (section, index);
+    }
+    model.filter = None;
+}
+
+/// Stop typing the query and show the first match, starting at the visible page
+fn confirm_search(model: &mut Model) {
+    let (Some(pager), Some(output)) = (&mut model.pager, &model.output) else {
+        return;
+    };
+    pager.searching = false;
+    pager.jump_to_next_match(&output.lines, true);
+}
+
+/// Stop typing the filter of the output, the lines stay filtered until it is cleared with Esc
+fn confirm_output_filter(model: &mut Model) {
+    if let Some(output) = &mut model.output {
+        output.filtering = false;
+    }
+}
+
+fn jump_to_next_match(model: &mut Model) {
+    if let (Some(pager), Some(output)) = (&mut model.pager, &model.output) {
+        pager.jump_to_next_match(&output.lines, false);
+    }
+}
+
+/// Scroll the argument view, which has one line per argument including the program
+fn scroll_argv(model: &mut Model, direction: Direction) {
+    let argv_len = convert_to_cli(&model.parameters).get_args().len() + 1;
+    match direction {
+        Direction::Down => model.argv_scroll = (model.argv_scroll + 1).min(argv_len - 1),
+        Direction::Up => model.argv_scroll = model.argv_scroll.saturating_sub(1),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Scroll the statistics view, which has one block of lines per tool
+fn scroll_stats(model: &mut Model, direction: Direction) {
+    let tool_count = model.stats.as_ref().map_or(0, Vec::len);
+    match direction {
+        Direction::Down => model.stats_scroll = (model.stats_scroll + 1).min(tool_count.saturating_sub(1)),
+        Direction::Up => model.stats_scroll = model.stats_scroll.saturating_sub(1),
+        Direction::Left | Direction::Right => (),
+    }
+}
+
 fn move_selected_index(model: &mut Model, direction: Direction) {
+    if model.dropdown.is_some() {
+        return move_in_dropdown(model, direction);
+    }
+    if model.file_picker.is_some() {
+        return move_in_file_picker(model, direction);
+    }
+    if model.finder.is_some() {
+        return move_in_finder(model, direction);
+    }
+    if model.showing_help {
+        return scroll_help(model, direction);
+    }
+    if model.showing_argv {
+        return scroll_argv(model, direction);
+    }
+    if model.stats.is_some() {
+        return scroll_stats(model, direction);
+    }
+    if model.pager.is_some() {
+        return scroll_pager(model, direction);
+    }
+    if model.output.is_some() {
+        return scroll_output(model, direction);
+    }
+    if model.selecting_subcommand {
+        return move_selected_subcommand_index(model, direction);
+    }
+    if model.get_selected_parameter_len() == 0 || !evaluate_selected_expressions(model) {
+        return;
+    }
     match direction {
         Direction::Down => {
             if model.current_key_index >= model.get_selected_parameter_len() - 1 {
@@ -75,7 +438,7 @@ fn move_selected_index(model: &mut Model, direction: Direction) {
             }
         },
         Direction::Up => {
-            if model.current_key_index <= 0 {
+            if model.current_key_index == 0 {
                 model.current_key_index = model.get_selected_parameter_len() - 1;
             } else {
                 model.current_key_index -= 1;
@@ -86,35 +449,557 @@ fn move_selected_index(model: &mut Model, direction: Direction) {
     }
 }
 
-fn edit_text(model: &mut Model, ch: char) {
-    match model.current_section {
-        Section::Arguments => model.parameters.arguments[model.current_key_index].value.push(ch),
-        Section::Options => model.parameters.options[model.current_key_index].value.push(ch),
-        Section::Flags => (),
+/// Replace expressions like `4*1024` in the values of a numeric argument with their result
+/// Returns false and shows the error if an expression is invalid
+fn evaluate_selected_expressions(model: &mut Model) -> bool {
+    let Some(argument) = get_argument(model, model.current_section, model.current_key_index).filter(|argument| argument.value_type.is_numeric()) else {
+        return true;
+    };
+    let result = argument.values
+        .iter_mut()
+        .filter(|value| expression::is_expression(value))
+        .try_for_each(|value| {
+            *value = expression::evaluate(value)?;
+            Ok(())
+        })
+        .map_err(|error: String| format!("Invalid expression for {}: {error}", argument.name));
+    if let Err(error) = result {
+        model.error = Some(error);
+        return false;
+    }
+    // number fields take any text while typing, e.g. `4K` or `1e6`, and are checked once they are left
+    if argument.value_type == ValueType::Number && !argument.filled_values().all(|value| expression::is_number_value(value)) {
+        model.error = Some(format!("{} takes a number", argument.name));
+        return false;
+    }
+    true
+}
+
+/// The argument or option at the index of the section, positionals and flags have no value type
+#[allow(clippy::needless_return)]
+fn get_argument(model: &mut Model, section: Section, index: usize) -> Option<&mut CLIArgument> {
+    match section {
+        Section::Arguments => return model.parameters.arguments.get_mut(index),
+        Section::Options => return model.parameters.options.get_mut(index),
+        Section::Positionals | Section::Flags => return None,
+    }
+}
+
+/// Values of the parameter and whether it takes more than one value, derived arguments cannot be edited
+#[allow(clippy::needless_return)]
+fn get_values(model: &mut Model, section: Section, index: usize) -> Option<(&mut Vec<String>, bool)> {
+    match section {
+        Section::Arguments => return model.parameters.arguments.get_mut(index).filter(|argument| argument.template.is_none()).map(|argument| (&mut argument.values, argument.multiple)),
+        Section::Positionals => return model.parameters.positionals.get_mut(index).map(|positional| (&mut positional.values, positional.multiple)),
+        Section::Options => return model.parameters.options.get_mut(index).filter(|option| option.template.is_none()).map(|option| (&mut option.values, option.multiple)),
+        Section::Flags => return None,
+    }
+}
+
+fn edit_search(model: &mut Model, ch: char) {
+    if model.dropdown.is_some() {
+        jump_in_dropdown(model, ch);
+    } else if let Some(finder) = &mut model.finder {
+        finder.query.push(ch);
+        finder.selected = 0;
+    } else if let Some(pager) = model.pager.as_mut().filter(|pager| pager.searching) {
+        pager.query.push(ch);
+    } else if let Some(output) = model.output.as_mut().filter(|output| output.filtering) {
+        let filter = format!("{}{ch}", output.filter);
+        output.set_filter(filter);
+    } else if let Some(filter) = &mut model.filter {
+        filter.push(ch);
+    }
+}
+
+fn remove_search_text(model: &mut Model) {
+    if let Some(finder) = &mut model.finder {
+        finder.query.pop();
+        finder.selected = 0;
+    } else if let Some(pager) = model.pager.as_mut().filter(|pager| pager.searching) {
+        pager.query.pop();
+    } else if let Some(output) = model.output.as_mut().filter(|output| output.filtering) {
+        let mut filter = output.filter.clone();
+        filter.pop();
+        output.set_filter(filter);
+    } else if let Some(filter) = &mut model.filter {
+        filter.pop();
+    }
+}
+
+/// Typing inserts at the text cursor of the last value, numbers only accept digits and arithmetic
+fn edit_text(model: &mut Model, field: &FieldId, ch: char) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    model.error = None;
+    model.share_code = None;
+    if let Some(argument) = get_argument(model, field.section, index) {
+        if let Some(template) = &argument.template {
+            model.error = Some(format!("{} is computed from {template}", argument.name));
+            return;
+        }
+    }
+    let position = model.cursor_position(field);
+    if let Some((values, _)) = get_values(model, field.section, index) {
+        match values.last_mut() {
+            Some(value) => value.insert(byte_index(value, position), ch),
+            None => values.push(ch.to_string()),
+        }
+        model.text_cursor = Some(TextCursor { field: field.clone(), position: position + 1 });
+    }
+    if let Some(key) = get_argument(model, field.section, index).map(|argument| argument.key.clone()) {
+        model.unset_conflicting_flags(&key);
+    }
+}
+
+/// Backspace removes the character before the text cursor, in an empty value it removes the value itself
+fn remove_text(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    model.error = None;
+    model.share_code = None;
+    let position = model.cursor_position(field);
+    if let Some((values, _)) = get_values(model, field.section, index) {
+        let position = if values.last().is_some_and(String::is_empty) {
+            values.pop();
+            values.last().map_or(0, |value| value.chars().count())
+        } else if let Some(value) = values.last_mut().filter(|_| position > 0) {
+            value.remove(byte_index(value, position - 1));
+            position - 1
+        } else {
+            position
+        };
+        model.text_cursor = Some(TextCursor { field: field.clone(), position });
+    }
+}
+
+/// Delete removes the character after the text cursor
+fn remove_next_text(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    model.error = None;
+    model.share_code = None;
+    let position = model.cursor_position(field);
+    if let Some((values, _)) = get_values(model, field.section, index) {
+        if let Some(value) = values.last_mut().filter(|value| position < value.chars().count()) {
+            value.remove(byte_index(value, position));
+        }
+        model.text_cursor = Some(TextCursor { field: field.clone(), position });
+    }
+}
+
+/// Move the text cursor within the last value of the field
+fn move_cursor(model: &mut Model, field: &FieldId, movement: CursorMove) {
+    let length = model.last_value(field).map_or(0, |value| value.chars().count());
+    let position = model.cursor_position(field);
+    let position = match movement {
+        CursorMove::Left => position.saturating_sub(1),
+        CursorMove::Right => (position + 1).min(length),
+        CursorMove::Up | CursorMove::Down => position,
+        CursorMove::Home => 0,
+        CursorMove::End => length,
+    };
+    model.text_cursor = Some(TextCursor { field: field.clone(), position });
+}
+
+/// Open the value editor with the last value of the field, derived arguments cannot be edited
+fn open_value_editor(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    if let Some(argument) = get_argument(model, field.section, index) {
+        if let Some(template) = &argument.template {
+            model.error = Some(format!("{} is computed from {template}", argument.name));
+            return;
+        }
+    }
+    let value = model.last_value(field).cloned().unwrap_or_default();
+    model.text_cursor = None;
+    model.value_editor = Some(ValueEditor::new(field.clone(), &value));
+}
+
+fn in_value_editor(model: &Model, field: &FieldId) -> bool {
+    model.value_editor.as_ref().is_some_and(|editor| editor.field == *field)
+}
+
+fn edit_in_value_editor(model: &mut Model, edit: impl FnOnce(&mut ValueEditor)) {
+    if let Some(editor) = &mut model.value_editor {
+        edit(editor);
+    }
+}
+
+fn move_in_value_editor(editor: &mut ValueEditor, movement: CursorMove) {
+    match movement {
+        CursorMove::Left => editor.move_left(),
+        CursorMove::Right => editor.move_right(),
+        CursorMove::Up => editor.move_up(),
+        CursorMove::Down => editor.move_down(),
+        CursorMove::Home => editor.move_home(),
+        CursorMove::End => editor.move_end(),
+    }
+}
+
+/// Replace the last value of the field, or add it as the first value
+fn set_last_value(model: &mut Model, field: &FieldId, value: String) -> Result<(), String> {
+    let index = model.field_index(field).ok_or(format!("Unknown parameter: {}", field.key))?;
+    let mut values = get_values(model, field.section, index).map(|(values, _)| values.clone()).unwrap_or_default();
+    match values.last_mut() {
+        Some(last) => *last = value,
+        None if value.is_empty() => (),
+        None => values.push(value),
+    }
+    model.set_values(&field.key, values)
+}
+
+/// Replace the last value of the field by the text of the value editor and close it
+/// The editor stays open with the error if the parameter does not take the value, e.g. a number
+fn confirm_value_editor(model: &mut Model) {
+    let Some(editor) = &model.value_editor else {
+        return;
+    };
+    let (field, text) = (editor.field.clone(), editor.text());
+    match set_last_value(model, &field, text) {
+        Ok(()) => {
+            model.error = None;
+            model.value_editor = None;
+        },
+        Err(error) => model.error = Some(error),
+    }
+}
+
+/// Open the file picker in the directory of the last value of the field
+fn open_file_picker(model: &mut Model, field: &FieldId) {
+    let value = model.last_value(field).cloned().unwrap_or_default();
+    let opened = std::env::current_dir()
+        .map_err(|error| format!("Cannot read the working directory: {error}"))
+        .and_then(|working_directory| FilePicker::open(field.clone(), &value, &working_directory));
+    match opened {
+        Ok(picker) => {
+            model.error = None;
+            model.text_cursor = None;
+            model.file_picker = Some(picker);
+        },
+        Err(error) => model.error = Some(error),
+    }
+}
+
+/// Up and Down highlight an entry, Left goes to the parent directory and Right opens the highlighted directory
+fn move_in_file_picker(model: &mut Model, direction: Direction) {
+    let Some(picker) = &mut model.file_picker else {
+        return;
+    };
+    let result = match direction {
+        Direction::Up | Direction::Down => {
+            picker.move_selection(direction == Direction::Down);
+            Ok(())
+        },
+        Direction::Left => picker.go_up(),
+        Direction::Right if picker.entries.get(picker.selected).is_some_and(|entry| entry.is_dir) => picker.open_selected().map(|_| ()),
+        Direction::Right => Ok(()),
+    };
+    model.error = result.err();
+}
+
+/// Open the highlighted directory, or write the path of the highlighted file or of `.` into the value and close the picker
+fn confirm_file_picker(model: &mut Model) {
+    let Some(picker) = &mut model.file_picker else {
+        return;
+    };
+    let (field, chosen) = (picker.field.clone(), picker.open_selected());
+    let result = match chosen {
+        Ok(Some(path)) => {
+            let working_directory = std::env::current_dir().unwrap_or_default();
+            model.file_picker = None;
+            set_last_value(model, &field, filepicker::display_path(&path, &working_directory))
+        },
+        Ok(None) => Ok(()),
+        Err(error) => Err(error),
+    };
+    model.error = result.err();
+}
+
+/// Start another value of a parameter that can be passed multiple times
+fn add_value(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    model.share_code = None;
+    if let Some((values, true)) = get_values(model, field.section, index) {
+        if values.last().is_some_and(|value| !value.is_empty()) {
+            values.push(String::new());
+            model.text_cursor = None;
+        }
+    }
+}
+
+/// Position of the last value of the field among its choices, None if it is unset or not one of them
+fn chosen_index(model: &Model, field: &FieldId) -> Option<usize> {
+    let value = model.last_value(field)?;
+    model.choices(field)?.iter().position(|choice| choice == value)
+}
+
+/// Open the dropdown with the current value highlighted, or the first choice if there is none
+fn open_dropdown(model: &mut Model, field: &FieldId) {
+    if model.choices(field).is_none() {
+        return;
+    }
+    let selected = chosen_index(model, field).unwrap_or(0);
+    model.error = None;
+    model.dropdown = Some(Dropdown { field: field.clone(), selected });
+}
+
+/// Highlight the previous or next choice, wrapping around like the lists of the form
+fn move_in_dropdown(model: &mut Model, direction: Direction) {
+    let Some(dropdown) = &model.dropdown else {
+        return;
+    };
+    let len = model.choices(&dropdown.field).map_or(0, <[String]>::len);
+    let Some(dropdown) = model.dropdown.as_mut().filter(|_| len > 0) else {
+        return;
+    };
+    match direction {
+        Direction::Down => dropdown.selected = (dropdown.selected + 1) % len,
+        Direction::Up => dropdown.selected = (dropdown.selected + len - 1) % len,
+        Direction::Left | Direction::Right => (),
+    }
+}
+
+/// Highlight the next choice after the highlighted one that starts with the character, ignoring case
+fn jump_in_dropdown(model: &mut Model, ch: char) {
+    let Some(dropdown) = &model.dropdown else {
+        return;
+    };
+    let choices = model.choices(&dropdown.field).unwrap_or_default();
+    let starts_with = |choice: &String| choice.chars().next().is_some_and(|first| first.to_lowercase().eq(ch.to_lowercase()));
+    let next = (1..=choices.len())
+        .map(|offset| (dropdown.selected + offset) % choices.len())
+        .find(|index| starts_with(&choices[*index]));
+    if let (Some(next), Some(dropdown)) = (next, &mut model.dropdown) {
+        dropdown.selected = next;
+    }
+}
+
+/// Write the highlighted choice into the value and close the dropdown
+fn confirm_dropdown(model: &mut Model) {
+    let Some(dropdown) = model.dropdown.take() else {
+        return;
+    };
+    let Some(choice) = model.choices(&dropdown.field).and_then(|choices| choices.get(dropdown.selected)).cloned() else {
+        return;
+    };
+    model.error = set_last_value(model, &dropdown.field, choice).err();
+}
+
+/// `+` and `-` choose the next or previous possible value without opening the dropdown
+fn cycle_choice(model: &mut Model, field: &FieldId, forward: bool) {
+    let Some(len) = model.choices(field).map(<[String]>::len) else {
+        return;
+    };
+    let index = match (chosen_index(model, field), forward) {
+        (Some(index), true) => (index + 1) % len,
+        (Some(index), false) => (index + len - 1) % len,
+        (None, true) => 0,
+        (None, false) => len - 1,
+    };
+    let choice = model.choices(field).unwrap_or_default()[index].clone();
+    model.error = set_last_value(model, field, choice).err();
+}
+
+/// Backspace unsets a value chosen from the possible values, it is never edited character by character
+fn unset_choice(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    model.error = None;
+    model.share_code = None;
+    if let Some((values, _)) = get_values(model, field.section, index) {
+        values.pop();
+    }
+}
+
+/// Only arguments and positionals taking files expand globs, or those already expanding them
+fn toggle_glob(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
+    };
+    model.share_code = None;
+    let glob = match field.section {
+        Section::Positionals => model.parameters.positionals.get_mut(index).filter(|positional| positional.glob || positional.takes_files()).map(|positional| &mut positional.glob),
+        _ => get_argument(model, field.section, index).filter(|argument| argument.glob || argument.takes_files()).map(|argument| &mut argument.glob),
+    };
+    if let Some(glob) = glob {
+        *glob = !*glob;
+    }
+}
+
+/// Flags are set and unset, boolean arguments go through `true`, `false` and unset
+fn toggle_field(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
     };
+    model.error = None;
+    model.share_code = None;
+    if let Some(argument) = get_argument(model, field.section, index).filter(|argument| argument.value_type == ValueType::Bool && argument.template.is_none()) {
+        argument.values = argument.next_bool_value();
+        let key = argument.key.clone();
+        model.unset_conflicting_flags(&key);
+        return;
+    }
+    if field.section == Section::Flags {
+        let _ = model.toggle_flag(&field.key);
+    }
 }
 
-fn remove_text(model: &mut Model) {
-    match model.current_section {
-        Section::Arguments => model.parameters.arguments[model.current_key_index].value.pop(),
-        Section::Options => model.parameters.options[model.current_key_index].value.pop(),
-        Section::Flags => None,
+/// Pass the repeatable flag once more or once less, e.g. `-vv` to `-vvv`
+fn count_flag(model: &mut Model, field: &FieldId, increment: bool) {
+    model.error = None;
+    model.share_code = None;
+    if field.section != Section::Flags {
+        return;
+    }
+    let Some(flag) = model.field_index(field).map(|index| &mut model.parameters.flags[index]).filter(|flag| flag.multiple) else {
+        return;
     };
+    let count = flag.times() as u8;
+    flag.set_count(if increment { count.saturating_add(1) } else { count.saturating_sub(1) });
+    if flag.set {
+        let key = flag.key.clone();
+        model.unset_conflicting_flags(&key);
+    }
 }
 
-fn toggle_flag(model: &mut Model) {
-    match model.current_section {
-        Section::Flags => model.parameters.flags[model.current_key_index].set = !model.parameters.flags[model.current_key_index].set,
-        _ => ()
+/// Select the clicked parameter, like the arrow keys it stays on a numeric value whose expression is invalid
+/// A click on a flag or a yes/no argument also toggles it
+fn click_field(model: &mut Model, field: &FieldId) {
+    let Some(index) = model.field_index(field) else {
+        return;
     };
+    if model.selected_field().as_ref() != Some(field) && !evaluate_selected_expressions(model) {
+        return;
+    }
+    model.filter = None;
+    (model.current_section, model.current_key_index) = (field.section, index);
+    if model.choices(field).is_some() {
+        return open_dropdown(model, field);
+    }
+    toggle_field(model, field);
+}
+
+/// Select the clicked subcommand, a click on the selected one enters it like Enter
+fn click_subcommand(model: &mut Model, index: usize) {
+    if index >= model.get_subcommand_len() {
+        return;
+    }
+    if index == model.subcommand_index {
+        return select_subcommand(model);
+    }
+    model.subcommand_index = index;
+}
+
+fn select_subcommand(model: &mut Model) {
+    if model.subcommand_index == 0 {
+        model.selecting_subcommand = false;
+    } else {
+        model.enter_subcommand(model.subcommand_index - 1);
+    }
+}
+
+fn toggle_help(model: &mut Model) {
+    model.showing_help = !model.showing_help;
+    model.help_scroll = 0;
+}
+
+fn toggle_argv(model: &mut Model) {
+    model.showing_argv = !model.showing_argv;
+    model.argv_scroll = 0;
+}
+
+/// The statistics are computed by the app, which has access to the history
+fn toggle_stats(model: &mut Model) {
+    if model.stats.take().is_none() {
+        model.commands.push(AppCommand::ShowStats);
+    }
+    model.stats_scroll = 0;
 }
 
-fn run(model: &mut Model) {
-    model.run = true;
-} 
+#[allow(clippy::needless_return)]
+fn back(model: &mut Model) {
+    if model.showing_help {
+        model.showing_help = false;
+    } else if model.showing_argv {
+        model.showing_argv = false;
+    } else if model.stats.is_some() {
+        model.stats = None;
+    } else if let Some(pager) = model.pager.as_mut().filter(|pager| pager.searching) {
+        pager.searching = false;
+        pager.query.clear();
+    } else if model.pager.is_some() {
+        model.pager = None;
+    } else if model.share_code.is_some() {
+        model.share_code = None;
+    } else if model.dropdown.is_some() {
+        model.dropdown = None;
+    } else if model.file_picker.is_some() {
+        model.file_picker = None;
+    } else if model.value_editor.is_some() {
+        model.value_editor = None;
+    } else if model.finder.is_some() {
+        model.finder = None;
+    } else if model.filter.is_some() {
+        model.filter = None;
+    } else if let Some(output) = model.output.as_mut().filter(|output| output.filtering || !output.filter.is_empty()) {
+        output.filtering = false;
+        output.set_filter(String::new());
+    } else if model.output.as_ref().is_some_and(|output| !output.is_running()) {
+        model.output = None;
+    } else if model.output.is_some() {
+        return;
+    } else if model.text_cursor.is_some() {
+        model.text_cursor = None;
+    } else if !model.selecting_subcommand && !model.parameters.subcommands.is_empty() {
+        model.selecting_subcommand = true;
+    } else {
+        model.leave_subcommand();
+    }
+}
+
+fn share(model: &mut Model) {
+    model.share_code = Some(SharedForm::from_parameters(&model.parameters).encode());
+    model.last_share_code.clone_from(&model.share_code);
+}
+
+/// Request a run or a test run once every value is valid
+fn run(model: &mut Model, command: AppCommand) {
+    if !evaluate_selected_expressions(model) {
+        return;
+    }
+    if let Some((section, index, name)) = model.find_missing_required() {
+        model.error = Some(format!("Missing value for required {name}"));
+        model.current_section = section;
+        model.current_key_index = index;
+        return;
+    }
+    if let Some((section, index, error)) = model.find_choice_violation().or_else(|| model.find_path_violation()) {
+        model.error = Some(error);
+        model.current_section = section;
+        model.current_key_index = index;
+        return;
+    }
+    model.commands.push(command);
+}
 
 fn quit(model: &mut Model) {
-    model.exit = true;
+    model.commands.push(AppCommand::Quit);
+}
+
+/// Send the message for the selected parameter like the key handling does
+#[allow(dead_code)]
+fn update_selected(model: &mut Model, message: impl FnOnce(FieldId) -> Message) {
+    let field = model.selected_field().unwrap();
+    update(model, message(field));
 }
 
 #[allow(dead_code)]
@@ -124,20 +1009,23 @@ fn create_test_model() -> Model {
             key: String::from("--name"),
             name: String::from("NAME"),
             description: Some(String::from("Name to greet")),
-            value: String::new(),
+            ..Default::default()
         },
         CLIArgument {
             key: String::from("--count"),
             name: String::from("COUNT"),
             description: Some(String::from("Numeber of times to greet.")),
-            value: String::from("1"),
+            values: vec![String::from("1")],
+            value_type: ValueType::Number,
+            ..Default::default()
         }
     ];
     let flags = vec![
         CLIFlag {
             key: String::from("--help"),
             description: Some(String::from("Print help")),
-            set: false
+            set: false,
+            ..Default::default()
         }
     ];
     let parameters = CLIParameters {
@@ -146,6 +1034,7 @@ fn create_test_model() -> Model {
         flags,
         options: Vec::new(),
         cli_lib: CLILib::Clap,
+        ..Default::default()
     };
 
     Model::new(parameters)
@@ -257,40 +1146,577 @@ fn test_left_wraps() {
 fn test_text_edit() {
     let mut model = create_test_model();
     
-    update(&mut model, Message::TextEdit('a'));
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
 
     assert_eq!(
-        model.parameters.arguments[0].value,
-        "a",
+        model.parameters.arguments[0].values,
+        vec![String::from("a")],
     );
 }
 
 #[test]
 fn test_remove_text() {
     let mut model = create_test_model();
-    model.parameters.arguments[0].value.push('a');
+    model.parameters.arguments[0].values = vec![String::from("a")];
     
-    update(&mut model, Message::RemoveText);
+    update_selected(&mut model, Message::RemoveText);
 
     assert_eq!(
-        model.parameters.arguments[0].value,
-        "",
+        model.parameters.arguments[0].values,
+        vec![String::new()],
     );
 }
 
 #[test]
-fn test_toggle_flag() {
+fn test_edit_text_at_cursor() {
+    use messages::CursorMove;
+
     let mut model = create_test_model();
-    model.current_section = Section::Flags;
-    
-    update(&mut model, Message::Toggle);
+
+    "Ferris".chars().for_each(|ch| update_selected(&mut model, |field| Message::TextEdit(field, ch)));
+    update_selected(&mut model, |field| Message::MoveCursor(field, CursorMove::Left));
+    update_selected(&mut model, |field| Message::MoveCursor(field, CursorMove::Left));
+    update_selected(&mut model, |field| Message::TextEdit(field, 'ü'));
+    update_selected(&mut model, |field| Message::MoveCursor(field, CursorMove::Home));
+    update_selected(&mut model, Message::RemoveNextText);
+    update_selected(&mut model, |field| Message::TextEdit(field, 'f'));
+    update_selected(&mut model, |field| Message::MoveCursor(field, CursorMove::End));
+    update_selected(&mut model, Message::RemoveText);
+
+    assert_eq!(model.parameters.arguments[0].values, vec![String::from("ferrüi")]);
+    assert_eq!(model.text_cursor, Some(TextCursor { field: model.selected_field().unwrap(), position: 6 }));
+    update(&mut model, Message::Back);
+    assert_eq!(model.text_cursor, None);
+}
+
+#[test]
+fn test_value_editor_writes_back_on_select() {
+    use messages::CursorMove;
+
+    let mut model = create_test_model();
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+
+    update_selected(&mut model, Message::Expand);
+    "\nb".chars().for_each(|ch| update_selected(&mut model, |field| Message::TextEdit(field, ch)));
+    update_selected(&mut model, |field| Message::MoveCursor(field, CursorMove::Up));
+    update_selected(&mut model, |field| Message::TextEdit(field, '!'));
+    assert_eq!(model.parameters.arguments[0].values, vec![String::from("a")]);
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.parameters.arguments[0].values, vec![String::from("a!\nb")]);
+    assert_eq!(model.value_editor, None);
+    assert_eq!(model.text_cursor, None);
+}
+
+#[test]
+fn test_value_editor_keeps_refused_value() {
+    let mut model = create_test_model();
+    model.current_key_index = 1;
+
+    update_selected(&mut model, Message::Expand);
+    update_selected(&mut model, |field| Message::TextEdit(field, '\n'));
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.error, Some(String::from("COUNT takes a number")));
+    assert_eq!(model.value_editor.as_ref().map(ValueEditor::text), Some(String::from("1\n")));
+    update(&mut model, Message::Back);
+    assert_eq!(model.value_editor, None);
+    assert_eq!(model.parameters.arguments[1].values, vec![String::from("1")]);
+}
+
+#[test]
+fn test_file_picker_writes_chosen_path() {
+    let directory = std::env::temp_dir().join(format!("clitui-controller-picker-{}", std::process::id()));
+    std::fs::create_dir_all(directory.join("logs")).unwrap();
+    std::fs::write(directory.join("names.txt"), "Ferris").unwrap();
+    let directory = directory.canonicalize().unwrap();
+    let mut model = create_test_model_with_positional();
+    model.set_value("FILE", &directory.to_string_lossy()).unwrap();
+    model.current_section = Section::Positionals;
+
+    update_selected(&mut model, Message::PickFile);
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Right));
+    assert_eq!(model.file_picker.as_ref().map(|picker| picker.directory.clone()), Some(directory.join("logs")));
+    update(&mut model, Message::Move(Direction::Left));
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.file_picker, None);
+    assert_eq!(model.parameters.positionals[0].values, vec![directory.join("names.txt").to_string_lossy().to_string()]);
+}
+
+#[allow(dead_code)]
+fn create_test_model_with_choices() -> Model {
+    let mut model = create_test_model();
+    model.parameters.arguments.push(CLIArgument {
+        key: String::from("--format"),
+        name: String::from("FORMAT"),
+        choices: vec![String::from("json"), String::from("yaml"), String::from("toml")],
+        ..Default::default()
+    });
+    model.current_key_index = 2;
+    model
+}
+
+#[test]
+fn test_dropdown_writes_chosen_value() {
+    let mut model = create_test_model_with_choices();
+
+    update_selected(&mut model, Message::OpenDropdown);
+    update(&mut model, Message::Move(Direction::Up));
+    assert_eq!(model.dropdown.as_ref().map(|dropdown| dropdown.selected), Some(2));
+    update(&mut model, Message::SearchText('Y'));
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.parameters.arguments[2].values, vec![String::from("yaml")]);
+    assert_eq!(model.dropdown, None);
+    update_selected(&mut model, Message::OpenDropdown);
+    assert_eq!(model.dropdown.as_ref().map(|dropdown| dropdown.selected), Some(1));
+    update(&mut model, Message::Back);
+    assert_eq!(model.dropdown, None);
+    assert_eq!(model.parameters.arguments[2].values, vec![String::from("yaml")]);
+}
+
+#[test]
+fn test_cycle_and_unset_choice() {
+    let mut model = create_test_model_with_choices();
+
+    update_selected(&mut model, Message::Decrement);
+    assert_eq!(model.parameters.arguments[2].values, vec![String::from("toml")]);
+    update_selected(&mut model, Message::Increment);
+    assert_eq!(model.parameters.arguments[2].values, vec![String::from("json")]);
+    update_selected(&mut model, Message::RemoveText);
+
+    assert!(model.parameters.arguments[2].values.is_empty());
+}
+
+#[test]
+fn test_moving_selection_ends_editing() {
+    let mut model = create_test_model();
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+    update(&mut model, Message::Move(Direction::Down));
+
+    assert_eq!(model.text_cursor, None);
+    assert_eq!(model.cursor_position(&model.selected_field().unwrap()), 1);
+}
+
+#[allow(dead_code)]
+fn create_test_model_with_positional() -> Model {
+    let mut model = create_test_model();
+    model.parameters.positionals.push(CLIPositional {
+        name: String::from("FILE"),
+        description: Some(String::from("File with names")),
+        ..Default::default()
+    });
+    model
+}
+
+#[test]
+fn test_right_visits_positionals() {
+    let mut model = create_test_model_with_positional();
+
+    update(&mut model, Message::Move(Direction::Right));
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+
+    assert_eq!(model.current_section, Section::Positionals);
+    assert_eq!(model.parameters.positionals[0].values, vec![String::from("a")]);
+}
+
+#[test]
+fn test_add_value() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.positionals[0].multiple = true;
+    model.current_section = Section::Positionals;
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+    update_selected(&mut model, Message::AddValue);
+    update_selected(&mut model, Message::AddValue);
+    update_selected(&mut model, |field| Message::TextEdit(field, 'b'));
+
+    assert_eq!(model.parameters.positionals[0].values, vec![String::from("a"), String::from("b")]);
+}
+
+#[test]
+fn test_add_value_to_single_value_parameter() {
+    let mut model = create_test_model_with_positional();
+    model.current_section = Section::Positionals;
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+    update_selected(&mut model, Message::AddValue);
+
+    assert_eq!(model.parameters.positionals[0].values, vec![String::from("a")]);
+}
+
+#[test]
+fn test_toggle_glob() {
+    let mut model = create_test_model_with_positional();
+    model.current_section = Section::Positionals;
+
+    update_selected(&mut model, Message::ToggleGlob);
+    assert!(model.parameters.positionals[0].glob);
+    update_selected(&mut model, Message::ToggleGlob);
+    assert!(!model.parameters.positionals[0].glob);
+
+    // --name does not take files
+    model.current_section = Section::Arguments;
+    update_selected(&mut model, Message::ToggleGlob);
+    assert!(!model.parameters.arguments[0].glob);
+}
+
+#[test]
+fn test_remove_text_removes_empty_value() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.positionals[0].multiple = true;
+    model.parameters.positionals[0].values = vec![String::from("a"), String::new()];
+    model.current_section = Section::Positionals;
+
+    update_selected(&mut model, Message::RemoveText);
+    update_selected(&mut model, Message::RemoveText);
+
+    assert_eq!(model.parameters.positionals[0].values, vec![String::new()]);
+}
+
+#[test]
+fn test_toggle_flag() {
+    let mut model = create_test_model();
+    model.current_section = Section::Flags;
+    
+    update_selected(&mut model, Message::Toggle);
+
+    assert!(model.parameters.flags[0].set);
+}
+
+#[test]
+fn test_toggle_negatable_flag() {
+    let mut model = create_test_model();
+    model.parameters.flags[0].negated_key = Some(String::from("--no-verbose"));
+    model.current_section = Section::Flags;
+
+    update_selected(&mut model, Message::Toggle);
+    assert!(model.parameters.flags[0].set && !model.parameters.flags[0].negated);
+    update_selected(&mut model, Message::Toggle);
+    assert!(model.parameters.flags[0].set && model.parameters.flags[0].negated);
+    update_selected(&mut model, Message::Toggle);
+    assert!(!model.parameters.flags[0].set && !model.parameters.flags[0].negated);
+}
+
+#[test]
+fn test_count_flag() {
+    let mut model = create_test_model();
+    model.parameters.flags[0].multiple = true;
+    model.current_section = Section::Flags;
+
+    update_selected(&mut model, Message::Increment);
+    update_selected(&mut model, Message::Increment);
+    update_selected(&mut model, Message::Increment);
+    update_selected(&mut model, Message::Decrement);
+
+    assert_eq!(model.parameters.flags[0].times(), 2);
+    update_selected(&mut model, Message::Toggle);
+    assert_eq!(model.parameters.flags[0].times(), 0);
+}
+
+#[test]
+fn test_toggle_flag_unsets_conflicting_flag() {
+    let mut model = create_test_model();
+    model.parameters.flags = vec![
+        CLIFlag { key: String::from("--json"), set: true, ..Default::default() },
+        CLIFlag { key: String::from("--yaml"), ..Default::default() },
+    ];
+    model.parameters.exclusive_groups = vec![vec![String::from("--json"), String::from("--yaml")]];
+    model.current_section = Section::Flags;
+    model.current_key_index = 1;
+
+    update_selected(&mut model, Message::Toggle);
+
+    assert!(!model.parameters.flags[0].set);
+    assert!(model.parameters.flags[1].set);
+}
+
+#[test]
+fn test_text_edit_unsets_conflicting_flag() {
+    let mut model = create_test_model();
+    model.parameters.flags[0].set = true;
+    model.parameters.exclusive_groups = vec![vec![String::from("--name"), String::from("--help")]];
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+
+    assert!(!model.parameters.flags[0].set);
+}
+
+#[test]
+fn test_text_edit_clears_error() {
+    let mut model = create_test_model();
+    model.error = Some(String::from("error: invalid value"));
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
 
     assert_eq!(
-        model.parameters.flags[0].set,
-        true,
+        model.error,
+        None,
     );
 }
 
+#[test]
+fn test_share_shows_code_until_next_edit() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::Share);
+    let share_code = model.share_code.clone();
+    update_selected(&mut model, |field| Message::TextEdit(field, 'a'));
+
+    assert_eq!(share_code, Some(SharedForm::from_parameters(&create_test_model().parameters).encode()));
+    assert_eq!(model.share_code, None);
+    assert_eq!(model.last_share_code, share_code);
+    update(&mut model, Message::Share);
+    update(&mut model, Message::Back);
+    assert_eq!(model.share_code, None);
+}
+
+#[test]
+fn test_select_key() {
+    let mut model = create_test_model();
+
+    assert!(model.select_key("--help"));
+    assert_eq!(model.current_section, Section::Flags);
+    assert_eq!(model.current_key_index, 0);
+}
+
+#[test]
+fn test_select_unknown_key() {
+    let mut model = create_test_model();
+
+    assert!(!model.select_key("--unknown"));
+    assert_eq!(model.current_section, Section::Arguments);
+}
+
+#[test]
+fn test_get_and_set_value() {
+    use crate::model::ParameterValue;
+
+    let mut model = create_test_model_with_positional();
+    model.parameters.arguments[0].short_key = Some(String::from("-n"));
+    model.parameters.arguments[0].aliases = vec![String::from("--nickname")];
+
+    model.set_value("-n", "Ferris").unwrap();
+    model.set_values("FILE", vec![String::from("a.txt"), String::from("b.txt")]).unwrap();
+
+    assert_eq!(model.get("--nickname"), Some(ParameterValue::Values(vec![String::from("Ferris")])));
+    assert_eq!(model.get("FILE"), Some(ParameterValue::Values(vec![String::from("a.txt"), String::from("b.txt")])));
+    assert_eq!(model.get("--unknown"), None);
+    assert_eq!(model.set_value("--unknown", "1"), Err(String::from("Unknown parameter: --unknown")));
+    assert_eq!(model.set_value("--count", "ten"), Err(String::from("COUNT takes a number")));
+    assert_eq!(model.set_value("--help", "maybe"), Err(String::from("--help is a flag and takes true, false or a count")));
+}
+
+#[test]
+fn test_set_flag_value() {
+    use crate::model::ParameterValue;
+
+    let mut model = create_test_model();
+    model.parameters.flags.push(CLIFlag {
+        key: String::from("--color"),
+        negated_key: Some(String::from("--no-color")),
+        ..Default::default()
+    });
+    model.parameters.flags.push(CLIFlag { key: String::from("-v"), multiple: true, ..Default::default() });
+    model.parameters.exclusive_groups = vec![vec![String::from("--name"), String::from("--help")]];
+
+    model.set_value("--help", "true").unwrap();
+    model.set_value("--no-color", "true").unwrap();
+    model.set_value("-v", "3").unwrap();
+
+    assert_eq!(model.get("--help"), Some(ParameterValue::Flag { times: 1, negated: false }));
+    assert_eq!(model.get("--color"), Some(ParameterValue::Flag { times: 1, negated: true }));
+    assert_eq!(model.get("-v"), Some(ParameterValue::Flag { times: 3, negated: false }));
+    model.set_value("--name", "Ferris").unwrap();
+    assert_eq!(model.get("--help"), Some(ParameterValue::Flag { times: 0, negated: false }));
+}
+
+#[test]
+fn test_toggle_flag_by_key() {
+    use crate::model::ParameterValue;
+
+    let mut model = create_test_model();
+    model.parameters.flags[0].negated_key = Some(String::from("--no-help"));
+
+    model.toggle_flag("--no-help").unwrap();
+    model.toggle_flag("--help").unwrap();
+
+    assert_eq!(model.get("--help"), Some(ParameterValue::Flag { times: 1, negated: true }));
+    model.toggle_flag("--help").unwrap();
+    assert_eq!(model.get("--help"), Some(ParameterValue::Flag { times: 0, negated: false }));
+    assert_eq!(model.toggle_flag("--name"), Err(String::from("Unknown flag: --name")));
+}
+
+#[allow(dead_code)]
+fn create_test_model_with_subcommand() -> Model {
+    let subcommand_parameters = CLIParameters {
+        cli_name: String::from("cargo"),
+        command_path: vec![String::from("build")],
+        flags: vec![
+            CLIFlag {
+                key: String::from("--release"),
+                ..Default::default()
+            }
+        ],
+        ..Default::default()
+    };
+    let parameters = CLIParameters {
+        cli_name: String::from("cargo"),
+        flags: vec![
+            CLIFlag {
+                key: String::from("--version"),
+                ..Default::default()
+            }
+        ],
+        subcommands: vec![
+            CLISubcommand {
+                name: String::from("build"),
+                description: Some(String::from("Compile the current package")),
+                parameters: Some(subcommand_parameters),
+            }
+        ],
+        ..Default::default()
+    };
+
+    Model::new(parameters)
+}
+
+#[test]
+fn test_model_starts_with_subcommand_picker() {
+    let model = create_test_model_with_subcommand();
+
+    assert!(model.selecting_subcommand);
+    assert_eq!(model.current_section, Section::Flags);
+}
+
+#[test]
+fn test_down_wraps_in_subcommand_picker() {
+    let mut model = create_test_model_with_subcommand();
+
+    update(&mut model, Message::Move(Direction::Down));
+    assert_eq!(model.subcommand_index, 1);
+    update(&mut model, Message::Move(Direction::Down));
+    assert_eq!(model.subcommand_index, 0);
+}
+
+#[test]
+fn test_select_command_itself() {
+    let mut model = create_test_model_with_subcommand();
+
+    update(&mut model, Message::Select);
+
+    assert!(!model.selecting_subcommand);
+    assert_eq!(model.parameters.flags[0].key, "--version");
+}
+
+#[test]
+fn test_select_subcommand() {
+    let mut model = create_test_model_with_subcommand();
+
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Select);
+
+    assert!(!model.selecting_subcommand);
+    assert_eq!(model.parameters.flags[0].key, "--release");
+    assert_eq!(model.parent_parameters.len(), 1);
+}
+
+#[test]
+fn test_back_restores_parent() {
+    let mut model = create_test_model_with_subcommand();
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Select);
+    update_selected(&mut model, Message::Toggle);
+
+    update(&mut model, Message::Back);
+
+    assert!(model.selecting_subcommand);
+    assert_eq!(model.subcommand_index, 1);
+    assert_eq!(model.parameters.flags[0].key, "--version");
+    let subcommand_parameters = model.parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert!(subcommand_parameters.flags[0].set);
+}
+
+#[test]
+fn test_attach_discovered_subcommands() {
+    let mut model = create_test_model_with_subcommand();
+    let mut discovered = model.parameters.clone();
+    discovered.flags[0].global = true;
+    let subcommand_parameters = model.parameters.subcommands[0].parameters.take();
+    model.discovering = true;
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Select);
+    assert!(model.selecting_subcommand);
+    update(&mut model, Message::Move(Direction::Up));
+    update(&mut model, Message::Select);
+    update_selected(&mut model, Message::Toggle);
+
+    model.attach_discovered(discovered);
+
+    assert!(!model.discovering);
+    assert!(model.parameters.flags[0].set);
+    assert!(model.parameters.flags[0].global);
+    assert_eq!(model.parameters.subcommands[0].parameters, subcommand_parameters);
+}
+
+#[test]
+fn test_global_flag_is_shared_with_subcommand() {
+    let mut model = create_test_model_with_subcommand();
+    model.parameters.flags[0].global = true;
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.parameters.flags[1].key, "--version");
+    assert_eq!(model.parameters.flags[1].inherited, Some(0));
+    update(&mut model, Message::Toggle(FieldId { section: Section::Flags, key: String::from("--version") }));
+    update(&mut model, Message::Back);
+
+    assert!(model.parameters.flags[0].set);
+    let subcommand_parameters = model.parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(subcommand_parameters.flags.len(), 1);
+}
+
+#[test]
+fn test_back_from_form_shows_subcommand_picker() {
+    let mut model = create_test_model_with_subcommand();
+    update(&mut model, Message::Select);
+
+    update(&mut model, Message::Back);
+
+    assert!(model.selecting_subcommand);
+    assert!(model.parent_parameters.is_empty());
+}
+
+#[test]
+fn test_empty_model_ignores_input() {
+    let mut model = Model::new(CLIParameters::default());
+
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Right));
+    update(&mut model, Message::TextEdit(FieldId { section: Section::Arguments, key: String::from("--name") }, 'a'));
+    update(&mut model, Message::Toggle(FieldId { section: Section::Flags, key: String::from("--help") }));
+
+    assert_eq!(model.selected_field(), None);
+    assert_eq!(model.get_selected_description(), None);
+}
+
+#[test]
+fn test_message_targets_field_instead_of_selection() {
+    let mut model = create_test_model();
+    let field = model.selected_field().unwrap();
+    model.current_key_index = 1;
+
+    update(&mut model, Message::TextEdit(field, 'x'));
+
+    assert_eq!(model.parameters.arguments[0].values, vec![String::from("x")]);
+    assert_eq!(model.parameters.arguments[1].values, vec![String::from("1")]);
+}
+
 #[test]
 fn test_run() {
     let mut model = create_test_model();
@@ -298,7 +1724,20 @@ fn test_run() {
 
     update(&mut model, message);
 
-    assert!(model.run);
+    assert_eq!(model.commands, vec![AppCommand::Run]);
+}
+
+#[test]
+fn test_run_interactive_command_in_terminal() {
+    let mut model = create_test_model();
+    model.parameters.flags.push(CLIFlag { key: String::from("--interactive"), short_key: Some(String::from("-i")), set: true, ..Default::default() });
+    update(&mut model, Message::Run);
+
+    model.parameters.flags.clear();
+    model.run_in_terminal = true;
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.commands, vec![AppCommand::RunInTerminal, AppCommand::RunInTerminal]);
 }
 
 #[test]
@@ -308,5 +1747,498 @@ fn test_quit() {
 
     update(&mut model, message);
 
-    assert!(model.exit);
+    assert_eq!(model.commands, vec![AppCommand::Quit]);
+}
+
+#[test]
+fn test_help_scrolls_and_closes() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::TogglePopup(Popup::Help));
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Up));
+
+    assert!(model.showing_help);
+    assert_eq!(model.help_scroll, 1);
+    assert_eq!(model.current_key_index, 0);
+
+    update(&mut model, Message::Back);
+
+    assert!(!model.showing_help);
+}
+
+#[allow(dead_code)]
+fn create_test_model_with_output() -> Model {
+    let mut model = create_test_model();
+    let mut output = crate::output::OutputPane::new(String::from("greeter.exe"));
+    for index in 0..20 {
+        output.push(crate::output::OutputLine::Stdout(format!("Hello {index}")));
+    }
+    model.output = Some(output);
+    model
+}
+
+#[test]
+fn test_scroll_output_stops_following() {
+    let mut model = create_test_model_with_output();
+
+    update(&mut model, Message::Move(Direction::Up));
+    update(&mut model, Message::Move(Direction::Up));
+
+    let output = model.output.as_ref().unwrap();
+    assert!(!output.follow);
+    assert_eq!(output.scroll_back, 2);
+    assert_eq!(model.current_key_index, 0);
+}
+
+#[test]
+fn test_follow_output() {
+    let mut model = create_test_model_with_output();
+    update(&mut model, Message::Move(Direction::Up));
+
+    update(&mut model, Message::Follow);
+
+    let output = model.output.as_ref().unwrap();
+    assert!(output.follow);
+    assert_eq!(output.scroll_back, 0);
+}
+
+#[test]
+fn test_filter_output_while_running() {
+    let mut model = create_test_model_with_output();
+
+    update(&mut model, Message::Search);
+    "o 1".chars().for_each(|ch| update(&mut model, Message::SearchText(ch)));
+    update(&mut model, Message::RemoveSearchText);
+    update(&mut model, Message::SearchText('7'));
+    update(&mut model, Message::Select);
+
+    let output = model.output.as_ref().unwrap();
+    assert!(!output.filtering);
+    assert_eq!(output.shown_lines(), [7]);
+    update(&mut model, Message::Back);
+    assert_eq!(model.output.as_ref().unwrap().shown_lines().len(), 20);
+    update(&mut model, Message::Back);
+    assert!(model.output.is_some());
+}
+
+#[test]
+fn test_back_keeps_running_output() {
+    let mut model = create_test_model_with_output();
+
+    update(&mut model, Message::Back);
+
+    assert!(model.output.is_some());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_back_closes_finished_output() {
+    use std::os::unix::process::ExitStatusExt;
+    let mut model = create_test_model_with_output();
+    model.output.as_mut().unwrap().status = Some(std::process::ExitStatus::from_raw(0));
+
+    update(&mut model, Message::Back);
+
+    assert_eq!(model.output, None);
+}
+
+#[test]
+fn test_leaving_numeric_argument_evaluates_expression() {
+    let mut model = create_test_model();
+    model.current_key_index = 1;
+    model.set_value("--count", "4*1024").unwrap();
+
+    update(&mut model, Message::Move(Direction::Up));
+
+    assert_eq!(model.parameters.arguments[1].values, vec![String::from("4096")]);
+    assert_eq!(model.current_key_index, 0);
+}
+
+#[test]
+fn test_invalid_expression_blocks_run() {
+    let mut model = create_test_model();
+    model.current_key_index = 1;
+    model.set_value("--count", "60*").unwrap();
+
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.commands, Vec::new());
+    assert_eq!(model.error, Some(String::from("Invalid expression for COUNT: expected a number at the end")));
+    assert_eq!(model.current_key_index, 1);
+}
+
+#[test]
+fn test_expression_in_text_argument_is_kept() {
+    let mut model = create_test_model();
+    model.set_value("--name", "1+1").unwrap();
+
+    update(&mut model, Message::Move(Direction::Down));
+
+    assert_eq!(model.parameters.arguments[0].values, vec![String::from("1+1")]);
+}
+
+#[test]
+fn test_run_blocked_by_missing_required_value() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.positionals[0].required = true;
+
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.commands, Vec::new());
+    assert_eq!(model.error, Some(String::from("Missing value for required FILE")));
+    assert_eq!(model.current_section, Section::Positionals);
+}
+
+#[test]
+fn test_run_blocked_by_path_check() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.positionals[0].path_check = crate::parsing::PathCheck::MustExist;
+    model.parameters.positionals[0].values = vec![String::from("/nonexistent/names.txt")];
+
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.commands, Vec::new());
+    assert_eq!(model.error, Some(String::from("FILE does not exist: /nonexistent/names.txt")));
+    model.parameters.positionals[0].path_check = crate::parsing::PathCheck::MustNotExist;
+    model.parameters.positionals[0].values = vec![std::env::temp_dir().to_string_lossy().to_string()];
+    update(&mut model, Message::Run);
+    assert!(model.error.unwrap().starts_with("FILE already exists: "));
+}
+
+#[test]
+fn test_values_outside_choices_refused() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.arguments[0].choices = vec![String::from("json"), String::from("yaml")];
+
+    assert_eq!(model.set_value("--name", "xml"), Err(String::from("NAME takes one of json, yaml, not xml")));
+    model.set_value("--name", "yaml").unwrap();
+    model.parameters.arguments[0].values = vec![String::from("toml")];
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.commands, Vec::new());
+    assert_eq!(model.error, Some(String::from("NAME takes one of json, yaml, not toml")));
+    assert_eq!(model.current_section, Section::Arguments);
+}
+
+#[test]
+fn test_run_with_required_value() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.positionals[0].required = true;
+    model.set_value("FILE", "names.txt").unwrap();
+
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.commands, vec![AppCommand::Run]);
+}
+
+#[test]
+fn test_test_run() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::TestRun);
+
+    assert_eq!(model.commands, vec![AppCommand::TestRun]);
+}
+
+#[test]
+fn test_numeric_argument_checked_when_left() {
+    let mut model = create_test_model();
+    model.current_key_index = 1;
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'K'));
+    update(&mut model, Message::Run);
+    assert_eq!(model.parameters.arguments[1].values, vec![String::from("1K")]);
+    assert_eq!(model.error, None);
+    update_selected(&mut model, |field| Message::TextEdit(field, '!'));
+    update(&mut model, Message::Run);
+
+    assert_eq!(model.parameters.arguments[1].values, vec![String::from("1K!")]);
+    assert_eq!(model.error, Some(String::from("COUNT takes a number")));
+    assert_eq!(model.commands, vec![AppCommand::Run]);
+}
+
+#[test]
+fn test_argv_view_scrolls_and_closes() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::TogglePopup(Popup::Argv));
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Down));
+    update(&mut model, Message::Move(Direction::Down));
+
+    assert!(model.showing_argv);
+    assert_eq!(model.argv_scroll, 2);
+
+    update(&mut model, Message::Back);
+
+    assert!(!model.showing_argv);
+}
+
+#[cfg(unix)]
+#[allow(dead_code)]
+fn create_test_model_with_pager() -> Model {
+    use std::os::unix::process::ExitStatusExt;
+    let mut model = create_test_model_with_output();
+    model.output.as_mut().unwrap().status = Some(std::process::ExitStatus::from_raw(0));
+    update(&mut model, Message::Pager);
+    model.pager.as_ref().unwrap().page_height.set(5);
+    model
+}
+
+#[test]
+fn test_pager_needs_finished_output() {
+    let mut model = create_test_model_with_output();
+
+    update(&mut model, Message::Pager);
+
+    assert_eq!(model.pager, None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pager_jumps_to_bottom_and_top() {
+    let mut model = create_test_model_with_pager();
+
+    update(&mut model, Message::Bottom);
+    assert_eq!(model.pager.as_ref().unwrap().top, 15);
+
+    update(&mut model, Message::Top);
+    assert_eq!(model.pager.as_ref().unwrap().top, 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pager_search() {
+    let mut model = create_test_model_with_pager();
+
+    update(&mut model, Message::Search);
+    "lo 12".chars().for_each(|ch| update(&mut model, Message::SearchText(ch)));
+    update(&mut model, Message::Select);
+
+    let pager = model.pager.as_ref().unwrap();
+    assert!(!pager.searching);
+    assert_eq!(pager.top, 12);
+}
+
+#[test]
+fn test_filter_selects_first_match() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::Search);
+    "PRINT".chars().for_each(|ch| update(&mut model, Message::SearchText(ch)));
+    assert_eq!(model.shown_indices(Section::Arguments), Vec::<usize>::new());
+    assert_eq!(model.shown_indices(Section::Flags), [0]);
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.filter, None);
+    assert_eq!((model.current_section, model.current_key_index), (Section::Flags, 0));
+}
+
+#[test]
+fn test_filter_ignores_diacritics() {
+    let mut model = create_test_model();
+    model.parameters.arguments[1].description = Some(String::from("Größe der Ausgabe"));
+
+    update(&mut model, Message::Search);
+    "grosse".chars().for_each(|ch| update(&mut model, Message::SearchText(ch)));
+
+    assert_eq!(model.shown_indices(Section::Arguments), [1]);
+    assert_eq!(model.shown_indices(Section::Flags), Vec::<usize>::new());
+}
+
+#[test]
+fn test_finder_jumps_to_chosen_match() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::Jump);
+    "e".chars().for_each(|ch| update(&mut model, Message::SearchText(ch)));
+    update(&mut model, Message::Move(Direction::Up));
+    update(&mut model, Message::Select);
+
+    assert_eq!(model.finder, None);
+    assert_eq!((model.current_section, model.current_key_index), (Section::Flags, 0));
+}
+
+#[test]
+fn test_click_selects_and_toggles_flag() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::Click(FieldId { section: Section::Flags, key: String::from("--help") }));
+
+    assert_eq!((model.current_section, model.current_key_index), (Section::Flags, 0));
+    assert!(model.parameters.flags[0].set);
+    update(&mut model, Message::Click(FieldId { section: Section::Arguments, key: String::from("--count") }));
+    assert_eq!((model.current_section, model.current_key_index), (Section::Arguments, 1));
+    assert!(model.parameters.flags[0].set);
+}
+
+#[test]
+fn test_click_subcommand_enters_selected_one() {
+    let mut model = create_test_model_with_subcommand();
+
+    update(&mut model, Message::ClickSubcommand(1));
+    assert_eq!(model.subcommand_index, 1);
+    assert!(model.selecting_subcommand);
+    update(&mut model, Message::ClickSubcommand(1));
+
+    assert_eq!(model.parameters.flags[0].key, "--release");
+}
+
+#[test]
+fn test_back_cancels_filter() {
+    let mut model = create_test_model();
+    model.current_key_index = 1;
+
+    update(&mut model, Message::Search);
+    update(&mut model, Message::SearchText('h'));
+    update(&mut model, Message::Back);
+
+    assert_eq!(model.filter, None);
+    assert_eq!((model.current_section, model.current_key_index), (Section::Arguments, 1));
+    assert_eq!(model.shown_indices(Section::Arguments), [0, 1]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_back_closes_pager_before_output() {
+    let mut model = create_test_model_with_pager();
+    update(&mut model, Message::Search);
+
+    update(&mut model, Message::Back);
+    update(&mut model, Message::Back);
+
+    assert_eq!(model.pager, None);
+    assert!(model.output.is_some());
+}
+
+#[test]
+fn test_next_field_follows_fill_order() {
+    let mut model = create_test_model_with_positional();
+    model.parameters.options.push(CLIArgument { key: String::from("--greeting"), name: String::from("GREETING"), ..Default::default() });
+
+    let mut visited = Vec::new();
+    for _ in 0..6 {
+        update(&mut model, Message::NextField);
+        visited.push((model.current_section, model.current_key_index));
+    }
+
+    assert_eq!(visited, [
+        (Section::Arguments, 1),
+        (Section::Positionals, 0),
+        (Section::Options, 0),
+        (Section::Flags, 0),
+        (Section::Arguments, 0),
+        (Section::Arguments, 1),
+    ]);
+}
+
+#[test]
+fn test_hidden_sections_are_skipped() {
+    let mut model = create_test_model_with_positional();
+    model.hidden_sections = vec![Section::Arguments, Section::Flags];
+    model.select_first_section();
+
+    update(&mut model, Message::Move(Direction::Right));
+    update(&mut model, Message::NextField);
+
+    assert_eq!((model.current_section, model.current_key_index), (Section::Positionals, 0));
+}
+
+#[test]
+fn test_previous_field_wraps_to_last_field() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::PreviousField);
+
+    assert_eq!((model.current_section, model.current_key_index), (Section::Flags, 0));
+}
+
+#[test]
+fn test_derived_option_follows_fields() {
+    let mut model = create_test_model();
+    model.parameters.add_derived("--output", "{name}.log");
+    model.current_section = Section::Arguments;
+    model.current_key_index = 0;
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'x'));
+
+    assert_eq!(model.parameters.options.last().unwrap().values, vec![String::from("x.log")]);
+}
+
+#[test]
+fn test_derived_option_is_read_only() {
+    let mut model = create_test_model();
+    model.parameters.add_derived("--output", "out.log");
+    model.current_section = Section::Options;
+    model.current_key_index = model.parameters.options.len() - 1;
+
+    update_selected(&mut model, |field| Message::TextEdit(field, 'x'));
+
+    assert_eq!(model.parameters.options.last().unwrap().values, vec![String::from("out.log")]);
+    assert_eq!(model.error, Some(String::from("OUTPUT is computed from out.log")));
+}
+
+#[test]
+fn test_stats_view_is_requested_and_closed() {
+    let mut model = create_test_model();
+
+    update(&mut model, Message::TogglePopup(Popup::Stats));
+    assert_eq!(model.commands, vec![AppCommand::ShowStats]);
+
+    model.stats = Some(Vec::new());
+    update(&mut model, Message::Back);
+    assert_eq!(model.stats, None);
+}
+
+#[test]
+fn test_home_and_end_jump_in_section() {
+    let mut model = create_test_model();
+    model.current_section = Section::Arguments;
+
+    update(&mut model, Message::Bottom);
+    assert_eq!(model.current_key_index, 1);
+
+    update(&mut model, Message::Top);
+    assert_eq!(model.current_key_index, 0);
+}
+
+#[test]
+fn test_page_down_and_up_in_section() {
+    let mut model = create_test_model();
+    model.parameters.options = (0..10).map(|index| CLIArgument { key: format!("--option-{index}"), ..Default::default() }).collect();
+    model.current_section = Section::Options;
+    model.current_key_index = 0;
+    model.section_page_height.set(4);
+
+    update(&mut model, Message::PageDown);
+    assert_eq!(model.current_key_index, 4);
+
+    update(&mut model, Message::PageDown);
+    update(&mut model, Message::PageDown);
+    assert_eq!(model.current_key_index, 9);
+
+    update(&mut model, Message::PageUp);
+    assert_eq!(model.current_key_index, 5);
+}
+
+#[test]
+fn test_toggle_boolean_option() {
+    let mut model = create_test_model();
+    model.parameters.options.push(CLIArgument { key: String::from("--enabled"), name: String::from("BOOL"), value_type: ValueType::Bool, ..Default::default() });
+    model.current_section = Section::Options;
+    model.current_key_index = 0;
+
+    update_selected(&mut model, Message::Toggle);
+    assert_eq!(convert_to_cli(&model.parameters).get_args().collect::<Vec<_>>(), ["--enabled", "true", "--count", "1"]);
+
+    update_selected(&mut model, Message::Toggle);
+    assert_eq!(model.parameters.options[0].values, vec![String::from("false")]);
+}
+
+#[test]
+fn test_civil_from_days() {
+    assert_eq!(civil_from_days(0), (1970, 1, 1));
+    assert_eq!(civil_from_days(19844), (2024, 5, 1));
 }
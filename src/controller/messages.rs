@@ -1,9 +1,10 @@
-use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers, KeyEventKind};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
 
 use crate::{
-    model::{Model, Section},
+    keymap::Action,
+    model::{FieldId, Mode, Model, Popup, Section},
     parsing::{
-        CLIArgument, CLIFlag, CLILib, CLIParameters
+        CLIArgument, CLIFlag, CLILib, CLIParameters, ValueType
     }
 };
 
@@ -15,33 +16,234 @@ pub enum Direction {
     Right,
 }
 
+/// Where the text cursor moves within the edited value
+#[derive(Debug, PartialEq)]
+pub enum CursorMove {
+    Left,
+    Right,
+    /// To the previous line of the value editor
+    Up,
+    /// To the next line of the value editor
+    Down,
+    Home,
+    End,
+}
+
+/// Changes to the model, parameters are addressed by their id instead of the selection
+/// so messages from other sources than the keyboard cannot change the parameter the user is editing
 #[derive(Debug, PartialEq)]
 pub enum Message {
     Move(Direction),
-    TextEdit(char),
-    RemoveText,
-    Toggle,
+    /// Insert the character at the text cursor
+    TextEdit(FieldId, char),
+    /// Remove the character before the text cursor
+    RemoveText(FieldId),
+    /// Remove the character after the text cursor
+    RemoveNextText(FieldId),
+    MoveCursor(FieldId, CursorMove),
+    AddValue(FieldId),
+    /// Expand globs in the values of a file field when running, or stop expanding them
+    ToggleGlob(FieldId),
+    /// Open the value editor for the last value of the parameter
+    Expand(FieldId),
+    /// Open the file picker for the last value of the parameter
+    PickFile(FieldId),
+    /// Open the dropdown choosing among the possible values of the parameter
+    OpenDropdown(FieldId),
+    Toggle(FieldId),
+    Increment(FieldId),
+    Decrement(FieldId),
+    /// Type into the search query of the pager or the filter of the form
+    SearchText(char),
+    RemoveSearchText,
+    Select,
+    Follow,
+    Pager,
+    ExternalPager,
+    Search,
+    /// Open the overlay jumping to any parameter
+    Jump,
+    /// Select the parameter under the mouse, flags and yes/no arguments are toggled as well
+    Click(FieldId),
+    /// Select the subcommand under the mouse, or enter it if it is selected already
+    ClickSubcommand(usize),
+    NextMatch,
+    Top,
+    Bottom,
+    PageUp,
+    PageDown,
+    Back,
+    NextField,
+    PreviousField,
+    Share,
+    Screenshot,
+    /// Open the popup or close it if it is open
+    TogglePopup(Popup),
     Run,
+    TestRun,
+    RunInTerminal,
     Quit,
 }
 
+#[allow(clippy::needless_return)]
 pub fn handle_key_event(key: KeyEvent, model: &Model) -> Option<Message>{
     if key.kind != KeyEventKind::Press {
         return None;
     }
 
-    match key.code {
-        KeyCode::Up => return Some(Message::Move(Direction::Up)),
-        KeyCode::Down => return Some(Message::Move(Direction::Down)),
-        KeyCode::Left => return Some(Message::Move(Direction::Left)),
-        KeyCode::Right => return Some(Message::Move(Direction::Right)),
-        KeyCode::Enter => return Some(Message::Run),
-        KeyCode::Char('q') | KeyCode::Char('Q') if key.modifiers == KeyModifiers::CONTROL => return Some(Message::Quit),
-        KeyCode::Char(' ') if model.current_section == Section::Flags => return Some(Message::Toggle),
-        KeyCode::Char(c) if model.current_section == Section::Arguments || model.current_section == Section::Options => return Some(Message::TextEdit(c)),
-        KeyCode::Backspace if model.current_section == Section::Arguments || model.current_section == Section::Options => return Some(Message::RemoveText),
+    let mode = model.mode();
+    // the value editor takes every key while it is open, Enter starts a new line and the expand keys save the value
+    if let Some(editor) = &model.value_editor {
+        let field = editor.field.clone();
+        if model.keymap.keys(Action::Expand).iter().any(|binding| binding.matches(key)) {
+            return Some(Message::Select);
+        }
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => return Some(Message::TextEdit(field, c)),
+            KeyCode::Enter => return Some(Message::TextEdit(field, '\n')),
+            KeyCode::Backspace => return Some(Message::RemoveText(field)),
+            KeyCode::Delete => return Some(Message::RemoveNextText(field)),
+            KeyCode::Left => return Some(Message::MoveCursor(field, CursorMove::Left)),
+            KeyCode::Right => return Some(Message::MoveCursor(field, CursorMove::Right)),
+            KeyCode::Up => return Some(Message::MoveCursor(field, CursorMove::Up)),
+            KeyCode::Down => return Some(Message::MoveCursor(field, CursorMove::Down)),
+            KeyCode::Home => return Some(Message::MoveCursor(field, CursorMove::Home)),
+            KeyCode::End => return Some(Message::MoveCursor(field, CursorMove::End)),
+            KeyCode::Esc => return Some(Message::Back),
+            _ => return None,
+        }
+    }
+    // the file picker takes every key while it is open, Left and Backspace go to the parent directory
+    if model.file_picker.is_some() {
+        match key.code {
+            KeyCode::Up => return Some(Message::Move(Direction::Up)),
+            KeyCode::Down => return Some(Message::Move(Direction::Down)),
+            KeyCode::Left | KeyCode::Backspace => return Some(Message::Move(Direction::Left)),
+            KeyCode::Right => return Some(Message::Move(Direction::Right)),
+            KeyCode::Enter => return Some(Message::Select),
+            KeyCode::Esc => return Some(Message::Back),
+            _ => return None,
+        }
+    }
+    // the dropdown takes every key while it is open, typing jumps to the next choice starting with the character
+    if model.dropdown.is_some() {
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => return Some(Message::SearchText(c)),
+            KeyCode::Up => return Some(Message::Move(Direction::Up)),
+            KeyCode::Down => return Some(Message::Move(Direction::Down)),
+            KeyCode::Enter => return Some(Message::Select),
+            KeyCode::Esc => return Some(Message::Back),
+            _ => return None,
+        }
+    }
+    // the finder takes every key while it is open, Up and Down choose the match
+    if model.finder.is_some() {
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => return Some(Message::SearchText(c)),
+            KeyCode::Backspace => return Some(Message::RemoveSearchText),
+            KeyCode::Up => return Some(Message::Move(Direction::Up)),
+            KeyCode::Down => return Some(Message::Move(Direction::Down)),
+            KeyCode::Enter => return Some(Message::Select),
+            KeyCode::Esc => return Some(Message::Back),
+            _ => return None,
+        }
+    }
+    // the search query of the pager and the filters of the output and the form are typed like a value, Enter confirms and Esc cancels them
+    let filtering_output = model.pager.is_none() && model.output.as_ref().is_some_and(|output| output.filtering);
+    if model.pager.as_ref().is_some_and(|pager| pager.searching) || filtering_output || model.filter.is_some() {
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => return Some(Message::SearchText(c)),
+            KeyCode::Backspace => return Some(Message::RemoveSearchText),
+            KeyCode::Enter => return Some(Message::Select),
+            KeyCode::Esc => return Some(Message::Back),
+            _ => return None,
+        }
+    }
+    // plain characters edit the selected value instead of triggering actions, booleans are toggled like flags
+    let selected_bool = model.get_selected_argument().is_some_and(|argument| argument.value_type == ValueType::Bool && argument.template.is_none());
+    let field = model.selected_field();
+    // values with possible values are chosen instead of typed, Backspace and Delete unset them
+    let selected_choices = field.as_ref().is_some_and(|field| model.choices(field).is_some());
+    if let Some(field) = field.clone().filter(|_| mode == Mode::Form && selected_choices) {
+        if matches!(key.code, KeyCode::Backspace | KeyCode::Delete) {
+            return Some(Message::RemoveText(field));
+        }
+    }
+    // while a value is edited the arrow keys, Home and End move the text cursor instead of the selection
+    if let Some(field) = field.clone().filter(|field| mode == Mode::Form && field.section != Section::Flags && !selected_bool && !selected_choices) {
+        let editing = model.text_cursor.as_ref().is_some_and(|cursor| cursor.field == field);
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => return Some(Message::TextEdit(field, c)),
+            KeyCode::Backspace => return Some(Message::RemoveText(field)),
+            KeyCode::Delete => return Some(Message::RemoveNextText(field)),
+            KeyCode::Left if editing => return Some(Message::MoveCursor(field, CursorMove::Left)),
+            KeyCode::Right if editing => return Some(Message::MoveCursor(field, CursorMove::Right)),
+            KeyCode::Home if editing => return Some(Message::MoveCursor(field, CursorMove::Home)),
+            KeyCode::End if editing => return Some(Message::MoveCursor(field, CursorMove::End)),
+            _ => (),
+        }
+    }
+
+    match model.keymap.action(key, mode)? {
+        Action::MoveUp => return Some(Message::Move(Direction::Up)),
+        Action::MoveDown => return Some(Message::Move(Direction::Down)),
+        Action::MoveLeft => return Some(Message::Move(Direction::Left)),
+        Action::MoveRight => return Some(Message::Move(Direction::Right)),
+        Action::Run | Action::TestRun | Action::RunInTerminal | Action::Pager | Action::ExternalPager if model.output.as_ref().is_some_and(|output| output.is_running()) => return None,
+        Action::Run => return Some(Message::Run),
+        Action::TestRun if model.parameters.flags.iter().any(|flag| flag.is_dry_run()) => return Some(Message::TestRun),
+        Action::TestRun => return None,
+        Action::RunInTerminal => return Some(Message::RunInTerminal),
+        Action::Toggle if selected_choices => return field.map(Message::OpenDropdown),
+        Action::Toggle => return field.filter(|field| field.section == Section::Flags || selected_bool).map(Message::Toggle),
+        Action::Increment => return field.filter(|field| field.section == Section::Flags || selected_choices).map(Message::Increment),
+        Action::Decrement => return field.filter(|field| field.section == Section::Flags || selected_choices).map(Message::Decrement),
+        Action::AddValue => return field.map(Message::AddValue),
+        Action::ToggleGlob => return field.filter(|_| model.get_selected_glob().is_some()).map(Message::ToggleGlob),
+        Action::Expand => return field.filter(|field| field.section != Section::Flags && !selected_bool && !selected_choices).map(Message::Expand),
+        Action::PickFile => return field.filter(|_| model.selected_takes_files()).map(Message::PickFile),
+        Action::Select => return Some(Message::Select),
+        Action::Follow => return Some(Message::Follow),
+        Action::Pager => return Some(Message::Pager),
+        Action::ExternalPager => return Some(Message::ExternalPager),
+        Action::Search => return Some(Message::Search),
+        Action::Jump => return Some(Message::Jump),
+        Action::NextMatch => return Some(Message::NextMatch),
+        Action::Top => return Some(Message::Top),
+        Action::Bottom => return Some(Message::Bottom),
+        Action::PageUp => return Some(Message::PageUp),
+        Action::PageDown => return Some(Message::PageDown),
+        Action::Back if matches!(mode, Mode::Help | Mode::Argv | Mode::Stats | Mode::Pager) || model.can_go_back() => return Some(Message::Back),
+        Action::Back => return None,
+        Action::NextField => return Some(Message::NextField),
+        Action::PreviousField => return Some(Message::PreviousField),
+        Action::Share => return Some(Message::Share),
+        Action::Screenshot => return Some(Message::Screenshot),
+        Action::Argv => return Some(Message::TogglePopup(Popup::Argv)),
+        Action::Stats => return Some(Message::TogglePopup(Popup::Stats)),
+        Action::Help => return Some(Message::TogglePopup(Popup::Help)),
+        Action::Quit => return Some(Message::Quit),
+    }
+}
+
+/// Clicks select what is under the mouse in the lists of the last render, the wheel moves like the arrow keys
+#[allow(clippy::needless_return)]
+pub fn handle_mouse_event(mouse: MouseEvent, model: &Model) -> Option<Message> {
+    match mouse.kind {
+        // the selection of the form is hidden while the filter is typed
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown if model.filter.is_some() => return None,
+        // the value editor stays on its parameter until it is closed
+        _ if model.value_editor.is_some() => return None,
+        MouseEventKind::ScrollUp => return Some(Message::Move(Direction::Up)),
+        MouseEventKind::ScrollDown => return Some(Message::Move(Direction::Down)),
+        // the finder and the popups cover the lists
+        MouseEventKind::Down(MouseButton::Left) if model.finder.is_none() && model.file_picker.is_none() && model.dropdown.is_none() => (),
         _ => return None,
     }
+    match model.list_item_at(mouse.column, mouse.row)? {
+        (Some(section), index) => return model.field_id(section, index).map(Message::Click),
+        (None, index) => return Some(Message::ClickSubcommand(index)),
+    }
 }
 
 #[allow(dead_code)]
@@ -51,20 +253,22 @@ fn create_test_model() -> Model {
             key: String::from("--name"),
             name: String::from("NAME"),
             description: Some(String::from("Name to greet")),
-            value: String::new(),
+            ..Default::default()
         },
         CLIArgument {
             key: String::from("--count"),
             name: String::from("COUNT"),
             description: Some(String::from("Numeber of times to greet.")),
-            value: String::from("1"),
+            values: vec![String::from("1")],
+            ..Default::default()
         }
     ];
     let flags = vec![
         CLIFlag {
             key: String::from("--help"),
             description: Some(String::from("Print help")),
-            set: false
+            set: false,
+            ..Default::default()
         }
     ];
     let parameters = CLIParameters {
@@ -73,6 +277,7 @@ fn create_test_model() -> Model {
         flags,
         options: Vec::new(),
         cli_lib: CLILib::Clap,
+        ..Default::default()
     };
 
     Model::new(parameters)
@@ -113,10 +318,69 @@ fn test_char_pressed() {
 
     assert_eq!(
         message,
-        Some(Message::TextEdit('a'))
+        Some(Message::TextEdit(FieldId { section: Section::Arguments, key: String::from("--name") }, 'a'))
     );
 }
 
+#[test]
+fn test_arrows_move_text_cursor_while_editing() {
+    let key = KeyEvent::new_with_kind(KeyCode::Left, KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    let field = FieldId { section: Section::Arguments, key: String::from("--name") };
+
+    assert_eq!(handle_key_event(key, &model), Some(Message::Move(Direction::Left)));
+    model.text_cursor = Some(crate::model::TextCursor { field: field.clone(), position: 0 });
+    assert_eq!(handle_key_event(key, &model), Some(Message::MoveCursor(field, CursorMove::Left)));
+}
+
+#[test]
+fn test_value_editor_takes_keys() {
+    let key = |code| KeyEvent::new_with_kind(code, KeyModifiers::empty(), KeyEventKind::Press);
+    let expand = KeyEvent::new_with_kind(KeyCode::Char('o'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let mut model = create_test_model();
+    let field = FieldId { section: Section::Arguments, key: String::from("--name") };
+
+    assert_eq!(handle_key_event(expand, &model), Some(Message::Expand(field.clone())));
+    model.value_editor = Some(crate::editor::ValueEditor::new(field.clone(), ""));
+    assert_eq!(handle_key_event(key(KeyCode::Enter), &model), Some(Message::TextEdit(field.clone(), '\n')));
+    assert_eq!(handle_key_event(key(KeyCode::Up), &model), Some(Message::MoveCursor(field, CursorMove::Up)));
+    assert_eq!(handle_key_event(expand, &model), Some(Message::Select));
+    assert_eq!(handle_key_event(key(KeyCode::F(1)), &model), None);
+}
+
+#[test]
+fn test_file_picker_takes_keys() {
+    let key = |code| KeyEvent::new_with_kind(code, KeyModifiers::empty(), KeyEventKind::Press);
+    let browse = KeyEvent::new_with_kind(KeyCode::Char('b'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.parameters.positionals.push(crate::parsing::CLIPositional { name: String::from("FILE"), ..Default::default() });
+
+    assert_eq!(handle_key_event(browse, &model), None);
+    model.current_section = Section::Positionals;
+    let field = FieldId { section: Section::Positionals, key: String::from("FILE") };
+    assert_eq!(handle_key_event(browse, &model), Some(Message::PickFile(field.clone())));
+    model.file_picker = Some(crate::filepicker::FilePicker::open(field, "", &std::env::temp_dir()).unwrap());
+    assert_eq!(handle_key_event(key(KeyCode::Backspace), &model), Some(Message::Move(Direction::Left)));
+    assert_eq!(handle_key_event(key(KeyCode::Enter), &model), Some(Message::Select));
+    assert_eq!(handle_key_event(key(KeyCode::Char('a')), &model), None);
+}
+
+#[test]
+fn test_possible_values_are_chosen_instead_of_typed() {
+    let key = |code| KeyEvent::new_with_kind(code, KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.parameters.arguments[0].choices = vec![String::from("Ferris"), String::from("Corro")];
+    let field = FieldId { section: Section::Arguments, key: String::from("--name") };
+
+    assert_eq!(handle_key_event(key(KeyCode::Char(' ')), &model), Some(Message::OpenDropdown(field.clone())));
+    assert_eq!(handle_key_event(key(KeyCode::Char('+')), &model), Some(Message::Increment(field.clone())));
+    assert_eq!(handle_key_event(key(KeyCode::Char('a')), &model), None);
+    assert_eq!(handle_key_event(key(KeyCode::Backspace), &model), Some(Message::RemoveText(field.clone())));
+    model.dropdown = Some(crate::model::Dropdown { field, selected: 0 });
+    assert_eq!(handle_key_event(key(KeyCode::Char('c')), &model), Some(Message::SearchText('c')));
+    assert_eq!(handle_key_event(key(KeyCode::Enter), &model), Some(Message::Select));
+}
+
 #[test]
 fn test_number_pressed() {
     let key = KeyEvent::new_with_kind(KeyCode::Char('1'), KeyModifiers::empty(), KeyEventKind::Press);
@@ -126,7 +390,7 @@ fn test_number_pressed() {
 
     assert_eq!(
         message,
-        Some(Message::TextEdit('1'))
+        Some(Message::TextEdit(FieldId { section: Section::Arguments, key: String::from("--name") }, '1'))
     );
 }
 
@@ -144,6 +408,21 @@ fn test_char_pressed_during_flag_section() {
     );
 }
 
+#[test]
+fn test_char_pressed_during_positional_section() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('a'), KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.parameters.positionals.push(crate::parsing::CLIPositional { name: String::from("FILE"), ..Default::default() });
+    model.current_section = Section::Positionals;
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::TextEdit(FieldId { section: Section::Positionals, key: String::from("FILE") }, 'a'))
+    );
+}
+
 #[test]
 fn test_space_pressed_during_flag_section() {
     let key = KeyEvent::new_with_kind(KeyCode::Char(' '), KeyModifiers::empty(), KeyEventKind::Press);
@@ -154,7 +433,7 @@ fn test_space_pressed_during_flag_section() {
 
     assert_eq!(
         message,
-        Some(Message::Toggle),
+        Some(Message::Toggle(FieldId { section: Section::Flags, key: String::from("--help") })),
     );
 }
 
@@ -183,3 +462,246 @@ fn test_ctrl_and_upper_q_pressed() {
         Some(Message::Quit)
     );
 }
+
+#[test]
+fn test_enter_pressed_in_subcommand_picker() {
+    let key = KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.selecting_subcommand = true;
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Select)
+    );
+}
+
+#[test]
+fn test_escape_pressed_without_parent() {
+    let key = KeyEvent::new_with_kind(KeyCode::Esc, KeyModifiers::empty(), KeyEventKind::Press);
+    let model = create_test_model();
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        None
+    );
+}
+
+#[test]
+fn test_question_mark_pressed_during_flag_section() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('?'), KeyModifiers::SHIFT, KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.current_section = Section::Flags;
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::TogglePopup(Popup::Help))
+    );
+}
+
+#[test]
+fn test_question_mark_pressed_during_argument_section() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('?'), KeyModifiers::SHIFT, KeyEventKind::Press);
+    let model = create_test_model();
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::TextEdit(FieldId { section: Section::Arguments, key: String::from("--name") }, '?'))
+    );
+}
+
+#[test]
+fn test_escape_pressed_in_help() {
+    let key = KeyEvent::new_with_kind(KeyCode::Esc, KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.showing_help = true;
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Back)
+    );
+}
+
+#[test]
+fn test_remapped_key_pressed() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('x'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let mut model = create_test_model();
+    let config = std::collections::BTreeMap::from([(String::from("quit"), vec![String::from("Ctrl+X")])]);
+    model.keymap = crate::keymap::Keymap::from_config(&config).unwrap();
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Quit)
+    );
+}
+
+#[test]
+fn test_f_pressed_in_output() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('f'), KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.output = Some(crate::output::OutputPane::new(String::from("greeter.exe")));
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Follow)
+    );
+}
+
+#[test]
+fn test_ctrl_and_t_pressed_without_dry_run_flag() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('t'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let model = create_test_model();
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        None
+    );
+}
+
+#[test]
+fn test_ctrl_and_t_pressed_with_dry_run_flag() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('t'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.parameters.flags.push(CLIFlag { key: String::from("--dry-run"), ..Default::default() });
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::TestRun)
+    );
+}
+
+#[test]
+fn test_enter_pressed_while_output_is_running() {
+    let key = KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.output = Some(crate::output::OutputPane::new(String::from("greeter.exe")));
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        None
+    );
+}
+
+#[test]
+fn test_slash_pressed_during_flag_section() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('/'), KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.current_section = Section::Flags;
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Search)
+    );
+}
+
+#[test]
+fn test_ctrl_and_f_pressed_during_argument_section() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('f'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let model = create_test_model();
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Search)
+    );
+}
+
+#[test]
+fn test_ctrl_and_p_pressed() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('p'), KeyModifiers::CONTROL, KeyEventKind::Press);
+    let model = create_test_model();
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Jump)
+    );
+}
+
+#[test]
+fn test_arrow_down_pressed_in_finder() {
+    let key = KeyEvent::new_with_kind(KeyCode::Down, KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.finder = Some(crate::finder::Finder::default());
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::Move(Direction::Down))
+    );
+}
+
+#[test]
+fn test_char_pressed_while_filtering() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('a'), KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.filter = Some(String::new());
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::SearchText('a'))
+    );
+}
+
+#[test]
+fn test_n_pressed_while_searching_in_pager() {
+    let key = KeyEvent::new_with_kind(KeyCode::Char('n'), KeyModifiers::empty(), KeyEventKind::Press);
+    let mut model = create_test_model();
+    model.output = Some(crate::output::OutputPane::new(String::from("greeter.exe")));
+    model.pager = Some(crate::pager::Pager { searching: true, ..Default::default() });
+
+    let message = handle_key_event(key, &model);
+
+    assert_eq!(
+        message,
+        Some(Message::SearchText('n'))
+    );
+}
+
+#[test]
+fn test_left_click_on_flag() {
+    use ratatui::layout::Rect;
+    use crate::model::{FieldId, ListRows};
+
+    let model = create_test_model();
+    model.list_rows.borrow_mut().push(ListRows { section: Some(Section::Flags), area: Rect::new(40, 5, 20, 10), indices: vec![0] });
+    let click = |column, row| MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column, row, modifiers: KeyModifiers::empty() };
+
+    assert_eq!(handle_mouse_event(click(45, 5), &model), Some(Message::Click(FieldId { section: Section::Flags, key: String::from("--help") })));
+    assert_eq!(handle_mouse_event(click(45, 6), &model), None);
+    assert_eq!(handle_mouse_event(click(10, 5), &model), None);
+}
+
+#[test]
+fn test_mouse_wheel_moves() {
+    let model = create_test_model();
+    let scroll = MouseEvent { kind: MouseEventKind::ScrollDown, column: 0, row: 0, modifiers: KeyModifiers::empty() };
+
+    assert_eq!(handle_mouse_event(scroll, &model), Some(Message::Move(Direction::Down)));
+}
@@ -0,0 +1,194 @@
+use std::{env, thread, time::Duration};
+
+use crate::parsing::{self, CLIParameters};
+
+/// Hidden first argument that makes clitui behave like the greeter of the demo
+pub const GREETER_FLAG: &str = "--demo-greeter";
+
+/// Pause between the printed lines, so following the output can be watched
+const LINE_DELAY: Duration = Duration::from_millis(150);
+
+/// Help of the bundled greeter, touching every kind of field the form has
+pub const DEMO_HELP: &str = "Simple program to greet a person
+
+Usage: greeter [OPTIONS] --first-name <FIRST_NAME> --last-name <LAST_NAME> [FRIENDS]...
+
+Arguments:
+  [FRIENDS]...  Friends to greet as well
+
+Options:
+  -f, --first-name <FIRST_NAME>  First name of the person to greet
+  -l, --last-name <LAST_NAME>    Last name of the person to greet
+  -c, --count <COUNT>            Number of times to greet [default: 1]
+      --color=<WHEN>             When to color the greeting [default: auto] [possible values: auto, always, never]
+      --caps                     Greet in caps
+      --german                   Greet in german
+      --json                     Print the greeting as JSON, cannot be used with --yaml
+      --yaml                     Print the greeting as YAML
+  -n, --dry-run                  Only print who would be greeted
+  -v, --verbose...               Explain what is done, more often for more details
+  -h, --help                     Print help
+  -V, --version                  Print version";
+
+/// Command running the bundled greeter, clitui itself with the hidden greeter flag
+pub fn greeter_command() -> Vec<String> {
+    let program = env::current_exe().map_or_else(|_| String::from("clitui"), |path| path.to_string_lossy().to_string());
+    vec![program, GREETER_FLAG.to_string()]
+}
+
+/// Parameters of the bundled greeter, read from its embedded help instead of running it
+pub fn parameters() -> CLIParameters {
+    let mut parameters = parsing::parse_help_string(DEMO_HELP).unwrap_or_default();
+    parameters.version = Some(String::from("1.0.0"));
+    parameters
+}
+
+/// Value of the option, from `--key=value` or the next argument
+#[allow(clippy::needless_return)]
+fn option_value(key: &str, inline: Option<&str>, args: &mut impl Iterator<Item = String>, usage: &str) -> Result<String, String> {
+    match inline {
+        Some(value) => return Ok(value.to_string()),
+        None => return args.next().ok_or(format!("error: a value is required for '{key} {usage}' but none was supplied")),
+    }
+}
+
+/// Output lines of the greeter for the arguments, or its error message
+/// Errors are worded like the ones of clap, so the failed field is selected
+fn greet(args: &[String]) -> Result<Vec<String>, String> {
+    let mut first_name = None;
+    let mut last_name = None;
+    let mut count = 1;
+    let (mut caps, mut german, mut json, mut yaml, mut dry_run) = (false, false, false, false, false);
+    let mut verbose = 0;
+    let mut friends = Vec::new();
+    let mut args = args.iter().cloned();
+    while let Some(arg) = args.next() {
+        let (key, inline) = match arg.split_once('=') {
+            Some((key, value)) if key.starts_with("--") => (key.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        match key.as_str() {
+            "-f" | "--first-name" => first_name = Some(option_value("--first-name", inline, &mut args, "<FIRST_NAME>")?),
+            "-l" | "--last-name" => last_name = Some(option_value("--last-name", inline, &mut args, "<LAST_NAME>")?),
+            "-c" | "--count" => {
+                let value = option_value("--count", inline, &mut args, "<COUNT>")?;
+                count = value.parse().map_err(|_| format!("error: invalid value '{value}' for '--count <COUNT>': not a number"))?;
+            },
+            "--color" => {
+                let value = option_value("--color", inline, &mut args, "<WHEN>")?;
+                if !["auto", "always", "never"].contains(&value.as_str()) {
+                    return Err(format!("error: invalid value '{value}' for '--color <WHEN>'"));
+                }
+            },
+            "--caps" => caps = true,
+            "--german" => german = true,
+            "--json" => json = true,
+            "--yaml" => yaml = true,
+            "-n" | "--dry-run" => dry_run = true,
+            "-v" | "--verbose" => verbose += 1,
+            "-h" | "--help" => return Ok(DEMO_HELP.lines().map(String::from).collect()),
+            "-V" | "--version" => return Ok(vec![String::from("greeter 1.0.0")]),
+            "--" => friends.extend(args.by_ref()),
+            _ if arg.starts_with('-') => return Err(format!("error: unexpected argument '{key}' found")),
+            _ => friends.push(arg),
+        }
+    }
+    if json && yaml {
+        return Err(String::from("error: the argument '--json' cannot be used with '--yaml'"));
+    }
+    let missing: Vec<&str> = [(&first_name, "--first-name <FIRST_NAME>"), (&last_name, "--last-name <LAST_NAME>")]
+        .into_iter()
+        .filter(|(name, _)| name.is_none())
+        .map(|(_, usage)| usage)
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("error: the following required arguments were not provided:\n  {}", missing.join("\n  ")));
+    }
+    let names: Vec<String> = [format!("{} {}", first_name.unwrap_or_default(), last_name.unwrap_or_default())].into_iter().chain(friends).collect();
+    let mut lines = Vec::new();
+    if verbose > 0 {
+        lines.push(format!("Greeting {} people {count} times", names.len()));
+    }
+    if dry_run {
+        lines.extend(names.iter().map(|name| format!("Would greet {name}")));
+        return Ok(lines);
+    }
+    let greeting = if german { "Hallo" } else { "Hello" };
+    for round in 1..=count {
+        if verbose > 1 {
+            lines.push(format!("Round {round} of {count}"));
+        }
+        for name in &names {
+            let text = format!("{greeting} {name}!");
+            let text = if caps { text.to_uppercase() } else { text };
+            match (json, yaml) {
+                (true, _) => lines.push(format!("{{\"greeting\": \"{text}\"}}")),
+                (_, true) => lines.push(format!("greeting: {text}")),
+                _ => lines.push(text),
+            }
+        }
+    }
+    Ok(lines)
+}
+
+/// Run the greeter with the arguments after the hidden flag, returns its exit code
+#[allow(clippy::needless_return)]
+pub fn run_greeter(args: &[String]) -> i32 {
+    match greet(args) {
+        Ok(lines) => {
+            for line in lines {
+                println!("{line}");
+                thread::sleep(LINE_DELAY);
+            }
+            return 0;
+        },
+        Err(error) => {
+            eprintln!("{error}");
+            return 2;
+        },
+    }
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[test]
+fn test_demo_parameters() {
+    let parameters = parameters();
+
+    assert_eq!(parameters.cli_name, "greeter");
+    assert_eq!(parameters.arguments.len(), 2);
+    assert_eq!(parameters.positionals.len(), 1);
+    assert!(parameters.options.iter().any(|option| option.key == "--color" && option.joined));
+    assert!(parameters.flags.iter().any(|flag| flag.key == "--verbose" && flag.multiple));
+    assert_eq!(parameters.exclusive_groups, vec![vec![String::from("--json"), String::from("--yaml")]]);
+}
+
+#[test]
+fn test_greet() {
+    let args = create_test_args(&["--first-name", "Ferris", "--last-name=Crab", "--count", "2", "--german", "-v", "Tux"]);
+
+    assert_eq!(greet(&args), Ok(create_test_args(&[
+        "Greeting 2 people 2 times",
+        "Hallo Ferris Crab!",
+        "Hallo Tux!",
+        "Hallo Ferris Crab!",
+        "Hallo Tux!",
+    ])));
+}
+
+#[test]
+fn test_greet_errors_select_the_failed_field() {
+    let args = create_test_args(&["-f", "Ferris", "-l", "Crab", "--count", "two"]);
+    let error = greet(&args).unwrap_err();
+
+    assert_eq!(crate::fixup::find_failed_key(&error), Some(String::from("--count")));
+    assert_eq!(
+        greet(&create_test_args(&["-f", "Ferris"])),
+        Err(String::from("error: the following required arguments were not provided:\n  --last-name <LAST_NAME>"))
+    );
+}
@@ -0,0 +1,89 @@
+/// Part of a character diff between two values
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DiffSpan {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Longer values are shown as completely replaced instead of comparing every character
+const MAX_DIFF_LEN: usize = 512;
+
+/// Character diff from the old to the new value, based on their longest common subsequence
+pub fn diff_chars(old: &str, new: &str) -> Vec<DiffSpan> {
+    let old: Vec<char> = old.chars().collect();
+    let new: Vec<char> = new.chars().collect();
+    if old.len() > MAX_DIFF_LEN || new.len() > MAX_DIFF_LEN {
+        let mut spans = Vec::new();
+        push_char(&mut spans, DiffSpan::Removed(old.iter().collect()));
+        push_char(&mut spans, DiffSpan::Added(new.iter().collect()));
+        return spans;
+    }
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut spans = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            push_char(&mut spans, DiffSpan::Same(old[i].to_string()));
+            (i, j) = (i + 1, j + 1);
+        } else if i < old.len() && (j == new.len() || common[i + 1][j] >= common[i][j + 1]) {
+            push_char(&mut spans, DiffSpan::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            push_char(&mut spans, DiffSpan::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    spans
+}
+
+/// Append the text to the last span if it is of the same kind
+fn push_char(spans: &mut Vec<DiffSpan>, span: DiffSpan) {
+    match (spans.last_mut(), span) {
+        (_, DiffSpan::Same(text) | DiffSpan::Added(text) | DiffSpan::Removed(text)) if text.is_empty() => (),
+        (Some(DiffSpan::Same(last)), DiffSpan::Same(text))
+        | (Some(DiffSpan::Added(last)), DiffSpan::Added(text))
+        | (Some(DiffSpan::Removed(last)), DiffSpan::Removed(text)) => last.push_str(&text),
+        (_, span) => spans.push(span),
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_diff_chars_added_suffix() {
+    assert_eq!(
+        diff_chars("~/.config/tool.toml", "~/.config/tool.local.toml"),
+        vec![
+            DiffSpan::Same(String::from("~/.config/tool.")),
+            DiffSpan::Added(String::from("local.")),
+            DiffSpan::Same(String::from("toml")),
+        ],
+    );
+}
+
+#[test]
+fn test_diff_chars_replaced() {
+    assert_eq!(
+        diff_chars("info", "debug"),
+        vec![
+            DiffSpan::Removed(String::from("info")),
+            DiffSpan::Added(String::from("debug")),
+        ],
+    );
+}
+
+#[test]
+fn test_diff_chars_equal() {
+    assert_eq!(diff_chars("10", "10"), vec![DiffSpan::Same(String::from("10"))]);
+}
@@ -0,0 +1,168 @@
+use crate::model::FieldId;
+
+/// Byte index of the character at the position, the length of the text past its end
+pub fn byte_index(text: &str, position: usize) -> usize {
+    text.char_indices().nth(position).map_or(text.len(), |(index, _)| index)
+}
+
+/// Popup editing a long value over several lines, e.g. a commit message or JSON
+#[derive(Debug, PartialEq)]
+pub struct ValueEditor {
+    /// Parameter whose last value is edited, it is written back once the editor is confirmed
+    pub field: FieldId,
+    /// Lines of the value, at least one
+    pub lines: Vec<String>,
+    /// Line of the cursor
+    pub row: usize,
+    /// Characters of the line before the cursor
+    pub column: usize,
+}
+
+impl ValueEditor {
+    /// Editor of the value with the cursor at its end
+    pub fn new(field: FieldId, value: &str) -> ValueEditor {
+        let lines: Vec<String> = value.split('\n').map(str::to_string).collect();
+        let row = lines.len() - 1;
+        let column = lines[row].chars().count();
+        ValueEditor { field, lines, row, column }
+    }
+
+    /// The value with its lines joined by newlines
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_length(&self, row: usize) -> usize {
+        self.lines[row].chars().count()
+    }
+
+    /// Insert the character at the cursor, a newline splits the line
+    pub fn insert(&mut self, ch: char) {
+        let index = byte_index(&self.lines[self.row], self.column);
+        if ch == '\n' {
+            let rest = self.lines[self.row].split_off(index);
+            self.lines.insert(self.row + 1, rest);
+            self.row += 1;
+            self.column = 0;
+        } else {
+            self.lines[self.row].insert(index, ch);
+            self.column += 1;
+        }
+    }
+
+    /// Remove the character before the cursor, at the start of a line the line is joined to the previous one
+    pub fn remove_before(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+            let index = byte_index(&self.lines[self.row], self.column);
+            self.lines[self.row].remove(index);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.column = self.line_length(self.row);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    /// Remove the character after the cursor, at the end of a line the next line is joined to it
+    pub fn remove_after(&mut self) {
+        if self.column < self.line_length(self.row) {
+            let index = byte_index(&self.lines[self.row], self.column);
+            self.lines[self.row].remove(index);
+        } else if self.row + 1 < self.lines.len() {
+            let line = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    /// Move the cursor one character back, from the start of a line to the end of the previous one
+    pub fn move_left(&mut self) {
+        if self.column > 0 {
+            self.column -= 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.column = self.line_length(self.row);
+        }
+    }
+
+    /// Move the cursor one character forward, from the end of a line to the start of the next one
+    pub fn move_right(&mut self) {
+        if self.column < self.line_length(self.row) {
+            self.column += 1;
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.column = 0;
+        }
+    }
+
+    /// Move the cursor to the previous line, at most to its end
+    pub fn move_up(&mut self) {
+        if self.row > 0 {
+            self.row -= 1;
+            self.column = self.column.min(self.line_length(self.row));
+        }
+    }
+
+    /// Move the cursor to the next line, at most to its end
+    pub fn move_down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.column = self.column.min(self.line_length(self.row));
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.column = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.column = self.line_length(self.row);
+    }
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_editor(value: &str) -> ValueEditor {
+    ValueEditor::new(FieldId { section: crate::model::Section::Arguments, key: String::from("--message") }, value)
+}
+
+#[test]
+fn test_editor_splits_and_joins_lines() {
+    let mut editor = create_test_editor("Fix parser");
+
+    editor.move_home();
+    editor.move_right();
+    editor.move_right();
+    editor.move_right();
+    editor.insert('\n');
+    editor.move_end();
+    editor.insert('\n');
+    "Details ü".chars().for_each(|ch| editor.insert(ch));
+
+    assert_eq!(editor.text(), "Fix\n parser\nDetails ü");
+    assert_eq!((editor.row, editor.column), (2, 9));
+    editor.move_up();
+    editor.move_up();
+    editor.move_end();
+    editor.remove_after();
+    editor.remove_before();
+    assert_eq!(editor.text(), "Fi parser\nDetails ü");
+    assert_eq!((editor.row, editor.column), (0, 2));
+}
+
+#[test]
+fn test_editor_moves_across_lines() {
+    let mut editor = create_test_editor("{\n  \"a\": 1\n}");
+
+    editor.move_left();
+    editor.move_left();
+    assert_eq!((editor.row, editor.column), (1, 8));
+    editor.move_right();
+    editor.move_right();
+    assert_eq!((editor.row, editor.column), (2, 1));
+    editor.move_down();
+    editor.move_home();
+    editor.remove_before();
+    assert_eq!(editor.text(), "{\n  \"a\": 1}");
+}
@@ -0,0 +1,146 @@
+use std::{iter::Peekable, str::Chars};
+
+/// Whether the character can be part of a number or an arithmetic expression
+pub fn is_number_char(ch: char) -> bool {
+    ch.is_ascii_digit() || ch == ' ' || "+-*/().".contains(ch)
+}
+
+/// Whether the value is an arithmetic expression rather than a literal number,
+/// e.g. `4*1024` or `60 * 15` but neither `-5` nor `main.rs`
+pub fn is_expression(value: &str) -> bool {
+    let value = value.trim();
+    value.chars().all(is_number_char)
+        && value.chars().skip(1).any(|ch| "+-*/()".contains(ch))
+        && value.chars().any(|ch| ch.is_ascii_digit())
+}
+
+/// Whether the value is a plain integer or decimal like `-5` or `1.5`, unlike `1e6`, `inf` or `NaN` that also parse as floats
+pub fn is_plain_number(value: &str) -> bool {
+    let unsigned = value.strip_prefix('-').unwrap_or(value);
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, "0"));
+    [whole, fraction].iter().all(|digits| !digits.is_empty() && digits.chars().all(|ch| ch.is_ascii_digit()))
+}
+
+/// Whether a number field may pass the value: an expression, a number in any notation like `1e6`
+/// or one followed by a unit like `4K`
+pub fn is_number_value(value: &str) -> bool {
+    let number = value.trim_end_matches(|ch: char| ch.is_ascii_alphabetic());
+    is_expression(value)
+        || number.starts_with(|ch: char| ch.is_ascii_digit() || "+-.".contains(ch)) && number.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+/// Evaluate `+`, `-`, `*`, `/` and parentheses into the literal value,
+/// whole numbers are written without a fraction
+pub fn evaluate(expression: &str) -> Result<String, String> {
+    let mut parser = Parser { chars: expression.chars().peekable() };
+    let result = parser.sum()?;
+    parser.skip_whitespace();
+    if let Some(ch) = parser.chars.next() {
+        return Err(format!("unexpected '{ch}'"));
+    }
+    if !result.is_finite() {
+        return Err(String::from("division by zero"));
+    }
+    if result.fract() == 0.0 && result.abs() < 1e15 {
+        return Ok(format!("{}", result as i64));
+    }
+    Ok(result.to_string())
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+    }
+
+    fn next_operator(&mut self, operators: &str) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.next_if(|ch| operators.contains(*ch))
+    }
+
+    fn sum(&mut self) -> Result<f64, String> {
+        let mut result = self.product()?;
+        while let Some(operator) = self.next_operator("+-") {
+            match operator {
+                '+' => result += self.product()?,
+                _ => result -= self.product()?,
+            }
+        }
+        Ok(result)
+    }
+
+    fn product(&mut self) -> Result<f64, String> {
+        let mut result = self.factor()?;
+        while let Some(operator) = self.next_operator("*/") {
+            match operator {
+                '*' => result *= self.factor()?,
+                _ => result /= self.factor()?,
+            }
+        }
+        Ok(result)
+    }
+
+    fn factor(&mut self) -> Result<f64, String> {
+        if self.next_operator("-").is_some() {
+            return Ok(-self.factor()?);
+        }
+        if self.next_operator("(").is_some() {
+            let result = self.sum()?;
+            return match self.next_operator(")") {
+                Some(_) => Ok(result),
+                None => Err(String::from("missing ')'")),
+            };
+        }
+        let mut number = String::new();
+        while let Some(ch) = self.chars.next_if(|ch| ch.is_ascii_digit() || *ch == '.') {
+            number.push(ch);
+        }
+        if number.is_empty() {
+            return match self.chars.peek() {
+                Some(ch) => Err(format!("expected a number instead of '{ch}'")),
+                None => Err(String::from("expected a number at the end")),
+            };
+        }
+        number.parse().map_err(|_| format!("invalid number {number}"))
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_is_expression() {
+    assert!(is_expression("4*1024"));
+    assert!(is_expression("(1 + 2) / 3"));
+    assert!(!is_expression("-5"));
+    assert!(!is_expression("1.5"));
+    assert!(!is_expression("main.rs"));
+}
+
+#[test]
+fn test_is_number_value() {
+    assert!(is_plain_number("-5") && is_plain_number("1.5"));
+    assert!(!is_plain_number("1e6") && !is_plain_number("inf") && !is_plain_number("NaN") && !is_plain_number("1."));
+    assert!(is_number_value("4K") && is_number_value("1e6") && is_number_value("4*1024") && is_number_value("-.5"));
+    assert!(!is_number_value("ten") && !is_number_value("inf") && !is_number_value("1\n"));
+}
+
+#[test]
+fn test_evaluate_precedence() {
+    assert_eq!(evaluate("60*15 + 4*1024"), Ok(String::from("4996")));
+    assert_eq!(evaluate("-(1 + 2) * 3"), Ok(String::from("-9")));
+}
+
+#[test]
+fn test_evaluate_fraction() {
+    assert_eq!(evaluate("7/2"), Ok(String::from("3.5")));
+}
+
+#[test]
+fn test_evaluate_errors() {
+    assert_eq!(evaluate("4*"), Err(String::from("expected a number at the end")));
+    assert_eq!(evaluate("(1+2"), Err(String::from("missing ')'")));
+    assert_eq!(evaluate("1/0"), Err(String::from("division by zero")));
+}
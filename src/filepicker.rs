@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::model::FieldId;
+
+/// Entry of the directory shown by the file picker
+#[derive(Debug, PartialEq, Clone)]
+pub struct PickerEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+/// Popup browsing the directories for the value of a parameter taking paths
+#[derive(Debug, PartialEq)]
+pub struct FilePicker {
+    /// Parameter whose last value is replaced by the chosen path
+    pub field: FieldId,
+    /// Absolute path of the shown directory
+    pub directory: PathBuf,
+    /// `.` choosing the shown directory itself, `..` unless it is the root, then the directories and the files
+    pub entries: Vec<PickerEntry>,
+    /// Position of the highlighted entry
+    pub selected: usize,
+}
+
+/// Entries of the directory, directories first, each sorted by name ignoring case
+fn read_entries(directory: &Path) -> Result<Vec<PickerEntry>, String> {
+    let read = fs::read_dir(directory).map_err(|error| format!("Cannot read {}: {error}", directory.display()))?;
+    let mut entries: Vec<PickerEntry> = read
+        .filter_map(Result::ok)
+        .map(|entry| PickerEntry { name: entry.file_name().to_string_lossy().to_string(), is_dir: entry.path().is_dir() })
+        .collect();
+    entries.sort_by_key(|entry| (!entry.is_dir, entry.name.to_lowercase()));
+    let parent = directory.parent().map(|_| PickerEntry { name: String::from(".."), is_dir: true });
+    Ok([PickerEntry { name: String::from("."), is_dir: true }].into_iter().chain(parent).chain(entries).collect())
+}
+
+impl FilePicker {
+    /// Picker showing the directory of the value, the value itself if it is a directory,
+    /// or the working directory if the value names no existing directory
+    pub fn open(field: FieldId, value: &str, working_directory: &Path) -> Result<FilePicker, String> {
+        let path = working_directory.join(value);
+        let directory = [Some(path.as_path()), path.parent()]
+            .into_iter()
+            .flatten()
+            .find(|directory| !value.is_empty() && directory.is_dir())
+            .unwrap_or(working_directory);
+        let directory = directory.canonicalize().unwrap_or_else(|_| directory.to_path_buf());
+        let entries = read_entries(&directory)?;
+        Ok(FilePicker { field, directory, entries, selected: 0 })
+    }
+
+    /// Show another directory, the highlighted entry is the one the picker came from when going up
+    fn enter(&mut self, directory: PathBuf) -> Result<(), String> {
+        let came_from = self.directory.file_name().map(|name| name.to_string_lossy().to_string());
+        self.entries = read_entries(&directory)?;
+        self.selected = self.entries.iter().position(|entry| Some(&entry.name) == came_from.as_ref()).unwrap_or(0);
+        self.directory = directory;
+        Ok(())
+    }
+
+    /// Show the parent directory
+    #[allow(clippy::needless_return)]
+    pub fn go_up(&mut self) -> Result<(), String> {
+        match self.directory.parent() {
+            Some(parent) => return self.enter(parent.to_path_buf()),
+            None => return Ok(()),
+        }
+    }
+
+    /// Open the highlighted directory, or return the path of the highlighted file or of `.`
+    pub fn open_selected(&mut self) -> Result<Option<PathBuf>, String> {
+        let Some(entry) = self.entries.get(self.selected).cloned() else {
+            return Ok(None);
+        };
+        match entry.name.as_str() {
+            "." => return Ok(Some(self.directory.clone())),
+            ".." => self.go_up()?,
+            name if entry.is_dir => self.enter(self.directory.join(name))?,
+            name => return Ok(Some(self.directory.join(name))),
+        }
+        Ok(None)
+    }
+
+    /// Highlight the previous or next entry, wrapping around like the lists of the form
+    pub fn move_selection(&mut self, forward: bool) {
+        let len = self.entries.len().max(1);
+        self.selected = if forward { (self.selected + 1) % len } else { (self.selected + len - 1) % len };
+    }
+}
+
+/// Path as written into the value, relative to the working directory if it is inside of it, e.g. `src/main.rs`
+#[allow(clippy::needless_return)]
+pub fn display_path(path: &Path, working_directory: &Path) -> String {
+    let working_directory = working_directory.canonicalize().unwrap_or_else(|_| working_directory.to_path_buf());
+    match path.strip_prefix(&working_directory) {
+        Ok(relative) if relative.as_os_str().is_empty() => return String::from("."),
+        Ok(relative) => return relative.to_string_lossy().to_string(),
+        Err(_) => return path.to_string_lossy().to_string(),
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_file_picker_browses_directories() {
+    let directory = std::env::temp_dir().join(format!("clitui-filepicker-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(directory.join("logs")).unwrap();
+    fs::write(directory.join("logs").join("out.log"), "").unwrap();
+    fs::write(directory.join("Cargo.toml"), "").unwrap();
+    let field = FieldId { section: crate::model::Section::Arguments, key: String::from("--file") };
+
+    let mut picker = FilePicker::open(field, "logs/missing.log", &directory).unwrap();
+    assert_eq!(picker.entries.iter().map(|entry| entry.name.as_str()).collect::<Vec<&str>>(), [".", "..", "out.log"]);
+    picker.go_up().unwrap();
+    assert_eq!(picker.entries.iter().map(|entry| entry.name.as_str()).collect::<Vec<&str>>(), [".", "..", "logs", "Cargo.toml"]);
+    assert_eq!(picker.selected, 2);
+    picker.move_selection(true);
+
+    let path = picker.open_selected().unwrap().unwrap();
+    assert_eq!(display_path(&path, &directory), "Cargo.toml");
+    picker.selected = 0;
+    assert_eq!(display_path(&picker.open_selected().unwrap().unwrap(), &directory), ".");
+    assert_eq!(display_path(Path::new("/"), &directory), "/");
+}
@@ -0,0 +1,136 @@
+use crate::{collation::collation_key, model::{Model, Section}};
+
+/// Characters after which a new word starts, e.g. the `d` of `--no-default`
+const WORD_SEPARATORS: [char; 4] = ['-', '_', ' ', '.'];
+
+/// Penalty of the longest gap between two matched characters, longer gaps cost the same
+const MAX_GAP_PENALTY: usize = 5;
+
+/// Overlay jumping to any parameter of the form, fuzzy matching the typed query
+#[derive(Debug, Default, PartialEq)]
+pub struct Finder {
+    pub query: String,
+    /// Position of the highlighted match, Enter jumps to it
+    pub selected: usize,
+}
+
+/// Parameter matching the query of the finder
+#[derive(Debug, PartialEq)]
+pub struct FinderMatch {
+    pub section: Section,
+    pub index: usize,
+    /// Keys and value name shown in the overlay, e.g. `-n, --name NAME`
+    pub label: String,
+    pub score: i64,
+}
+
+/// Score of the text for the query, None unless every character of the query appears in order, ignoring case and diacritics
+/// Characters following each other or starting a word score higher, e.g. `nd` matches `--no-default` better than `--ignored`
+/// Both are compared by their collation key, the positions are those of the key, e.g. `ß` counts as `ss`
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = collation_key(text).chars().collect();
+    let query: Vec<char> = collation_key(query).chars().filter(|ch| !ch.is_whitespace()).collect();
+    let matches = |index: usize, query_char: char| text[index] == query_char;
+    let bonus = |index: usize| if index == 0 || WORD_SEPARATORS.contains(&text[index - 1]) { 9 } else { 1 };
+    // best score of the query so far with its last character at each index of the text
+    let mut scores: Vec<Option<i64>> = vec![Some(0); usize::from(query.is_empty())];
+    for (position, query_char) in query.iter().enumerate() {
+        // gaps cost at most 5, so every earlier character further away counts the same
+        let mut best_far: Option<i64> = None;
+        scores = (0..text.len())
+            .map(|index| {
+                if index > MAX_GAP_PENALTY && position > 0 {
+                    best_far = best_far.max(scores[index - MAX_GAP_PENALTY - 1].map(|score| score - MAX_GAP_PENALTY as i64));
+                }
+                if !matches(index, *query_char) {
+                    return None;
+                }
+                if position == 0 {
+                    return Some(bonus(index));
+                }
+                let near = (index.saturating_sub(MAX_GAP_PENALTY)..index).filter_map(|previous| {
+                    let gap = index - previous - 1;
+                    let joined = if gap == 0 { 5 } else { -(gap as i64) };
+                    Some(scores[previous]? + joined)
+                });
+                Some(near.chain(best_far).max()? + bonus(index))
+            })
+            .collect();
+    }
+    scores.into_iter().flatten().max()
+}
+
+impl Finder {
+    /// Parameters of the shown sections matching the query, the best first and in form order among equals
+    pub fn matches(&self, model: &Model) -> Vec<FinderMatch> {
+        let parameters = &model.parameters;
+        let argument_label = |argument: &crate::parsing::CLIArgument| match &argument.short_key {
+            Some(short_key) if *short_key != argument.key => format!("{short_key}, {} {}", argument.key, argument.name),
+            _ => format!("{} {}", argument.key, argument.name),
+        };
+        let labels: Vec<(Section, usize, String)> = Section::ALL
+            .into_iter()
+            .filter(|section| model.section_is_available(*section))
+            .flat_map(|section| {
+                let labels: Vec<String> = match section {
+                    Section::Arguments => parameters.arguments.iter().map(argument_label).collect(),
+                    Section::Options => parameters.options.iter().map(argument_label).collect(),
+                    Section::Positionals => parameters.positionals.iter().map(|positional| positional.name.clone()).collect(),
+                    Section::Flags => parameters.flags
+                        .iter()
+                        .map(|flag| match &flag.short_key {
+                            Some(short_key) if *short_key != flag.key => format!("{short_key}, {}", flag.key),
+                            _ => flag.key.clone(),
+                        })
+                        .collect(),
+                };
+                labels.into_iter().enumerate().map(move |(index, label)| (section, index, label))
+            })
+            .collect();
+        let mut matches: Vec<FinderMatch> = labels
+            .into_iter()
+            .filter_map(|(section, index, label)| {
+                let score = fuzzy_score(&self.query, &label)?;
+                Some(FinderMatch { section, index, label, score })
+            })
+            .collect();
+        // the sort is stable, equal scores keep the order of the form
+        matches.sort_by_key(|found| -found.score);
+        matches
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_fuzzy_score() {
+    assert_eq!(fuzzy_score("xyz", "--no-default-features"), None);
+    assert!(fuzzy_score("ndf", "--no-default-features") > fuzzy_score("ndf", "--index-of-files"));
+    assert!(fuzzy_score("rel", "--release") > fuzzy_score("rel", "--all-features"));
+    assert_eq!(fuzzy_score("", "--release"), Some(0));
+    assert!(fuzzy_score("REL", "--release").is_some());
+    assert!(fuzzy_score("element", "--Élément").is_some());
+    assert_eq!(fuzzy_score("grosse", "--größe"), fuzzy_score("grosse", "--grosse"));
+}
+
+#[test]
+fn test_finder_matches_across_sections() {
+    use crate::parsing::{CLIArgument, CLIFlag, CLIParameters};
+
+    let parameters = CLIParameters {
+        arguments: vec![CLIArgument { key: String::from("--features"), name: String::from("FEATURES"), ..Default::default() }],
+        flags: vec![
+            CLIFlag { key: String::from("--verbose"), ..Default::default() },
+            CLIFlag { key: String::from("--no-default-features"), ..Default::default() },
+        ],
+        ..Default::default()
+    };
+    let model = Model::new(parameters);
+    let finder = Finder { query: String::from("ndf"), ..Default::default() };
+
+    let matches = finder.matches(&model);
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!((matches[0].section, matches[0].index), (Section::Flags, 1));
+    assert_eq!(Finder::default().matches(&model).len(), 3);
+}
@@ -0,0 +1,79 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Error messages of clap that name the offending parameter
+const ERROR_PATTERNS: [&str; 5] = [
+    r"unexpected argument '(?P<key>--?[\w-]+)",
+    r"invalid value '[^']*' for '(?P<key>--?[\w-]+)",
+    r"a value is required for '(?P<key>--?[\w-]+)",
+    r"the argument '(?P<key>--?[\w-]+)[^']*' cannot be used",
+    r"required arguments were not provided:\s+(?P<key>--?[\w-]+)",
+];
+
+/// Find the key of the parameter that made the command fail
+pub fn find_failed_key(error_output: &str) -> Option<String> {
+    static ERROR_REGEXES: LazyLock<Vec<Regex>> = LazyLock::new(|| ERROR_PATTERNS.iter().map(|pattern| Regex::new(pattern).unwrap()).collect());
+    ERROR_REGEXES.iter().find_map(|re| re.captures(error_output).map(|caps| caps["key"].to_string()))
+}
+
+/// The line of the error output that explains the failure
+pub fn summarize_error(error_output: &str) -> String {
+    error_output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("error:"))
+        .or_else(|| error_output.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or_default()
+        .to_string()
+}
+
+// Unit tests
+
+#[test]
+fn test_find_failed_key_unexpected_argument() {
+    let error_output = "error: unexpected argument '--nmae' found\n\n  tip: a similar argument exists: '--name'";
+
+    assert_eq!(
+        find_failed_key(error_output),
+        Some(String::from("--nmae")),
+    )
+}
+
+#[test]
+fn test_find_failed_key_invalid_value() {
+    let error_output = "error: invalid value 'ten' for '--count <COUNT>': invalid digit found in string";
+
+    assert_eq!(
+        find_failed_key(error_output),
+        Some(String::from("--count")),
+    )
+}
+
+#[test]
+fn test_find_failed_key_missing_required() {
+    let error_output = "error: the following required arguments were not provided:\n  --last-name <LAST_NAME>\n\nUsage: greeter.exe";
+
+    assert_eq!(
+        find_failed_key(error_output),
+        Some(String::from("--last-name")),
+    )
+}
+
+#[test]
+fn test_find_failed_key_unknown_error() {
+    assert_eq!(
+        find_failed_key("Segmentation fault"),
+        None,
+    )
+}
+
+#[test]
+fn test_summarize_error() {
+    let error_output = "\nerror: invalid value 'ten' for '--count <COUNT>'\n\nFor more information, try '--help'.";
+
+    assert_eq!(
+        summarize_error(error_output),
+        "error: invalid value 'ten' for '--count <COUNT>'",
+    )
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// Whether the value contains a wildcard, `*`, `?` or a `[...]` class
+pub fn is_pattern(value: &str) -> bool {
+    value.contains(['*', '?', '['])
+}
+
+/// Whether the class starting after `[` contains the character, None if it is not closed
+/// `[!...]` and `[^...]` negate the class, `a-z` is a range
+fn class_matches(class: &[char], ch: char) -> Option<(bool, usize)> {
+    let negated = matches!(class.first(), Some('!' | '^'));
+    let mut index = usize::from(negated);
+    let mut found = false;
+    // a `]` right after the opening bracket is part of the class
+    while index < class.len() && (class[index] != ']' || index == usize::from(negated)) {
+        if index + 2 < class.len() && class[index + 1] == '-' && class[index + 2] != ']' {
+            found |= (class[index]..=class[index + 2]).contains(&ch);
+            index += 3;
+        } else {
+            found |= class[index] == ch;
+            index += 1;
+        }
+    }
+    if index >= class.len() {
+        return None;
+    }
+    Some((found != negated, index + 1))
+}
+
+/// Length of the pattern element at the start of the pattern if it matches the character, a `?`, a class or a literal
+#[allow(clippy::needless_return)]
+fn element_matches(pattern: &[char], ch: char) -> Option<usize> {
+    match pattern.first()? {
+        '?' => return Some(1),
+        '[' => match class_matches(&pattern[1..], ch) {
+            Some((found, length)) => return found.then_some(1 + length),
+            None => return (ch == '[').then_some(1),
+        },
+        literal => return (*literal == ch).then_some(1),
+    }
+}
+
+/// Iterative matching, a mismatch after a `*` lets the last `*` take one more character,
+/// which keeps patterns with many stars linear per star instead of exponential
+fn matches_chars(pattern: &[char], name: &[char]) -> bool {
+    let (mut pattern_index, mut name_index) = (0, 0);
+    // position after the last `*` and the name position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while name_index < name.len() {
+        if pattern.get(pattern_index) == Some(&'*') {
+            pattern_index += 1;
+            backtrack = Some((pattern_index, name_index));
+            continue;
+        }
+        if let Some(length) = element_matches(&pattern[pattern_index..], name[name_index]) {
+            pattern_index += length;
+            name_index += 1;
+            continue;
+        }
+        let Some((star_pattern_index, star_name_index)) = backtrack else {
+            return false;
+        };
+        pattern_index = star_pattern_index;
+        name_index = star_name_index + 1;
+        backtrack = Some((star_pattern_index, name_index));
+    }
+    pattern[pattern_index..].iter().all(|ch| *ch == '*')
+}
+
+/// Whether the file name matches the pattern of one path component, like the shell does
+/// Hidden files only match patterns starting with a `.`
+pub fn matches(pattern: &str, name: &str) -> bool {
+    if name.starts_with('.') && !pattern.starts_with('.') {
+        return false;
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    matches_chars(&pattern, &name)
+}
+
+/// Paths matching the pattern, sorted like the shell sorts them, e.g. `logs/*.log` or `src/*/mod.rs`
+/// Components without wildcards are taken as they are, empty if nothing matches
+pub fn expand(pattern: &str) -> Vec<String> {
+    let mut paths = vec![PathBuf::new()];
+    for component in Path::new(pattern).components() {
+        let part = component.as_os_str().to_string_lossy();
+        paths = match component {
+            Component::Normal(_) if is_pattern(&part) => paths
+                .iter()
+                .flat_map(|path| {
+                    let directory = if path.as_os_str().is_empty() { Path::new(".") } else { path.as_path() };
+                    let mut names: Vec<String> = fs::read_dir(directory)
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                        .filter(|name| matches(&part, name))
+                        .collect();
+                    names.sort();
+                    names.into_iter().map(move |name| path.join(name))
+                })
+                .collect(),
+            _ => paths.into_iter().map(|path| path.join(component)).filter(|path| path.exists()).collect(),
+        };
+    }
+    paths.into_iter().map(|path| path.to_string_lossy().to_string()).collect()
+}
+
+// Unit tests
+
+#[test]
+fn test_matches() {
+    assert!(matches("*.log", "build.log"));
+    assert!(!matches("*.log", "build.log.gz"));
+    assert!(matches("report-??.csv", "report-07.csv"));
+    assert!(matches("[a-c]*", "boot"));
+    assert!(!matches("[!a-c]*", "boot"));
+    assert!(!matches("*", ".hidden"));
+    assert!(matches(".*", ".hidden"));
+    assert!(matches("[x", "[x"));
+    assert!(matches("*a*b*", "xaybz"));
+    assert!(!matches("*a*b", "xaybz"));
+}
+
+#[test]
+fn test_matches_many_stars() {
+    let name = "a".repeat(100);
+
+    assert!(!matches(&format!("{}b", "*a".repeat(20)), &name));
+    assert!(matches(&"*a".repeat(20), &name));
+}
+
+#[test]
+fn test_expand() {
+    let directory = std::env::temp_dir().join(format!("clitui-glob-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(directory.join("logs").join("old")).unwrap();
+    for name in ["b.log", "a.log", "notes.txt", "old/c.log"] {
+        fs::write(directory.join("logs").join(name), "").unwrap();
+    }
+    let root = directory.to_string_lossy();
+
+    assert_eq!(expand(&format!("{root}/logs/*.log")), [format!("{root}/logs/a.log"), format!("{root}/logs/b.log")]);
+    assert_eq!(expand(&format!("{root}/*/*/c.log")), [format!("{root}/logs/old/c.log")]);
+    assert_eq!(expand(&format!("{root}/logs/*.csv")), Vec::<String>::new());
+    assert!(is_pattern("*.log"));
+    assert!(!is_pattern("build.log"));
+}
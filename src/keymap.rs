@@ -0,0 +1,485 @@
+use std::collections::BTreeMap;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::model::Mode;
+
+/// Everything a key can be bound to
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Run,
+    TestRun,
+    RunInTerminal,
+    Toggle,
+    Increment,
+    Decrement,
+    AddValue,
+    ToggleGlob,
+    Expand,
+    PickFile,
+    Select,
+    Follow,
+    Pager,
+    ExternalPager,
+    Search,
+    Jump,
+    NextMatch,
+    Top,
+    Bottom,
+    PageUp,
+    PageDown,
+    Back,
+    NextField,
+    PreviousField,
+    Share,
+    Screenshot,
+    Argv,
+    Stats,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: [Action; 34] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Run,
+        Action::TestRun,
+        Action::RunInTerminal,
+        Action::Toggle,
+        Action::Increment,
+        Action::Decrement,
+        Action::AddValue,
+        Action::ToggleGlob,
+        Action::Expand,
+        Action::PickFile,
+        Action::Select,
+        Action::Follow,
+        Action::Pager,
+        Action::ExternalPager,
+        Action::Search,
+        Action::Jump,
+        Action::NextMatch,
+        Action::Top,
+        Action::Bottom,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Back,
+        Action::NextField,
+        Action::PreviousField,
+        Action::Share,
+        Action::Screenshot,
+        Action::Argv,
+        Action::Stats,
+        Action::Help,
+        Action::Quit,
+    ];
+
+    /// Name of the action in the keymap section of the config
+    #[allow(clippy::needless_return)]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveUp => return "move_up",
+            Action::MoveDown => return "move_down",
+            Action::MoveLeft => return "move_left",
+            Action::MoveRight => return "move_right",
+            Action::Run => return "run",
+            Action::TestRun => return "test_run",
+            Action::RunInTerminal => return "run_in_terminal",
+            Action::Toggle => return "toggle",
+            Action::Increment => return "increment",
+            Action::Decrement => return "decrement",
+            Action::AddValue => return "add_value",
+            Action::ToggleGlob => return "toggle_glob",
+            Action::Expand => return "expand",
+            Action::PickFile => return "pick_file",
+            Action::Select => return "select",
+            Action::Follow => return "follow",
+            Action::Pager => return "pager",
+            Action::ExternalPager => return "external_pager",
+            Action::Search => return "search",
+            Action::Jump => return "jump",
+            Action::NextMatch => return "next_match",
+            Action::Top => return "top",
+            Action::Bottom => return "bottom",
+            Action::PageUp => return "page_up",
+            Action::PageDown => return "page_down",
+            Action::Back => return "back",
+            Action::NextField => return "next_field",
+            Action::PreviousField => return "previous_field",
+            Action::Share => return "share",
+            Action::Screenshot => return "screenshot",
+            Action::Argv => return "argv",
+            Action::Stats => return "stats",
+            Action::Help => return "help",
+            Action::Quit => return "quit",
+        }
+    }
+
+    #[allow(clippy::needless_return)]
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::MoveUp => return "Select the previous entry or scroll up",
+            Action::MoveDown => return "Select the next entry or scroll down",
+            Action::MoveLeft => return "Select the previous section",
+            Action::MoveRight => return "Select the next section",
+            Action::Run => return "Run the command",
+            Action::TestRun => return "Run the command with its dry-run flag, e.g. --dry-run",
+            Action::RunInTerminal => return "Run the command in the terminal instead of the output pane, e.g. when it prompts for input",
+            Action::Toggle => return "Toggle the selected flag",
+            Action::Increment => return "Pass the selected repeatable flag once more, e.g. -vv to -vvv",
+            Action::Decrement => return "Pass the selected repeatable flag once less",
+            Action::AddValue => return "Add another value to a repeatable parameter",
+            Action::ToggleGlob => return "Expand values like *.log of the selected file field into the matching files when running",
+            Action::Expand => return "Edit the selected value over several lines in a popup, e.g. a commit message or JSON, pressing it again in the popup saves the value",
+            Action::PickFile => return "Browse the directories for the path of the selected file field, Enter opens a directory or picks a file",
+            Action::Select => return "Open the selected subcommand",
+            Action::Follow => return "Follow the output of the command",
+            Action::Pager => return "Show the finished output in a full screen pager",
+            Action::ExternalPager => return "Show the finished output in $PAGER",
+            Action::Search => return "Search the output in the pager, filter the lines of the output pane or the parameters of the form, Enter selects the first match",
+            Action::Jump => return "Jump to any parameter by typing parts of its keys or name, Up and Down choose among the matches",
+            Action::NextMatch => return "Jump to the next match of the search",
+            Action::Top => return "Jump to the first parameter of the section or the first line of the pager",
+            Action::Bottom => return "Jump to the last parameter of the section or the last line of the pager",
+            Action::PageUp => return "Move up by the height of the section or the pager",
+            Action::PageDown => return "Move down by the height of the section or the pager",
+            Action::Back => return "Go back to the form or subcommands, or close this help",
+            Action::NextField => return "Select the next field, required arguments first and flags last",
+            Action::PreviousField => return "Select the previous field, required arguments first and flags last",
+            Action::Share => return "Show the share code of the form",
+            Action::Screenshot => return "Save the screen to a file, e.g. for a bug report",
+            Action::Argv => return "Show or close the arguments exactly as the command receives them",
+            Action::Stats => return "Show or close how often each tool and flag was used, from the history",
+            Action::Help => return "Show or close this help",
+            Action::Quit => return "Quit without running the command",
+        }
+    }
+
+    /// Label in the instruction bar of the main border, None for actions only listed in the keybinding help
+    /// Going back closes the views shown on top of the form
+    #[allow(clippy::needless_return)]
+    pub fn label(&self, mode: Mode) -> Option<&'static str> {
+        match self {
+            Action::Run => return Some("Run"),
+            Action::TestRun => return Some("Test run"),
+            Action::RunInTerminal => return Some("Run in terminal"),
+            Action::Toggle => return Some("Toggle"),
+            Action::AddValue => return Some("Add value"),
+            Action::ToggleGlob => return Some("Glob"),
+            Action::Expand => return Some("Expand"),
+            Action::PickFile => return Some("Browse"),
+            Action::Select => return Some("Select"),
+            Action::Follow => return Some("Follow"),
+            Action::Pager => return Some("Pager"),
+            Action::ExternalPager => return Some("$PAGER"),
+            // filtering the form is listed in the keybinding help, the bar keeps its room for the fields
+            Action::Search if mode == Mode::Form => return None,
+            Action::Search if mode == Mode::Output => return Some("Filter"),
+            Action::Search => return Some("Search"),
+            Action::NextMatch => return Some("Next match"),
+            Action::Top => return Some("Top"),
+            Action::Bottom => return Some("Bottom"),
+            Action::Back if matches!(mode, Mode::Help | Mode::Argv | Mode::Stats | Mode::Pager) => return Some("Close"),
+            Action::Back => return Some("Back"),
+            Action::Share => return Some("Share"),
+            Action::Argv | Action::Stats | Action::Help if matches!(mode, Mode::Help | Mode::Argv | Mode::Stats) => return None,
+            Action::Argv => return Some("Arguments"),
+            Action::Stats => return Some("Stats"),
+            Action::Help => return Some("Help"),
+            Action::Quit => return Some("Quit"),
+            Action::MoveUp | Action::MoveDown | Action::MoveLeft | Action::MoveRight | Action::Increment | Action::Decrement | Action::Jump
+            | Action::PageUp | Action::PageDown | Action::NextField | Action::PreviousField | Action::Screenshot => return None,
+        }
+    }
+
+    /// Rank in the instruction bar, the lowest ranks are kept when the bar is too narrow for every label
+    #[allow(clippy::needless_return)]
+    pub fn priority(&self) -> u8 {
+        match self {
+            Action::Help | Action::Quit => return 0,
+            Action::Run | Action::Select | Action::Search | Action::Back => return 1,
+            Action::Toggle | Action::Follow | Action::NextMatch => return 2,
+            Action::TestRun | Action::Pager => return 3,
+            Action::Top | Action::Bottom | Action::ExternalPager => return 4,
+            Action::Share => return 5,
+            Action::RunInTerminal => return 6,
+            Action::AddValue | Action::ToggleGlob | Action::Expand | Action::PickFile => return 7,
+            Action::Argv | Action::Stats => return 8,
+            _ => return u8::MAX,
+        }
+    }
+
+    /// Whether the action does anything in the given mode
+    #[allow(clippy::needless_return)]
+    pub fn is_available(&self, mode: Mode) -> bool {
+        match self {
+            Action::MoveUp | Action::MoveDown | Action::Screenshot | Action::Help | Action::Quit => return true,
+            Action::MoveLeft | Action::MoveRight | Action::NextField | Action::PreviousField | Action::Jump | Action::Toggle | Action::Increment | Action::Decrement | Action::AddValue | Action::ToggleGlob | Action::Expand | Action::PickFile | Action::Share => return mode == Mode::Form,
+            Action::Run | Action::TestRun | Action::RunInTerminal => return mode == Mode::Form || mode == Mode::Output,
+            Action::Select => return mode == Mode::Subcommands,
+            Action::Follow | Action::Pager => return mode == Mode::Output,
+            Action::ExternalPager => return mode == Mode::Output || mode == Mode::Pager,
+            Action::Search => return matches!(mode, Mode::Form | Mode::Output | Mode::Pager),
+            Action::NextMatch => return mode == Mode::Pager,
+            Action::Top | Action::Bottom | Action::PageUp | Action::PageDown => return mode == Mode::Form || mode == Mode::Pager,
+            Action::Argv => return mode == Mode::Form || mode == Mode::Argv,
+            Action::Stats => return mode == Mode::Form || mode == Mode::Stats,
+            Action::Back => return true,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Key with its modifiers, e.g. `Ctrl+Q`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// Parse a key like `Enter`, `F1`, `?` or `Ctrl+Q`
+    pub fn parse(value: &str) -> Option<KeyBinding> {
+        let mut parts: Vec<&str> = value.split('+').map(str::trim).collect();
+        let key = parts.pop()?;
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                _ => return None,
+            }
+        }
+        let code = match key.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "space" => KeyCode::Char(' '),
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            lower if lower.len() > 1 && lower.starts_with('f') => KeyCode::F(lower[1..].parse().ok()?),
+            _ if key.chars().count() == 1 => KeyCode::Char(key.chars().next()?.to_ascii_lowercase()),
+            _ => return None,
+        };
+        Some(KeyBinding::new(code, modifiers))
+    }
+
+    /// Shift is ignored for characters and `BackTab` as it is already part of the key, e.g. `?`
+    #[allow(clippy::needless_return)]
+    pub fn matches(&self, key: KeyEvent) -> bool {
+        let same_modifiers_except_shift = self.modifiers.difference(KeyModifiers::SHIFT) == key.modifiers.difference(KeyModifiers::SHIFT);
+        match (self.code, key.code) {
+            (KeyCode::Char(expected), KeyCode::Char(actual)) => {
+                return expected == actual.to_ascii_lowercase() && same_modifiers_except_shift;
+            },
+            (KeyCode::BackTab, KeyCode::BackTab) => return same_modifiers_except_shift,
+            _ => return self.code == key.code && self.modifiers == key.modifiers,
+        }
+    }
+
+    /// Display the key as shown in the instructions, e.g. `<Ctrl + Q>`
+    pub fn display(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            parts.push(String::from("Ctrl"));
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            parts.push(String::from("Alt"));
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            parts.push(String::from("Shift"));
+        }
+        let key = match self.code {
+            KeyCode::Char(' ') => String::from("Space"),
+            KeyCode::Char(ch) if self.modifiers.is_empty() => ch.to_string(),
+            KeyCode::Char(ch) => ch.to_ascii_uppercase().to_string(),
+            KeyCode::F(number) => format!("F{number}"),
+            KeyCode::PageUp => String::from("PgUp"),
+            KeyCode::PageDown => String::from("PgDn"),
+            KeyCode::BackTab => String::from("Shift + Tab"),
+            code => format!("{code:?}"),
+        };
+        parts.push(key);
+        format!("<{}>", parts.join(" + "))
+    }
+}
+
+/// Keys of every action, the defaults overridden by the keymap section of the config
+#[derive(Debug, PartialEq, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let key = |code| KeyBinding::new(code, KeyModifiers::NONE);
+        let ctrl = |ch| KeyBinding::new(KeyCode::Char(ch), KeyModifiers::CONTROL);
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = match action {
+                    Action::MoveUp => vec![key(KeyCode::Up)],
+                    Action::MoveDown => vec![key(KeyCode::Down)],
+                    Action::MoveLeft => vec![key(KeyCode::Left)],
+                    Action::MoveRight => vec![key(KeyCode::Right)],
+                    Action::Run => vec![key(KeyCode::Enter)],
+                    Action::TestRun => vec![ctrl('t')],
+                    Action::RunInTerminal => vec![ctrl('r')],
+                    Action::Toggle => vec![key(KeyCode::Char(' '))],
+                    Action::Increment => vec![key(KeyCode::Char('+'))],
+                    Action::Decrement => vec![key(KeyCode::Char('-'))],
+                    Action::AddValue => vec![ctrl('n')],
+                    Action::ToggleGlob => vec![ctrl('g')],
+                    Action::Expand => vec![ctrl('o')],
+                    Action::PickFile => vec![ctrl('b')],
+                    Action::Select => vec![key(KeyCode::Enter)],
+                    Action::Follow => vec![key(KeyCode::Char('f'))],
+                    Action::Pager => vec![key(KeyCode::Char('p'))],
+                    Action::ExternalPager => vec![key(KeyCode::Char('o'))],
+                    // `/` is typed into value fields, Ctrl+F filters the form from them as well
+                    Action::Search => vec![key(KeyCode::Char('/')), ctrl('f')],
+                    Action::Jump => vec![ctrl('p')],
+                    Action::NextMatch => vec![key(KeyCode::Char('n'))],
+                    Action::Top => vec![key(KeyCode::Home)],
+                    Action::Bottom => vec![key(KeyCode::End)],
+                    Action::PageUp => vec![key(KeyCode::PageUp)],
+                    Action::PageDown => vec![key(KeyCode::PageDown)],
+                    Action::Back => vec![key(KeyCode::Esc)],
+                    Action::NextField => vec![key(KeyCode::Tab)],
+                    Action::PreviousField => vec![key(KeyCode::BackTab)],
+                    Action::Share => vec![ctrl('e')],
+                    Action::Screenshot => vec![ctrl('s')],
+                    Action::Argv => vec![key(KeyCode::F(2))],
+                    Action::Stats => vec![key(KeyCode::F(3))],
+                    Action::Help => vec![key(KeyCode::F(1)), key(KeyCode::Char('?'))],
+                    Action::Quit => vec![ctrl('q')],
+                };
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Replace the default keys of the configured actions
+    pub fn from_config(keymap: &BTreeMap<String, Vec<String>>) -> Result<Keymap, String> {
+        let mut result = Keymap::default();
+        for (name, keys) in keymap {
+            let action = Action::from_name(name).ok_or(format!("Unknown action in keymap: {name}"))?;
+            let keys = keys
+                .iter()
+                .map(|key| KeyBinding::parse(key).ok_or(format!("Unknown key in keymap: {key}")))
+                .collect::<Result<Vec<KeyBinding>, String>>()?;
+            if let Some((_, bindings)) = result.bindings.iter_mut().find(|(bound_action, _)| *bound_action == action) {
+                *bindings = keys;
+            }
+        }
+        Ok(result)
+    }
+
+    /// The action bound to the key that is available in the mode
+    pub fn action(&self, key: KeyEvent, mode: Mode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .filter(|(action, _)| action.is_available(mode))
+            .find(|(_, keys)| keys.iter().any(|binding| binding.matches(key)))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyBinding] {
+        self.bindings
+            .iter()
+            .find(|(bound_action, _)| *bound_action == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Keys of the action as shown in the instructions, e.g. `<F1>/<?>`
+    pub fn display_keys(&self, action: Action) -> String {
+        self.keys(action).iter().map(KeyBinding::display).collect::<Vec<String>>().join("/")
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_parse_key_binding() {
+    assert_eq!(KeyBinding::parse("Ctrl+Q"), Some(KeyBinding::new(KeyCode::Char('q'), KeyModifiers::CONTROL)));
+    assert_eq!(KeyBinding::parse("F1"), Some(KeyBinding::new(KeyCode::F(1), KeyModifiers::NONE)));
+    assert_eq!(KeyBinding::parse("?"), Some(KeyBinding::new(KeyCode::Char('?'), KeyModifiers::NONE)));
+    assert_eq!(KeyBinding::parse("Alt + Enter"), Some(KeyBinding::new(KeyCode::Enter, KeyModifiers::ALT)));
+    assert_eq!(KeyBinding::parse("Hyper+X"), None);
+}
+
+#[test]
+fn test_display_key_binding() {
+    assert_eq!(KeyBinding::parse("Ctrl+Q").unwrap().display(), "<Ctrl + Q>");
+    assert_eq!(KeyBinding::parse("Space").unwrap().display(), "<Space>");
+    assert_eq!(KeyBinding::parse("Enter").unwrap().display(), "<Enter>");
+}
+
+#[test]
+fn test_matches_ignores_shift_of_characters() {
+    let binding = KeyBinding::parse("?").unwrap();
+
+    assert!(binding.matches(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+}
+
+#[test]
+fn test_shift_tab_selects_previous_field() {
+    let keymap = Keymap::default();
+
+    assert_eq!(keymap.action(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT), Mode::Form), Some(Action::PreviousField));
+}
+
+#[test]
+fn test_action_depends_on_mode() {
+    let keymap = Keymap::default();
+    let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+    assert_eq!(keymap.action(enter, Mode::Form), Some(Action::Run));
+    assert_eq!(keymap.action(enter, Mode::Subcommands), Some(Action::Select));
+    assert_eq!(keymap.action(enter, Mode::Help), None);
+}
+
+#[test]
+fn test_from_config_replaces_defaults() {
+    let config = BTreeMap::from([(String::from("quit"), vec![String::from("Ctrl+X")])]);
+
+    let keymap = Keymap::from_config(&config).unwrap();
+
+    assert_eq!(keymap.action(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL), Mode::Form), Some(Action::Quit));
+    assert_eq!(keymap.action(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL), Mode::Form), None);
+}
+
+#[test]
+fn test_from_config_unknown_action() {
+    let config = BTreeMap::from([(String::from("explode"), vec![String::from("Ctrl+X")])]);
+
+    assert_eq!(Keymap::from_config(&config), Err(String::from("Unknown action in keymap: explode")));
+}
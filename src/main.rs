@@ -1,6 +1,7 @@
 use std::{
     env,
     io,
+    process,
 };
 
 use model::Model;
@@ -11,27 +12,246 @@ mod app;
 mod model;
 mod controller;
 mod cli;
+mod quoting;
+mod fixup;
+mod collation;
+mod audit;
+mod share;
+mod config;
+mod keymap;
+mod output;
+mod expression;
+mod pager;
+mod diff;
+mod storage;
+mod screenshot;
+mod cache;
+mod stats;
+mod paths;
+mod demo;
+mod glob;
+mod bundle;
+mod pathcheck;
+mod finder;
+mod editor;
+mod filepicker;
 
 fn main() -> io::Result<()> {
     // setup
-    let args: Vec<String> = env::args().skip(1).collect();
-    if args.is_empty() {
-        panic!("No arguments provided")
-    }
-    let help_command = cli::build_help_command(args);
-    let help_string = cli::run_help_command(help_command)?;
-    let parameters = parsing::parse_help_string(&help_string);
-    let mut model = Model::new(parameters.expect("Cannot parse the help string"));
-    let mut terminal = ui::init()?;
-
-    // main loop
-    let cli_command = app::run(&mut terminal, &mut model)?;
+    let app_args: Vec<String> = env::args().skip(1).collect();
+    if app_args.first().is_some_and(|arg| arg == demo::GREETER_FLAG) {
+        process::exit(demo::run_greeter(&app_args[1..]));
+    }
+    let mut args = cli::parse_app_args(app_args).unwrap_or_else(|error| panic!("{error}"));
+    if args.demo {
+        args.command = demo::greeter_command();
+    }
+    // bundles are handled before the config is read, an imported one replaces it
+    if args.export_bundle.is_some() || args.import_bundle.is_some() {
+        let config_file = args.config_file.clone().or_else(paths::config_file);
+        // the storage of the current config, a broken config is about to be replaced by the bundle
+        let backend = config::load_config(config_file.as_deref()).ok().and_then(|config| config.storage_backend().ok()).unwrap_or_default();
+        let mut storage = storage::open(backend, &paths::data_directory())?;
+        if let Some(path) = &args.export_bundle {
+            let bundle = bundle::export(config_file.as_deref(), storage.as_ref()).and_then(|bundle| bundle::write_bundle(path, &bundle).map(|_| bundle));
+            let bundle = bundle.unwrap_or_else(|error| panic!("{error}"));
+            println!("Exported the config and {} documents to {}", bundle.documents.len(), path.display());
+        }
+        if let Some(path) = &args.import_bundle {
+            let bundle = bundle::read_bundle(path).unwrap_or_else(|error| panic!("{error}"));
+            let backup = bundle::import(&bundle, config_file.as_deref(), storage.as_mut()).unwrap_or_else(|error| panic!("{error}"));
+            println!("Imported the config and {} documents from {}", bundle.documents.len(), path.display());
+            if let Some(backup) = backup {
+                println!("The previous config was kept in {}", backup.display());
+            }
+        }
+        return Ok(());
+    }
+    let config = config::load_config(args.config_file.as_deref()).unwrap_or_else(|error| panic!("{error}"));
+    // an alias wraps the program of its command line, the other words and those after the alias fill the form
+    let alias = args.command.first().filter(|_| !args.demo).and_then(|name| Some((name.clone(), config.alias(name)?)));
+    let mut alias_arguments = Vec::new();
+    if let Some((_, words)) = &alias {
+        alias_arguments = words[1..].iter().chain(&args.command[1..]).cloned().collect();
+        args.command = vec![words[0].clone()];
+    }
+    let keymap = keymap::Keymap::from_config(&config.keymap).unwrap_or_else(|error| panic!("{error}"));
+    let cli_libs = config.parsers_for(&args.command).unwrap_or_else(|error| panic!("{error}"));
+    let subcommand_help = config.subcommand_help_for(&args.command).unwrap_or_else(|error| panic!("{error}"));
+    let bare_help = config.bare_help_for(&args.command);
+    let list_style = config.list_style().unwrap_or_else(|error| panic!("{error}"));
+    let animation = config.animation().unwrap_or_else(|error| panic!("{error}"));
+    let theme = config.theme().unwrap_or_else(|error| panic!("{error}"));
+    let key_style = args.key_style.map_or_else(|| config.key_style(), Ok).unwrap_or_else(|error| panic!("{error}"));
+    if let Some(directory) = &args.audit {
+        let reports = audit::audit_directory(directory)?;
+        reports.iter().for_each(|report| println!("{report}"));
+        let failed = reports.iter().filter(|report| report.failed()).count();
+        println!("{} files parsed, {failed} failed", reports.len());
+        if failed > 0 {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+    if args.lint_help {
+        let help_string = cli::retrieve_help_string(&args.command, bare_help).unwrap_or_else(|| {
+            eprintln!("Cannot retrieve the help of {}", args.command.join(" "));
+            process::exit(1);
+        });
+        let mut findings = audit::lint_help_string(&help_string, &cli_libs);
+        // with the short help merged the report tells which parameters only one of the helps lists
+        if args.merge_help {
+            let parameters = parsing::parse_help_string_with(&help_string, &cli_libs);
+            if let (Some(mut parameters), Some(short_parameters)) = (parameters, cli::probe_short_help(args.command.clone(), &cli_libs)) {
+                parameters.merge_short_help(short_parameters);
+                findings.extend(audit::lint_probes(&help_string, &parameters));
+            }
+        }
+        findings.iter().for_each(|finding| println!("{finding}\n"));
+        println!("{} findings in the help of {}", findings.len(), args.command.join(" "));
+        if !findings.is_empty() {
+            process::exit(1);
+        }
+        return Ok(());
+    }
+    // runs of the demo are kept out of the real history
+    let mut storage = if args.demo {
+        storage::open(storage::StorageBackend::Files, &env::temp_dir().join("clitui-demo"))?
+    } else {
+        storage::open(config.storage_backend().unwrap_or_else(|error| panic!("{error}")), &paths::data_directory())?
+    };
+    if let Some(path) = &args.import_storage {
+        let imported = storage::open_path(path)?;
+        let count = storage::migrate(imported.as_ref(), storage.as_mut())?;
+        println!("Imported {count} history entries into {}", paths::data_directory().display());
+        return Ok(());
+    }
+    // the settings of the config for the command, applied once the parameters are complete
+    let derived = config.derived_for(&args.command);
+    let joined = config.joined_for(&args.command).to_vec();
+    let meta_flags = config.meta_flags_for(&args.command).to_vec();
+    let path_checks = config.path_checks_for(&args.command).unwrap_or_else(|error| panic!("{error}"));
+    let (command, sort) = (args.command.clone(), args.sort);
+    let configure = move |parameters: &mut parsing::CLIParameters| {
+        parameters.fill_env_values(&|name| env::var(name).ok());
+        for (key, template) in &derived {
+            parameters.add_derived(key, template);
+        }
+        parameters.fill_derived_values(&controller::template_builtin);
+        parameters.set_joined(&joined);
+        parameters.set_passed_meta_flags(&meta_flags);
+        parameters.set_path_checks(&path_checks);
+        parameters.set_program(&command);
+        parameters.set_key_style(key_style);
+        if sort {
+            parameters.sort();
+        }
+    };
+    let mut discovery = None;
+    let mut parameters = if args.demo {
+        demo::parameters()
+    } else if let Some(spec_file) = &args.spec_file {
+        let parameters = cli::load_spec(spec_file).unwrap_or_else(|error| panic!("{error}"));
+        if args.command.is_empty() {
+            args.command = vec![parameters.cli_name.clone()];
+        }
+        parameters
+    } else {
+        // parsed parameters are cached until the program changes, for each way of parsing its help
+        let help_cache = cache::HelpCache { directory: paths::cache_directory() };
+        let cache_options = format!("{cli_libs:?} {subcommand_help:?} bare_help={bare_help} merge_help={} no_subcommands={}", args.merge_help, args.no_subcommands);
+        let cached_parameters = help_cache.load(&args.command, &cache_options).filter(|_| !args.refresh_cache);
+        match cached_parameters {
+            Some(parameters) => parameters,
+            None => {
+                let mut parameters = cli::retrieve_help(&args.command, &cli_libs, bare_help).unwrap_or_else(|error| {
+                    // parse errors span several lines and are expected, unlike the errors of clitui itself
+                    eprintln!("{error}");
+                    process::exit(1);
+                });
+                // clap prints terse descriptions for `-h` and detailed ones for `--help`
+                let clap_help = parameters.cli_lib == parsing::CLILib::Clap
+                    && parameters.flags.iter().any(|flag| flag.has_key("--help") && flag.has_key("-h"));
+                if args.merge_help || clap_help {
+                    if let Some(short_parameters) = cli::probe_short_help(args.command.clone(), &cli_libs) {
+                        parameters.merge_short_help(short_parameters);
+                    }
+                }
+                // the form opens while the subcommands are discovered, unless it is filled in right away
+                // or strict mode needs the guesses of every subcommand first
+                let undiscovered = parameters.subcommands.iter().any(|subcommand| subcommand.parameters.is_none());
+                if !args.no_subcommands && undiscovered && alias.is_none() && args.import_code.is_none() && !args.strict {
+                    let (command, configure) = (args.command.clone(), configure.clone());
+                    discovery = Some(cli::SubcommandDiscovery::spawn(args.command.clone(), parameters.clone(), cli_libs.clone(), subcommand_help, move |parameters| {
+                        parameters.mark_globals();
+                        let _ = help_cache.store(&command, &cache_options, parameters);
+                        configure(parameters);
+                    }));
+                    parameters.mark_globals();
+                } else {
+                    if !args.no_subcommands {
+                        cli::discover_subcommands(&args.command, &mut parameters, &cli_libs, subcommand_help);
+                    }
+                    // subcommands of a command dump are known without discovering them
+                    parameters.mark_globals();
+                    let _ = help_cache.store(&args.command, &cache_options, &parameters);
+                }
+                parameters
+            },
+        }
+    };
+    // strict mode prefers failing over a command built on guesses
+    let guesses = parameters.all_guesses();
+    if args.strict && !guesses.is_empty() {
+        eprintln!("Refusing to wrap {} in strict mode, the help was read with guesses:", args.command.join(" "));
+        guesses.iter().for_each(|guess| eprintln!("  {guess}"));
+        process::exit(1);
+    }
+    let program_warning = cli::program_name_warning(&args.command, &parameters.cli_name).filter(|_| !args.demo);
+    configure(&mut parameters);
+    let mut model = Model::new(parameters);
+    model.discovering = discovery.is_some();
+    model.keymap = keymap;
+    model.list_style = list_style;
+    model.quote_options = args.quote_options;
+    // NO_COLOR is honored like other terminal programs do, see no-color.org
+    model.monochrome = args.monochrome || config.monochrome || env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
+    // without colors the selection has to be reverse video, which becomes bold and underlined
+    if !model.monochrome {
+        model.theme = theme;
+    }
+    model.reduced_animation = animation.is_reduced(&|name| env::var(name).ok());
+    model.run_in_terminal = config.is_interactive(&args.command);
+    model.hidden_sections = args.hidden_sections.clone();
+    model.notice = program_warning;
+    model.select_first_section();
+    if let Some(import_code) = &args.import_code {
+        share::SharedForm::decode(import_code)
+            .and_then(|form| form.apply(&mut model))
+            .unwrap_or_else(|error| panic!("{error}"));
+    }
+    if let Some((name, _)) = alias {
+        share::apply_command_line(&mut model, &alias_arguments).unwrap_or_else(|error| panic!("Cannot open alias {name}: {error}"));
+        model.alias = Some(name);
+    }
 
-    // run actual cli
+    // main loop, commands run inside the output pane
+    let mut terminal = ui::init()?;
+    let detached_command = app::run(&mut terminal, &mut model, &args, storage.as_mut(), discovery);
     ui::restore()?;
-    if let Some(cli_command) = cli_command {
-        println!("Call command: {:?}", cli_command);
-        cli::run_external_command(cli_command)?;
+    if let Some(share_code) = &model.last_share_code {
+        println!("Share code: {share_code}");
+    }
+
+    // run detached cli
+    let Some(cli_command) = detached_command? else {
+        return Ok(());
+    };
+    println!("Call command: {}", quoting::format_command(&cli_command, args.quote_options));
+    let pid = cli::run_detached_command(cli_command, args.log_file.as_deref())?;
+    println!("Started detached process with PID {pid}");
+    if let Some(log_file) = &args.log_file {
+        println!("Output is written to {}", log_file.display());
     }
     Ok(())
 }
@@ -1,42 +1,403 @@
-use crate::parsing::CLIParameters;
+use std::{cell::{Cell, RefCell}, collections::BTreeMap, mem};
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+use ratatui::layout::Rect;
+
+use crate::{collation::collation_key, editor::ValueEditor, expression, filepicker::FilePicker, finder::Finder, keymap::Keymap, output::OutputPane, pager::Pager, parsing::{CLIArgument, CLIParameters, HelpProbe, PathCheck, ValueType}, pathcheck::PathState, quoting::QuoteOptions, stats::ToolStats};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum Section {
     Arguments,
+    Positionals,
     Flags,
     Options,
 }
 
+impl Section {
+    pub const ALL: [Section; 4] = [Section::Arguments, Section::Positionals, Section::Flags, Section::Options];
+
+    /// Name of the section in `--only` and `--hide`, e.g. `args`
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<Section> {
+        match name {
+            "args" | "arguments" => return Some(Section::Arguments),
+            "positionals" => return Some(Section::Positionals),
+            "flags" => return Some(Section::Flags),
+            "options" => return Some(Section::Options),
+            _ => return None,
+        }
+    }
+}
+
+/// Identifies a parameter independently of the selection, by its key or the name of a positional
+/// Messages carry it, so a message handled after the selection moved still changes the parameter it was meant for
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldId {
+    pub section: Section,
+    /// Key identifying an argument, option or flag, e.g. `--name`, or the name of a positional
+    pub key: String,
+}
+
+/// Cursor in the text of a value while it is edited
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TextCursor {
+    /// Parameter whose last value is edited, typing always edits the last value
+    pub field: FieldId,
+    /// Number of characters before the cursor
+    pub position: usize,
+}
+
+/// Popup choosing one of the possible values of an argument or option
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Dropdown {
+    pub field: FieldId,
+    /// Position of the highlighted choice, Enter writes it into the value
+    pub selected: usize,
+}
+
+/// Value of a parameter as `Model::get` returns it
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParameterValue {
+    /// Values of an argument, option or positional, including the one being typed
+    Values(Vec<String>),
+    /// How often the flag is passed, zero if it is unset, and whether its negated key is passed instead
+    Flag { times: usize, negated: bool },
+}
+
+/// Rows of a list as it was last rendered, so a click finds the item under the mouse
+#[derive(Debug, PartialEq, Clone)]
+pub struct ListRows {
+    /// Section of the listed parameters, None for the subcommands
+    pub section: Option<Section>,
+    pub area: Rect,
+    /// Index of the item in each row from the top
+    pub indices: Vec<usize>,
+}
+
+impl ListRows {
+    /// Index of the item at the cell, None outside of the list or below its last item
+    pub fn index_at(&self, column: u16, row: u16) -> Option<usize> {
+        if !(self.area.left()..self.area.right()).contains(&column) || !(self.area.top()..self.area.bottom()).contains(&row) {
+            return None;
+        }
+        self.indices.get(usize::from(row - self.area.top())).copied()
+    }
+}
+
+/// Views shown on top of the form, opened and closed by their own key
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Popup {
+    Help,
+    Argv,
+    Stats,
+}
+
+/// What is shown and therefore which keys are available
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Mode {
+    Form,
+    Subcommands,
+    Output,
+    Argv,
+    Stats,
+    Pager,
+    Help,
+}
+
+/// Effects requested by the controller, drained by the app loop after every update
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AppCommand {
+    Run,
+    /// Run with the dry-run flag set
+    TestRun,
+    /// Run with the terminal handed over while the TUI is suspended, for commands that prompt for input
+    RunInTerminal,
+    /// Show the output in `$PAGER` while the TUI is suspended
+    ExternalPager,
+    /// Save the current screen to a file
+    Screenshot,
+    /// Compute the usage statistics from the history
+    ShowStats,
+    Quit,
+}
+
 #[derive(Debug)]
 pub struct Model {
     pub parameters: CLIParameters,
     pub current_section: Section,
     pub current_key_index: usize,
-    pub run: bool,
-    pub exit: bool,
+    pub commands: Vec<AppCommand>,
+    pub error: Option<String>,
+    /// Result of an effect like a saved screenshot, shown until the next key
+    pub notice: Option<String>,
+    /// Share code of the form, shown until the next edit
+    pub share_code: Option<String>,
+    /// Latest share code, printed once clitui quits so it can be copied from the terminal
+    pub last_share_code: Option<String>,
+    /// Whether the subcommand picker is shown instead of the form
+    pub selecting_subcommand: bool,
+    /// Selected entry of the subcommand picker, 0 is the command itself
+    pub subcommand_index: usize,
+    /// Parameters of the parent commands and the index of the entered subcommand
+    pub parent_parameters: Vec<(CLIParameters, usize)>,
+    /// Whether the subcommands are still discovered in the background, they cannot be entered until then
+    pub discovering: bool,
+    pub keymap: Keymap,
+    /// Whether the keybinding help is shown on top of everything else
+    pub showing_help: bool,
+    /// First visible line of the keybinding help
+    pub help_scroll: usize,
+    /// Whether the arguments the command would receive are shown instead of the form
+    pub showing_argv: bool,
+    /// First visible argument of the argument view
+    pub argv_scroll: usize,
+    /// Usage statistics per tool, shown instead of the form once computed from the history
+    pub stats: Option<Vec<ToolStats>>,
+    /// First visible line of the statistics view
+    pub stats_scroll: usize,
+    /// Output of the command run from the form, shown instead of the form
+    pub output: Option<OutputPane>,
+    /// Full screen view of the finished output, shown instead of the output pane
+    pub pager: Option<Pager>,
+    /// Sections that are neither shown nor editable, their values are still passed to the command
+    pub hidden_sections: Vec<Section>,
+    pub list_style: ListStyle,
+    /// Widths of the name columns of `ListStyle::Columns` per command path and section, computed once as the names do not change while editing
+    pub name_widths: RefCell<BTreeMap<(Vec<String>, Section), usize>>,
+    /// Overlay jumping to any parameter, shown on top of the form while it is open
+    pub finder: Option<Finder>,
+    /// Cursor of the value being edited, None while no value is edited, typing then starts at the end of the value
+    pub text_cursor: Option<TextCursor>,
+    /// Popup editing the selected value over several lines, shown on top of the form while it is open
+    pub value_editor: Option<ValueEditor>,
+    /// Popup browsing the directories for the path of the selected parameter, shown on top of the form while it is open
+    pub file_picker: Option<FilePicker>,
+    /// Popup choosing the value of an argument with possible values, shown on top of the form while it is open
+    pub dropdown: Option<Dropdown>,
+    /// Query filtering the parameter lists while it is typed, None when the lists are not filtered
+    pub filter: Option<String>,
+    /// Name of the alias from the config the form was opened with, shown in the title
+    pub alias: Option<String>,
+    /// Colors of the roles text plays on the screen
+    pub theme: Theme,
+    /// Quoting of the command line shown above the description, like it is printed
+    pub quote_options: QuoteOptions,
+    /// Whether state is shown by symbols and text only, without colors or reverse video
+    pub monochrome: bool,
+    /// Whether the screen is refreshed less often, e.g. over SSH
+    pub reduced_animation: bool,
+    /// Whether every run hands the terminal over to the command, set for tools marked as interactive in the config
+    pub run_in_terminal: bool,
+    /// Number of parameters the selected section showed when it was last rendered, the step of PageUp and PageDown
+    pub section_page_height: Cell<usize>,
+    /// Lists shown by the last render, where clicks select parameters and subcommands
+    pub list_rows: RefCell<Vec<ListRows>>,
+    /// What is at the paths of the path values, filled in the background by the path checker
+    pub path_states: BTreeMap<String, PathState>,
 }
 
-use crate::ui::GUIDisplay;
+use crate::{config::ListStyle, ui::{GUIDisplay, Theme}};
 
 impl Model {
     pub fn new(parameters: CLIParameters) -> Self {
-        Self {
+        let mut model = Self {
+            selecting_subcommand: !parameters.subcommands.is_empty(),
             parameters,
             current_section: Section::Arguments,
             current_key_index: 0,
-            run: false,
-            exit: false,
-        }
+            commands: Vec::new(),
+            error: None,
+            notice: None,
+            share_code: None,
+            last_share_code: None,
+            subcommand_index: 0,
+            parent_parameters: Vec::new(),
+            discovering: false,
+            keymap: Keymap::default(),
+            showing_help: false,
+            help_scroll: 0,
+            showing_argv: false,
+            argv_scroll: 0,
+            stats: None,
+            stats_scroll: 0,
+            output: None,
+            pager: None,
+            hidden_sections: Vec::new(),
+            list_style: ListStyle::Inline,
+            name_widths: RefCell::new(BTreeMap::new()),
+            finder: None,
+            text_cursor: None,
+            value_editor: None,
+            file_picker: None,
+            dropdown: None,
+            filter: None,
+            alias: None,
+            theme: Theme::default(),
+            quote_options: QuoteOptions::default(),
+            monochrome: false,
+            reduced_animation: false,
+            run_in_terminal: false,
+            section_page_height: Cell::new(0),
+            list_rows: RefCell::new(Vec::new()),
+            path_states: BTreeMap::new(),
+        };
+        model.select_first_section();
+        model
+    }
+
+    /// Select the first parameter of the first section that is not empty
+    pub fn select_first_section(&mut self) {
+        self.current_section = Section::ALL
+            .into_iter()
+            .find(|section| self.section_is_available(*section))
+            .unwrap_or(Section::Arguments);
+        self.current_key_index = 0;
     }
 
+    /// Description of the selected parameter, noting if only one of the merged help probes has it
+    #[allow(clippy::needless_return)]
     pub fn get_selected_description(&self) -> Option<String> {
+        let index = self.current_key_index;
+        let (description, key) = match self.current_section {
+            Section::Arguments => self.parameters.arguments.get(index).map(|argument| (argument.display_description(), &argument.key))?,
+            Section::Positionals => self.parameters.positionals.get(index).map(|positional| (positional.display_description(), &positional.name))?,
+            Section::Flags => self.parameters.flags.get(index).map(|flag| (flag.display_description(), &flag.key))?,
+            Section::Options => self.parameters.options.get(index).map(|option| (option.display_description(), &option.key))?,
+        };
+        match self.parameters.probes.get(key).and_then(HelpProbe::note) {
+            Some(note) => return description.map(|description| format!("{description} {note}")),
+            None => return description,
+        }
+    }
+
+    /// The selected argument or option, the only parameters with a default value
+    #[allow(clippy::needless_return)]
+    pub fn get_selected_argument(&self) -> Option<&CLIArgument> {
         match self.current_section {
-            Section::Arguments => return self.parameters.arguments[self.current_key_index].display_description(),
-            Section::Flags => return self.parameters.flags[self.current_key_index].display_description(),
-            Section::Options => return self.parameters.options[self.current_key_index].display_description(),
+            Section::Arguments => return self.parameters.arguments.get(self.current_key_index),
+            Section::Options => return self.parameters.options.get(self.current_key_index),
+            Section::Positionals | Section::Flags => return None,
         }
     }
 
+    /// Whether the selected argument or positional expands globs and its values,
+    /// None unless it takes files or already expands globs, e.g. from a spec
+    pub fn get_selected_glob(&self) -> Option<(bool, &[String])> {
+        let (glob, takes_files, values) = match self.current_section {
+            Section::Arguments | Section::Options => self.get_selected_argument().map(|argument| (argument.glob, argument.takes_files(), &argument.values))?,
+            Section::Positionals => self.parameters.positionals.get(self.current_key_index).map(|positional| (positional.glob, positional.takes_files(), &positional.values))?,
+            Section::Flags => return None,
+        };
+        (glob || takes_files).then_some((glob, values))
+    }
+
+    /// Whether the selected argument, option or positional takes paths, e.g. `<FILE>`
+    #[allow(clippy::needless_return)]
+    pub fn selected_takes_files(&self) -> bool {
+        match self.current_section {
+            Section::Arguments | Section::Options => return self.get_selected_argument().is_some_and(|argument| argument.takes_files() && argument.template.is_none()),
+            Section::Positionals => return self.parameters.positionals.get(self.current_key_index).is_some_and(|positional| positional.takes_files()),
+            Section::Flags => return false,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        if self.showing_help {
+            return Mode::Help;
+        }
+        if self.showing_argv {
+            return Mode::Argv;
+        }
+        if self.stats.is_some() {
+            return Mode::Stats;
+        }
+        if self.pager.is_some() {
+            return Mode::Pager;
+        }
+        if self.output.is_some() {
+            return Mode::Output;
+        }
+        if self.selecting_subcommand {
+            return Mode::Subcommands;
+        }
+        Mode::Form
+    }
+
+    /// Number of entries in the subcommand picker including the command itself
+    pub fn get_subcommand_len(&self) -> usize {
+        self.parameters.subcommands.len() + 1
+    }
+
+    /// Whether there is a finished output, a filter of the output to clear, a value being edited, a subcommand picker or a parent command to go back to
+    pub fn can_go_back(&self) -> bool {
+        if let Some(output) = &self.output {
+            return !output.is_running() || !output.filter.is_empty();
+        }
+        if self.selecting_subcommand {
+            return !self.parent_parameters.is_empty();
+        }
+        self.text_cursor.is_some() || !self.parameters.subcommands.is_empty() || !self.parent_parameters.is_empty()
+    }
+
+    /// Replace the parameters with the ones of the subcommand
+    /// Returns false if the subcommand has not been probed
+    pub fn enter_subcommand(&mut self, index: usize) -> bool {
+        let Some(mut subcommand_parameters) = self.parameters.subcommands.get_mut(index).and_then(|subcommand| subcommand.parameters.take()) else {
+            return false;
+        };
+        // global options are set once, in whichever form the user is in
+        subcommand_parameters.inherit_globals(&self.parameters);
+        let parameters = mem::replace(&mut self.parameters, subcommand_parameters);
+        self.parent_parameters.push((parameters, index));
+        self.selecting_subcommand = !self.parameters.subcommands.is_empty();
+        self.subcommand_index = 0;
+        self.select_first_section();
+        true
+    }
+
+    /// Take the subcommands of the parameters discovered in the background, the values filled in meanwhile are kept
+    /// Which options of the command are globals and which derived options it lists depends on its subcommands, so these are taken as well
+    pub fn attach_discovered(&mut self, discovered: CLIParameters) {
+        self.discovering = false;
+        let parameters = match self.parent_parameters.first_mut() {
+            Some((parameters, _)) => parameters,
+            None => &mut self.parameters,
+        };
+        let discovered_arguments: Vec<&CLIArgument> = discovered.arguments.iter().chain(&discovered.options).collect();
+        for argument in parameters.arguments.iter_mut().chain(&mut parameters.options) {
+            argument.global = discovered_arguments.iter().any(|discovered| discovered.key == argument.key && discovered.global);
+        }
+        for flag in &mut parameters.flags {
+            flag.global = discovered.flags.iter().any(|discovered| discovered.key == flag.key && discovered.global);
+        }
+        // a derived option of a subcommand is only added to the command while the subcommand is unknown
+        parameters.options.retain(|option| option.template.is_none() || discovered.options.iter().any(|discovered| discovered.key == option.key));
+        // the form of an entered subcommand is put back once it is left
+        for (subcommand, discovered_subcommand) in parameters.subcommands.iter_mut().zip(discovered.subcommands) {
+            if subcommand.name == discovered_subcommand.name && subcommand.parameters.is_none() {
+                subcommand.parameters = discovered_subcommand.parameters;
+            }
+        }
+        if self.current_key_index >= self.get_selected_parameter_len() {
+            self.select_first_section();
+        }
+    }
+
+    /// Restore the parameters of the parent command and show its subcommand picker
+    /// Returns false if there is no parent command
+    pub fn leave_subcommand(&mut self) -> bool {
+        let Some((parameters, index)) = self.parent_parameters.pop() else {
+            return false;
+        };
+        let mut subcommand_parameters = mem::replace(&mut self.parameters, parameters);
+        subcommand_parameters.return_globals(&mut self.parameters);
+        self.parameters.subcommands[index].parameters = Some(subcommand_parameters);
+        self.selecting_subcommand = true;
+        self.subcommand_index = index + 1;
+        self.select_first_section();
+        true
+    }
+
+    #[allow(clippy::needless_return)]
     pub fn get_selected_index(&self, section: Section) -> Option<usize> {
         if section == self.current_section {
             return Some(self.current_key_index);
@@ -46,16 +407,352 @@ impl Model {
     }
 
     pub fn get_selected_parameter_len(&self) -> usize {
-        match self.current_section {
+        if !self.section_is_available(self.current_section) {
+            return 0;
+        }
+        self.get_parameter_len(self.current_section)
+    }
+
+    #[allow(clippy::needless_return)]
+    pub fn get_parameter_len(&self, section: Section) -> usize {
+        match section {
             Section::Arguments => return self.parameters.arguments.len(),
+            Section::Positionals => return self.parameters.positionals.len(),
             Section::Flags => return self.parameters.flags.len(),
             Section::Options => return self.parameters.options.len(),
         }
     }
 
+    /// Id of the parameter at the index of the section
+    pub fn field_id(&self, section: Section, index: usize) -> Option<FieldId> {
+        let key = match section {
+            Section::Arguments => &self.parameters.arguments.get(index)?.key,
+            Section::Positionals => &self.parameters.positionals.get(index)?.name,
+            Section::Flags => &self.parameters.flags.get(index)?.key,
+            Section::Options => &self.parameters.options.get(index)?.key,
+        };
+        Some(FieldId { section, key: key.clone() })
+    }
+
+    /// Section and index of the parameter or subcommand rendered at the cell, the section is None for subcommands
+    pub fn list_item_at(&self, column: u16, row: u16) -> Option<(Option<Section>, usize)> {
+        self.list_rows.borrow().iter().find_map(|rows| Some((rows.section, rows.index_at(column, row)?)))
+    }
+
+    /// Value typing edits, the last value of an argument, option or positional
+    #[allow(clippy::needless_return)]
+    pub fn last_value(&self, field: &FieldId) -> Option<&String> {
+        let index = self.field_index(field)?;
+        match field.section {
+            Section::Arguments => return self.parameters.arguments[index].values.last(),
+            Section::Positionals => return self.parameters.positionals[index].values.last(),
+            Section::Options => return self.parameters.options[index].values.last(),
+            Section::Flags => return None,
+        }
+    }
+
+    /// Possible values of an argument or option that is chosen from them instead of typed, None for booleans and derived arguments
+    pub fn choices(&self, field: &FieldId) -> Option<&[String]> {
+        let argument = match field.section {
+            Section::Arguments => &self.parameters.arguments[self.field_index(field)?],
+            Section::Options => &self.parameters.options[self.field_index(field)?],
+            Section::Positionals | Section::Flags => return None,
+        };
+        let chosen = !argument.choices.is_empty() && argument.value_type != ValueType::Bool && argument.template.is_none();
+        chosen.then_some(argument.choices.as_slice())
+    }
+
+    /// Characters of the last value before the text cursor, the end of the value unless the field is being edited
+    #[allow(clippy::needless_return)]
+    pub fn cursor_position(&self, field: &FieldId) -> usize {
+        let length = self.last_value(field).map_or(0, |value| value.chars().count());
+        match &self.text_cursor {
+            Some(cursor) if cursor.field == *field => return cursor.position.min(length),
+            _ => return length,
+        }
+    }
+
+    /// Id of the selected parameter, None if its section is empty or hidden
+    pub fn selected_field(&self) -> Option<FieldId> {
+        if !self.section_is_available(self.current_section) {
+            return None;
+        }
+        self.field_id(self.current_section, self.current_key_index)
+    }
+
+    /// Index of the parameter in its section, None if the form no longer has it, e.g. after entering a subcommand
+    #[allow(clippy::needless_return)]
+    pub fn field_index(&self, field: &FieldId) -> Option<usize> {
+        match field.section {
+            Section::Arguments => return self.parameters.arguments.iter().position(|argument| argument.key == field.key),
+            Section::Positionals => return self.parameters.positionals.iter().position(|positional| positional.name == field.key),
+            Section::Flags => return self.parameters.flags.iter().position(|flag| flag.key == field.key),
+            Section::Options => return self.parameters.options.iter().position(|option| option.key == field.key),
+        }
+    }
+
+    /// Section and index of the parameter with the given key in any form, e.g. `-n`, `--name` or an alias,
+    /// or of the positional with the given name
+    pub fn find_key(&self, key: &str) -> Option<(Section, usize)> {
+        [
+            (Section::Arguments, self.parameters.arguments.iter().position(|argument| argument.has_key(key))),
+            (Section::Flags, self.parameters.flags.iter().position(|flag| flag.has_key(key))),
+            (Section::Options, self.parameters.options.iter().position(|option| option.has_key(key))),
+            (Section::Positionals, self.parameters.positionals.iter().position(|positional| positional.name == key)),
+        ]
+        .into_iter()
+        .find_map(|(section, index)| Some((section, index?)))
+    }
+
+    /// Move the selection to the parameter with the given key in any form or to the positional with the given name
+    /// Returns false if no shown parameter has this key
+    #[allow(clippy::needless_return)]
+    pub fn select_key(&mut self, key: &str) -> bool {
+        match self.find_key(key).filter(|(section, _)| self.section_is_available(*section)) {
+            Some((section, index)) => {
+                self.current_section = section;
+                self.current_key_index = index;
+                return true;
+            },
+            None => return false,
+        }
+    }
+
+    /// Value of the parameter with the given key in any form or of the positional with the given name
+    #[allow(clippy::needless_return)]
+    pub fn get(&self, key: &str) -> Option<ParameterValue> {
+        let parameters = &self.parameters;
+        match self.find_key(key)? {
+            (Section::Arguments, index) => return Some(ParameterValue::Values(parameters.arguments[index].values.clone())),
+            (Section::Options, index) => return Some(ParameterValue::Values(parameters.options[index].values.clone())),
+            (Section::Positionals, index) => return Some(ParameterValue::Values(parameters.positionals[index].values.clone())),
+            (Section::Flags, index) => {
+                let flag = &parameters.flags[index];
+                return Some(ParameterValue::Flag { times: flag.times(), negated: flag.set && flag.negated });
+            },
+        }
+    }
+
+    /// Replace the values of the parameter with the given key by a single value, see `set_values`
+    pub fn set_value(&mut self, key: &str, value: &str) -> Result<(), String> {
+        self.set_values(key, vec![value.to_string()])
+    }
+
+    /// Replace the values of the argument, option or positional with the given key, no values unset it
+    /// Values outside the choices of the parameter are refused
+    /// A flag takes `true`, `false` or how often it is passed, given its negated key it is passed negated,
+    /// e.g. `--no-color` and `true`
+    /// Flags that cannot be passed together with the parameter are unset
+    pub fn set_values(&mut self, key: &str, values: Vec<String>) -> Result<(), String> {
+        let (section, index) = self.find_key(key).ok_or(format!("Unknown parameter: {key}"))?;
+        self.share_code = None;
+        let parameters = &mut self.parameters;
+        let argument = match section {
+            Section::Arguments => &mut parameters.arguments[index],
+            Section::Options => &mut parameters.options[index],
+            Section::Positionals => {
+                parameters.positionals[index].values = values;
+                return Ok(());
+            },
+            Section::Flags => {
+                let [value] = values.as_slice() else {
+                    return Err(format!("{key} is a flag and takes a single value"));
+                };
+                let count = match value.as_str() {
+                    "true" => 1,
+                    "false" => 0,
+                    _ => value.parse::<u8>().map_err(|_| format!("{key} is a flag and takes true, false or a count"))?,
+                };
+                let flag = &mut parameters.flags[index];
+                flag.set_count(if flag.multiple { count } else { count.min(1) });
+                flag.negated = flag.set && flag.negated_key.as_deref() == Some(key);
+                if flag.set && !flag.negated {
+                    let key = flag.key.clone();
+                    self.unset_conflicting_flags(&key);
+                }
+                return Ok(());
+            },
+        };
+        if let Some(template) = &argument.template {
+            return Err(format!("{} is computed from {template}", argument.name));
+        }
+        if argument.value_type == ValueType::Number && !values.iter().filter(|value| !value.is_empty()).all(|value| expression::is_number_value(value)) {
+            return Err(format!("{} takes a number", argument.name));
+        }
+        if let Some(error) = argument.check_choices(&values) {
+            return Err(error);
+        }
+        argument.values = values;
+        let key = argument.key.clone();
+        self.unset_conflicting_flags(&key);
+        Ok(())
+    }
+
+    /// Set the unset flag with the given key in any form and unset the set one
+    /// A flag with a negated key goes through unset, on, off and unset again
+    pub fn toggle_flag(&mut self, key: &str) -> Result<(), String> {
+        let index = match self.find_key(key) {
+            Some((Section::Flags, index)) => index,
+            _ => return Err(format!("Unknown flag: {key}")),
+        };
+        self.share_code = None;
+        let flag = &mut self.parameters.flags[index];
+        if flag.negated_key.is_some() {
+            let (set, negated) = match (flag.set, flag.negated) {
+                (false, _) => (true, false),
+                (true, false) => (true, true),
+                (true, true) => (false, false),
+            };
+            flag.set_count(set as u8);
+            flag.negated = negated;
+        } else {
+            flag.set_count(if flag.set { 0 } else { 1 });
+        }
+        if flag.set && !flag.negated {
+            let key = flag.key.clone();
+            self.unset_conflicting_flags(&key);
+        }
+        Ok(())
+    }
+
+    /// Unset the flags that cannot be passed together with the key, e.g. `--yaml` once `--json` is set
+    pub fn unset_conflicting_flags(&mut self, key: &str) {
+        let conflicting_keys: Vec<String> = self.parameters.conflicting_keys(key).into_iter().cloned().collect();
+        self.parameters.flags
+            .iter_mut()
+            .filter(|flag| conflicting_keys.contains(&flag.key))
+            .for_each(|flag| flag.set_count(0));
+    }
+
+    /// Section, index and name of the first required parameter without a value
+    pub fn find_missing_required(&self) -> Option<(Section, usize, &str)> {
+        let arguments = self.parameters.arguments
+            .iter()
+            .position(|argument| argument.required && argument.filled_values().next().is_none())
+            .map(|index| (Section::Arguments, index, self.parameters.arguments[index].name.as_str()));
+        let positionals = self.parameters.positionals
+            .iter()
+            .position(|positional| positional.required && positional.filled_values().next().is_none())
+            .map(|index| (Section::Positionals, index, self.parameters.positionals[index].name.as_str()));
+        let options = self.parameters.options
+            .iter()
+            .position(|option| option.required && option.filled_values().next().is_none())
+            .map(|index| (Section::Options, index, self.parameters.options[index].name.as_str()));
+        [arguments, positionals, options]
+            .into_iter()
+            .flatten()
+            .find(|(section, _, _)| self.section_is_available(*section))
+    }
+
+    /// Section, index and error of the first argument or option with a value that is not one of its choices,
+    /// e.g. one restored from the history or the environment
+    pub fn find_choice_violation(&self) -> Option<(Section, usize, String)> {
+        let find = |section: Section, arguments: &[CLIArgument]| {
+            arguments.iter().enumerate().find_map(|(index, argument)| Some((section, index, argument.check_choices(&argument.values)?)))
+        };
+        [find(Section::Arguments, &self.parameters.arguments), find(Section::Options, &self.parameters.options)]
+            .into_iter()
+            .flatten()
+            .find(|(section, ..)| self.section_is_available(*section))
+    }
+
+    /// Section, index, name, check and values of the parameters taking paths
+    fn path_parameters(&self) -> impl Iterator<Item = (Section, usize, &str, PathCheck, &[String])> {
+        fn with_paths<'a, T: GUIDisplay>(section: Section, parameters: &'a [T], name: impl Fn(&'a T) -> &'a str) -> impl Iterator<Item = (Section, usize, &'a str, PathCheck, &'a [String])> {
+            parameters.iter().enumerate().filter_map(move |(index, parameter)| {
+                let (check, values) = parameter.display_paths()?;
+                Some((section, index, name(parameter), check, values))
+            })
+        }
+        with_paths(Section::Arguments, &self.parameters.arguments, |argument| argument.name.as_str())
+            .chain(with_paths(Section::Positionals, &self.parameters.positionals, |positional| positional.name.as_str()))
+            .chain(with_paths(Section::Options, &self.parameters.options, |option| option.name.as_str()))
+            .filter(|(section, ..)| self.section_is_available(*section))
+    }
+
+    /// Filled values of the parameters taking paths, in the sections that are shown
+    pub fn path_values(&self) -> Vec<&String> {
+        self.path_parameters().flat_map(|(.., values)| values.iter().filter(|value| !value.is_empty())).collect()
+    }
+
+    /// Section, index and error of the first path value breaking the check of its parameter
+    /// Paths the checker has not reached yet are checked right away
+    pub fn find_path_violation(&self) -> Option<(Section, usize, String)> {
+        self.find_path_violation_by(|value| Some(self.path_states.get(value).copied().unwrap_or_else(|| PathState::of(value))))
+    }
+
+    /// Like `find_path_violation` with only the paths the checker has reached, nothing touches the file system
+    pub fn find_checked_path_violation(&self) -> Option<(Section, usize, String)> {
+        self.find_path_violation_by(|value| self.path_states.get(value).copied())
+    }
+
+    /// Whether a path of the selected parameter has a check but has not been checked yet
+    pub fn is_selected_path_unchecked(&self) -> bool {
+        let selected = (self.current_section, self.current_key_index);
+        self.path_parameters()
+            .filter(|(section, index, _, check, _)| (*section, *index) == selected && *check != PathCheck::Any)
+            .any(|(.., values)| values.iter().any(|value| !value.is_empty() && !self.path_states.contains_key(value)))
+    }
+
+    /// Paths whose state is None are taken as valid
+    #[allow(clippy::needless_return)]
+    fn find_path_violation_by(&self, state: impl Fn(&str) -> Option<PathState>) -> Option<(Section, usize, String)> {
+        self.path_parameters().find_map(|(section, index, name, check, values)| {
+            let value = values.iter().filter(|value| !value.is_empty()).find(|value| state(value).is_some_and(|state| state.violates(check)))?;
+            match check {
+                PathCheck::MustNotExist => return Some((section, index, format!("{name} already exists: {value}"))),
+                _ => return Some((section, index, format!("{name} does not exist: {value}"))),
+            }
+        })
+    }
+
+    /// Indices of the parameters of the section shown in the form, only those whose key, name or description contain the filter
+    /// The filter ignores case and diacritics, every parameter is shown without one
+    pub fn shown_indices(&self, section: Section) -> Vec<usize> {
+        let Some(query) = &self.filter else {
+            return (0..self.get_parameter_len(section)).collect();
+        };
+        let argument_text = |argument: &CLIArgument| {
+            [Some(&argument.key), argument.short_key.as_ref(), Some(&argument.name), argument.description.as_ref()].into_iter().flatten().cloned().collect::<Vec<String>>()
+        };
+        let texts: Vec<Vec<String>> = match section {
+            Section::Arguments => self.parameters.arguments.iter().map(argument_text).collect(),
+            Section::Options => self.parameters.options.iter().map(argument_text).collect(),
+            Section::Positionals => self.parameters.positionals
+                .iter()
+                .map(|positional| [Some(&positional.name), positional.description.as_ref()].into_iter().flatten().cloned().collect())
+                .collect(),
+            Section::Flags => self.parameters.flags
+                .iter()
+                .map(|flag| [Some(&flag.key), flag.short_key.as_ref(), flag.negated_key.as_ref(), flag.description.as_ref()].into_iter().flatten().cloned().collect())
+                .collect(),
+        };
+        let query = collation_key(query);
+        texts
+            .iter()
+            .enumerate()
+            .filter(|(_, texts)| texts.iter().any(|text| collation_key(text).contains(&query)))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Section and index of the first parameter matching the filter, the one Enter selects
+    pub fn first_filter_match(&self) -> Option<(Section, usize)> {
+        Section::ALL
+            .into_iter()
+            .filter(|section| self.section_is_available(*section))
+            .find_map(|section| Some((section, *self.shown_indices(section).first()?)))
+    }
+
+    /// Whether the section has parameters and is not hidden
+    #[allow(clippy::needless_return)]
     pub fn section_is_available(&self, section: Section) -> bool {
+        if self.hidden_sections.contains(&section) {
+            return false;
+        }
         match section {
             Section::Arguments => return !self.parameters.arguments.is_empty(),
+            Section::Positionals => return !self.parameters.positionals.is_empty(),
             Section::Flags => return !self.parameters.flags.is_empty(),
             Section::Options => return !self.parameters.options.is_empty(),
         }
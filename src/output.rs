@@ -0,0 +1,340 @@
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    ops::Range,
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use regex::{Regex, RegexBuilder};
+
+/// Line written by the command, on standard output or standard error
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OutputLine {
+    Stdout(String),
+    Stderr(String),
+}
+
+impl OutputLine {
+    #[allow(clippy::needless_return)]
+    pub fn text(&self) -> &str {
+        match self {
+            OutputLine::Stdout(text) | OutputLine::Stderr(text) => return text,
+        }
+    }
+}
+
+/// Filter of the output pane, a substring or `re:` followed by a regular expression
+/// A leading `!` keeps the lines that do not match, case is ignored unless the filter has an uppercase letter
+#[derive(Debug)]
+pub struct LineFilter {
+    pattern: Regex,
+    exclude: bool,
+}
+
+impl LineFilter {
+    /// None for an empty filter, which keeps every line
+    pub fn parse(filter: &str) -> Result<Option<LineFilter>, String> {
+        let (exclude, filter) = match filter.strip_prefix('!') {
+            Some(filter) => (true, filter),
+            None => (false, filter),
+        };
+        let expression = match filter.strip_prefix("re:") {
+            Some(expression) => expression.to_string(),
+            None => regex::escape(filter),
+        };
+        if expression.is_empty() {
+            return Ok(None);
+        }
+        let pattern = RegexBuilder::new(&expression)
+            .case_insensitive(!filter.chars().any(char::is_uppercase))
+            .build()
+            // the last line of a syntax error names the problem, the others draw the expression
+            .map_err(|error| format!("Invalid filter: {}", error.to_string().lines().last().unwrap_or_default().trim_start_matches("error: ")))?;
+        Ok(Some(LineFilter { pattern, exclude }))
+    }
+
+    pub fn keeps(&self, text: &str) -> bool {
+        self.pattern.is_match(text) != self.exclude
+    }
+}
+
+impl PartialEq for LineFilter {
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.as_str() == other.pattern.as_str() && self.exclude == other.exclude
+    }
+}
+
+/// Output of the command run from the form, streamed in while it runs
+#[derive(Debug, PartialEq)]
+pub struct OutputPane {
+    /// Command line as shown to the user
+    pub command_line: String,
+    pub lines: Vec<OutputLine>,
+    /// Filter of the lines as typed, see `LineFilter`
+    pub filter: String,
+    /// Whether the filter is being typed
+    pub filtering: bool,
+    /// Compiled filter, the error of an invalid one shows every line
+    line_filter: Result<Option<LineFilter>, String>,
+    /// Indices of the lines the filter keeps, updated as lines stream in
+    shown: Vec<usize>,
+    /// Number of shown lines between the last visible line and the bottom
+    pub scroll_back: usize,
+    /// Whether new lines keep the bottom in view, like `tail -f`
+    pub follow: bool,
+    /// Exit status once the command has finished
+    pub status: Option<ExitStatus>,
+    pub started: SystemTime,
+    /// Fingerprint of the parameters recorded in the history, None for test runs, see `CLIParameters::fingerprint`
+    pub fingerprint: Option<String>,
+    /// Usual duration of the command from the history, shown as progress while it runs
+    pub estimate: Option<Duration>,
+}
+
+impl OutputPane {
+    pub fn new(command_line: String) -> Self {
+        Self {
+            command_line,
+            lines: Vec::new(),
+            filter: String::new(),
+            filtering: false,
+            line_filter: Ok(None),
+            shown: Vec::new(),
+            scroll_back: 0,
+            follow: true,
+            status: None,
+            started: SystemTime::now(),
+            fingerprint: None,
+            estimate: None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.status.is_none()
+    }
+
+    #[allow(clippy::needless_return)]
+    fn keeps(&self, line: &OutputLine) -> bool {
+        match &self.line_filter {
+            Ok(Some(filter)) => return filter.keeps(line.text()),
+            _ => return true,
+        }
+    }
+
+    /// New lines move the view only while following, otherwise the visible lines stay in place
+    pub fn push(&mut self, line: OutputLine) {
+        if self.keeps(&line) {
+            self.shown.push(self.lines.len());
+            if !self.follow {
+                self.scroll_back += 1;
+            }
+        }
+        self.lines.push(line);
+    }
+
+    /// Filter the lines again, the view moves to the last line the filter keeps
+    pub fn set_filter(&mut self, filter: String) {
+        self.line_filter = LineFilter::parse(&filter);
+        self.filter = filter;
+        self.shown = (0..self.lines.len()).filter(|index| self.keeps(&self.lines[*index])).collect();
+        self.scroll_back = 0;
+    }
+
+    /// Why the filter cannot be applied, None if it is valid
+    pub fn filter_error(&self) -> Option<&str> {
+        self.line_filter.as_ref().err().map(String::as_str)
+    }
+
+    /// Indices of the lines the filter keeps, every line without a filter
+    pub fn shown_lines(&self) -> &[usize] {
+        &self.shown
+    }
+
+    /// Scrolling up stops following the output
+    pub fn scroll_up(&mut self) {
+        self.scroll_back = (self.scroll_back + 1).min(self.shown.len().saturating_sub(1));
+        self.follow = false;
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll_back = self.scroll_back.saturating_sub(1);
+    }
+
+    /// Jump to the bottom and keep following the output
+    pub fn follow(&mut self) {
+        self.scroll_back = 0;
+        self.follow = true;
+    }
+
+    /// Positions among the shown lines that fit into the given height, a full page is shown even when scrolled past the top
+    pub fn visible_lines(&self, height: usize) -> Range<usize> {
+        let len = self.shown.len();
+        let end = (len - self.scroll_back.min(len)).max(height.min(len));
+        end.saturating_sub(height)..end
+    }
+
+    /// Both streams in the order they were written, e.g. for `$PAGER`
+    pub fn text(&self) -> String {
+        self.lines.iter().map(OutputLine::text).collect::<Vec<&str>>().join("\n")
+    }
+
+    /// Standard error of the command, used to find the parameter it complained about
+    pub fn error_output(&self) -> String {
+        self.lines
+            .iter()
+            .filter(|line| matches!(line, OutputLine::Stderr(_)))
+            .map(OutputLine::text)
+            .collect::<Vec<&str>>()
+            .join("\n")
+    }
+}
+
+/// Command running while the TUI is shown, one thread per stream forwards its lines
+#[derive(Debug)]
+pub struct RunningCommand {
+    child: Child,
+    receiver: Receiver<OutputLine>,
+}
+
+impl RunningCommand {
+    /// The command gets no input as the terminal belongs to the TUI
+    pub fn spawn(mut command: Command) -> io::Result<RunningCommand> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let (sender, receiver) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, sender.clone(), OutputLine::Stdout);
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, sender, OutputLine::Stderr);
+        }
+        Ok(RunningCommand { child, receiver })
+    }
+
+    /// Move the new lines into the pane and set its status once all output has been read
+    pub fn poll(&mut self, pane: &mut OutputPane) -> io::Result<()> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(line) => pane.push(line),
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        pane.status = self.child.try_wait()?;
+        Ok(())
+    }
+}
+
+/// A command that is still running when clitui quits is killed
+impl Drop for RunningCommand {
+    fn drop(&mut self) {
+        if let Ok(None) = self.child.try_wait() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+fn forward_lines(stream: impl Read + Send + 'static, sender: Sender<OutputLine>, wrap: fn(String) -> OutputLine) {
+    thread::spawn(move || {
+        for line in BufReader::new(stream).split(b'\n').map_while(Result::ok) {
+            let line = String::from_utf8_lossy(&line).trim_end_matches('\r').to_string();
+            if sender.send(wrap(line)).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_pane(len: usize) -> OutputPane {
+    let mut pane = OutputPane::new(String::from("tool"));
+    (0..len).for_each(|index| pane.push(OutputLine::Stdout(format!("line {index}"))));
+    pane
+}
+
+#[test]
+fn test_following_pane_shows_bottom() {
+    let pane = create_test_pane(100);
+
+    assert_eq!(pane.visible_lines(10), 90..100);
+}
+
+#[test]
+fn test_scroll_up_stops_following() {
+    let mut pane = create_test_pane(100);
+
+    pane.scroll_up();
+    pane.push(OutputLine::Stdout(String::from("new line")));
+
+    assert!(!pane.follow);
+    assert_eq!(pane.visible_lines(10), 89..99);
+}
+
+#[test]
+fn test_follow_jumps_to_bottom() {
+    let mut pane = create_test_pane(100);
+    pane.scroll_up();
+    pane.push(OutputLine::Stdout(String::from("new line")));
+
+    pane.follow();
+
+    assert!(pane.follow);
+    assert_eq!(pane.visible_lines(10), 91..101);
+}
+
+#[test]
+fn test_visible_lines_scrolled_past_top() {
+    let mut pane = create_test_pane(5);
+    (0..10).for_each(|_| pane.scroll_up());
+
+    assert_eq!(pane.visible_lines(3), 0..3);
+}
+
+#[test]
+fn test_filter_keeps_matching_lines() {
+    let mut pane = create_test_pane(30);
+
+    pane.set_filter(String::from("LINE 2"));
+    pane.push(OutputLine::Stderr(String::from("line 2 failed")));
+    pane.push(OutputLine::Stdout(String::from("line 30")));
+
+    // uppercase letters make the filter case sensitive
+    assert!(pane.shown_lines().is_empty());
+    pane.set_filter(String::from("line 2"));
+    assert_eq!(pane.shown_lines(), [2, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30]);
+    assert_eq!(pane.visible_lines(3), 9..12);
+    pane.set_filter(String::from("!re:^line \\d+$"));
+    assert_eq!(pane.shown_lines(), [30]);
+    pane.set_filter(String::from("re:line ("));
+    assert_eq!(pane.filter_error(), Some("Invalid filter: unclosed group"));
+    assert_eq!(pane.shown_lines().len(), 32);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_running_command_streams_output() {
+    let mut command = Command::new("sh");
+    command.args(["-c", "echo out; echo err >&2; exit 3"]);
+    let mut pane = OutputPane::new(String::from("sh"));
+
+    let mut running_command = RunningCommand::spawn(command).unwrap();
+    for _ in 0..250 {
+        running_command.poll(&mut pane).unwrap();
+        if !pane.is_running() {
+            break;
+        }
+        thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    assert_eq!(pane.status.and_then(|status| status.code()), Some(3));
+    assert!(pane.lines.contains(&OutputLine::Stdout(String::from("out"))));
+    assert_eq!(pane.error_output(), "err");
+}
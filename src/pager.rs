@@ -0,0 +1,113 @@
+use std::cell::Cell;
+
+use crate::output::OutputLine;
+
+/// Full screen view of the output of a finished command, with line numbers and search
+#[derive(Debug, Default, PartialEq)]
+pub struct Pager {
+    /// First visible line
+    pub top: usize,
+    /// Number of lines that fit on the page when it was last rendered
+    pub page_height: Cell<usize>,
+    pub query: String,
+    /// Whether the search query is being typed
+    pub searching: bool,
+}
+
+impl Pager {
+    /// The last page is always full
+    fn max_top(&self, len: usize) -> usize {
+        len.saturating_sub(self.page_height.get().max(1))
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.top = self.top.saturating_sub(1);
+    }
+
+    pub fn scroll_down(&mut self, len: usize) {
+        self.top = (self.top + 1).min(self.max_top(len));
+    }
+
+    pub fn page_up(&mut self) {
+        self.top = self.top.saturating_sub(self.page_height.get().max(1));
+    }
+
+    pub fn page_down(&mut self, len: usize) {
+        self.top = (self.top + self.page_height.get().max(1)).min(self.max_top(len));
+    }
+
+    pub fn jump_to_top(&mut self) {
+        self.top = 0;
+    }
+
+    pub fn jump_to_bottom(&mut self, len: usize) {
+        self.top = self.max_top(len);
+    }
+
+    /// Show the next line containing the query at the top of the page, wrapping around at the end
+    /// Returns false if no line contains the query
+    pub fn jump_to_next_match(&mut self, lines: &[OutputLine], include_top: bool) -> bool {
+        if self.query.is_empty() {
+            return false;
+        }
+        let start = if include_top { self.top } else { self.top + 1 };
+        let next_match = (0..lines.len())
+            .map(|offset| (start + offset) % lines.len())
+            .find(|index| lines[*index].text().contains(&self.query));
+        if let Some(index) = next_match {
+            self.top = index;
+            return true;
+        }
+        false
+    }
+
+    /// Number of lines containing the query
+    pub fn match_count(&self, lines: &[OutputLine]) -> usize {
+        if self.query.is_empty() {
+            return 0;
+        }
+        lines.iter().filter(|line| line.text().contains(&self.query)).count()
+    }
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_lines() -> Vec<OutputLine> {
+    (0..50)
+        .map(|index| match index % 10 {
+            7 => OutputLine::Stderr(format!("error in line {index}")),
+            _ => OutputLine::Stdout(format!("line {index}")),
+        })
+        .collect()
+}
+
+#[test]
+fn test_scroll_keeps_last_page_full() {
+    let mut pager = Pager::default();
+    pager.page_height.set(10);
+
+    pager.jump_to_bottom(50);
+    pager.scroll_down(50);
+
+    assert_eq!(pager.top, 40);
+}
+
+#[test]
+fn test_jump_to_next_match_wraps_around() {
+    let lines = create_test_lines();
+    let mut pager = Pager { query: String::from("error"), top: 47, ..Default::default() };
+
+    assert!(pager.jump_to_next_match(&lines, false));
+    assert_eq!(pager.top, 7);
+    assert_eq!(pager.match_count(&lines), 5);
+}
+
+#[test]
+fn test_jump_to_next_match_without_match() {
+    let lines = create_test_lines();
+    let mut pager = Pager { query: String::from("warning"), top: 3, ..Default::default() };
+
+    assert!(!pager.jump_to_next_match(&lines, true));
+    assert_eq!(pager.top, 3);
+}
@@ -1,595 +1,6505 @@
-use std::process::Command;
+use std::{collections::BTreeMap, fmt, mem, process::Command, sync::LazyLock};
 
-use regex::Regex;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, PartialEq, Eq)]
+use crate::{collation, expression, glob};
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum CLILib {
     #[default]
     Clap,
+    Argparse,
+    Cobra,
+    Docopt,
+    Gnu,
+    /// Classic Windows tools like robocopy and xcopy with `/MIR` and `/LOG:file` options
+    WindowsSlash,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct CLIArgument {
-    pub key: String,
-    pub name: String,
-    pub description: Option<String>,
-    pub value: String
-}
+impl CLILib {
+    /// Every parser in the order they are tried
+    pub const ALL: [CLILib; 6] = [CLILib::Clap, CLILib::Argparse, CLILib::Cobra, CLILib::Docopt, CLILib::Gnu, CLILib::WindowsSlash];
 
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct CLIFlag {
-    pub key: String,
-    pub description: Option<String>,
-    pub set: bool,
-}
+    /// Name of the parser in the config, e.g. `gnu`
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<CLILib> {
+        match name.to_lowercase().as_str() {
+            "clap" => return Some(CLILib::Clap),
+            "argparse" => return Some(CLILib::Argparse),
+            "cobra" => return Some(CLILib::Cobra),
+            "docopt" => return Some(CLILib::Docopt),
+            "gnu" => return Some(CLILib::Gnu),
+            "windows" => return Some(CLILib::WindowsSlash),
+            _ => return None,
+        }
+    }
 
-impl CLIFlag {
-    pub fn name(&self) -> String {
-        self.key.trim_start_matches('-').to_uppercase()
+    /// Whether the parser tells required options apart, the others list every keyed parameter as optional
+    pub fn reads_requiredness(&self) -> bool {
+        matches!(self, CLILib::Clap | CLILib::Argparse | CLILib::Docopt)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum CLIParameter {
-    Argument(CLIArgument),
-    Flag(CLIFlag),
+/// Which form of a key is passed to the command, the other form is used if a parameter has only one
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum KeyStyle {
+    #[default]
+    Long,
+    Short,
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
-pub struct CLIParameters {
-    pub cli_name: String,
-    pub arguments: Vec<CLIArgument>,
-    pub options: Vec<CLIArgument>,
-    pub flags: Vec<CLIFlag>,
-    pub cli_lib: CLILib,
-}
+impl KeyStyle {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<KeyStyle> {
+        match name {
+            "long" => return Some(KeyStyle::Long),
+            "short" => return Some(KeyStyle::Short),
+            _ => return None,
+        }
+    }
 
-/// Parses a help string from a CLI to determine the arguments and the options
-pub fn parse_help_string(help_string: &str) -> Option<CLIParameters> {
-    let parses_to_try = vec![
-        parse_clap_help_string
-    ];
-    parses_to_try.iter().find_map(|parse| parse(help_string))
+    /// The key of the style, falling back to the key identifying the parameter
+    #[allow(clippy::needless_return)]
+    fn choose<'a>(&self, key: &'a str, short_key: Option<&'a str>, long_key: Option<&'a str>) -> &'a str {
+        match self {
+            KeyStyle::Long => return long_key.unwrap_or(key),
+            KeyStyle::Short => return short_key.unwrap_or(key),
+        }
+    }
 }
 
-/// Parses a clap help string
-fn parse_clap_help_string(help_string: &str) -> Option<CLIParameters> {
-    let option_explanation = retrieve_clap_option_explanation(help_string)?;
-    let parameters = parse_clap_option_explanation(option_explanation)?;
-    let usage_explanation = retrieve_clap_usage_explanation(help_string)?;
-    let (cli_name, argument_keys) = parse_clap_usage_explanation(usage_explanation);
-    let mut result = CLIParameters::default();
-    result.cli_name = cli_name;
-    result.cli_lib = CLILib::Clap;
-
-    for parameter in parameters {
-        match parameter {
-            CLIParameter::Argument(argument) => {
-                if argument_keys.contains(&argument.key) {
-                    result.arguments.push(argument);
-                } else {
-                    result.options.push(argument);
-                }
-            },
-            CLIParameter::Flag(flag) => result.flags.push(flag)
+/// Short and long form among the variants of a key, e.g. `-n` and `--name`,
+/// and the other variants as aliases, e.g. `--ver` of `-V, --version, --ver`
+fn split_key_variants<'a>(variants: impl IntoIterator<Item = &'a str>) -> (Option<String>, Option<String>, Vec<String>) {
+    let (mut short_key, mut long_key, mut aliases) = (None, None, Vec::new());
+    for variant in variants {
+        if variant.starts_with("--") && long_key.is_none() {
+            long_key = Some(variant.to_string());
+        } else if variant.starts_with('-') && !variant.starts_with("--") && short_key.is_none() {
+            short_key = Some(variant.to_string());
+        } else if variant.starts_with('-') {
+            aliases.push(variant.to_string());
         }
     }
-    Some(result)
+    (short_key, long_key, aliases)
 }
 
-/// Retrieve the option explanation of a clap help string, e.g.
-/// Options:
-///     -n, --name <NAME> Name of the person to greet
-fn retrieve_clap_option_explanation<'a>(help_string: &'a str) -> Option<&'a str> {
-    let option_index = help_string.find("Options:")?;
-    Some(&help_string[option_index..])
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CLIArgument {
+    /// Identifies the parameter, the long key if there is one
+    pub key: String,
+    /// Short form of the key, e.g. `-n`
+    pub short_key: Option<String>,
+    /// Long form of the key, e.g. `--name`
+    pub long_key: Option<String>,
+    /// Further keys, e.g. `--nick` of `-n, --name, --nick`
+    pub aliases: Vec<String>,
+    pub name: String,
+    pub description: Option<String>,
+    /// Every value is passed with the key, more than one only if `multiple`
+    pub values: Vec<String>,
+    pub multiple: bool,
+    /// Has to be passed according to the usage line
+    pub required: bool,
+    /// Only these values are accepted, e.g. `[possible values: json, yaml]`, empty if any value is
+    pub choices: Vec<String>,
+    /// Environment variable the CLI reads the value from, e.g. `[env: MY_VAR=]`
+    pub env_var: Option<String>,
+    pub value_type: ValueType,
+    /// Value the help states as default, e.g. `[default: 10]`
+    pub default_value: Option<String>,
+    /// Computes the value from other fields, e.g. `{name}-{date}.log`, which makes the argument read-only
+    pub template: Option<String>,
+    /// Passed as `--key=value` instead of two arguments, e.g. for `--color=<WHEN>` in the help
+    pub joined: bool,
+    /// Values like `*.log` are expanded by clitui when the command runs, see `passed_values`
+    pub glob: bool,
+    /// Heading the help lists the argument under, e.g. `Network Options`, None for the plain `Options:`
+    pub group: Option<String>,
+    /// What has to be at the paths of the values before the command runs, only for path arguments
+    pub path_check: PathCheck,
+    /// Accepted by the subcommands as well, e.g. a clap `global` option, kept only at the command defining it
+    pub global: bool,
+    /// Depth of the command a global option is copied from into the form of a subcommand, it is passed right after that command
+    pub inherited: Option<usize>,
 }
 
-/// Retrieve the usage explanation of a clap help string, e.g.
-/// Usage: clap_example.exe [OPTIONS] --name <NAME>
-fn retrieve_clap_usage_explanation<'a>(help_string: &'a str) -> Option<&'a str> {
-    help_string
-        .lines()
-        .find(|line| line.starts_with("Usage:"))
-        .clone()
+/// Kind of value an argument takes, inferred from its value name and its default value
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum ValueType {
+    #[default]
+    String,
+    Number,
+    Path,
+    Bool,
+    Duration,
 }
 
-/// Parse a single clap option line for cli parameters
-/// There exists two version of option line
-/// 1. Arguments: -n, --name <NAME> Name of the person to greet [default: Me]
-/// 2. Flags: -h, --help Print help
-fn parse_clap_option_line(option_line: &str) -> Option<CLIParameter> {
-    let re = Regex::new(r"[ ]*(?P<short_key>-\w)?[ ,]*(?P<long_key>--\w+(?:-\w+)?)?\s*(<(?P<name>\w+)>)?(?P<description>[ \w]+)?(\[default: (?P<value>.+)\])?").ok()?;
-    let caps = re.captures(option_line)?;
-    let key = caps.name("long_key")
-        .or_else(|| caps.name("short_key"))
-        .map(|k| k.as_str().to_string())?;
-    let name = caps.name("name").map(|name| name.as_str().to_string());
-    let description = caps.name("description").map(|description| description.as_str().trim().to_string());
-    let value = caps.name("value").map(|value| value.as_str().to_string()).unwrap_or(String::new());
-    if let Some(name) = name {
-        Some(CLIParameter::Argument(CLIArgument {
-            key,
-            name,
-            description,
-            value,
-        }))
-    } else {
-        Some(CLIParameter::Flag(CLIFlag {
-            key,
-            description,
-            set: false,
-        }))
+impl ValueType {
+    /// Infer the type from the last word of a value name, e.g. `<CONFIG_FILE>` or `<N>`,
+    /// falling back to the default value, e.g. `[default: 10]`
+    #[allow(clippy::needless_return)]
+    pub fn infer(name: &str, default_value: Option<&str>) -> ValueType {
+        let name = name.to_uppercase();
+        if is_boolean_pair(name.split(['|', ',']).map(|word| word.trim_matches(['<', '>', '{', '}', '[', ']']))) {
+            return ValueType::Bool;
+        }
+        let word = name.rsplit(['_', '-']).next().unwrap_or_default();
+        match word {
+            "N" | "NUM" | "NUMBER" | "COUNT" | "BYTES" | "JOBS" | "PORT" | "LIMIT" | "DEPTH" | "RETRIES" | "INT" => return ValueType::Number,
+            "PATH" | "FILE" | "FILENAME" | "DIR" | "DIRECTORY" | "FOLDER" => return ValueType::Path,
+            "BOOL" | "BOOLEAN" => return ValueType::Bool,
+            "TIMEOUT" | "DURATION" | "DELAY" | "INTERVAL" | "SECONDS" | "SECS" | "MS" | "MILLIS" => return ValueType::Duration,
+            _ => (),
+        }
+        match default_value {
+            Some(value) if expression::is_plain_number(value) => return ValueType::Number,
+            Some("true" | "false") => return ValueType::Bool,
+            _ => return ValueType::String,
+        }
     }
-}
 
-/// Parse the option explanation of a clap help string
-fn parse_clap_option_explanation(option_string: &str) -> Option<Vec<CLIParameter>> {
-    let parsed_options = option_string
-        .lines()
-        .skip(1) // Skip the "Options:" line
-        .filter_map(|line| parse_clap_option_line(line))
-        .collect::<Vec<CLIParameter>>();
+    /// Numbers and durations accept arithmetic expressions
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, ValueType::Number | ValueType::Duration)
+    }
 
-    if parsed_options.is_empty() {
-        None
-    } else {
-        Some(parsed_options)
+    /// Shown next to the description of the argument
+    #[allow(clippy::needless_return)]
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            ValueType::String => return None,
+            ValueType::Number => return Some("number, e.g. 4*1024"),
+            ValueType::Path => return Some("path"),
+            ValueType::Bool => return Some("true or false"),
+            ValueType::Duration => return Some("duration, e.g. 60*15 or 30s"),
+        }
     }
 }
 
-/// Parse the usage explanation of a clap help string
-/// Used to distinguish between arguments and options
-fn parse_clap_usage_explanation(usage_string: &str) -> (String, Vec<String>) {
-    let cli_name_pattern = Regex::new(r"Usage: (?P<cli_name>[\w\.]+)").unwrap();
-    let caps = cli_name_pattern.captures(usage_string).unwrap();
-    let cli_name: String = caps.name("cli_name").map(|m| m.as_str().to_string()).unwrap();
-    let key_pattern = Regex::new(r"--?\w+(?:-\w+)?").unwrap();
-    let keys = key_pattern.find_iter(usage_string)
-        .map(|mat| mat.as_str().to_string())
-        .collect();
-    (cli_name, keys)
+/// What has to be at the path a value names before the command runs, set per parameter in the config or the spec
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum PathCheck {
+    #[default]
+    Any,
+    /// A file or directory, e.g. an input
+    MustExist,
+    /// Nothing yet, e.g. an output that should not be overwritten
+    MustNotExist,
 }
 
-/// Convert the parameters to an actual cli command
-pub fn convert_to_cli(parameters: &CLIParameters) -> Command {
-    match  parameters.cli_lib {
-        CLILib::Clap => return convert_to_clap_cli(parameters),
+impl PathCheck {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<PathCheck> {
+        match name {
+            "any" => return Some(PathCheck::Any),
+            "exist" => return Some(PathCheck::MustExist),
+            "absent" => return Some(PathCheck::MustNotExist),
+            _ => return None,
+        }
     }
 }
 
-/// Convert the parameters to clap cli command
-fn convert_to_clap_cli(parameters: &CLIParameters) -> Command {
-    let mut cli_command = Command::new(parameters.cli_name.clone());
-    for option in &parameters.options {
-        if !option.value.is_empty() {
-            cli_command.args([&option.key, &option.value]);
+/// Whether the words are the two values of a boolean, e.g. `TRUE|FALSE` or the choices `true, false`
+fn is_boolean_pair<S: AsRef<str>>(words: impl Iterator<Item = S>) -> bool {
+    let mut words: Vec<String> = words.map(|word| word.as_ref().to_uppercase()).collect();
+    words.sort();
+    matches!(words.iter().map(String::as_str).collect::<Vec<&str>>()[..], ["FALSE", "TRUE"] | ["NO", "YES"] | ["OFF", "ON"])
+}
+
+impl CLIArgument {
+    /// Next value of a boolean toggle, optional arguments go back to unset after false
+    #[allow(clippy::needless_return)]
+    pub fn next_bool_value(&self) -> Vec<String> {
+        let (on, off) = match self.choices.iter().map(|choice| choice.to_lowercase()).collect::<Vec<String>>()[..] {
+            [ref first, _] if first == "yes" || first == "no" => (String::from("yes"), String::from("no")),
+            [ref first, _] if first == "on" || first == "off" => (String::from("on"), String::from("off")),
+            _ => (String::from("true"), String::from("false")),
+        };
+        match self.values.first().map(|value| value.to_lowercase()) {
+            Some(value) if value == on => return vec![off],
+            Some(value) if value == off && !self.required => return Vec::new(),
+            _ => return vec![on],
         }
     }
-    for flag in &parameters.flags {
-        if flag.set {
-            cli_command.arg(&flag.key);
-        }
+
+    /// Values that were filled in, empty values are never passed
+    pub fn filled_values(&self) -> impl Iterator<Item = &String> {
+        self.values.iter().filter(|value| !value.is_empty())
     }
-    for argument in &parameters.arguments {
-        cli_command.args([&argument.key, &argument.value]);
+
+    /// Why a filled value is not one of the choices, None if every value is or the argument has no choices
+    pub fn check_choices(&self, values: &[String]) -> Option<String> {
+        if self.choices.is_empty() {
+            return None;
+        }
+        let value = values.iter().find(|value| !value.is_empty() && !self.choices.contains(value))?;
+        Some(format!("{} takes one of {}, not {value}", self.name, self.choices.join(", ")))
     }
-    cli_command
-}
 
-// Unit tests
+    /// Values as the command receives them, see `expand_globs`
+    pub fn passed_values(&self) -> Vec<String> {
+        expand_globs(self.filled_values(), self.glob, self.multiple)
+    }
 
-#[allow(dead_code)]
-fn get_test_clap_help_string() -> String {
-    String::from("Simple program to greet a person
+    /// Whether the argument takes a path, only those can expand globs
+    pub fn takes_files(&self) -> bool {
+        self.value_type == ValueType::Path
+    }
 
-Usage: greeter.exe [OPTIONS] --first-name <FIRST_NAME> --last-name <LAST_NAME>
+    /// Whether the key is any form of the key of this argument
+    pub fn has_key(&self, key: &str) -> bool {
+        self.key == key
+            || self.short_key.as_deref() == Some(key)
+            || self.long_key.as_deref() == Some(key)
+            || self.aliases.iter().any(|alias| alias == key)
+    }
 
-Options:
-    -f, --first-name <FIRST_NAME>  First name of the person to greet
-    -l, --last-name <LAST_NAME>    Last name of the person to greet
-        --caps                     Greet in caps
-        --german                   Greet in german
-    -c, --count <COUNT>            Number of times to greet [default: 1]
-    -h, --help                     Print help
-    -V, --version                  Print version")
+    /// Key passed to the command
+    pub fn emitted_key(&self, key_style: KeyStyle) -> &str {
+        key_style.choose(&self.key, self.short_key.as_deref(), self.long_key.as_deref())
+    }
 }
 
-#[allow(dead_code)]
-fn get_test_clap_option_explanation() -> String {
-    String::from("Options:
-    -f, --first-name <FIRST_NAME>  First name of the person to greet
-    -l, --last-name <LAST_NAME>    Last name of the person to greet
-        --caps                     Greet in caps
-        --german                   Greet in german
-    -c, --count <COUNT>            Number of times to greet [default: 1]
-    -h, --help                     Print help
-    -V, --version                  Print version")
+/// Argument passed by its position without a key, e.g. `<FILE>`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CLIPositional {
+    pub name: String,
+    pub description: Option<String>,
+    pub values: Vec<String>,
+    pub multiple: bool,
+    pub required: bool,
+    /// Values like `*.log` are expanded by clitui when the command runs, see `passed_values`
+    pub glob: bool,
+    /// What has to be at the paths of the values before the command runs
+    pub path_check: PathCheck,
 }
 
-#[allow(dead_code)]
-fn get_test_clap_usage_explanation() -> String {
-    String::from("Usage: greeter.exe [OPTIONS] --first-name <FIRST_NAME> --last-name <LAST_NAME>")
-}
+impl CLIPositional {
+    /// Values that were filled in, empty values are never passed
+    pub fn filled_values(&self) -> impl Iterator<Item = &String> {
+        self.values.iter().filter(|value| !value.is_empty())
+    }
 
-#[test]
-fn test_retrieve_clap_option_explanation() {
-    let help_string = get_test_clap_help_string();
-    let usage_explanation = retrieve_clap_usage_explanation(&help_string).unwrap();
+    /// Whether the positional names files, e.g. `<FILE>` or `[PATHS]...`
+    pub fn takes_files(&self) -> bool {
+        [self.name.as_str(), self.name.trim_end_matches(['s', 'S'])].iter().any(|name| ValueType::infer(name, None) == ValueType::Path)
+    }
 
-    assert_eq!(
-        usage_explanation,
-        get_test_clap_usage_explanation()
-    )
+    /// Values as the command receives them, see `expand_globs`
+    pub fn passed_values(&self) -> Vec<String> {
+        expand_globs(self.filled_values(), self.glob, self.multiple)
+    }
 }
 
-#[test]
-fn test_retrieve_clap_usage_explanation() {
-    let help_string = get_test_clap_help_string();
-    let option_explanation = retrieve_clap_option_explanation(&help_string).unwrap();
+/// Expand the values that are globs into the files they match, a glob without matches is passed as typed like the shell does
+/// Every match is a value of its own for repeatable parameters, the others get them as one space-separated value
+#[allow(clippy::needless_return)]
+pub fn expand_globs<'a>(values: impl Iterator<Item = &'a String>, glob: bool, multiple: bool) -> Vec<String> {
+    if !glob {
+        return values.cloned().collect();
+    }
+    let expanded: Vec<String> = values
+        .flat_map(|value| match glob::expand(value) {
+            matches if glob::is_pattern(value) && !matches.is_empty() => return matches,
+            _ => return vec![value.clone()],
+        })
+        .collect();
+    if multiple || expanded.is_empty() {
+        return expanded;
+    }
+    vec![expanded.join(" ")]
+}
 
-    assert_eq!(
-        option_explanation,
-        get_test_clap_option_explanation()
-    )
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CLIFlag {
+    /// Identifies the flag, the long key if there is one
+    pub key: String,
+    /// Short form of the key, e.g. `-v`
+    pub short_key: Option<String>,
+    /// Long form of the key, e.g. `--verbose`
+    pub long_key: Option<String>,
+    /// Further keys, e.g. `--ver` of `-V, --version, --ver`
+    pub aliases: Vec<String>,
+    pub description: Option<String>,
+    pub set: bool,
+    /// Can be passed more than once, e.g. `-vvv`
+    pub multiple: bool,
+    /// Number of times a set repeatable flag is passed
+    pub count: u8,
+    /// Key that turns the flag off, e.g. `--no-color` for `--color`
+    pub negated_key: Option<String>,
+    /// Whether the set flag passes its negated key instead of its key
+    pub negated: bool,
+    /// Heading the help lists the flag under, e.g. `Output Options`, None for the plain `Options:`
+    pub group: Option<String>,
+    /// Accepted by the subcommands as well, e.g. `--verbose`, kept only at the command defining it
+    pub global: bool,
+    /// Depth of the command a global flag is copied from into the form of a subcommand, it is passed right after that command
+    pub inherited: Option<usize>,
+    /// Whether the help or version flag is set by share codes and aliases, only for tools listed in the config
+    #[serde(default)]
+    pub passes_meta: bool,
 }
 
-#[test]
-fn test_parse_clap_option_line() {
-    let option_line = "-n, --name <NAME>    Name of the person to greet";
+/// Keys of the flags printing the help, every subcommand has its own
+const HELP_KEYS: [&str; 2] = ["--help", "-h"];
 
-    let argument = parse_clap_option_line(&option_line).unwrap();
+/// Keys of the flags printing the help or the version instead of doing anything, never set in bulk
+const META_KEYS: [&str; 4] = ["--help", "-h", "--version", "-V"];
 
-    assert_eq!(
-        argument,
-        CLIParameter::Argument(CLIArgument {
-            name: String::from("NAME"),
-            key: String::from("--name"),
-            description: Some(String::from("Name of the person to greet")),
-            value: String::new(),
-        }),
-    )
-}
+/// Keys of flags that make a CLI only show what it would do
+const DRY_RUN_KEYS: [&str; 3] = ["--dry-run", "--dryrun", "-n"];
 
-#[test]
-fn test_parse_clap_option_line_multiple_words_in_key() {
-    let option_line = "-n, --first-name <FIRST_NAME>    Name of the person to greet";
+/// Keys of flags that make a CLI prompt for input or require a terminal
+const INTERACTIVE_KEYS: [&str; 4] = ["--interactive", "--tty", "--prompt", "--ask"];
 
-    let argument = parse_clap_option_line(&option_line).unwrap();
+impl CLIFlag {
+    pub fn name(&self) -> String {
+        self.key.trim_start_matches('-').to_uppercase()
+    }
 
-    assert_eq!(
-        argument,
-        CLIParameter::Argument(CLIArgument {
-            name: String::from("FIRST_NAME"),
-            key: String::from("--first-name"),
-            description: Some(String::from("Name of the person to greet")),
-            value: String::new(),
-        }),
-    )
-}
+    pub fn is_dry_run(&self) -> bool {
+        DRY_RUN_KEYS.contains(&self.key.as_str())
+    }
 
-#[test]
-fn test_parse_clap_option_line_default_value() {
-    let option_line = "-c, --count <COUNT>  Number of times to greet [default: 10]";
+    /// Whether the flag prints the help or the version, judged by its identifying key only,
+    /// so `-h, --human-readable` is not one
+    pub fn is_meta(&self) -> bool {
+        META_KEYS.contains(&self.key.as_str())
+    }
 
-    let argument = parse_clap_option_line(&option_line).unwrap();
+    /// Whether the flag makes the CLI prompt for input, e.g. `--interactive`
+    pub fn is_interactive(&self) -> bool {
+        INTERACTIVE_KEYS.iter().any(|key| self.has_key(key))
+    }
 
-    assert_eq!(
-        argument,
-        CLIParameter::Argument(CLIArgument {
-            name: String::from("COUNT"),
-            key: String::from("--count"),
-            description: Some(String::from("Number of times to greet")),
-            value: String::from("10"),
-        }),
-    )
-}
+    /// Whether the key is any form of the key of this flag
+    pub fn has_key(&self, key: &str) -> bool {
+        self.key == key
+            || self.short_key.as_deref() == Some(key)
+            || self.long_key.as_deref() == Some(key)
+            || self.aliases.iter().any(|alias| alias == key)
+            || self.negated_key.as_deref() == Some(key)
+    }
 
-#[test]
-fn test_parse_clap_option_line_only_short_key() {
-    let option_line = "-n <NAME>    Name of the person to greet";
+    /// Key passed to the command, the negated key if the flag is turned off
+    #[allow(clippy::needless_return)]
+    pub fn emitted_key(&self, key_style: KeyStyle) -> &str {
+        match &self.negated_key {
+            Some(negated_key) if self.negated => return negated_key,
+            _ => return key_style.choose(&self.key, self.short_key.as_deref(), self.long_key.as_deref()),
+        }
+    }
 
-    let argument = parse_clap_option_line(&option_line).unwrap();
+    /// How often the flag is passed, only repeatable flags more than once
+    #[allow(clippy::needless_return)]
+    pub fn times(&self) -> usize {
+        match (self.set, self.multiple) {
+            (false, _) => return 0,
+            (true, false) => return 1,
+            (true, true) => return self.count.max(1) as usize,
+        }
+    }
 
-    assert_eq!(
-        argument,
-        CLIParameter::Argument(CLIArgument {
-            name: String::from("NAME"),
-            key: String::from("-n"),
-            description: Some(String::from("Name of the person to greet")),
-            value: String::new(),
-        }),
-    )
-}
+    /// Whether filling the form in one go, from a share code or an alias, leaves the flag unset
+    /// Help and version flags are only passed when checked by hand, unless the config passes them for the tool
+    pub fn is_skipped_in_bulk(&self) -> bool {
+        self.is_meta() && !self.passes_meta
+    }
 
-#[test]
-fn test_parse_clap_option_line_only_long_key() {
-    let option_line = "--name <NAME>    Name of the person to greet";
+    /// Pass a repeatable flag the given number of times, zero unsets it
+    pub fn set_count(&mut self, count: u8) {
+        self.count = count;
+        self.set = count > 0;
+    }
+}
 
-    let argument = parse_clap_option_line(&option_line).unwrap();
+/// Help probe a parameter was found in when both `-h` and `--help` are parsed
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum HelpProbe {
+    Short,
+    Long,
+    Both,
+}
 
-    assert_eq!(
-        argument,
-        CLIParameter::Argument(CLIArgument {
-            name: String::from("NAME"),
-            key: String::from("--name"),
-            description: Some(String::from("Name of the person to greet")),
-            value: String::new(),
-        }),
-    )
+impl HelpProbe {
+    /// Shown next to the description of parameters missing in one of the probes
+    #[allow(clippy::needless_return)]
+    pub fn note(&self) -> Option<&'static str> {
+        match self {
+            HelpProbe::Short => return Some("[only in -h]"),
+            HelpProbe::Long => return Some("[only in --help]"),
+            HelpProbe::Both => return None,
+        }
+    }
 }
 
-#[test]
-fn test_parse_clap_option_missing_keys() {
-    let option_line = "<NAME>    Name of the person to greet";
+/// Keep the longer of two descriptions
+fn merge_description(description: &mut Option<String>, other: Option<String>) {
+    if other.as_ref().map(String::len) > description.as_ref().map(String::len) {
+        *description = other;
+    }
+}
 
-    let argument = parse_clap_option_line(&option_line);
+/// Merge the terse description of `-h` into the detailed one of `--help`, e.g. clap's `help` and `long_help`
+/// A description repeating the other is dropped, otherwise the summary becomes the first line above the detail
+fn merge_summary(detail: &mut Option<String>, summary: Option<String>) {
+    let normalize = |text: &str| text.trim().trim_end_matches('.').to_lowercase();
+    match (detail.as_deref(), summary) {
+        (Some(long), Some(short)) if !normalize(long).contains(&normalize(&short)) && !normalize(&short).contains(&normalize(long)) => {
+            *detail = Some(format!("{short}\n{long}"));
+        },
+        (_, summary) => merge_description(detail, summary),
+    }
+}
 
-    assert_eq!(
-        argument,
+#[derive(Debug, PartialEq, Eq)]
+enum CLIParameter {
+    Argument(CLIArgument),
+    Flag(CLIFlag),
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CLISubcommand {
+    pub name: String,
+    pub description: Option<String>,
+    /// Parameters of the subcommand, available once its help was probed
+    pub parameters: Option<CLIParameters>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CLIParameters {
+    /// Name of the command in its help, only displayed
+    pub cli_name: String,
+    /// Command line the user invoked, e.g. `python greeter.py`, executed instead of the name in the help if set
+    pub program: Vec<String>,
+    /// Subcommands leading to these parameters, e.g. `build` for `cargo build`
+    pub command_path: Vec<String>,
+    pub arguments: Vec<CLIArgument>,
+    /// Passed after all keyed parameters in this order
+    pub positionals: Vec<CLIPositional>,
+    pub options: Vec<CLIArgument>,
+    pub flags: Vec<CLIFlag>,
+    pub subcommands: Vec<CLISubcommand>,
+    /// Keys of which at most one can be passed, e.g. `[-a | -b]` in the usage
+    /// or `cannot be used with --yaml` in a description
+    pub exclusive_groups: Vec<Vec<String>>,
+    pub cli_lib: CLILib,
+    /// Probe every key and positional name was found in, empty unless `-h` and `--help` were merged
+    pub probes: BTreeMap<String, HelpProbe>,
+    /// Form of the keys passed to the command, chosen by the user
+    pub key_style: KeyStyle,
+    /// Version printed by `--version`, e.g. `1.78.0`, only known for the command itself
+    pub version: Option<String>,
+    /// Heuristic guesses made while reading the help, e.g. a type inferred from a value name, refused by `--strict`
+    pub guesses: Vec<String>,
+}
+
+/// Groups in the order they first appear in, e.g. the plain options before `Network Options`
+fn group_order<'a>(groups: impl Iterator<Item = &'a Option<String>>) -> Vec<Option<String>> {
+    let mut order: Vec<Option<String>> = Vec::new();
+    for group in groups {
+        if !order.contains(group) {
+            order.push(group.clone());
+        }
+    }
+    order
+}
+
+impl CLIParameters {
+    /// Sort options and flags alphabetically by their displayed name, within the groups of the help
+    /// Arguments and positionals keep the order of the help string
+    pub fn sort(&mut self) {
+        let groups = group_order(self.options.iter().map(|option| &option.group));
+        let rank = |group: &Option<String>| groups.iter().position(|known| known == group);
+        self.options.sort_by(|a, b| rank(&a.group).cmp(&rank(&b.group)).then_with(|| collation::compare(&a.name, &b.name)));
+        let groups = group_order(self.flags.iter().map(|flag| &flag.group));
+        let rank = |group: &Option<String>| groups.iter().position(|known| known == group);
+        self.flags.sort_by(|a, b| rank(&a.group).cmp(&rank(&b.group)).then_with(|| collation::compare(&a.name(), &b.name())));
+        self.subcommands.sort_by(|a, b| collation::compare(&a.name, &b.name));
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(CLIParameters::sort);
+    }
+
+    /// Whether the form has no fields besides the flags printing help and version, e.g. for a help without option rows
+    pub fn has_only_help_flags(&self) -> bool {
+        self.arguments.is_empty()
+            && self.positionals.is_empty()
+            && self.options.is_empty()
+            && self.flags.iter().all(|flag| META_KEYS.iter().any(|key| flag.has_key(key)))
+    }
+
+    /// Keep the options and flags the subcommands repeat from their parent, like `--verbose`, only at the parent and mark them global
+    /// The help flags stay, each prints the help of its own command
+    pub fn mark_globals(&mut self) {
+        self.mark_globals_below(&[], &[]);
+    }
+
+    /// Mark the globals of this command, the globals of its parents are removed from the subcommands as well
+    /// A subcommand repeats an option of its parent only if the long key, the value name and the description are the same,
+    /// e.g. `-o FILE` of `build` is a different option than `-o FORMAT` of the program
+    fn mark_globals_below(&mut self, inherited_arguments: &[CLIArgument], inherited_flags: &[CLIFlag]) {
+        let is_same_argument = |parent: &CLIArgument, option: &CLIArgument| {
+            parent.has_key(&option.key) && parent.long_key == option.long_key && parent.name == option.name && parent.description == option.description
+        };
+        let is_same_flag = |parent: &CLIFlag, flag: &CLIFlag| {
+            parent.has_key(&flag.key) && parent.long_key == flag.long_key && parent.description == flag.description
+        };
+        let CLIParameters { arguments, options, flags, subcommands, .. } = self;
+        for child in subcommands.iter_mut().filter_map(|subcommand| subcommand.parameters.as_mut()) {
+            let mut is_repeated = |option: &CLIArgument| {
+                let parent = arguments.iter_mut().chain(options.iter_mut()).find(|parent| is_same_argument(parent, option));
+                let repeated = parent.is_some();
+                parent.into_iter().for_each(|parent| parent.global = true);
+                repeated || inherited_arguments.iter().any(|parent| is_same_argument(parent, option))
+            };
+            child.arguments.retain(|argument| !is_repeated(argument));
+            child.options.retain(|option| !is_repeated(option));
+            child.flags.retain(|flag| {
+                if HELP_KEYS.iter().any(|key| flag.has_key(key)) {
+                    return true;
+                }
+                let parent = flags.iter_mut().find(|parent| is_same_flag(parent, flag));
+                let repeated = parent.is_some();
+                parent.into_iter().for_each(|parent| parent.global = true);
+                !repeated && !inherited_flags.iter().any(|parent| is_same_flag(parent, flag))
+            });
+        }
+        let mut globals = inherited_arguments.to_vec();
+        globals.extend(arguments.iter().chain(options.iter()).filter(|option| option.global).cloned());
+        let mut global_flags = inherited_flags.to_vec();
+        global_flags.extend(flags.iter().filter(|flag| flag.global).cloned());
+        subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|child| child.mark_globals_below(&globals, &global_flags));
+    }
+
+    /// Copies of the globals of a parent command for the form of one of its subcommands, see `mark_globals`
+    pub fn inherit_globals(&mut self, parent: &CLIParameters) {
+        let depth = parent.command_path.len();
+        let inherit_argument = |argument: &CLIArgument| CLIArgument { global: false, inherited: argument.inherited.or(Some(depth)), ..argument.clone() };
+        let is_global_argument = |argument: &&CLIArgument| argument.global || argument.inherited.is_some();
+        self.arguments.extend(parent.arguments.iter().filter(is_global_argument).map(inherit_argument));
+        self.options.extend(parent.options.iter().filter(is_global_argument).map(inherit_argument));
+        self.flags.extend(
+            parent.flags
+                .iter()
+                .filter(|flag| flag.global || flag.inherited.is_some())
+                .map(|flag| CLIFlag { global: false, inherited: flag.inherited.or(Some(depth)), ..flag.clone() }),
+        );
+    }
+
+    /// Move the values of the inherited globals back to the parent command they were copied from, see `inherit_globals`
+    pub fn return_globals(&mut self, parent: &mut CLIParameters) {
+        let inherited_arguments = self.arguments.iter().chain(&self.options).filter(|argument| argument.inherited.is_some());
+        for argument in inherited_arguments {
+            if let Some(parent_argument) = parent.arguments.iter_mut().chain(&mut parent.options).find(|parent_argument| parent_argument.key == argument.key) {
+                parent_argument.values = argument.values.clone();
+            }
+        }
+        for flag in self.flags.iter().filter(|flag| flag.inherited.is_some()) {
+            if let Some(parent_flag) = parent.flags.iter_mut().find(|parent_flag| parent_flag.key == flag.key) {
+                parent_flag.set = flag.set;
+                parent_flag.count = flag.count;
+                parent_flag.negated = flag.negated;
+            }
+        }
+        self.arguments.retain(|argument| argument.inherited.is_none());
+        self.options.retain(|option| option.inherited.is_none());
+        self.flags.retain(|flag| flag.inherited.is_none());
+    }
+
+    /// Whether a set flag makes the CLI prompt for input, so it has to run in the terminal
+    pub fn needs_terminal(&self) -> bool {
+        self.flags.iter().any(|flag| flag.set && flag.is_interactive())
+    }
+
+    /// Merge the parameters parsed from `-h` into the ones parsed from `--help`,
+    /// matching them by key or positional name and merging the summary into the detailed description
+    pub fn merge_short_help(&mut self, short_parameters: CLIParameters) {
+        let mut probes: BTreeMap<String, HelpProbe> = self.arguments
+            .iter()
+            .chain(&self.options)
+            .map(|argument| argument.key.clone())
+            .chain(self.flags.iter().map(|flag| flag.key.clone()))
+            .chain(self.positionals.iter().map(|positional| positional.name.clone()))
+            .map(|key| (key, HelpProbe::Long))
+            .collect();
+        let mut mark_short = |key: &str| {
+            let probe = probes.entry(key.to_string()).or_insert(HelpProbe::Short);
+            if *probe == HelpProbe::Long {
+                *probe = HelpProbe::Both;
+            }
+        };
+        for short_argument in short_parameters.arguments.into_iter().chain(short_parameters.options) {
+            mark_short(&short_argument.key);
+            let argument = self.arguments
+                .iter_mut()
+                .chain(&mut self.options)
+                .find(|argument| argument.key == short_argument.key);
+            match argument {
+                Some(argument) => {
+                    merge_summary(&mut argument.description, short_argument.description);
+                    argument.multiple |= short_argument.multiple;
+                    if argument.values.is_empty() {
+                        argument.values = short_argument.values;
+                    }
+                    if argument.choices.is_empty() {
+                        argument.choices = short_argument.choices;
+                    }
+                    argument.env_var = argument.env_var.take().or(short_argument.env_var);
+                    argument.short_key = argument.short_key.take().or(short_argument.short_key);
+                    argument.long_key = argument.long_key.take().or(short_argument.long_key);
+                },
+                None if short_argument.required => self.arguments.push(short_argument),
+                None => self.options.push(short_argument),
+            }
+        }
+        for short_flag in short_parameters.flags {
+            mark_short(&short_flag.key);
+            match self.flags.iter_mut().find(|flag| flag.key == short_flag.key) {
+                Some(flag) => {
+                    merge_summary(&mut flag.description, short_flag.description);
+                    flag.multiple |= short_flag.multiple;
+                    flag.short_key = flag.short_key.take().or(short_flag.short_key);
+                    flag.long_key = flag.long_key.take().or(short_flag.long_key);
+                },
+                None => self.flags.push(short_flag),
+            }
+        }
+        for short_positional in short_parameters.positionals {
+            mark_short(&short_positional.name);
+            match self.positionals.iter_mut().find(|positional| positional.name == short_positional.name) {
+                Some(positional) => merge_summary(&mut positional.description, short_positional.description),
+                None => self.positionals.push(short_positional),
+            }
+        }
+        for short_subcommand in short_parameters.subcommands {
+            match self.subcommands.iter_mut().find(|subcommand| subcommand.name == short_subcommand.name) {
+                Some(subcommand) => merge_description(&mut subcommand.description, short_subcommand.description),
+                None => self.subcommands.push(short_subcommand),
+            }
+        }
+        for group in short_parameters.exclusive_groups {
+            if !self.exclusive_groups.contains(&group) {
+                self.exclusive_groups.push(group);
+            }
+        }
+        for guess in short_parameters.guesses {
+            if !self.guesses.contains(&guess) {
+                self.guesses.push(guess);
+            }
+        }
+        self.probes = probes;
+    }
+
+    /// Merge the parameters of a completion script into the ones parsed from the help,
+    /// matching them by any of their keys, parameters only the completion knows are added
+    pub fn merge_completion(&mut self, completion: CLIParameters) {
+        for completed in completion.arguments.into_iter().chain(completion.options) {
+            let keys: Vec<&String> = [&completed.key].into_iter().chain(&completed.short_key).chain(&completed.long_key).collect();
+            let known_flag = self.flags.iter().any(|flag| keys.iter().any(|key| flag.has_key(key)));
+            let argument = self.arguments.iter_mut().chain(&mut self.options).find(|argument| keys.iter().any(|key| argument.has_key(key)));
+            match argument {
+                Some(argument) => {
+                    merge_description(&mut argument.description, completed.description);
+                    if argument.choices.is_empty() {
+                        argument.choices = completed.choices;
+                    }
+                    if argument.value_type == ValueType::String {
+                        argument.value_type = completed.value_type;
+                    }
+                    argument.short_key = argument.short_key.take().or(completed.short_key);
+                },
+                None if !known_flag => self.options.push(completed),
+                None => (),
+            }
+        }
+        for completed in completion.flags {
+            let keys: Vec<&String> = [&completed.key].into_iter().chain(&completed.short_key).chain(&completed.long_key).collect();
+            let known_argument = self.arguments.iter().chain(&self.options).any(|argument| keys.iter().any(|key| argument.has_key(key)));
+            match self.flags.iter_mut().find(|flag| keys.iter().any(|key| flag.has_key(key))) {
+                Some(flag) => {
+                    merge_description(&mut flag.description, completed.description);
+                    flag.short_key = flag.short_key.take().or(completed.short_key);
+                },
+                None if !known_argument => self.flags.push(completed),
+                None => (),
+            }
+        }
+        for completed in completion.subcommands {
+            match self.subcommands.iter_mut().find(|subcommand| subcommand.name == completed.name) {
+                Some(subcommand) => merge_description(&mut subcommand.description, completed.description),
+                None => self.subcommands.push(completed),
+            }
+        }
+    }
+
+    /// Pass the values of the arguments with one of the keys as `--key=value`, also in the subcommands
+    pub fn set_joined(&mut self, keys: &[String]) {
+        for argument in self.arguments.iter_mut().chain(self.options.iter_mut()) {
+            argument.joined |= keys.iter().any(|key| argument.has_key(key));
+        }
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|parameters| parameters.set_joined(keys));
+    }
+
+    /// Pass the help and version flags with one of the keys once they are set, also in the subcommands
+    pub fn set_passed_meta_flags(&mut self, keys: &[String]) {
+        for flag in &mut self.flags {
+            flag.passes_meta |= keys.iter().any(|key| flag.has_key(key));
+        }
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|parameters| parameters.set_passed_meta_flags(keys));
+    }
+
+    /// Set the checks of the path values of the parameters with one of the keys or positional names, also in the subcommands
+    pub fn set_path_checks(&mut self, checks: &[(String, PathCheck)]) {
+        for (key, check) in checks {
+            for argument in self.arguments.iter_mut().chain(self.options.iter_mut()).filter(|argument| argument.has_key(key)) {
+                argument.path_check = *check;
+            }
+            for positional in self.positionals.iter_mut().filter(|positional| positional.name == *key) {
+                positional.path_check = *check;
+            }
+        }
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|parameters| parameters.set_path_checks(checks));
+    }
+
+    /// Execute the command line the user invoked, also for the subcommands
+    pub fn set_program(&mut self, program: &[String]) {
+        self.program = program.to_vec();
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|parameters| parameters.set_program(program));
+    }
+
+    /// Pass the keys in the given form, also in the subcommands
+    pub fn set_key_style(&mut self, key_style: KeyStyle) {
+        self.key_style = key_style;
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|parameters| parameters.set_key_style(key_style));
+    }
+
+    /// Key identifying the parameter that has the given key in any form
+    fn find_canonical_key(&self, key: &str) -> Option<&String> {
+        self.arguments
+            .iter()
+            .chain(&self.options)
+            .find(|argument| argument.has_key(key))
+            .map(|argument| &argument.key)
+            .or_else(|| self.flags.iter().find(|flag| flag.has_key(key)).map(|flag| &flag.key))
+    }
+
+    /// Add the conflicts stated in descriptions as exclusive groups of two, e.g.
+    /// --json  Print JSON, cannot be used with --yaml
+    fn add_described_conflicts(&mut self) {
+        static CONFLICT_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(?:cannot be used (?:together )?with|conflicts with|mutually exclusive with)[:\s]*(?P<keys>(?:--?\w[\w-]*(?:,\s*|\s+(?:and|or)\s+)?)+)").unwrap());
+        static KEY_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"--?\w[\w-]*").unwrap());
+        let described: Vec<(&String, &String)> = self.arguments
+            .iter()
+            .chain(&self.options)
+            .filter_map(|argument| Some((&argument.key, argument.description.as_ref()?)))
+            .chain(self.flags.iter().filter_map(|flag| Some((&flag.key, flag.description.as_ref()?))))
+            .collect();
+        let mut groups: Vec<Vec<String>> = Vec::new();
+        for (key, description) in described {
+            for caps in CONFLICT_PATTERN.captures_iter(description) {
+                for other_key in KEY_PATTERN.find_iter(&caps["keys"]) {
+                    let Some(other_key) = self.find_canonical_key(other_key.as_str()).filter(|other_key| *other_key != key) else {
+                        continue;
+                    };
+                    let already_known = self.exclusive_groups
+                        .iter()
+                        .chain(&groups)
+                        .any(|group| group.contains(key) && group.contains(other_key));
+                    if !already_known {
+                        groups.push(vec![key.clone(), other_key.clone()]);
+                    }
+                }
+            }
+        }
+        self.exclusive_groups.extend(groups);
+    }
+
+    /// Mark flags as repeatable whose description says so, e.g.
+    /// -v, --verbose  Increase verbosity, can be used multiple times
+    fn detect_counted_flags(&mut self) {
+        static REPEATABLE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)(?:can|may) be (?:used|given|specified|passed|repeated) (?:multiple|several|more than once)|\brepeatable\b").unwrap());
+        for flag in &mut self.flags {
+            if flag.description.as_deref().is_some_and(|description| REPEATABLE_PATTERN.is_match(description)) {
+                flag.multiple = true;
+            }
+        }
+    }
+
+    /// Merge flags like `--no-color` into their flag `--color`, which is then unset, on or off,
+    /// also when both keys are listed in one row like `--color, --no-color`
+    fn collapse_negatable_flags(&mut self) {
+        for flag in &mut self.flags {
+            let negated_key = flag.key.strip_prefix("--").map(|name| format!("--no-{name}"));
+            if let Some(position) = flag.aliases.iter().position(|alias| Some(alias) == negated_key.as_ref()) {
+                flag.negated_key = Some(flag.aliases.remove(position));
+            }
+        }
+        let negated_keys: Vec<(usize, String)> = self.flags
+            .iter()
+            .filter_map(|flag| Some((flag.key.clone(), flag.key.strip_prefix("--no-")?.to_string())))
+            .filter_map(|(negated_key, name)| {
+                let index = self.flags.iter().position(|flag| flag.key == format!("--{name}"))?;
+                Some((index, negated_key))
+            })
+            .collect();
+        for (index, negated_key) in negated_keys {
+            let Some(negated_index) = self.flags.iter().position(|flag| flag.key == negated_key) else {
+                continue;
+            };
+            let negated_flag = self.flags.remove(negated_index);
+            let index = if negated_index < index { index - 1 } else { index };
+            let flag = &mut self.flags[index];
+            flag.description = flag.description.take().or(negated_flag.description);
+            flag.negated_key = Some(negated_key);
+        }
+        // a flag and its negation always conflict, this is covered by having one toggle
+        let flags = &self.flags;
+        self.exclusive_groups.retain(|group| {
+            !flags.iter().any(|flag| group.contains(&flag.key) && flag.negated_key.as_ref().is_some_and(|key| group.contains(key)))
+        });
+    }
+
+    /// Keys that cannot be passed together with the given key
+    pub fn conflicting_keys(&self, key: &str) -> Vec<&String> {
+        self.exclusive_groups
+            .iter()
+            .filter(|group| group.iter().any(|group_key| group_key == key))
+            .flatten()
+            .filter(|group_key| *group_key != key)
+            .collect()
+    }
+
+    /// Keep the default values, the parsers prefill the values with them
+    /// and infer the value types of the arguments and options from their names and default values
+    fn infer_value_types(&mut self) {
+        for argument in self.arguments.iter_mut().chain(&mut self.options) {
+            argument.default_value = argument.values.first().cloned();
+            argument.value_type = ValueType::infer(&argument.name, argument.default_value.as_deref());
+            if is_boolean_pair(argument.choices.iter().map(|choice| choice.to_uppercase())) {
+                argument.value_type = ValueType::Bool;
+            }
+        }
+    }
+
+    /// Guesses behind the parameters parsed from the help string: rows that look like parameters but were not read,
+    /// types inferred from value names or defaults and requiredness the parser cannot tell
+    pub fn find_guesses(&self, help_string: &str) -> Vec<String> {
+        self.find_guess_kinds(help_string).iter().map(Guess::to_string).collect()
+    }
+
+    /// Like `find_guesses`, for callers that treat the kinds of guesses differently
+    pub fn find_guess_kinds(&self, help_string: &str) -> Vec<Guess> {
+        let mut guesses: Vec<Guess> = find_unparsed_lines(help_string, self).into_iter().map(Guess::UnparsedLine).collect();
+        for argument in self.arguments.iter().chain(&self.options) {
+            if argument.value_type != ValueType::String && !is_boolean_pair(argument.choices.iter()) {
+                guesses.push(Guess::ValueType { key: argument.key.clone(), value_type: argument.value_type, name: argument.name.clone() });
+            }
+        }
+        let keyed = !self.arguments.is_empty() || !self.options.is_empty();
+        if keyed && !self.cli_lib.reads_requiredness() {
+            guesses.push(Guess::Requiredness(self.cli_lib));
+        }
+        guesses
+    }
+
+    /// Guesses of these parameters and of the probed subcommands, prefixed with the subcommand path, e.g. `build: ...`
+    pub fn all_guesses(&self) -> Vec<String> {
+        let prefix = if self.command_path.is_empty() { String::new() } else { format!("{}: ", self.command_path.join(" ")) };
+        self.guesses
+            .iter()
+            .map(|guess| format!("{prefix}{guess}"))
+            .chain(self.subcommands.iter().filter_map(|subcommand| subcommand.parameters.as_ref()).flat_map(CLIParameters::all_guesses))
+            .collect()
+    }
+
+    /// Prefill arguments and options whose environment variable is set, also of the subcommands
+    pub fn fill_env_values(&mut self, lookup: &impl Fn(&str) -> Option<String>) {
+        for argument in self.arguments.iter_mut().chain(&mut self.options) {
+            if let Some(value) = argument.env_var.as_deref().and_then(lookup).filter(|value| !value.is_empty()) {
+                argument.values = vec![value];
+            }
+        }
+        self.subcommands
+            .iter_mut()
+            .filter_map(|subcommand| subcommand.parameters.as_mut())
+            .for_each(|parameters| parameters.fill_env_values(lookup));
+    }
+
+    /// Compute the option from other fields with the template, adding the option if the help does not list it
+    pub fn add_derived(&mut self, key: &str, template: &str) {
+        if self.set_template(key, template) {
+            return;
+        }
+        self.options.push(CLIArgument {
+            key: key.to_string(),
+            long_key: Some(key.to_string()).filter(|key| key.starts_with("--")),
+            short_key: Some(key.to_string()).filter(|key| !key.starts_with("--")),
+            name: key.trim_start_matches('-').to_uppercase(),
+            description: Some(format!("Computed from {template}")),
+            template: Some(template.to_string()),
+            ..Default::default()
+        });
+    }
+
+    /// Derive the argument or option with the key at every level of subcommands listing it, false if none does
+    fn set_template(&mut self, key: &str, template: &str) -> bool {
+        let mut found = false;
+        if let Some(argument) = self.arguments.iter_mut().chain(&mut self.options).find(|argument| argument.has_key(key)) {
+            argument.template = Some(template.to_string());
+            argument.description.get_or_insert(format!("Computed from {template}"));
+            found = true;
+        }
+        for child in self.subcommands.iter_mut().filter_map(|subcommand| subcommand.parameters.as_mut()) {
+            found |= child.set_template(key, template);
+        }
+        found
+    }
+
+    /// Recompute the values of derived arguments, `{name}` in a template is replaced by the value of `--name`
+    /// or the positional `NAME` of the same command, other placeholders like `{date}` by the lookup
+    /// The subcommands are filled from their own fields
+    pub fn fill_derived_values(&mut self, lookup: &impl Fn(&str) -> Option<String>) {
+        for child in self.subcommands.iter_mut().filter_map(|subcommand| subcommand.parameters.as_mut()) {
+            child.fill_derived_values(lookup);
+        }
+        if !self.arguments.iter().chain(&self.options).any(|argument| argument.template.is_some()) {
+            return;
+        }
+        let mut fields: Vec<(String, String)> = Vec::new();
+        for argument in self.arguments.iter().chain(&self.options) {
+            let value = argument.filled_values().cloned().collect::<Vec<String>>().join(",");
+            let keys = [Some(&argument.key), argument.short_key.as_ref(), argument.long_key.as_ref()];
+            fields.extend(keys.into_iter().flatten().map(|key| (key.trim_start_matches('-').to_lowercase(), value.clone())));
+        }
+        for positional in &self.positionals {
+            fields.push((positional.name.to_lowercase(), positional.filled_values().cloned().collect::<Vec<String>>().join(" ")));
+        }
+        static PLACEHOLDER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([^{}]+)\}").unwrap());
+        for argument in self.arguments.iter_mut().chain(&mut self.options) {
+            let Some(template) = &argument.template else {
+                continue;
+            };
+            let value = PLACEHOLDER_REGEX.replace_all(template, |captures: &Captures| {
+                let name = captures[1].trim_start_matches('-').to_lowercase();
+                fields.iter().find(|(key, _)| *key == name).map(|(_, value)| value.clone()).or_else(|| lookup(&name)).unwrap_or_default()
+            });
+            argument.values = vec![value.to_string()];
+        }
+    }
+
+    /// Subcommands and the sorted keys of the passed parameters without their values, e.g. `build --release --target`
+    /// Runs with the same fingerprint are expected to take about as long, whatever the values
+    pub fn fingerprint(&self) -> String {
+        let mut keys: Vec<&str> = self.arguments
+            .iter()
+            .chain(&self.options)
+            .filter(|argument| argument.filled_values().next().is_some())
+            .map(|argument| argument.key.as_str())
+            .chain(self.flags.iter().filter(|flag| flag.times() > 0).map(|flag| flag.emitted_key(KeyStyle::Long)))
+            .chain(self.positionals.iter().filter(|positional| positional.filled_values().next().is_some()).map(|positional| positional.name.as_str()))
+            .collect();
+        keys.sort();
+        keys.dedup();
+        self.command_path.iter().map(String::as_str).chain(keys).collect::<Vec<&str>>().join(" ")
+    }
+
+    /// The command line that precedes the parameters, e.g. `cargo build`
+    pub fn display_name(&self) -> String {
+        let mut parts = vec![self.cli_name.as_str()];
+        parts.extend(self.command_path.iter().map(String::as_str));
+        parts.join(" ")
+    }
+}
+
+/// Version in the output of `--version`, e.g. `1.78.0` of `cargo 1.78.0 (54d8815d0 2024-03-26)`,
+/// the first line if it contains no version number
+#[allow(clippy::needless_return)]
+pub fn parse_version(output: &str) -> Option<String> {
+    let line = output.lines().map(str::trim).find(|line| !line.is_empty())?;
+    static VERSION_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bv?(?P<version>\d+(?:\.\d+)+(?:[-+][\w.]+)?)").unwrap());
+    match VERSION_PATTERN.captures(line) {
+        Some(caps) => return Some(caps["version"].to_string()),
+        None => return Some(line.to_string()),
+    }
+}
+
+/// Something the parameters were read with that the help string does not state, see `CLIParameters::find_guesses`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Guess {
+    /// Trimmed line that looks like a parameter row but was not read
+    UnparsedLine(String),
+    /// Type inferred from the value name or the default
+    ValueType { key: String, value_type: ValueType, name: String },
+    /// The parser lists every option as optional
+    Requiredness(CLILib),
+}
+
+impl fmt::Display for Guess {
+    #[allow(clippy::needless_return)]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Guess::UnparsedLine(line) => return write!(f, "Unparsed line: {line}"),
+            Guess::ValueType { key, value_type, name } => return write!(f, "Type of {key} guessed as {value_type:?} from {name}"),
+            Guess::Requiredness(cli_lib) => return write!(f, "Required options are unknown, the {cli_lib:?} parser lists every option as optional"),
+        }
+    }
+}
+
+/// Why a parser did not accept a help string
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    /// Name of the parser, e.g. `clap`
+    pub parser: &'static str,
+    pub message: String,
+    /// Number and text of the line the parser stopped at, e.g. the first line where a usage line was expected
+    pub line: Option<(usize, String)>,
+    /// What the help would need to look like for this parser
+    pub suggestion: Option<String>,
+}
+
+impl ParseError {
+    /// Error pointing at the line the parser stopped at, counted from 0
+    fn at_line(parser: &'static str, message: &str, help_string: &str, index: usize, suggestion: &str) -> ParseError {
+        let line = help_string.lines().nth(index).map(|line| (index + 1, line.trim().to_string()));
+        ParseError { parser, message: message.to_string(), line, suggestion: Some(suggestion.to_string()) }
+    }
+}
+
+/// Index of the first line the predicate accepts, for the parsers to report where they stopped
+fn find_line_index(help_string: &str, predicate: impl Fn(&str) -> bool) -> Option<usize> {
+    help_string.lines().position(predicate)
+}
+
+/// Index of the first non-empty line, where every format starts with its usage
+fn first_line_index(help_string: &str) -> usize {
+    find_line_index(help_string, |line| !line.trim().is_empty()).unwrap_or_default()
+}
+
+/// Index of the usage line in any casing, the first non-empty line if there is none
+fn usage_line_index(help_string: &str) -> usize {
+    find_line_index(help_string, |line| line.trim_start().get(..5).is_some_and(|start| start.eq_ignore_ascii_case("usage")))
+        .unwrap_or_else(|| first_line_index(help_string))
+}
+
+/// The suggestion is left out, it is only shown when no parser accepts the help of the wrapped tool
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.parser, self.message)?;
+        if let Some((number, line)) = &self.line {
+            write!(f, ", line {number} is \"{line}\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// Turns the help string of one help format into parameters
+pub trait HelpParser {
+    /// Name used in the config and in errors, e.g. `clap`
+    fn name(&self) -> &'static str;
+    /// How likely the help string is in this format from 0 to 100, more likely parsers are tried first
+    fn confidence(&self, help_string: &str) -> u8;
+    fn parse(&self, help_string: &str) -> Result<CLIParameters, ParseError>;
+}
+
+/// The built-in parsers, a recognized format is tried first
+/// Otherwise clap leads with a clap section heading like `Options:`, without one the order is argparse, cobra, docopt, clap, gnu and windows
+impl HelpParser for CLILib {
+    #[allow(clippy::needless_return)]
+    fn name(&self) -> &'static str {
+        match self {
+            CLILib::Clap => return "clap",
+            CLILib::Argparse => return "argparse",
+            CLILib::Cobra => return "cobra",
+            CLILib::Docopt => return "docopt",
+            CLILib::Gnu => return "gnu",
+            CLILib::WindowsSlash => return "windows",
+        }
+    }
+
+    #[allow(clippy::needless_return)]
+    fn confidence(&self, help_string: &str) -> u8 {
+        match self {
+            CLILib::Clap if help_string.contains("Print help") => return 90,
+            CLILib::Clap if has_clap_section(help_string) => return 50,
+            CLILib::Clap => return 15,
+            CLILib::Argparse if help_string.lines().any(|line| line.starts_with("usage:")) => return 90,
+            CLILib::Argparse => return 40,
+            CLILib::Cobra if help_string.lines().any(|line| line.trim_end() == "Flags:") => return 90,
+            CLILib::Cobra => return 30,
+            // a table of option rows is read by the GNU parser, docopt only reads the usage patterns
+            CLILib::Docopt if find_description_column(help_string.lines()).is_some() => return 5,
+            CLILib::Docopt => return 20,
+            CLILib::Gnu if help_string.contains("[OPTION]") => return 60,
+            CLILib::Gnu => return 10,
+            CLILib::WindowsSlash if has_windows_option_rows(help_string) => return 80,
+            CLILib::WindowsSlash => return 5,
+        }
+    }
+
+    fn parse(&self, help_string: &str) -> Result<CLIParameters, ParseError> {
+        let (parameters, message, suggestion) = match self {
+            CLILib::Clap => (parse_clap_help_string(help_string), "no Usage: line with an Options: section", "clap prints `Usage: tool [OPTIONS]` and an `Options:` heading"),
+            CLILib::Argparse => (parse_argparse_help_string(help_string), "no usage: line", "argparse help starts with `usage: tool [-h]`"),
+            CLILib::Cobra => (parse_cobra_help_string(help_string), "no Usage: section", "cobra prints a `Usage:` heading and a `Flags:` heading"),
+            CLILib::Docopt => (parse_docopt_help_string(help_string), "no Usage: patterns", "docopt lists patterns like `tool [options] <file>` below `Usage:`"),
+            CLILib::Gnu => (parse_gnu_help_string(help_string), "no Usage: line", "GNU style help has a `Usage: tool [OPTION]...` line and `-x, --xxx` option rows"),
+            CLILib::WindowsSlash => (parse_windows_help_string(help_string), "no /OPTION rows", "classic Windows help has a `TOOL source [/A]` usage line and `/A  description` option rows"),
+        };
+        parameters.map_err(|index| ParseError::at_line(self.name(), message, help_string, index, suggestion))
+    }
+}
+
+/// Parses a help string from a CLI to determine the arguments and the options
+/// Returns why each built-in parser failed if none accepts it
+pub fn parse_help_string(help_string: &str) -> Result<CLIParameters, Vec<ParseError>> {
+    let parsers: Vec<&dyn HelpParser> = CLILib::ALL.iter().map(|cli_lib| cli_lib as &dyn HelpParser).collect();
+    parse_help_string_with_parsers(help_string, &parsers)
+}
+
+/// Parses a help string with the given built-in parsers only
+pub fn parse_help_string_with(help_string: &str, cli_libs: &[CLILib]) -> Option<CLIParameters> {
+    let parsers: Vec<&dyn HelpParser> = cli_libs.iter().map(|cli_lib| cli_lib as &dyn HelpParser).collect();
+    parse_help_string_with_parsers(help_string, &parsers).ok()
+}
+
+/// Translations of the section headings the parsers look for, printed by tools compiled with translated help
+const HEADING_TRANSLATIONS: [(&str, &[&str]); 5] = [
+    ("Usage", &["Verwendung", "Aufruf", "Benutzung", "Utilisation", "Uso", "Utilizzo", "Gebruik", "Användning", "Użycie", "Использование", "用法", "使い方"]),
+    ("Options", &["Optionen", "Opciones", "Opções", "Opzioni", "Opties", "Alternativ", "Opcje", "Параметры", "选项", "オプション"]),
+    ("Arguments", &["Argumente", "Argumentos", "Argomenti", "Argumenten", "Argument", "Argumenty", "Аргументы", "参数", "引数"]),
+    ("Commands", &["Befehle", "Kommandos", "Commandes", "Comandos", "Comandi", "Opdrachten", "Kommandon", "Polecenia", "Команды", "命令", "コマンド"]),
+    ("Flags", &["Flaggen", "Drapeaux", "Indicadores"]),
+];
+
+/// English heading for a heading word in the case it is written in, e.g. `usage` for `utilisation`
+fn canonical_heading(word: &str) -> Option<String> {
+    let lowercase = word.to_lowercase();
+    let (canonical, _) = HEADING_TRANSLATIONS
+        .iter()
+        .find(|(_, translations)| translations.iter().any(|translation| translation.to_lowercase() == lowercase))?;
+    if word.chars().any(char::is_uppercase) && !word.chars().any(char::is_lowercase) {
+        return Some(canonical.to_uppercase());
+    }
+    if word.starts_with(char::is_lowercase) {
+        return Some(canonical.to_lowercase());
+    }
+    Some(canonical.to_string())
+}
+
+/// Replace translated section headings with the English ones, e.g. `Optionen:` with `Options:`,
+/// and `[OPTIONEN]` in the usage with `[OPTIONS]`
+/// With `guess` unknown languages fall back to the shape of the lines: a `Word: tool [...]` line becomes the usage
+/// and a `Word:` heading above `-x` rows the options, if the help has neither already
+fn normalize_headings(help_string: &str, guess: bool) -> String {
+    static HEADING_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<heading>\w+)\s?:(?P<rest>.*)$").unwrap());
+    let mut lines: Vec<String> = help_string
+        .lines()
+        .map(|line| {
+            HEADING_PATTERN
+                .captures(line)
+                .and_then(|caps| canonical_heading(&caps["heading"]).map(|heading| format!("{heading}:{}", &caps["rest"])))
+                .unwrap_or_else(|| line.to_string())
+        })
+        .collect();
+    let is_heading = |line: &str, names: &[&str]| HEADING_PATTERN.captures(line).is_some_and(|caps| names.iter().any(|name| caps["heading"].eq_ignore_ascii_case(name)));
+    if guess && !lines.iter().any(|line| is_heading(line.trim_start(), &["Usage"])) {
+        static USAGE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<heading>\w+):\s+\w[\w./-]*\s+[\[<-]").unwrap());
+        if let Some(line) = lines.iter_mut().find(|line| USAGE_PATTERN.is_match(line)) {
+            *line = HEADING_PATTERN.replace(line, "Usage:$rest").to_string();
+        }
+    }
+    // the usage of clap names its options `[OPTIONS]`, translated like the heading
+    static PLACEHOLDER_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[(?P<word>\w+)\]").unwrap());
+    for line in lines.iter_mut().filter(|line| is_heading(line.trim_start(), &["Usage"])) {
+        *line = PLACEHOLDER_PATTERN
+            .replace_all(line, |caps: &Captures| match canonical_heading(&caps["word"]) {
+                Some(word) if word.eq_ignore_ascii_case("options") => format!("[{word}]"),
+                _ => caps[0].to_string(),
+            })
+            .to_string();
+    }
+    if guess && !lines.iter().any(|line| is_heading(line, &["Options", "Flags"])) {
+        let index = (0..lines.len()).find(|&index| {
+            HEADING_PATTERN.captures(&lines[index]).is_some_and(|caps| caps["rest"].trim().is_empty())
+                && !is_heading(&lines[index], &["Usage", "Arguments", "Commands"])
+                && lines[index + 1..].iter().find(|line| !line.trim().is_empty()).is_some_and(|line| line.trim_start().starts_with('-'))
+        });
+        if let Some(index) = index {
+            lines[index] = String::from("Options:");
+        }
+    }
+    let mut normalized = lines.join("\n");
+    if help_string.ends_with('\n') {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Parses a help string with any parsers, the most confident one first
+/// Headings are only guessed from the shape of the lines once no parser accepts the translated ones,
+/// so a help without an options heading is not rewritten for parsers that read it as it is
+/// Returns why each parser failed if none accepts the help string
+pub fn parse_help_string_with_parsers(help_string: &str, parsers: &[&dyn HelpParser]) -> Result<CLIParameters, Vec<ParseError>> {
+    let normalized = normalize_headings(help_string, false);
+    let errors = match parse_normalized_help_string(&normalized, parsers) {
+        Ok(parameters) => return Ok(parameters),
+        Err(errors) => errors,
+    };
+    let guessed = normalize_headings(help_string, true);
+    if guessed == normalized {
+        return Err(errors);
+    }
+    parse_normalized_help_string(&guessed, parsers).map_err(|_| errors)
+}
+
+/// Parses a help string with normalized headings with any parsers, the most confident one first
+fn parse_normalized_help_string(help_string: &str, parsers: &[&dyn HelpParser]) -> Result<CLIParameters, Vec<ParseError>> {
+    let mut parsers = parsers.to_vec();
+    parsers.sort_by_key(|parser| std::cmp::Reverse(parser.confidence(help_string)));
+    let mut errors = Vec::new();
+    for parser in parsers {
+        match parser.parse(help_string) {
+            Ok(mut parameters) => {
+                parameters.infer_value_types();
+                parameters.add_described_conflicts();
+                parameters.detect_counted_flags();
+                parameters.collapse_negatable_flags();
+                return Ok(parameters);
+            },
+            Err(error) => errors.push(error),
+        }
+    }
+    Err(errors)
+}
+
+/// Lines that look like parameter rows but did not end up in the parameters, any form of a key counts,
+/// also with an optional value like `--color[=WHEN]`
+pub fn find_unparsed_lines(help_string: &str, parameters: &CLIParameters) -> Vec<String> {
+    let is_key = |word: &str| {
+        parameters.arguments.iter().chain(&parameters.options).any(|argument| argument.has_key(word))
+            || parameters.flags.iter().any(|flag| flag.has_key(word))
+    };
+    help_string
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('-'))
+        .filter(|line| !line.split([' ', ',', '=', '[']).any(is_key))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parameter with a key in a spec file, e.g.
+/// [[options]]
+/// key = "--count"
+/// short_key = "-c"
+/// default = "1"
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ArgumentSpec {
+    key: Option<String>,
+    short_key: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    default: Option<String>,
+    multiple: bool,
+    choices: Vec<String>,
+    env: Option<String>,
+    /// Pass the value as `--key=value`
+    joined: bool,
+    /// Expand values like `*.log` when the command runs
+    glob: bool,
+    /// What has to be at the path of the value, `exist`, `absent` or `any`
+    path_check: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PositionalSpec {
+    name: String,
+    description: Option<String>,
+    default: Option<String>,
+    multiple: bool,
+    required: bool,
+    /// Expand values like `*.log` when the command runs
+    glob: bool,
+    /// What has to be at the paths of the values, `exist`, `absent` or `any`
+    path_check: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct FlagSpec {
+    key: Option<String>,
+    short_key: Option<String>,
+    description: Option<String>,
+    multiple: bool,
+}
+
+/// Parameters of a tool declared in a TOML file instead of parsed from its help
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CLISpec {
+    /// Program the form belongs to, e.g. `mytool`
+    name: String,
+    /// Parser whose way of passing values is used, `gnu` passes `--key=value`, every other one `--key value`
+    style: Option<String>,
+    /// Required parameters with a key
+    arguments: Vec<ArgumentSpec>,
+    positionals: Vec<PositionalSpec>,
+    options: Vec<ArgumentSpec>,
+    flags: Vec<FlagSpec>,
+    /// Keys that cannot be passed together, e.g. `[["--json", "--yaml"]]`
+    exclusive_groups: Vec<Vec<String>>,
+}
+
+/// Key identifying a parameter of a spec, its short and its long key
+/// The kind of `key` follows from its form: `--verbose` is long, `-v` short and keys like `/MIR` or `-name` are neither
+fn spec_keys(key: Option<String>, short_key: Option<String>) -> Result<(String, Option<String>, Option<String>), String> {
+    let long_key = key.clone().filter(|key| key.starts_with("--"));
+    let is_short = |key: &String| key.starts_with('-') && !key.starts_with("--") && key.chars().count() == 2;
+    let short_key = short_key.or_else(|| key.clone().filter(is_short));
+    let key = key.or(short_key.clone()).ok_or(String::from("Invalid spec: parameter without key or short_key"))?;
+    Ok((key, short_key, long_key))
+}
+
+/// Check of a spec parameter, any path by default
+#[allow(clippy::needless_return)]
+fn spec_path_check(name: Option<&str>) -> Result<PathCheck, String> {
+    match name {
+        Some(name) => return PathCheck::from_name(name).ok_or(format!("Invalid spec: unknown path check {name}")),
+        None => return Ok(PathCheck::Any),
+    }
+}
+
+fn argument_from_spec(spec: ArgumentSpec, required: bool) -> Result<CLIArgument, String> {
+    let (key, short_key, long_key) = spec_keys(spec.key, spec.short_key)?;
+    let path_check = spec_path_check(spec.path_check.as_deref())?;
+    Ok(CLIArgument {
+        name: spec.name.unwrap_or_else(|| key.trim_start_matches(['-', '/']).to_uppercase()),
+        key,
+        short_key,
+        long_key,
+        description: spec.description,
+        values: spec.default.into_iter().collect(),
+        multiple: spec.multiple,
+        required,
+        choices: spec.choices,
+        env_var: spec.env,
+        joined: spec.joined,
+        glob: spec.glob,
+        path_check,
+        ..Default::default()
+    })
+}
+
+/// Parses a spec file declaring the parameters of a tool, bypassing its help entirely
+pub fn parse_spec(content: &str) -> Result<CLIParameters, String> {
+    let spec: CLISpec = toml::from_str(content).map_err(|error| format!("Invalid spec: {error}"))?;
+    let cli_lib = match &spec.style {
+        Some(style) => CLILib::from_name(style).ok_or(format!("Invalid spec: unknown style {style}"))?,
+        None => CLILib::Clap,
+    };
+    let flags = spec.flags
+        .into_iter()
+        .map(|flag| {
+            let (key, short_key, long_key) = spec_keys(flag.key, flag.short_key)?;
+            Ok(CLIFlag { key, short_key, long_key, description: flag.description, multiple: flag.multiple, ..Default::default() })
+        })
+        .collect::<Result<Vec<CLIFlag>, String>>()?;
+    let mut parameters = CLIParameters {
+        cli_name: spec.name,
+        arguments: spec.arguments.into_iter().map(|argument| argument_from_spec(argument, true)).collect::<Result<_, _>>()?,
+        positionals: spec.positionals
+            .into_iter()
+            .map(|positional| {
+                Ok(CLIPositional {
+                    path_check: spec_path_check(positional.path_check.as_deref())?,
+                    name: positional.name,
+                    description: positional.description,
+                    values: positional.default.into_iter().collect(),
+                    multiple: positional.multiple,
+                    required: positional.required,
+                    glob: positional.glob,
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        options: spec.options.into_iter().map(|option| argument_from_spec(option, false)).collect::<Result<_, _>>()?,
+        flags,
+        exclusive_groups: spec.exclusive_groups,
+        cli_lib,
+        ..Default::default()
+    };
+    parameters.infer_value_types();
+    parameters.collapse_negatable_flags();
+    Ok(parameters)
+}
+
+/// Argument of a command dumped as JSON, see `parse_json_help`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsonArg {
+    id: String,
+    short: Option<String>,
+    long: Option<String>,
+    help: Option<String>,
+    required: bool,
+    action: Option<String>,
+    num_args: Option<String>,
+    value_names: Vec<String>,
+    value_hint: Option<String>,
+    default_values: Vec<String>,
+    possible_values: Vec<String>,
+    aliases: Vec<String>,
+    env: Option<String>,
+    help_heading: Option<String>,
+    global: bool,
+}
+
+impl JsonArg {
+    /// `"short": "n"` and `"long": "name"` become `-n` and `--name`
+    fn keys(&self) -> (Option<String>, Option<String>) {
+        (self.short.as_ref().map(|short| format!("-{short}")), self.long.as_ref().map(|long| format!("--{long}")))
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsonGroup {
+    args: Vec<String>,
+    multiple: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsonCommand {
+    name: Option<String>,
+    about: Option<String>,
+    args: Vec<JsonArg>,
+    groups: Vec<JsonGroup>,
+    subcommands: Vec<JsonCommand>,
+}
+
+/// Parameters of a command dumped as JSON, its subcommands included
+fn parameters_from_json(command: JsonCommand, cli_name: &str, command_path: Vec<String>) -> CLIParameters {
+    let mut parameters = CLIParameters { cli_name: cli_name.to_string(), command_path: command_path.clone(), ..Default::default() };
+    for group in &command.groups {
+        if group.multiple {
+            continue;
+        }
+        let keys: Vec<String> = group.args
+            .iter()
+            .filter_map(|id| {
+                let (short_key, long_key) = command.args.iter().find(|arg| arg.id == *id)?.keys();
+                long_key.or(short_key)
+            })
+            .collect();
+        if keys.len() > 1 {
+            parameters.exclusive_groups.push(keys);
+        }
+    }
+    let mut value_hints = Vec::new();
+    for arg in command.args {
+        let (short_key, long_key) = arg.keys();
+        let action = arg.action.as_deref().unwrap_or("Set");
+        let multiple = action == "Append" || action == "Count" || arg.num_args.as_deref().is_some_and(|num_args| num_args.ends_with(".."));
+        let name = arg.value_names.first().cloned().unwrap_or_else(|| arg.id.to_uppercase());
+        let aliases = arg.aliases.iter().map(|alias| format!("--{alias}")).collect();
+        let Some(key) = long_key.clone().or(short_key.clone()) else {
+            parameters.positionals.push(CLIPositional { name, description: arg.help, values: arg.default_values, multiple, required: arg.required, ..Default::default() });
+            continue;
+        };
+        // a SetFalse flag like `--no-color` is passed like any other flag, it is collapsed into the flag it negates below
+        if matches!(action, "SetTrue" | "SetFalse" | "Count" | "Help" | "Version") {
+            parameters.flags.push(CLIFlag {
+                key,
+                short_key,
+                long_key,
+                aliases,
+                description: arg.help,
+                multiple: action == "Count",
+                group: arg.help_heading,
+                global: arg.global,
+                ..Default::default()
+            });
+            continue;
+        }
+        if let Some(value_hint) = arg.value_hint {
+            value_hints.push((key.clone(), value_hint));
+        }
+        let argument = CLIArgument {
+            key,
+            short_key,
+            long_key,
+            aliases,
+            name,
+            description: arg.help,
+            values: arg.default_values,
+            multiple,
+            required: arg.required,
+            choices: arg.possible_values,
+            env_var: arg.env,
+            group: arg.help_heading,
+            global: arg.global,
+            ..Default::default()
+        };
+        if arg.required {
+            parameters.arguments.push(argument);
+        } else {
+            parameters.options.push(argument);
+        }
+    }
+    parameters.infer_value_types();
+    // value hints are more precise than the type inferred from the value name
+    for argument in parameters.arguments.iter_mut().chain(&mut parameters.options) {
+        let hint = value_hints.iter().find(|(key, _)| *key == argument.key).map(|(_, hint)| hint.as_str());
+        if let Some("FilePath" | "DirPath" | "AnyPath" | "ExecutablePath") = hint {
+            argument.value_type = ValueType::Path;
+        }
+    }
+    parameters.collapse_negatable_flags();
+    for subcommand in command.subcommands {
+        let Some(name) = subcommand.name.clone() else {
+            continue;
+        };
+        let mut subcommand_path = command_path.clone();
+        subcommand_path.push(name.clone());
+        parameters.subcommands.push(CLISubcommand {
+            name,
+            description: subcommand.about.clone(),
+            parameters: Some(parameters_from_json(subcommand, cli_name, subcommand_path)),
+        });
+    }
+    parameters
+}
+
+/// Parses the command model a tool dumps as JSON, e.g. with `--dump-help-json`:
+/// {"name": "tool", "args": [{"id": "out", "long": "out", "value_names": ["DIR"], "required": true, "action": "Set",
+///   "value_hint": "DirPath", "default_values": [], "possible_values": []}], "groups": [], "subcommands": [{"name": "build", ...}]}
+/// Arguments without short and long key are positionals
+pub fn parse_json_help(text: &str) -> Result<CLIParameters, String> {
+    let mut command: JsonCommand = serde_json::from_str(text).map_err(|error| format!("Invalid command dump: {error}"))?;
+    let cli_name = command.name.take().ok_or(String::from("Invalid command dump: missing name"))?;
+    Ok(parameters_from_json(command, &cli_name, Vec::new()))
+}
+
+/// Option described by a completion script, every form of its key merged
+#[derive(Debug, Default)]
+struct CompletionOption {
+    keys: Vec<String>,
+    description: Option<String>,
+    /// Name of the value if the option takes one, e.g. `COUNT`
+    value_name: Option<String>,
+    choices: Vec<String>,
+    /// Whether the value is completed as a file or directory
+    path: bool,
+    multiple: bool,
+    joined: bool,
+    /// Keys that cannot be used together with this option, e.g. `(--yaml)` in front of `--json`
+    exclusions: Vec<String>,
+}
+
+impl CompletionOption {
+    /// Whether the other option is another form of this one, e.g. the `--count` of `-c`:
+    /// both have the same description and value but keys of different length
+    fn is_form_of(&self, other: &CompletionOption) -> bool {
+        let is_long = |key: &String| key.starts_with("--");
+        self.description == other.description
+            && self.value_name.is_some() == other.value_name.is_some()
+            && (self.exclusions.iter().any(|key| other.keys.contains(key)) || self.keys.iter().all(is_long) != other.keys.iter().all(is_long))
+    }
+}
+
+/// Build parameters from the options and positionals of a completion script, the first long key identifies an option
+fn parameters_from_completion(cli_name: &str, options: Vec<CompletionOption>, positionals: Vec<CLIPositional>, cli_lib: CLILib) -> CLIParameters {
+    let mut parameters = CLIParameters { cli_name: cli_name.to_string(), positionals, cli_lib, ..Default::default() };
+    let mut exclusions = Vec::new();
+    for option in options {
+        let short_key = option.keys.iter().find(|key| !key.starts_with("--")).cloned();
+        let long_key = option.keys.iter().find(|key| key.starts_with("--")).cloned();
+        let Some(key) = long_key.clone().or(short_key.clone()) else {
+            continue;
+        };
+        let aliases = option.keys.iter().filter(|alias| Some(*alias) != short_key.as_ref() && Some(*alias) != long_key.as_ref()).cloned().collect();
+        exclusions.extend(option.exclusions.into_iter().filter(|excluded| !option.keys.contains(excluded)).map(|excluded| (key.clone(), excluded)));
+        let Some(name) = option.value_name else {
+            parameters.flags.push(CLIFlag { key, short_key, long_key, aliases, description: option.description, multiple: option.multiple, ..Default::default() });
+            continue;
+        };
+        parameters.options.push(CLIArgument {
+            key,
+            short_key,
+            long_key,
+            aliases,
+            name,
+            description: option.description,
+            multiple: option.multiple,
+            choices: option.choices,
+            value_type: if option.path { ValueType::Path } else { ValueType::default() },
+            joined: option.joined,
+            ..Default::default()
+        });
+    }
+    // every exclusion between two flags becomes a group of its own, listed once
+    for (key, excluded) in exclusions {
+        let Some(excluded) = parameters.find_canonical_key(&excluded).cloned() else {
+            continue;
+        };
+        let is_flag = |key: &String| parameters.flags.iter().any(|flag| flag.key == *key);
+        let listed = parameters.exclusive_groups.iter().any(|group| group.contains(&key) && group.contains(&excluded));
+        if is_flag(&key) && is_flag(&excluded) && !listed {
+            parameters.exclusive_groups.push(vec![key, excluded]);
+        }
+    }
+    // the type of a value completed as a path is known, the others are inferred from their name
+    let paths: Vec<String> = parameters.options.iter().filter(|option| option.value_type == ValueType::Path).map(|option| option.key.clone()).collect();
+    parameters.infer_value_types();
+    for option in parameters.options.iter_mut().filter(|option| paths.contains(&option.key)) {
+        option.value_type = ValueType::Path;
+    }
+    parameters.detect_counted_flags();
+    parameters.collapse_negatable_flags();
+    parameters
+}
+
+/// Split a shell command into words like the shell does, e.g. `'(-n --name)'{-n+,--name=}'[Name]'` into two words
+/// Single quotes keep their content literally, brace lists are expanded and a backslash at the end of a line continues it
+pub fn split_shell_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    // the current word, once for every alternative of its brace lists
+    let mut alternatives = vec![String::new()];
+    let mut in_word = false;
+    let append = |alternatives: &mut Vec<String>, text: &str| alternatives.iter_mut().for_each(|alternative| alternative.push_str(text));
+    let mut chars = command.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' => {
+                let text: String = chars.by_ref().take_while(|ch| *ch != '\'').collect();
+                append(&mut alternatives, &text);
+            },
+            '"' => {
+                let mut text = String::new();
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => text.push(escaped),
+                            Some(other) => text.extend(['\\', other]),
+                            None => (),
+                        },
+                        _ => text.push(ch),
+                    }
+                }
+                append(&mut alternatives, &text);
+            },
+            '\\' => match chars.next() {
+                Some('\n') | None => continue,
+                Some(escaped) => append(&mut alternatives, &escaped.to_string()),
+            },
+            '{' => {
+                let group: String = chars.by_ref().take_while(|ch| *ch != '}').collect();
+                if group.contains(',') {
+                    alternatives = alternatives.iter().flat_map(|alternative| group.split(',').map(move |part| format!("{alternative}{part}"))).collect();
+                } else {
+                    append(&mut alternatives, &format!("{{{group}}}"));
+                }
+            },
+            _ if ch.is_whitespace() => {
+                if in_word {
+                    words.append(&mut mem::replace(&mut alternatives, vec![String::new()]));
+                    in_word = false;
+                }
+                continue;
+            },
+            _ => append(&mut alternatives, &ch.to_string()),
+        }
+        in_word = true;
+    }
+    if in_word {
+        words.append(&mut alternatives);
+    }
+    words
+}
+
+/// Name of a value from the message of a completion spec, e.g. `FILE` for `file`
+fn completion_value_name(message: &str, key: &str) -> String {
+    let message = message.trim();
+    if message.is_empty() {
+        return key.trim_start_matches('-').to_uppercase().replace('-', "_");
+    }
+    message.to_uppercase().replace([' ', '-'], "_")
+}
+
+/// Choices of a completion action, e.g. `(auto always never)` or `((auto\:"Detect" never\:"Never"))`, and whether it completes paths
+fn parse_completion_action(action: &str) -> (Vec<String>, bool) {
+    let action = action.trim();
+    if let Some(described) = action.strip_prefix("((").and_then(|action| action.strip_suffix("))")) {
+        let choices = split_shell_words(described).iter().map(|choice| choice.split(['\\', ':']).next().unwrap_or_default().to_string()).collect();
+        return (choices, false);
+    }
+    if let Some(choices) = action.strip_prefix('(').and_then(|action| action.strip_suffix(')')) {
+        return (choices.split_whitespace().map(str::to_string).collect(), false);
+    }
+    (Vec::new(), ["_files", "_path_files", "_directories"].iter().any(|function| action.starts_with(function)))
+}
+
+/// Words of the first `_arguments` call of a zsh completion function, up to the end of its command
+fn retrieve_zsh_arguments(script: &str) -> Option<Vec<String>> {
+    static CALL_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*_arguments(?:\s|$)").unwrap());
+    let call = CALL_PATTERN.find(script)?;
+    let mut command = String::new();
+    for line in script[call.end()..].lines() {
+        command.push_str(line);
+        command.push('\n');
+        if !line.trim_end().ends_with('\\') {
+            break;
+        }
+    }
+    let words = split_shell_words(&command);
+    let end = words.iter().position(|word| matches!(word.as_str(), "&&" | "||" | ";")).unwrap_or(words.len());
+    // options of `_arguments` itself come first, e.g. `-s -S` or `"${_arguments_options[@]}" :`
+    static OWN_OPTION_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(-[sSCwWn]+|:|\$.*)$").unwrap());
+    Some(words[..end].iter().skip_while(|word| OWN_OPTION_PATTERN.is_match(word)).cloned().collect())
+}
+
+/// Parses the `_arguments` specs of a zsh completion function, e.g.
+/// _arguments \
+///   '(-c --count)'{-c+,--count=}'[Number of times to greet]:count:' \
+///   '*'{-v,--verbose}'[More output]' \
+///   '*:file:_files'
+fn parse_zsh_completion(script: &str, cli_name: &str) -> Option<CLIParameters> {
+    static OPTION_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:\((?P<exclusions>[^)]*)\))?(?P<multiple>\*)?(?P<key>--?[\w][\w.-]*?)(?P<suffix>=-|[+=-])?(?:\[(?P<description>(?:\\.|[^\]])*)\])?(?P<value>:.*)?$").unwrap());
+    static POSITIONAL_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<multiple>\*)?\d*:(?P<optional>:*)(?P<message>[^:]*)(?::(?P<action>.*))?$").unwrap());
+    let mut options: Vec<CompletionOption> = Vec::new();
+    let mut positionals = Vec::new();
+    for spec in retrieve_zsh_arguments(script)? {
+        if let Some(caps) = POSITIONAL_PATTERN.captures(&spec) {
+            let action = caps.name("action").map_or("", |action| action.as_str());
+            // states and generated functions complete subcommands, not values
+            if caps["message"].trim().is_empty() || action.starts_with("->") || action.ends_with("_commands") {
+                continue;
+            }
+            let multiple = caps.name("multiple").is_some();
+            // the message may describe the value, e.g. `friends -- Friends to greet`
+            let (message, description) = caps["message"].split_once(" -- ").map_or((&caps["message"], None), |(message, description)| (message, Some(description)));
+            positionals.push(CLIPositional {
+                name: completion_value_name(message, ""),
+                description: description.map(|description| description.trim().to_string()),
+                multiple,
+                required: !multiple && caps["optional"].is_empty(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let Some(caps) = OPTION_PATTERN.captures(&spec) else {
+            continue;
+        };
+        let key = caps["key"].to_string();
+        let value = caps.name("value").map(|value| value.as_str().trim_start_matches(':'));
+        let (message, action) = value.map_or(("", ""), |value| value.split_once(':').unwrap_or((value, "")));
+        let (choices, path) = parse_completion_action(action);
+        let option = CompletionOption {
+            keys: vec![key.clone()],
+            description: caps.name("description").map(|description| description.as_str().replace("\\]", "]").replace("\\[", "[")),
+            value_name: value.map(|_| completion_value_name(message, &key)),
+            choices,
+            path,
+            multiple: caps.name("multiple").is_some(),
+            joined: caps.name("suffix").is_some_and(|suffix| suffix.as_str() == "=-"),
+            exclusions: caps.name("exclusions").map(|exclusions| exclusions.as_str().split_whitespace().map(str::to_string).collect()).unwrap_or_default(),
+        };
+        match options.last_mut() {
+            Some(previous) if previous.is_form_of(&option) => previous.keys.push(key),
+            _ => options.push(option),
+        }
+    }
+    if options.is_empty() && positionals.is_empty() {
+        return None;
+    }
+    Some(parameters_from_completion(cli_name, options, positionals, CLILib::Clap))
+}
+
+/// Body of a shell function, from its definition up to the closing brace at the start of a line
+fn retrieve_shell_function<'a>(script: &'a str, function: &str) -> Option<&'a str> {
+    let definition = Regex::new(&format!(r"(?m)^\s*(?:function\s+)?{}\s*(?:\(\s*\))?\s*\{{?\s*$", regex::escape(function))).unwrap();
+    let start = definition.find(script)?.end();
+    let end = script[start..].find("\n}").map_or(script.len(), |end| start + end);
+    Some(&script[start..end])
+}
+
+/// Parses a bash completion script, e.g.
+/// _greeter() {
+///     opts="-c -v --count --verbose <FILE>"
+///     case "${prev}" in
+///         --count|-c)
+///             COMPREPLY=($(compgen -W "1 2 3" -- "${cur}"))
+///             ;;
+///     esac
+/// }
+/// complete -F _greeter greeter
+/// The keys handled after `case "${prev}"` take a value, keys ending with `=` are passed joined
+fn parse_bash_completion(script: &str, cli_name: &str) -> Option<CLIParameters> {
+    let complete_pattern = Regex::new(&format!(r"(?m)^\s*complete\s+(?P<options>.*\s)?{}\s*$", regex::escape(cli_name))).unwrap();
+    let complete_options = split_shell_words(complete_pattern.captures(script)?.name("options")?.as_str());
+    // the keys are listed in `opts="..."` or directly in `compgen -W "..."`, the first list with a key belongs to the command itself
+    static WORD_LIST_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"(?P<kind>\bopts=|compgen\s+-W\s+)(?:"(?P<double>[^"]*)"|'(?P<single>[^']*)')"#).unwrap());
+    let (body, words) = match complete_options.iter().position(|option| option == "-W" || option == "-F") {
+        Some(index) if complete_options[index] == "-W" => ("", complete_options.get(index + 1)?.clone()),
+        Some(index) => {
+            let body = retrieve_shell_function(script, complete_options.get(index + 1)?)?;
+            let caps = WORD_LIST_PATTERN
+                .captures_iter(body)
+                .find(|caps| caps.name("double").or(caps.name("single")).is_some_and(|words| words.as_str().split_whitespace().any(|word| word.starts_with('-'))))?;
+            let words = caps.name("double").or(caps.name("single"))?.as_str().to_string();
+            // the cases of the command end where the keys of a subcommand are listed
+            let start = caps.get(0)?.end();
+            let end = body[start..].find("opts=").map_or(body.len(), |end| start + end);
+            (&body[start..end], words)
+        },
+        None => return None,
+    };
+    static CASE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?P<labels>-[^)\s]*(?:\s*\|\s*-[^)\s]*)*)\)").unwrap());
+    let mut cases: Vec<(Vec<String>, String)> = Vec::new();
+    let mut lines = body.lines();
+    while let Some(line) = lines.next() {
+        if let Some(caps) = CASE_PATTERN.captures(line) {
+            let labels = caps["labels"].split('|').map(|label| label.trim().trim_end_matches('=').to_string()).collect();
+            let branch = lines.by_ref().take_while(|line| !line.trim_start().starts_with(";;")).collect::<Vec<&str>>().join("\n");
+            cases.push((labels, branch));
+        }
+    }
+    static COMPGEN_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"compgen\s+-W\s+(?:"(?P<double>[^"]*)"|'(?P<single>[^']*)')"#).unwrap());
+    let mut options: Vec<CompletionOption> = Vec::new();
+    let mut positionals = Vec::new();
+    for word in words.split_whitespace() {
+        if !word.starts_with('-') {
+            let name = word.trim_end_matches("...");
+            if let Some(name) = name.strip_prefix('<').and_then(|name| name.strip_suffix('>')).or(name.strip_prefix('[').and_then(|name| name.strip_suffix(']'))) {
+                positionals.push(CLIPositional { name: name.to_string(), multiple: word.ends_with("..."), required: word.starts_with('<'), ..Default::default() });
+            }
+            continue;
+        }
+        let key = word.trim_end_matches('=');
+        if options.iter().any(|option| option.keys.iter().any(|known| known == key)) {
+            continue;
+        }
+        let case = cases.iter().find(|(labels, _)| labels.iter().any(|label| label == key));
+        let keys = case.map_or_else(|| vec![key.to_string()], |(labels, _)| labels.clone());
+        let branch = case.map(|(_, branch)| branch.as_str());
+        let choices = branch
+            .and_then(|branch| COMPGEN_PATTERN.captures(branch))
+            .and_then(|caps| caps.name("double").or(caps.name("single")).map(|choices| choices.as_str().split_whitespace().map(str::to_string).collect()))
+            .unwrap_or_default();
+        options.push(CompletionOption {
+            keys,
+            value_name: (case.is_some() || word.ends_with('=')).then(|| completion_value_name("", key)),
+            choices,
+            // clap completes every value with `compgen -f`, only directories and `_filedir` are paths for sure
+            path: branch.is_some_and(|branch| branch.contains("compgen -d") || branch.contains("_filedir")),
+            joined: word.ends_with('='),
+            ..Default::default()
+        });
+    }
+    if options.is_empty() {
+        return None;
+    }
+    // GNU tools list the long options taking a value as `--color=`
+    let cli_lib = if words.split_whitespace().any(|word| word.starts_with("--") && word.ends_with('=')) { CLILib::Gnu } else { CLILib::Clap };
+    Some(parameters_from_completion(cli_name, options, positionals, cli_lib))
+}
+
+/// Conditions of fish completions that hold for the command itself, before a subcommand was given
+const FISH_ROOT_CONDITIONS: [&str; 3] = ["__fish_use_subcommand", "__fish_no_subcommand", "not __fish_seen_subcommand_from"];
+
+/// Parses a fish completion file, e.g.
+/// complete -c greeter -s c -l count -x -a '1 2 3' -d 'Number of times to greet'
+/// complete -c greeter -l caps -d 'Greet in caps'
+/// complete -c greeter -n __fish_use_subcommand -a build -d 'Build the greeting'
+/// Completions of subcommands, with a `__fish_seen_subcommand_from` condition, are skipped
+fn parse_fish_completion(script: &str, cli_name: &str) -> Option<CLIParameters> {
+    let mut options: Vec<CompletionOption> = Vec::new();
+    let mut subcommands: Vec<CLISubcommand> = Vec::new();
+    for line in script.lines().filter(|line| line.trim_start().starts_with("complete ")) {
+        let mut words = split_shell_words(line).into_iter().skip(1);
+        let mut command = None;
+        let (mut keys, mut description, mut arguments, mut condition) = (Vec::new(), None, None, None);
+        let (mut requires_value, mut files) = (false, false);
+        while let Some(word) = words.next() {
+            // short options may be combined like `-xa 'a b'` and take their value from the rest of the word
+            let (option, attached) = match word.split_once('=') {
+                Some((option, value)) if option.starts_with("--") => (option.to_string(), Some(value.to_string())),
+                _ if word.starts_with("--") || word.len() <= 2 => (word.clone(), None),
+                _ => {
+                    let mut chars = word[1..].chars();
+                    let mut option = None;
+                    for flag in chars.by_ref() {
+                        if matches!(flag, 'c' | 's' | 'l' | 'o' | 'd' | 'a' | 'n' | 'p' | 'w') {
+                            option = Some(flag);
+                            break;
+                        }
+                        requires_value |= matches!(flag, 'r' | 'x');
+                        files |= flag == 'F';
+                    }
+                    let rest: String = chars.collect();
+                    match option {
+                        Some(flag) => (format!("-{flag}"), Some(rest).filter(|rest| !rest.is_empty())),
+                        None => continue,
+                    }
+                },
+            };
+            let mut value = || attached.clone().or_else(|| words.next());
+            match option.as_str() {
+                "-c" | "--command" => command = value(),
+                "-s" | "--short-option" => keys.extend(value().map(|key| format!("-{key}"))),
+                "-l" | "--long-option" => keys.extend(value().map(|key| format!("--{key}"))),
+                "-o" | "--old-option" => keys.extend(value().map(|key| format!("-{key}"))),
+                "-d" | "--description" => description = value(),
+                "-a" | "--arguments" => arguments = value(),
+                "-n" | "--condition" => condition = value(),
+                "-p" | "--path" | "-w" | "--wraps" => drop(value()),
+                "-r" | "--require-parameter" | "-x" | "--exclusive" => requires_value = true,
+                "-F" | "--force-files" => files = true,
+                _ => (),
+            }
+        }
+        if command.as_deref() != Some(cli_name) {
+            continue;
+        }
+        let condition = condition.unwrap_or_default();
+        let root_condition = FISH_ROOT_CONDITIONS.iter().any(|root| condition.starts_with(root));
+        if !root_condition && condition.contains("__fish_seen_subcommand_from") {
+            continue;
+        }
+        let arguments = arguments.unwrap_or_default();
+        // each argument may be followed by a tab and its description
+        let values = || arguments.split_whitespace().map(|value| value.split('\t').next().unwrap_or_default().to_string());
+        let is_generated = arguments.contains('(') || arguments.contains('$');
+        if keys.is_empty() {
+            // arguments offered before a subcommand was given are the subcommands
+            if root_condition && !is_generated {
+                for name in values() {
+                    if subcommands.iter().all(|subcommand| subcommand.name != name) {
+                        subcommands.push(CLISubcommand { name, description: description.clone(), ..Default::default() });
+                    }
+                }
+            }
+            continue;
+        }
+        if options.iter().any(|option| option.keys.iter().any(|key| keys.contains(key))) {
+            continue;
+        }
+        let path = files || ["__fish_complete_path", "__fish_complete_directories", "__fish_complete_suffix"].iter().any(|function| arguments.contains(function));
+        let key = keys.iter().find(|key| key.starts_with("--")).unwrap_or(&keys[0]).clone();
+        options.push(CompletionOption {
+            value_name: requires_value.then(|| completion_value_name("", &key)),
+            choices: if requires_value && !is_generated { values().collect() } else { Vec::new() },
+            path: requires_value && path,
+            keys,
+            description,
+            ..Default::default()
+        });
+    }
+    if options.is_empty() && subcommands.is_empty() {
+        return None;
+    }
+    let mut parameters = parameters_from_completion(cli_name, options, Vec::new(), CLILib::Clap);
+    parameters.subcommands = subcommands;
+    Some(parameters)
+}
+
+/// Parses a bash, zsh or fish completion script of the command,
+/// recognized by the `_arguments` of zsh and the `complete -c` of fish
+pub fn parse_completion_script(script: &str, cli_name: &str) -> Option<CLIParameters> {
+    if script.contains("_arguments") {
+        return parse_zsh_completion(script, cli_name);
+    }
+    static FISH_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*complete\s+(?:-c|--command)").unwrap());
+    if FISH_PATTERN.is_match(script) {
+        return parse_fish_completion(script, cli_name);
+    }
+    parse_bash_completion(script, cli_name)
+}
+
+/// Whether the help has one of the sections clap prints, `Options:`, `Arguments:` or `Commands:` in any casing
+/// `Available Commands:` of cobra does not count
+fn has_clap_section(help_string: &str) -> bool {
+    let headings = ["Options:", "Commands:", "Subcommands:"].into_iter().chain(CLAP_ARGUMENT_HEADINGS).collect::<Vec<&str>>();
+    help_string.lines().any(|line| is_section_heading(line, &headings))
+}
+
+/// Parses a clap help string
+/// Every section is optional, e.g. a tool with only positionals prints no `Options:`, but one of them has to be there
+fn parse_clap_help_string(help_string: &str) -> Result<CLIParameters, usize> {
+    if !has_clap_section(help_string) {
+        return Err(usage_line_index(help_string));
+    }
+    let option_explanation = retrieve_clap_option_explanation(help_string);
+    let parameters = option_explanation.and_then(parse_clap_option_explanation).unwrap_or_default();
+    let usage_explanation = retrieve_clap_usage_explanation(help_string).ok_or_else(|| usage_line_index(help_string))?;
+    let (cli_name, usage_groups) = parse_clap_usage_explanation(&usage_explanation).ok_or_else(|| usage_line_index(help_string))?;
+    // the keys and names of every variant of the command itself are its parameters
+    let usage = parse_clap_usage_variants(&usage_explanation).ok_or_else(|| usage_line_index(help_string))?.1.join("\n");
+    let multiplicity = parse_clap_usage_multiplicity(&usage);
+    let subcommands = parse_command_section(help_string);
+    let mut result = CLIParameters {
+        cli_name,
+        positionals: parse_clap_positionals(help_string, &usage, &usage_groups, !subcommands.is_empty()),
+        subcommands,
+        exclusive_groups: usage_groups.exclusive_groups,
+        cli_lib: CLILib::Clap,
+        ..Default::default()
+    };
+
+    for parameter in parameters {
+        match parameter {
+            CLIParameter::Argument(mut argument) => {
+                if usage_groups.required_keys.iter().any(|key| argument.has_key(key)) {
+                    argument.required = true;
+                    argument.multiple |= multiplicity.matches_argument(&argument, false);
+                    result.arguments.push(argument);
+                } else {
+                    argument.multiple |= multiplicity.matches_argument(&argument, true);
+                    result.options.push(argument);
+                }
+            },
+            CLIParameter::Flag(mut flag) => {
+                flag.multiple |= multiplicity.keys.iter().any(|key| flag.has_key(key));
+                result.flags.push(flag);
+            }
+        }
+    }
+    // the usage line may use the short key, e.g. `[-j | -y]` for `--json` and `--yaml`
+    let mut exclusive_groups = mem::take(&mut result.exclusive_groups);
+    for key in exclusive_groups.iter_mut().flatten() {
+        if let Some(canonical_key) = result.find_canonical_key(key) {
+            *key = canonical_key.clone();
+        }
+    }
+    result.exclusive_groups = exclusive_groups;
+    Ok(result)
+}
+
+/// Headings of the sections listing the subcommands, e.g. `Commands:` of clap and `Available Commands:` of cobra
+const COMMAND_HEADINGS: [&str; 3] = ["Commands:", "Available Commands:", "Subcommands:"];
+
+/// Headings of the clap section describing the positionals, `ARGS:` in older versions
+const CLAP_ARGUMENT_HEADINGS: [&str; 2] = ["Arguments:", "Args:"];
+
+/// Whether the line is one of the section headings in any casing, e.g. `OPTIONS:` of older clap versions for `Options:`
+fn is_section_heading(line: &str, headings: &[&str]) -> bool {
+    !line.starts_with(char::is_whitespace) && headings.iter().any(|heading| line.trim_end().eq_ignore_ascii_case(heading))
+}
+
+/// Parse the subcommands of the first command section of a help string, e.g.
+/// Commands:
+///   build, b  Compile the current package
+/// The generated `help` subcommand is skipped
+fn parse_command_section(help_string: &str) -> Vec<CLISubcommand> {
+    let rows = help_string
+        .lines()
+        .skip_while(|line| !is_section_heading(line, &COMMAND_HEADINGS))
+        .skip(1)
+        .take_while(|line| !line.trim().is_empty());
+    parse_command_rows(rows)
+}
+
+/// Parse the rows of a command section, lines indented deeper than the first row continue the previous description
+fn parse_command_rows<'a>(rows: impl Iterator<Item = &'a str>) -> Vec<CLISubcommand> {
+    static COMMAND_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s+(?P<name>\w[\w-]*)(?:,\s*[\w-]+)*(?:\s{2,}(?P<description>\S.*))?$").unwrap());
+    let mut subcommands: Vec<CLISubcommand> = Vec::new();
+    let mut row_indent = None;
+    for row in rows {
+        let indent = row.len() - row.trim_start().len();
+        if indent > *row_indent.get_or_insert(indent) {
+            if let Some(description) = subcommands.last_mut().map(|subcommand| &mut subcommand.description) {
+                match description {
+                    Some(description) => {
+                        description.push(' ');
+                        description.push_str(row.trim());
+                    },
+                    None => *description = Some(row.trim().to_string()),
+                }
+            }
+            continue;
+        }
+        let Some(caps) = COMMAND_PATTERN.captures(row) else {
+            continue;
+        };
+        subcommands.push(CLISubcommand {
+            name: caps["name"].to_string(),
+            description: caps.name("description").map(|description| description.as_str().to_string()),
+            parameters: None,
+        });
+    }
+    subcommands.retain(|subcommand| subcommand.name != "help");
+    subcommands
+}
+
+/// Retrieve the option explanation of a clap help string, e.g.
+/// Options:
+///     -n, --name <NAME> Name of the person to greet
+/// None if the help has no options section
+fn retrieve_clap_option_explanation(help_string: &str) -> Option<&str> {
+    let mut offset = 0;
+    for line in help_string.split_inclusive('\n') {
+        if is_section_heading(line, &["Options:"]) {
+            return Some(&help_string[offset..]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Retrieve a section of a clap help string from one of its headings up to the next heading, e.g.
+/// Arguments:
+///   <FILE>  File to read
+fn retrieve_clap_section<'a>(help_string: &'a str, headings: &[&str]) -> Option<&'a str> {
+    let mut start = None;
+    let mut offset = 0;
+    for line in help_string.split_inclusive('\n') {
+        let is_heading = !line.starts_with(char::is_whitespace) && !line.trim().is_empty();
+        match start {
+            None if is_section_heading(line, headings) => start = Some(offset),
+            Some(start) if is_heading => return Some(&help_string[start..offset]),
+            _ => (),
+        }
+        offset += line.len();
+    }
+    start.map(|start| &help_string[start..])
+}
+
+/// Retrieve the usage explanation of a clap help string, e.g.
+/// Usage: clap_example.exe [OPTIONS] --name <NAME>
+///        clap_example.exe add <ITEM>
+/// Every variant ends up on a line of its own, the lines a long variant is wrapped onto are joined to it
+fn retrieve_clap_usage_explanation(help_string: &str) -> Option<String> {
+    // older clap versions print `USAGE:` with the usage on the next line
+    let is_usage = |line: &str| line.get(..6).is_some_and(|start| start.eq_ignore_ascii_case("Usage:"));
+    let mut lines = help_string.lines().skip_while(|line| !is_usage(line));
+    let first = lines.next()?[6..].trim();
+    let mut variants: Vec<String> = Vec::from_iter((!first.is_empty()).then(|| first.to_string()));
+    for line in lines.take_while(|line| line.starts_with(char::is_whitespace) && !line.trim().is_empty()) {
+        let line = line.trim();
+        let starts_variant = variants.first().and_then(|variant| variant.split_whitespace().next()) == line.split_whitespace().next();
+        match variants.last_mut() {
+            Some(variant) if !starts_variant => {
+                variant.push(' ');
+                variant.push_str(line);
+            },
+            _ => variants.push(line.to_string()),
+        }
+    }
+    (!variants.is_empty()).then(|| format!("Usage: {}", variants.join("\n       ")))
+}
+
+/// Column where most descriptions of an options block start, found from their alignment, e.g. 24 for
+///   -n,  --name <NAME>    Name of the person to greet
+///   -c,  --count <COUNT>  Number of times to greet
+/// Every run of two or more spaces in a key row that is not followed by a key is a candidate,
+/// so spaces between the keys do not matter
+fn find_description_column<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<usize> {
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    for line in lines {
+        let indent = line.len() - line.trim_start().len();
+        if !line[indent..].starts_with('-') {
+            continue;
+        }
+        let mut columns = Vec::new();
+        let mut spaces = 0;
+        for (index, ch) in line.char_indices().skip_while(|(index, _)| *index < indent) {
+            if ch == ' ' {
+                spaces += 1;
+            } else {
+                // descriptions do not start with a key
+                if spaces >= 2 && ch != '-' {
+                    columns.push(index);
+                }
+                spaces = 0;
+            }
+        }
+        for column in columns {
+            *counts.entry(column).or_default() += 1;
+        }
+    }
+    // the most common column, the last one on ties
+    let (column, count) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    Some(column).filter(|_| count >= 2)
+}
+
+/// Keys and description of an option row, split at the description column if the row is aligned to it
+/// and at the first run of two spaces otherwise
+#[allow(clippy::needless_return)]
+fn split_option_row(line: &str, column: Option<usize>) -> (String, Option<String>) {
+    if let Some(column) = column.filter(|column| line.is_char_boundary(*column) && *column < line.len()) {
+        let (keys, description) = line.split_at(column);
+        if keys.ends_with(' ') && !description.starts_with(' ') && !keys.trim().is_empty() {
+            return (keys.split_whitespace().collect::<Vec<&str>>().join(" "), Some(description.trim().to_string()));
+        }
+    }
+    let line = line.trim();
+    match line.split_once("  ") {
+        Some((keys, description)) => return (keys.to_string(), Some(description.trim().to_string())),
+        None => return (line.to_string(), None),
+    }
+}
+
+/// Description of a clap option and the metadata blocks appended to it, e.g.
+/// Output directory [default: /tmp/my dir] [env: OUT_DIR=] [aliases: out]
+#[derive(Debug, Default, PartialEq)]
+struct ClapOptionMetadata {
+    description: Option<String>,
+    default_value: Option<String>,
+    env_var: Option<String>,
+    choices: Vec<String>,
+    /// Keys of `[aliases: out]` and `[short aliases: O]`, e.g. `--out` and `-O`
+    aliases: Vec<String>,
+}
+
+/// Labels of the blocks clap appends to a description, other brackets belong to the description
+const CLAP_METADATA_LABELS: [&str; 6] = ["default", "env", "possible values", "aliases", "alias", "short aliases"];
+
+/// Index of the bracket closing the one at the start of the text, nested brackets are skipped
+fn find_closing_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (index, ch) in text.char_indices() {
+        match ch {
+            '[' => depth += 1,
+            ']' if depth == 1 => return Some(index),
+            ']' => depth -= 1,
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Split at the commas that are not inside brackets, e.g. `[a, b], c` into `[a, b]` and `c`
+fn split_top_level_commas(text: &str) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut depth = 0usize;
+    for ch in text.chars() {
+        match ch {
+            '[' | '(' | '{' => depth += 1,
+            ']' | ')' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(String::new());
+                continue;
+            },
+            _ => (),
+        }
+        parts.last_mut().unwrap().push(ch);
+    }
+    parts.into_iter().map(|part| part.trim().to_string()).filter(|part| !part.is_empty()).collect()
+}
+
+/// Take the metadata blocks out of the description of a clap option,
+/// their values may contain spaces and brackets, e.g. `[default: [1, 2]]`
+fn split_clap_metadata(text: &str) -> ClapOptionMetadata {
+    let mut metadata = ClapOptionMetadata::default();
+    let mut description = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        description.push_str(&rest[..start]);
+        let block = &rest[start..];
+        let Some(end) = find_closing_bracket(block) else {
+            rest = block;
+            break;
+        };
+        let content = &block[1..end];
+        match content.split_once(':').filter(|(label, _)| CLAP_METADATA_LABELS.contains(&label.trim())) {
+            Some((label, value)) => {
+                let value = value.trim();
+                match label.trim() {
+                    "default" => metadata.default_value = Some(value.to_string()),
+                    "env" => metadata.env_var = Some(value.split_once('=').map_or(value, |(name, _)| name).to_string()),
+                    "possible values" => metadata.choices = split_top_level_commas(value),
+                    "short aliases" => metadata.aliases.extend(split_top_level_commas(value).iter().map(|alias| format!("-{}", alias.trim_start_matches('-')))),
+                    _ => metadata.aliases.extend(split_top_level_commas(value).iter().map(|alias| format!("--{}", alias.trim_start_matches('-')))),
+                }
+            },
+            None => description.push_str(&block[..=end]),
+        }
+        rest = &block[end + 1..];
+    }
+    description.push_str(rest);
+    let description = description.split_whitespace().collect::<Vec<&str>>().join(" ");
+    metadata.description = Some(description).filter(|description| !description.is_empty());
+    metadata
+}
+
+/// Parse a single clap option line for cli parameters
+/// There exists two version of option line
+/// 1. Arguments: -n, --name <NAME> Name of the person to greet [env: NAME=] [default: Me] [possible values: Me, You]
+/// 2. Flags: -h, --help Print help
+fn parse_clap_option_line(option_line: &str) -> Option<CLIParameter> {
+    let (keys, description) = split_option_row(option_line, None);
+    static OPTION_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<short_key>-\w)?[ ,]*(?P<long_key>--\w+(?:-\w+)?)?(?P<aliases>(?:,\s*--?\w[\w-]*)*)(?P<key_multiple>\.\.\.)?(?:\s*(?P<joined>=)?<(?P<name>\w+)>(?P<multiple>\.\.\.)?)?(?P<rest>.*)$").unwrap());
+    let caps = OPTION_PATTERN.captures(&keys)?;
+    let short_key = caps.name("short_key").map(|key| key.as_str().to_string());
+    let long_key = caps.name("long_key").map(|key| key.as_str().to_string());
+    let key = long_key.clone().or(short_key.clone())?;
+    let mut aliases: Vec<String> = caps.name("aliases")
+        .map(|aliases| aliases.as_str().split(',').map(str::trim).filter(|alias| !alias.is_empty()).map(str::to_string).collect())
+        .unwrap_or_default();
+    let name = caps.name("name").map(|name| name.as_str().to_string());
+    // anything after the keys that was not separated by two spaces belongs to the description
+    let rest = caps.name("rest").map_or("", |rest| rest.as_str());
+    let metadata = split_clap_metadata(&format!("{rest} {}", description.unwrap_or_default()));
+    aliases.extend(metadata.aliases.into_iter().filter(|alias| Some(alias) != long_key.as_ref() && Some(alias) != short_key.as_ref()));
+    if let Some(name) = name {
+        Some(CLIParameter::Argument(CLIArgument {
+            key,
+            short_key,
+            long_key,
+            aliases,
+            name,
+            description: metadata.description,
+            values: metadata.default_value.into_iter().collect(),
+            multiple: caps.name("multiple").is_some(),
+            choices: metadata.choices,
+            env_var: metadata.env_var,
+            joined: caps.name("joined").is_some(),
+            ..Default::default()
+        }))
+    } else {
+        Some(CLIParameter::Flag(CLIFlag {
+            key,
+            short_key,
+            long_key,
+            aliases,
+            description: metadata.description,
+            set: false,
+            multiple: caps.name("key_multiple").is_some(),
+            ..Default::default()
+        }))
+    }
+}
+
+/// Join wrapped descriptions to the line of their option, e.g.
+///   -n, --name <NAME>  Name of the person
+///                      to greet
+/// Indented lines that do not start with a key continue the previous option
+/// Key rows are normalized to their keys and description separated by two spaces
+/// Each row comes with the heading it is listed under, e.g. `Network Options` of a clap `help_heading`
+fn join_clap_option_lines(option_string: &str) -> Vec<(String, Option<String>)> {
+    let mut option_lines: Vec<(String, Option<String>)> = Vec::new();
+    let mut continues_option = false;
+    let mut group = None;
+    let column = find_description_column(option_string.lines().skip(1));
+    for line in option_string.lines().skip(1) { // Skip the "Options:" line
+        let trimmed_line = line.trim();
+        if trimmed_line.starts_with('-') {
+            let (keys, description) = split_option_row(line, column);
+            let option_line = match description {
+                Some(description) => format!("{keys}  {description}"),
+                None => keys,
+            };
+            option_lines.push((option_line, group.clone()));
+            continues_option = true;
+        } else if !line.starts_with(char::is_whitespace) && !trimmed_line.is_empty() {
+            continues_option = false;
+            group = trimmed_line.strip_suffix(':').filter(|heading| !heading.eq_ignore_ascii_case("Options")).map(str::to_string);
+        } else if let Some((option_line, _)) = option_lines.last_mut().filter(|_| continues_option && !trimmed_line.is_empty()) {
+            option_line.push(' ');
+            option_line.push_str(trimmed_line);
+        }
+    }
+    option_lines
+}
+
+/// Parse the option explanation of a clap help string
+#[allow(clippy::needless_return)]
+fn parse_clap_option_explanation(option_string: &str) -> Option<Vec<CLIParameter>> {
+    let parsed_options = join_clap_option_lines(option_string)
+        .into_iter()
+        .filter_map(|(option_line, group)| {
+            match parse_clap_option_line(&option_line)? {
+                CLIParameter::Argument(argument) => return Some(CLIParameter::Argument(CLIArgument { group, ..argument })),
+                CLIParameter::Flag(flag) => return Some(CLIParameter::Flag(CLIFlag { group, ..flag })),
+            }
+        })
+        .collect::<Vec<CLIParameter>>();
+
+    if parsed_options.is_empty() {
+        None
+    } else {
+        Some(parsed_options)
+    }
+}
+
+/// Parse the usage explanation of a clap help string
+/// Used to distinguish between arguments (required keys) and options
+fn parse_clap_usage_explanation(usage_string: &str) -> Option<(String, UsageGroups)> {
+    let (cli_name, variants) = parse_clap_usage_variants(usage_string)?;
+    Some((cli_name, merge_usage_groups(variants.iter().map(|variant| parse_usage_groups(variant)))))
+}
+
+/// Program name and the variants of a usage explanation that call the command itself, without the program name, e.g.
+/// `[OPTIONS] <FILE>` of `tool [OPTIONS] <FILE>`, while `tool add <ITEM>` and `tool <COMMAND>` call subcommands
+/// The variants of subcommands are only taken if there is no other one
+fn parse_clap_usage_variants(usage_string: &str) -> Option<(String, Vec<String>)> {
+    static CLI_NAME_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"Usage: (?P<cli_name>[\w\.]+)(?P<rest>.*)").unwrap());
+    let caps = CLI_NAME_PATTERN.captures(usage_string)?;
+    let cli_name: String = caps.name("cli_name").map(|m| m.as_str().to_string())?;
+    let variants: Vec<String> = std::iter::once(caps["rest"].to_string())
+        .chain(usage_string.lines().skip(1).filter_map(|line| Some(line.trim().strip_prefix(cli_name.as_str())?.to_string())))
+        .collect();
+    let calls_subcommand = |variant: &String| {
+        variant.split_whitespace().next().is_some_and(|word| word.starts_with(char::is_alphanumeric) || word == "<COMMAND>")
+    };
+    let own_variants: Vec<String> = variants.iter().filter(|variant| !calls_subcommand(variant)).cloned().collect();
+    if own_variants.is_empty() {
+        return Some((cli_name, variants));
+    }
+    Some((cli_name, own_variants))
+}
+
+/// Groups of alternative usage variants, keys and names are only required if every variant requires them
+fn merge_usage_groups(groups: impl Iterator<Item = UsageGroups>) -> UsageGroups {
+    let mut groups = groups.collect::<Vec<UsageGroups>>().into_iter();
+    let mut merged = groups.next().unwrap_or_default();
+    for group in groups {
+        merged.required_keys.retain(|key| group.required_keys.contains(key));
+        merged.required_names.retain(|name| group.required_names.contains(name));
+        for exclusive_group in group.exclusive_groups {
+            if !merged.exclusive_groups.contains(&exclusive_group) {
+                merged.exclusive_groups.push(exclusive_group);
+            }
+        }
+    }
+    merged
+}
+
+/// Parse the positional arguments of a clap help string from the usage line, e.g.
+/// Usage: tool [OPTIONS] --out <OUT> <FILE>... [MODE]
+/// and take their descriptions from the arguments section, e.g.
+/// Arguments:
+///   <FILE>...  Files to process
+/// Value names directly following a key belong to that key, `[COMMAND]` is the subcommand
+fn parse_clap_positionals(help_string: &str, usage_string: &str, usage_groups: &UsageGroups, has_subcommands: bool) -> Vec<CLIPositional> {
+    static USAGE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?P<key>--?\w[\w-]*\s+)?[<\[](?P<name>[A-Za-z_][\w-]*)[>\]](?P<multiple>\.\.\.)?").unwrap());
+    let mut positionals: Vec<CLIPositional> = USAGE_PATTERN
+        .captures_iter(usage_string)
+        .filter(|caps| caps.name("key").is_none())
+        .filter(|caps| &caps["name"] != "OPTIONS" && !(has_subcommands && &caps["name"] == "COMMAND"))
+        .map(|caps| CLIPositional {
+            required: usage_groups.required_names.iter().any(|name| *name == caps["name"]),
+            name: caps["name"].to_string(),
+            multiple: caps.name("multiple").is_some(),
+            ..Default::default()
+        })
+        .collect();
+
+    static ROW_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s+(?P<open>[<\[])(?P<name>[A-Za-z_][\w-]*)[>\]](?P<multiple>\.\.\.)?(?:\s{2,}(?P<description>\S.*))?$").unwrap());
+    // descriptions may be wrapped or, in the long help, start on the next line
+    let mut rows: Vec<(Captures, Vec<&str>)> = Vec::new();
+    for line in retrieve_clap_section(help_string, &CLAP_ARGUMENT_HEADINGS).unwrap_or_default().lines().skip(1) {
+        if let Some(caps) = ROW_PATTERN.captures(line) {
+            rows.push((caps, Vec::new()));
+        } else if let Some((_, lines)) = rows.last_mut().filter(|_| !line.trim().is_empty()) {
+            lines.push(line.trim());
+        }
+    }
+    for (caps, continuation_lines) in rows {
+        let description = caps.name("description")
+            .map(|description| description.as_str())
+            .into_iter()
+            .chain(continuation_lines)
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let description = Some(description).filter(|description| !description.is_empty());
+        match positionals.iter_mut().find(|positional| positional.name == caps["name"]) {
+            Some(positional) => positional.description = description,
+            None => positionals.push(CLIPositional {
+                name: caps["name"].to_string(),
+                description,
+                multiple: caps.name("multiple").is_some(),
+                required: &caps["open"] == "<",
+                ..Default::default()
+            }),
+        }
+    }
+    positionals
+}
+
+/// Parses a python argparse help string
+fn parse_argparse_help_string(help_string: &str) -> Result<CLIParameters, usize> {
+    let usage_explanation = retrieve_argparse_usage_explanation(help_string).ok_or_else(|| usage_line_index(help_string))?;
+    let (cli_name, usage_groups, repeated_names) = parse_argparse_usage_explanation(&usage_explanation).ok_or_else(|| usage_line_index(help_string))?;
+    let mut result = CLIParameters {
+        cli_name,
+        cli_lib: CLILib::Argparse,
+        ..Default::default()
+    };
+    // the usage line may use any variant of a key, e.g. `-n NAME` for `-n NAME, --name NAME`
+    let mut key_variants: Vec<(String, String)> = Vec::new();
+
+    for (heading, rows) in retrieve_argparse_sections(help_string) {
+        for (invocation, description) in rows {
+            if let Some(choices) = invocation.strip_prefix('{').and_then(|choices| choices.strip_suffix('}')) {
+                result.subcommands = parse_argparse_commands(help_string, &invocation, choices);
+                continue;
+            }
+            if heading == "positional arguments" {
+                result.positionals.push(CLIPositional {
+                    multiple: repeated_names.contains(&invocation),
+                    required: usage_groups.required_names.contains(&invocation),
+                    name: invocation,
+                    description,
+                    ..Default::default()
+                });
+                continue;
+            }
+            let variants: Vec<String> = invocation
+                .split(", ")
+                .filter_map(|variant| variant.split_whitespace().next())
+                .map(str::to_string)
+                .collect();
+            let is_required = variants.iter().any(|key| usage_groups.required_keys.contains(key));
+            let key = match parse_argparse_option(&invocation, description) {
+                Some(CLIParameter::Argument(mut argument)) => {
+                    let key = argument.key.clone();
+                    if is_required {
+                        argument.required = true;
+                        result.arguments.push(argument);
+                    } else {
+                        result.options.push(argument);
+                    }
+                    key
+                },
+                Some(CLIParameter::Flag(flag)) => {
+                    let key = flag.key.clone();
+                    result.flags.push(flag);
+                    key
+                },
+                None => continue,
+            };
+            key_variants.extend(variants.into_iter().map(|variant| (variant, key.clone())));
+        }
+    }
+
+    result.exclusive_groups = usage_groups.exclusive_groups
+        .into_iter()
+        .map(|group| {
+            group
+                .into_iter()
+                .map(|variant| {
+                    key_variants
+                        .iter()
+                        .find(|(known_variant, _)| *known_variant == variant)
+                        .map_or(variant, |(_, key)| key.clone())
+                })
+                .collect()
+        })
+        .collect();
+
+    if result.arguments.is_empty() && result.positionals.is_empty() && result.options.is_empty() && result.flags.is_empty() {
+        Err(find_line_index(help_string, |line| is_section_heading(line, &["options:"])).unwrap_or_else(|| usage_line_index(help_string)))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Parse the subparsers of an argparse help string, listed below their choices if they have a help, e.g.
+///   {build,test}
+///     build       Build the project
+/// Choices without a help have no description
+fn parse_argparse_commands(help_string: &str, invocation: &str, choices: &str) -> Vec<CLISubcommand> {
+    let mut lines = help_string.lines().skip_while(|line| line.trim() != invocation);
+    let choices_indent = lines.next().map_or(0, |line| line.len() - line.trim_start().len());
+    let rows = lines.take_while(|line| line.len() - line.trim_start().len() > choices_indent && !line.trim().is_empty());
+    let mut subcommands = parse_command_rows(rows);
+    for choice in choices.split(',') {
+        if choice != "help" && !subcommands.iter().any(|subcommand| subcommand.name == choice) {
+            subcommands.push(CLISubcommand { name: choice.to_string(), ..Default::default() });
+        }
+    }
+    subcommands
+}
+
+/// Retrieve the usage explanation of an argparse help string including its wrapped lines, e.g.
+/// usage: greeter.py [-h] [--count COUNT]
+///                   --name NAME file
+fn retrieve_argparse_usage_explanation(help_string: &str) -> Option<String> {
+    let mut lines = help_string.lines().skip_while(|line| !line.starts_with("usage:"));
+    let mut usage = lines.next()?.to_string();
+    for line in lines.take_while(|line| line.starts_with(' ') && !line.trim().is_empty()) {
+        usage.push(' ');
+        usage.push_str(line.trim());
+    }
+    Some(usage)
+}
+
+/// Parse the usage explanation of an argparse help string
+/// Returns the cli name, the groupings of the keys
+/// and the names followed by an ellipsis (repeated positionals)
+fn parse_argparse_usage_explanation(usage_string: &str) -> Option<(String, UsageGroups, Vec<String>)> {
+    let mut tokens = usage_string.trim_start_matches("usage:").split_whitespace();
+    let cli_name = tokens.next()?.to_string();
+    let usage_groups = parse_usage_groups(&tokens.collect::<Vec<&str>>().join(" "));
+    static REPEATED_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?P<name>[\w-]+) \.\.\.").unwrap());
+    let repeated_names = REPEATED_PATTERN.captures_iter(usage_string)
+        .map(|caps| caps["name"].to_string())
+        .collect();
+    Some((cli_name, usage_groups, repeated_names))
+}
+
+/// Invocation and description of a single row in a help section
+type HelpRow = (String, Option<String>);
+
+/// Heading and rows of a help section
+type HelpSection = (String, Vec<HelpRow>);
+
+/// Retrieve the rows of every section of an argparse help string, e.g.
+/// options:
+///   -n NAME, --name NAME  Name to greet
+///   --very-long-option VERY_LONG_OPTION
+///                         Wrapped description
+/// Each row is split into its invocation and its (unwrapped) description
+fn retrieve_argparse_sections(help_string: &str) -> Vec<HelpSection> {
+    static ROW_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<invocation>\S.*?)(?:\s{2,}(?P<description>\S.*))?$").unwrap());
+    let mut sections: Vec<HelpSection> = Vec::new();
+    let mut in_section = false;
+    let mut row_indent = None;
+    for line in help_string.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            in_section = line.ends_with(':') && !line.starts_with("usage:");
+            if in_section {
+                sections.push((line.trim_end_matches(':').to_string(), Vec::new()));
+                row_indent = None;
+            }
+            continue;
+        }
+        let Some((_, rows)) = sections.last_mut().filter(|_| in_section) else {
+            continue;
+        };
+        let indent = line.len() - line.trim_start().len();
+        let row_indent = *row_indent.get_or_insert(indent);
+        if indent > row_indent {
+            // continuation of the previous description
+            if let Some((_, description)) = rows.last_mut() {
+                let continuation = line.trim();
+                match description {
+                    Some(description) => {
+                        description.push(' ');
+                        description.push_str(continuation);
+                    },
+                    None => *description = Some(continuation.to_string()),
+                }
+            }
+            continue;
+        }
+        if let Some(caps) = ROW_PATTERN.captures(line.trim()) {
+            let invocation = caps["invocation"].to_string();
+            let description = caps.name("description").map(|description| description.as_str().to_string());
+            rows.push((invocation, description));
+        }
+    }
+    sections
+}
+
+/// Parse an argparse option row, e.g.
+/// -n NAME, --name NAME    Name to greet (default: World)
+fn parse_argparse_option(invocation: &str, description: Option<String>) -> Option<CLIParameter> {
+    let variants: Vec<(&str, String)> = invocation
+        .split(", ")
+        .filter_map(|variant| {
+            let mut parts = variant.split_whitespace();
+            let key = parts.next().filter(|key| key.starts_with('-'))?;
+            Some((key, parts.collect::<Vec<&str>>().join(" ")))
+        })
+        .collect();
+    let (key, metavar) = variants
+        .iter()
+        .find(|(key, _)| key.starts_with("--"))
+        .or_else(|| variants.first())?;
+    let (short_key, long_key, aliases) = split_key_variants(variants.iter().map(|(key, _)| *key));
+    static DEFAULT_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s*\(default: (?P<value>[^)]*)\)").unwrap());
+    let value = description
+        .as_deref()
+        .and_then(|description| DEFAULT_PATTERN.captures(description))
+        .map(|caps| caps["value"].to_string())
+        .filter(|value| !value.is_empty() && value != "None");
+    let description = description.map(|description| DEFAULT_PATTERN.replace(&description, "").to_string());
+
+    if metavar.is_empty() {
+        return Some(CLIParameter::Flag(CLIFlag {
+            key: key.to_string(),
+            short_key,
+            long_key,
+            aliases,
+            description,
+            set: false,
+            ..Default::default()
+        }));
+    }
+    Some(CLIParameter::Argument(CLIArgument {
+        key: key.to_string(),
+        short_key,
+        long_key,
+        aliases,
+        name: metavar.split_whitespace().next()?.to_string(),
+        description,
+        values: value.into_iter().collect(),
+        multiple: metavar.contains("..."),
+        ..Default::default()
+    }))
+}
+
+/// Parses a Go cobra help string
+/// Cobra does not mark required flags, so every value flag is an option
+fn parse_cobra_help_string(help_string: &str) -> Result<CLIParameters, usize> {
+    let cli_name = help_string
+        .lines()
+        .skip_while(|line| line.trim_end() != "Usage:")
+        .nth(1)
+        .and_then(|line| line.split_whitespace().next())
+        .ok_or_else(|| usage_line_index(help_string))?
+        .to_string();
+    let mut result = CLIParameters {
+        cli_name,
+        cli_lib: CLILib::Cobra,
+        ..Default::default()
+    };
+
+    let mut in_flag_section = false;
+    for line in help_string.lines() {
+        if !line.starts_with(' ') {
+            in_flag_section = line.trim_end().ends_with("Flags:");
+            continue;
+        }
+        if !in_flag_section {
+            continue;
+        }
+        match parse_cobra_flag_line(line) {
+            Some(CLIParameter::Argument(argument)) => result.options.push(argument),
+            Some(CLIParameter::Flag(flag)) => result.flags.push(flag),
+            None => (),
+        }
+    }
+    result.subcommands = parse_command_section(help_string);
+
+    if result.options.is_empty() && result.flags.is_empty() {
+        Err(find_line_index(help_string, |line| line.trim_end().ends_with("Flags:")).unwrap_or_else(|| usage_line_index(help_string)))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Parse a single row of a cobra flag section, e.g.
+///   -p, --port int         port to listen on (default 1313)
+///       --build-drafts     include content marked as draft
+fn parse_cobra_flag_line(flag_line: &str) -> Option<CLIParameter> {
+    static FLAG_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^\s+(?:(?P<short_key>-\w), )?(?P<key>--[\w-]+)(?: (?P<type>[\w.\[\]]+))?(?:\s{2,}(?P<description>.*?))?\s*$"#).unwrap());
+    static DEFAULT_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\s*\(default (?P<value>.*)\)$"#).unwrap());
+    let caps = FLAG_PATTERN.captures(flag_line)?;
+    let key = caps["key"].to_string();
+    let short_key = caps.name("short_key").map(|short_key| short_key.as_str().to_string());
+    let description = caps.name("description").map(|description| description.as_str().to_string());
+    let value = description
+        .as_deref()
+        .and_then(|description| DEFAULT_PATTERN.captures(description))
+        .map(|caps| caps["value"].trim_matches('"').to_string())
+        .filter(|value| !value.is_empty());
+    let description = description
+        .map(|description| DEFAULT_PATTERN.replace(&description, "").to_string())
+        .filter(|description| !description.is_empty());
+
+    let Some(value_type) = caps.name("type") else {
+        return Some(CLIParameter::Flag(CLIFlag {
+            long_key: Some(key.clone()),
+            key,
+            short_key,
+            description,
+            set: false,
+            ..Default::default()
+        }));
+    };
+    let value_type = value_type.as_str();
+    // cobra prints the type of the value instead of a name, e.g. `int`, the name comes from the key
+    Some(CLIParameter::Argument(CLIArgument {
+        long_key: Some(key.clone()),
+        name: key.trim_start_matches('-').to_uppercase().replace('-', "_"),
+        key,
+        short_key,
+        description,
+        values: value.into_iter().collect(),
+        multiple: value_type.ends_with("Array") || value_type.ends_with("Slice") || value_type == "strings",
+        ..Default::default()
+    }))
+}
+
+/// Single element of a docopt usage pattern
+#[derive(Debug, PartialEq, Eq)]
+enum DocoptElement {
+    Positional { name: String, description: Option<String>, multiple: bool },
+    Flag { key: String, multiple: bool },
+    ValueOption { key: String, name: String, required: bool, multiple: bool },
+}
+
+impl DocoptElement {
+    fn set_multiple(&mut self) {
+        match self {
+            DocoptElement::Positional { multiple, .. } => *multiple = true,
+            DocoptElement::Flag { multiple, .. } => *multiple = true,
+            DocoptElement::ValueOption { multiple, .. } => *multiple = true,
+        }
+    }
+}
+
+/// Parses a docopt help string purely from its usage patterns, e.g.
+/// Usage:
+///   naval_fate ship <name> move <x> <y> [--speed=<kn>]
+///   naval_fate mine (set|remove) <x> <y> [--moored | --drifting]
+/// Commands become positionals, a value option is only required
+/// if it is outside of brackets in every pattern
+fn parse_docopt_help_string(help_string: &str) -> Result<CLIParameters, usize> {
+    let patterns = retrieve_docopt_usage_patterns(help_string);
+    // `[OPTION]...` is the GNU convention, docopt uses `[options]`
+    if patterns.iter().any(|pattern| pattern.contains("[OPTION")) {
+        return Err(find_line_index(help_string, |line| line.contains("[OPTION")).unwrap_or_else(|| usage_line_index(help_string)));
+    }
+    let cli_name = patterns.first().and_then(|pattern| pattern.split_whitespace().next()).ok_or_else(|| usage_line_index(help_string))?.to_string();
+    let mut positionals: Vec<CLIPositional> = Vec::new();
+    let mut value_options: Vec<(CLIArgument, usize)> = Vec::new();
+    let mut flags: Vec<CLIFlag> = Vec::new();
+    let mut exclusive_groups: Vec<Vec<String>> = Vec::new();
+
+    for pattern in &patterns {
+        let rest = pattern.split_once(char::is_whitespace).map_or("", |(_, rest)| rest);
+        for group in parse_usage_groups(rest).exclusive_groups {
+            if !exclusive_groups.contains(&group) {
+                exclusive_groups.push(group);
+            }
+        }
+        for element in parse_docopt_pattern(pattern) {
+            match element {
+                DocoptElement::Positional { name, description, multiple } => {
+                    match positionals.iter_mut().find(|positional| positional.name == name) {
+                        Some(positional) => positional.multiple |= multiple,
+                        None => positionals.push(CLIPositional { name, description, multiple, ..Default::default() }),
+                    }
+                },
+                DocoptElement::Flag { key, multiple } => {
+                    match flags.iter_mut().find(|flag| flag.key == key) {
+                        Some(flag) => flag.multiple |= multiple,
+                        None => {
+                            let (short_key, long_key, _) = split_key_variants([key.as_str()]);
+                            flags.push(CLIFlag { key, short_key, long_key, multiple, ..Default::default() });
+                        },
+                    }
+                },
+                DocoptElement::ValueOption { key, name, required, multiple } => {
+                    match value_options.iter_mut().find(|(option, _)| option.key == key) {
+                        Some((option, required_count)) => {
+                            option.multiple |= multiple;
+                            *required_count += required as usize;
+                        },
+                        None => {
+                            let (short_key, long_key, _) = split_key_variants([key.as_str()]);
+                            value_options.push((CLIArgument { key, short_key, long_key, name, multiple, ..Default::default() }, required as usize));
+                        },
+                    }
+                },
+            }
+        }
+    }
+
+    let mut result = CLIParameters {
+        cli_name,
+        positionals,
+        flags,
+        exclusive_groups,
+        cli_lib: CLILib::Docopt,
+        ..Default::default()
+    };
+    for (mut option, required_count) in value_options {
+        if required_count == patterns.len() {
+            option.required = true;
+            result.arguments.push(option);
+        } else {
+            result.options.push(option);
+        }
+    }
+
+    if result.arguments.is_empty() && result.positionals.is_empty() && result.options.is_empty() && result.flags.is_empty() {
+        Err(usage_line_index(help_string))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Retrieve the usage patterns of a docopt help string, one per line
+fn retrieve_docopt_usage_patterns(help_string: &str) -> Vec<String> {
+    let mut lines = help_string
+        .lines()
+        .skip_while(|line| !line.trim_start().to_lowercase().starts_with("usage:"));
+    let Some(usage_line) = lines.next() else {
+        return Vec::new();
+    };
+    let first_pattern = usage_line.trim_start()["usage:".len()..].trim();
+    let mut patterns: Vec<String> = Vec::new();
+    if !first_pattern.is_empty() {
+        patterns.push(first_pattern.to_string());
+    }
+    let mut indented = lines
+        .take_while(|line| line.starts_with([' ', '\t']) && !line.trim().is_empty())
+        .map(|line| line.trim().to_string());
+    if patterns.is_empty() {
+        patterns.extend(indented.next());
+    }
+    // every pattern starts with the program, option rows below a one line usage are no patterns
+    let Some(program) = patterns.first().and_then(|pattern| pattern.split_whitespace().next()).map(str::to_string) else {
+        return patterns;
+    };
+    patterns.extend(indented.take_while(|line| line.split_whitespace().next() == Some(program.as_str())));
+    patterns
+}
+
+/// Parse a single docopt usage pattern into its elements
+fn parse_docopt_pattern(pattern: &str) -> Vec<DocoptElement> {
+    static COMMAND_ALTERNATIVES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\(\s*([A-Za-z][\w-]*(?:\s*\|\s*[A-Za-z][\w-]*)+)\s*\)").unwrap());
+    let pattern = COMMAND_ALTERNATIVES.replace_all(pattern, |caps: &regex::Captures| caps[1].replace(' ', ""));
+    static TOKEN_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\.\.\.|[\[\]()|]|--?[\w-]+(?:=(?:<[^>]+>|[\w-]+))?|<[^>]+>|[\w-]+(?:\|[\w-]+)*").unwrap());
+    let mut elements: Vec<DocoptElement> = Vec::new();
+    let mut brackets: Vec<&str> = Vec::new();
+    // skip the program name
+    for token in TOKEN_PATTERN.find_iter(&pattern).skip(1).map(|mat| mat.as_str()) {
+        match token {
+            "[" | "(" => brackets.push(token),
+            "]" | ")" => {
+                brackets.pop();
+            },
+            "|" | "options" => (),
+            "..." => {
+                if let Some(element) = elements.last_mut() {
+                    element.set_multiple();
+                }
+            },
+            _ if token.starts_with('-') => {
+                let element = match token.split_once('=') {
+                    Some((key, name)) => DocoptElement::ValueOption {
+                        key: key.to_string(),
+                        name: name.trim_start_matches('<').trim_end_matches('>').to_string(),
+                        required: !brackets.contains(&"["),
+                        multiple: false,
+                    },
+                    None => DocoptElement::Flag { key: token.to_string(), multiple: false },
+                };
+                elements.push(element);
+            },
+            _ if token.starts_with('<') => elements.push(DocoptElement::Positional {
+                name: token.trim_start_matches('<').trim_end_matches('>').to_string(),
+                description: None,
+                multiple: false,
+            }),
+            _ if token.chars().all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit() || ch == '_' || ch == '-') => {
+                elements.push(DocoptElement::Positional { name: token.to_string(), description: None, multiple: false });
+            },
+            _ => {
+                let description = if token.contains('|') {
+                    format!("One of: {}", token.split('|').collect::<Vec<&str>>().join(", "))
+                } else {
+                    String::from("Command")
+                };
+                elements.push(DocoptElement::Positional { name: token.to_string(), description: Some(description), multiple: false });
+            },
+        }
+    }
+    elements
+}
+
+/// Best effort parser for GNU getopt_long style help strings, used when no other parser matches
+/// Every line starting with `-x, --xxx` anywhere in the help string becomes a flag or option, e.g.
+/// Usage: ls [OPTION]... [FILE]...
+///   -a, --all                  do not ignore entries starting with .
+///       --block-size=SIZE      scale sizes by SIZE when printing them
+fn parse_gnu_help_string(help_string: &str) -> Result<CLIParameters, usize> {
+    static USAGE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^\s*usage:\s+(?P<cli_name>\S+)(?P<rest>.*)$").unwrap());
+    let usage_caps = help_string.lines().find_map(|line| USAGE_PATTERN.captures(line)).ok_or_else(|| usage_line_index(help_string))?;
+    let mut result = CLIParameters {
+        cli_name: usage_caps["cli_name"].to_string(),
+        cli_lib: CLILib::Gnu,
+        ..Default::default()
+    };
+
+    let column = find_description_column(help_string.lines());
+    let mut last_indent = None;
+    let mut last_description: Option<&mut Option<String>> = None;
+    for line in help_string.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            last_description = None;
+            continue;
+        }
+        if !trimmed.starts_with('-') {
+            // continuation of the previous description
+            match last_description.as_deref_mut().filter(|_| last_indent.is_some_and(|last_indent| indent > last_indent)) {
+                Some(Some(description)) => {
+                    description.push(' ');
+                    description.push_str(trimmed);
+                },
+                Some(description) => *description = Some(trimmed.to_string()),
+                None => last_description = None,
+            }
+            continue;
+        }
+        let (invocation, description) = split_option_row(line, column);
+        if invocation.len() < 2 {
+            continue;
+        }
+        last_indent = Some(indent);
+        last_description = match parse_gnu_invocation(&invocation, description) {
+            Some(CLIParameter::Argument(argument)) if !result.options.iter().any(|option| option.key == argument.key) => {
+                result.options.push(argument);
+                result.options.last_mut().map(|option| &mut option.description)
+            },
+            Some(CLIParameter::Flag(flag)) if !result.flags.iter().any(|existing| existing.key == flag.key) => {
+                result.flags.push(flag);
+                result.flags.last_mut().map(|flag| &mut flag.description)
+            },
+            _ => None,
+        };
+    }
+
+    result.subcommands = parse_command_section(help_string);
+    // positional arguments of the usage line, e.g. [FILE]..., the COMMAND is chosen among the subcommands
+    static POSITIONAL_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?P<optional>\[)?(?P<name>[A-Z][A-Z_]*)\]?(?P<multiple>\.\.\.)?").unwrap());
+    for caps in POSITIONAL_PATTERN.captures_iter(&usage_caps["rest"]) {
+        let name = caps["name"].to_string();
+        let is_command = !result.subcommands.is_empty() && name.starts_with("COMMAND");
+        if name.starts_with("OPTION") || is_command || result.positionals.iter().any(|positional| positional.name == name) {
+            continue;
+        }
+        result.positionals.push(CLIPositional {
+            name,
+            multiple: caps.name("multiple").is_some(),
+            required: caps.name("optional").is_none(),
+            ..Default::default()
+        });
+    }
+
+    if result.options.is_empty() && result.flags.is_empty() {
+        Err(usage_line_index(help_string))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Parse the invocation part of a GNU option row, e.g.
+/// -w, --width=COLS
+/// --color[=WHEN]
+/// -o FILE
+fn parse_gnu_invocation(invocation: &str, description: Option<String>) -> Option<CLIParameter> {
+    static VARIANT_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<key>--?[\w][\w-]*)(?:\[?[= ](?P<name>[^\s\]]+)\]?)?$").unwrap());
+    let variants: Vec<(String, Option<String>)> = invocation
+        .split(',')
+        .filter_map(|variant| VARIANT_PATTERN.captures(variant.trim()))
+        .map(|caps| (caps["key"].to_string(), caps.name("name").map(|name| name.as_str().to_string())))
+        .collect();
+    let (key, _) = variants
+        .iter()
+        .find(|(key, _)| key.starts_with("--"))
+        .or_else(|| variants.first())?;
+    let name = variants.iter().find_map(|(_, name)| name.clone());
+    let (short_key, long_key, aliases) = split_key_variants(variants.iter().map(|(key, _)| key.as_str()));
+    match name {
+        Some(name) => Some(CLIParameter::Argument(CLIArgument {
+            key: key.clone(),
+            short_key,
+            long_key,
+            aliases,
+            name,
+            description,
+            ..Default::default()
+        })),
+        None => Some(CLIParameter::Flag(CLIFlag {
+            key: key.clone(),
+            short_key,
+            long_key,
+            aliases,
+            description,
+            ..Default::default()
+        })),
+    }
+}
+
+/// Option row of a classic Windows help, the separator is two spaces or `::`, e.g.
+///   /D:m-d-y     Copies files changed on or after the specified date.
+///            /LOG:file :: output status to LOG file (overwrite existing log).
+static WINDOWS_OPTION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<indent>\s*)(?P<key>/[\w?][\w-]*)(?:(?P<optional>\[)?:(?P<name>[^\s\]]+)\]?)?(?:\s*::\s*|\s{2,})(?P<description>\S.*)?$").unwrap()
+});
+
+/// Whether the help lists at least two `/X` options and no `-x` ones
+fn has_windows_option_rows(help_string: &str) -> bool {
+    help_string.lines().filter(|line| WINDOWS_OPTION_PATTERN.is_match(line)).count() >= 2
+        && !help_string.lines().any(|line| line.trim_start().strip_prefix("--").is_some_and(|rest| rest.starts_with(char::is_alphanumeric)))
+}
+
+/// Row of a classic Windows help that deeper indented lines continue, by its index
+#[derive(Debug, Clone, Copy)]
+enum WindowsRow {
+    Option(usize),
+    Flag(usize),
+    Positional(usize),
+}
+
+/// Parses the help of a classic Windows tool, e.g.
+/// XCOPY source [destination] [/A | /M] [/D[:date]]
+///   source       Specifies the file(s) to copy.
+///   /A           Copies only files with the archive attribute set,
+///                doesn't change the attribute.
+/// Options with a value are passed as `/D:value`, positionals are the words of the usage line
+fn parse_windows_help_string(help_string: &str) -> Result<CLIParameters, usize> {
+    if !has_windows_option_rows(help_string) {
+        return Err(first_line_index(help_string));
+    }
+    // `Usage :: ROBOCOPY source destination [options]`, or a line starting with the name followed by options like `XCOPY source [/A]`
+    static USAGE_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?i:usage)\s*:+\s*(?P<cli_name>[\w.-]+)(?P<rest>.*)$").unwrap());
+    static SYNOPSIS_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*(?P<cli_name>[\w.-]+)(?P<rest>(?:\s+\S+)*?\s+\[?/.*)$").unwrap());
+    let usage_caps = help_string
+        .lines()
+        .find_map(|line| USAGE_PATTERN.captures(line))
+        .or_else(|| help_string.lines().find_map(|line| SYNOPSIS_PATTERN.captures(line)))
+        .ok_or_else(|| first_line_index(help_string))?;
+    let mut result = CLIParameters {
+        cli_name: usage_caps["cli_name"].to_string(),
+        cli_lib: CLILib::WindowsSlash,
+        ..Default::default()
+    };
+
+    // positionals are the words of the usage line up to the first option, e.g. `[file [file]...]`
+    for word in usage_caps["rest"].split_whitespace().take_while(|word| !word.trim_start_matches('[').starts_with('/')) {
+        let name = word.trim_matches(['[', ']', '.']);
+        if name.is_empty() || name.eq_ignore_ascii_case("options") || !name.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+            continue;
+        }
+        match result.positionals.iter_mut().find(|positional| positional.name == name) {
+            Some(positional) => positional.multiple |= word.ends_with("...") || word.ends_with("...]"),
+            None => result.positionals.push(CLIPositional { name: name.to_string(), required: !word.starts_with('['), ..Default::default() }),
+        }
+    }
+
+    static POSITIONAL_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?P<indent>\s*)(?P<name>\w+)(?:\s*::\s*|\s{2,})(?P<description>\S.*)$").unwrap());
+    // indentation and position of the row the following deeper indented lines continue
+    let mut last: Option<(usize, WindowsRow)> = None;
+    for line in help_string.lines() {
+        let indent = line.len() - line.trim_start().len();
+        if let Some(caps) = WINDOWS_OPTION_PATTERN.captures(line) {
+            let key = caps["key"].to_string();
+            let description = caps.name("description").map(|description| description.as_str().to_string());
+            last = match caps.name("name") {
+                Some(name) if !result.options.iter().any(|option| option.key == key) => {
+                    result.options.push(CLIArgument { key, name: name.as_str().to_uppercase(), description, joined: true, ..Default::default() });
+                    Some((indent, WindowsRow::Option(result.options.len() - 1)))
+                },
+                None if !result.flags.iter().any(|flag| flag.key == key) => {
+                    result.flags.push(CLIFlag { key, description, ..Default::default() });
+                    Some((indent, WindowsRow::Flag(result.flags.len() - 1)))
+                },
+                _ => None,
+            };
+            continue;
+        }
+        if let Some(caps) = POSITIONAL_PATTERN.captures(line) {
+            if let Some(index) = result.positionals.iter().position(|positional| positional.name == caps["name"]) {
+                result.positionals[index].description = Some(caps["description"].to_string());
+                last = Some((indent, WindowsRow::Positional(index)));
+                continue;
+            }
+        }
+        // continuation of the previous description, robocopy repeats the `::` separator
+        let continuation = line.trim().trim_start_matches("::").trim();
+        let Some((_, row)) = last.filter(|(last_indent, _)| indent > *last_indent && !continuation.is_empty()) else {
+            last = None;
+            continue;
+        };
+        let description = match row {
+            WindowsRow::Option(index) => &mut result.options[index].description,
+            WindowsRow::Flag(index) => &mut result.flags[index].description,
+            WindowsRow::Positional(index) => &mut result.positionals[index].description,
+        };
+        match description {
+            Some(description) => {
+                description.push(' ');
+                description.push_str(continuation);
+            },
+            None => *description = Some(continuation.to_string()),
+        }
+    }
+
+    if result.options.is_empty() && result.flags.is_empty() {
+        Err(first_line_index(help_string))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Requiredness and mutual exclusivity of the keys and positionals in a usage line
+#[derive(Debug, Default, PartialEq, Eq)]
+struct UsageGroups {
+    /// Keys outside of any optional or alternative group
+    required_keys: Vec<String>,
+    /// Positional names outside of any optional or alternative group
+    required_names: Vec<String>,
+    /// Keys of which at most one can be passed
+    exclusive_groups: Vec<Vec<String>>,
+}
+
+/// Open bracket of a usage line: whether it is optional and the keys and names of each
+/// of its alternatives, every entry paired with whether it is still required
+type UsageFrame = (bool, Vec<Vec<(String, bool)>>);
+
+/// Parse the brackets of a usage line following the program name, e.g.
+/// [OPTIONS] --name <NAME> [--count <COUNT>] [-a | -b] <--json|--yaml> <FILE>
+/// Keys inside of `[...]` are optional, keys separated by `|` exclude each other
+/// and are not required on their own even inside of `(...)` or `<...>`
+/// Value names directly following a key are not positionals
+fn parse_usage_groups(usage_string: &str) -> UsageGroups {
+    static TOKEN_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^<>|\s-][^<>|]*>|[\[\]()<>|]|--?\w[\w-]*|[\w.-]+").unwrap());
+    let mut frames: Vec<UsageFrame> = Vec::new();
+    let mut entries: Vec<(String, bool)> = Vec::new();
+    let mut groups = UsageGroups::default();
+    let mut follows_key = false;
+    for token in TOKEN_PATTERN.find_iter(usage_string).map(|mat| mat.as_str()) {
+        let is_key = token.starts_with('-');
+        match token {
+            "[" | "(" | "<" => frames.push((token == "[", vec![Vec::new()])),
+            "]" | ")" | ">" => close_usage_frame(&mut frames, &mut entries, &mut groups),
+            "|" => {
+                if let Some((_, alternatives)) = frames.last_mut() {
+                    alternatives.push(Vec::new());
+                }
+            },
+            _ if !is_key && (follows_key || token.chars().all(|ch| ch == '.')) => (),
+            _ => {
+                let entry = token.trim_start_matches('<').trim_end_matches('>').to_string();
+                match frames.last_mut().and_then(|(_, alternatives)| alternatives.last_mut()) {
+                    Some(alternative) => alternative.push((entry, true)),
+                    None => entries.push((entry, true)),
+                }
+            },
+        }
+        follows_key = is_key;
+    }
+    // tolerate unbalanced brackets
+    while !frames.is_empty() {
+        close_usage_frame(&mut frames, &mut entries, &mut groups);
+    }
+    for (entry, required) in entries {
+        if !required {
+            continue;
+        }
+        if entry.starts_with('-') {
+            groups.required_keys.push(entry);
+        } else {
+            groups.required_names.push(entry);
+        }
+    }
+    groups
+}
+
+/// Close the innermost bracket of a usage line and hand its entries to the enclosing one
+fn close_usage_frame(frames: &mut Vec<UsageFrame>, entries: &mut Vec<(String, bool)>, groups: &mut UsageGroups) {
+    let Some((optional, alternatives)) = frames.pop() else {
+        return;
+    };
+    let exclusive = alternatives.len() > 1;
+    if exclusive {
+        let group: Vec<String> = alternatives
+            .iter()
+            .filter_map(|alternative| alternative.first())
+            .map(|(key, _)| key.clone())
+            .filter(|key| key.starts_with('-'))
+            .collect();
+        if group.len() > 1 {
+            groups.exclusive_groups.push(group);
+        }
+    }
+    let closed_entries = alternatives
+        .into_iter()
+        .flatten()
+        .map(|(entry, required)| (entry, required && !optional && !exclusive));
+    match frames.last_mut().and_then(|(_, alternatives)| alternatives.last_mut()) {
+        Some(alternative) => alternative.extend(closed_entries),
+        None => entries.extend(closed_entries),
+    }
+}
+
+/// Parameters marked as repeatable by an ellipsis in a usage line
+#[derive(Debug, Default, PartialEq, Eq)]
+struct UsageMultiplicity {
+    keys: Vec<String>,
+    names: Vec<String>,
+    all_options: bool,
+}
+
+impl UsageMultiplicity {
+    fn matches_argument(&self, argument: &CLIArgument, is_option: bool) -> bool {
+        (is_option && self.all_options)
+            || self.keys.iter().any(|key| argument.has_key(key))
+            || self.names.contains(&argument.name)
+    }
+}
+
+/// Parse the ellipsis markers of a clap usage line, e.g.
+/// Usage: tool [OPTIONS]... [-v...] --file <FILE>...
+/// `[OPTIONS]...` marks every option as repeatable
+fn parse_clap_usage_multiplicity(usage_string: &str) -> UsageMultiplicity {
+    static ELLIPSIS_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?P<key>--?\w+(?:-\w+)*\s+)?(?P<token>\[[^\[\]]*\]|<[^<>]*>|--?\w+(?:-\w+)*)\.\.\.").unwrap());
+    static KEY_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"--?\w+(?:-\w+)*").unwrap());
+    static NAME_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<(?P<name>[^<>]+)>").unwrap());
+    let mut multiplicity = UsageMultiplicity::default();
+    for caps in ELLIPSIS_PATTERN.captures_iter(usage_string) {
+        let token = &caps["token"];
+        if token == "[OPTIONS]" {
+            multiplicity.all_options = true;
+            continue;
+        }
+        if let Some(key) = caps.name("key") {
+            multiplicity.keys.push(key.as_str().trim().to_string());
+        }
+        let token_without_names = NAME_PATTERN.replace_all(token, "");
+        multiplicity.keys.extend(KEY_PATTERN.find_iter(&token_without_names).map(|mat| mat.as_str().to_string()));
+        multiplicity.names.extend(NAME_PATTERN.captures_iter(token).map(|caps| caps["name"].to_string()));
+    }
+    multiplicity
+}
+
+/// Convert the parameters to an actual cli command
+#[allow(clippy::needless_return)]
+pub fn convert_to_cli(parameters: &CLIParameters) -> Command {
+    match  parameters.cli_lib {
+        CLILib::Clap => return convert_to_clap_cli(parameters),
+        CLILib::Argparse => return convert_to_clap_cli(parameters),
+        CLILib::Cobra => return convert_to_clap_cli(parameters),
+        CLILib::Docopt => return convert_to_clap_cli(parameters),
+        CLILib::Gnu => return convert_to_gnu_cli(parameters),
+        CLILib::WindowsSlash => return convert_to_windows_cli(parameters),
+    }
+}
+
+/// Convert the parameters to a command with the dry-run flag set, the flag itself is left as it is
+/// None if there is no dry-run flag
+pub fn convert_to_dry_run_cli(parameters: &mut CLIParameters) -> Option<Command> {
+    let flag = parameters.flags.iter_mut().find(|flag| flag.is_dry_run())?;
+    let was_set = mem::replace(&mut flag.set, true);
+    let cli_command = convert_to_cli(parameters);
+    if let Some(flag) = parameters.flags.iter_mut().find(|flag| flag.is_dry_run()) {
+        flag.set = was_set;
+    }
+    Some(cli_command)
+}
+
+/// Create the command for the cli including the subcommand path, the invoked program if there is one
+fn new_command(parameters: &CLIParameters) -> Command {
+    let (program, program_args) = match parameters.program.split_first() {
+        Some((program, program_args)) => (program.as_str(), program_args),
+        None => (parameters.cli_name.as_str(), &[][..]),
+    };
+    let mut cli_command = Command::new(program);
+    cli_command.args(program_args);
+    for (depth, subcommand) in parameters.command_path.iter().enumerate() {
+        add_inherited(&mut cli_command, parameters, depth);
+        cli_command.arg(subcommand);
+    }
+    cli_command
+}
+
+/// Append the globals inherited from the command at the depth, in the form the parser of the help expects
+fn add_inherited(cli_command: &mut Command, parameters: &CLIParameters, depth: usize) {
+    let key_style = parameters.key_style;
+    for option in parameters.arguments.iter().chain(&parameters.options).filter(|option| option.inherited == Some(depth)) {
+        for value in option.passed_values() {
+            match parameters.cli_lib {
+                CLILib::WindowsSlash => {
+                    cli_command.arg(format!("{}:{value}", option.key));
+                },
+                CLILib::Gnu => add_value(cli_command, option.emitted_key(key_style), &value, true),
+                _ => add_value(cli_command, option.emitted_key(key_style), &value, option.joined),
+            }
+        }
+    }
+    for flag in parameters.flags.iter().filter(|flag| flag.inherited == Some(depth)) {
+        let key = if parameters.cli_lib == CLILib::WindowsSlash { &flag.key } else { flag.emitted_key(key_style) };
+        for _ in 0..flag.times() {
+            cli_command.arg(key);
+        }
+    }
+}
+
+/// Append the key and the value as two arguments, or as `--key=value` if they are joined
+/// Values starting with `-` are always joined to long keys, the command would read them as a key otherwise
+fn add_value(cli_command: &mut Command, key: &str, value: &str, joined: bool) {
+    if key.starts_with("--") && (joined || value.starts_with('-')) {
+        cli_command.arg(format!("{key}={value}"));
+    } else {
+        cli_command.args([key, value]);
+    }
+}
+
+/// Convert the parameters to clap cli command
+/// Required arguments are passed even if empty so the cli can report them
+fn convert_to_clap_cli(parameters: &CLIParameters) -> Command {
+    let mut cli_command = new_command(parameters);
+    let key_style = parameters.key_style;
+    for option in parameters.options.iter().filter(|option| option.inherited.is_none()) {
+        for value in option.passed_values() {
+            add_value(&mut cli_command, option.emitted_key(key_style), &value, option.joined);
+        }
+    }
+    for flag in parameters.flags.iter().filter(|flag| flag.inherited.is_none()) {
+        for _ in 0..flag.times() {
+            cli_command.arg(flag.emitted_key(key_style));
+        }
+    }
+    for argument in parameters.arguments.iter().filter(|argument| argument.inherited.is_none()) {
+        if argument.required && argument.filled_values().next().is_none() {
+            cli_command.args([argument.emitted_key(key_style), ""]);
+        }
+        for value in argument.passed_values() {
+            add_value(&mut cli_command, argument.emitted_key(key_style), &value, argument.joined);
+        }
+    }
+    add_positionals(&mut cli_command, parameters);
+    cli_command
+}
+
+/// Append the values of the positionals without keys in their order
+fn add_positionals(cli_command: &mut Command, parameters: &CLIParameters) {
+    for positional in &parameters.positionals {
+        cli_command.args(positional.passed_values());
+    }
+}
+
+/// Convert the parameters to a GNU getopt_long cli command
+/// Long options are joined with their value, as optional values (`--color[=WHEN]`) require it
+fn convert_to_gnu_cli(parameters: &CLIParameters) -> Command {
+    let mut cli_command = new_command(parameters);
+    for option in parameters.arguments.iter().chain(&parameters.options).filter(|option| option.inherited.is_none()) {
+        for value in option.passed_values() {
+            add_value(&mut cli_command, option.emitted_key(parameters.key_style), &value, true);
+        }
+    }
+    for flag in parameters.flags.iter().filter(|flag| flag.inherited.is_none()) {
+        for _ in 0..flag.times() {
+            cli_command.arg(flag.emitted_key(parameters.key_style));
+        }
+    }
+    add_positionals(&mut cli_command, parameters);
+    cli_command
+}
+
+/// Convert the parameters to a classic Windows command, positionals first and values joined with `:`, e.g.
+/// robocopy src dst /MIR /LOG:copy.log
+fn convert_to_windows_cli(parameters: &CLIParameters) -> Command {
+    let mut cli_command = new_command(parameters);
+    add_positionals(&mut cli_command, parameters);
+    for option in parameters.arguments.iter().chain(&parameters.options).filter(|option| option.inherited.is_none()) {
+        for value in option.passed_values() {
+            cli_command.arg(format!("{}:{value}", option.key));
+        }
+    }
+    for flag in parameters.flags.iter().filter(|flag| flag.inherited.is_none()) {
+        for _ in 0..flag.times() {
+            cli_command.arg(&flag.key);
+        }
+    }
+    cli_command
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn get_test_clap_help_string() -> String {
+    String::from("Simple program to greet a person
+
+Usage: greeter.exe [OPTIONS] --first-name <FIRST_NAME> --last-name <LAST_NAME>
+
+Options:
+    -f, --first-name <FIRST_NAME>  First name of the person to greet
+    -l, --last-name <LAST_NAME>    Last name of the person to greet
+        --caps                     Greet in caps
+        --german                   Greet in german
+    -c, --count <COUNT>            Number of times to greet [default: 1]
+    -h, --help                     Print help
+    -V, --version                  Print version")
+}
+
+#[allow(dead_code)]
+fn get_test_clap_option_explanation() -> String {
+    String::from("Options:
+    -f, --first-name <FIRST_NAME>  First name of the person to greet
+    -l, --last-name <LAST_NAME>    Last name of the person to greet
+        --caps                     Greet in caps
+        --german                   Greet in german
+    -c, --count <COUNT>            Number of times to greet [default: 1]
+    -h, --help                     Print help
+    -V, --version                  Print version")
+}
+
+#[allow(dead_code)]
+fn get_test_clap_usage_explanation() -> String {
+    String::from("Usage: greeter.exe [OPTIONS] --first-name <FIRST_NAME> --last-name <LAST_NAME>")
+}
+
+#[test]
+fn test_retrieve_clap_option_explanation() {
+    let help_string = get_test_clap_help_string();
+    let usage_explanation = retrieve_clap_usage_explanation(&help_string).unwrap();
+
+    assert_eq!(
+        usage_explanation,
+        get_test_clap_usage_explanation()
+    )
+}
+
+#[test]
+fn test_retrieve_clap_usage_explanation() {
+    let help_string = get_test_clap_help_string();
+    let option_explanation = retrieve_clap_option_explanation(&help_string).unwrap();
+
+    assert_eq!(
+        option_explanation,
+        get_test_clap_option_explanation()
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line() {
+    let option_line = "-n, --name <NAME>    Name of the person to greet";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("NAME"),
+            key: String::from("--name"),
+            short_key: Some(String::from("-n")),
+            long_key: Some(String::from("--name")),
+            description: Some(String::from("Name of the person to greet")),
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_multiple_words_in_key() {
+    let option_line = "-n, --first-name <FIRST_NAME>    Name of the person to greet";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("FIRST_NAME"),
+            key: String::from("--first-name"),
+            short_key: Some(String::from("-n")),
+            long_key: Some(String::from("--first-name")),
+            description: Some(String::from("Name of the person to greet")),
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_default_value() {
+    let option_line = "-c, --count <COUNT>  Number of times to greet [default: 10]";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("COUNT"),
+            key: String::from("--count"),
+            short_key: Some(String::from("-c")),
+            long_key: Some(String::from("--count")),
+            description: Some(String::from("Number of times to greet")),
+            values: vec![String::from("10")],
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_possible_values() {
+    let option_line = "-f, --format <FORMAT>  Output format [default: json] [possible values: json, yaml, text]";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("FORMAT"),
+            key: String::from("--format"),
+            short_key: Some(String::from("-f")),
+            long_key: Some(String::from("--format")),
+            description: Some(String::from("Output format")),
+            values: vec![String::from("json")],
+            choices: vec![String::from("json"), String::from("yaml"), String::from("text")],
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_env_var() {
+    let option_line = "-t, --token <TOKEN>  API token [env: API_TOKEN=secret] [default: none]";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("TOKEN"),
+            key: String::from("--token"),
+            short_key: Some(String::from("-t")),
+            long_key: Some(String::from("--token")),
+            description: Some(String::from("API token")),
+            values: vec![String::from("none")],
+            env_var: Some(String::from("API_TOKEN")),
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_complex_default() {
+    let option_line = "-o, --out <DIR>  Output directory, see [docs] [default: /tmp/my dir] [aliases: output, dest]";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("DIR"),
+            key: String::from("--out"),
+            short_key: Some(String::from("-o")),
+            long_key: Some(String::from("--out")),
+            aliases: vec![String::from("--output"), String::from("--dest")],
+            description: Some(String::from("Output directory, see [docs]")),
+            values: vec![String::from("/tmp/my dir")],
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_aliases() {
+    let option_line = "-V, --version, --ver  Print version";
+
+    let flag = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        flag,
+        CLIParameter::Flag(CLIFlag {
+            key: String::from("--version"),
+            short_key: Some(String::from("-V")),
+            long_key: Some(String::from("--version")),
+            aliases: vec![String::from("--ver")],
+            description: Some(String::from("Print version")),
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_required_by_alias() {
+    let help_string = "Usage: tool --dest <DIR>
+
+Options:
+  -o, --out <DIR>  Output directory [aliases: dest] [short aliases: d]
+  -h, --help       Print help
+";
+
+    let parameters = parse_clap_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.arguments.len(), 1);
+    assert_eq!(parameters.arguments[0].key, "--out");
+    assert_eq!(parameters.arguments[0].aliases, vec![String::from("--dest"), String::from("-d")]);
+    assert!(parameters.arguments[0].required);
+    assert_eq!(parameters.options, Vec::new());
+}
+
+#[test]
+fn test_parse_gnu_invocation_aliases() {
+    let parameter = parse_gnu_invocation("-o, --output, --out=FILE", Some(String::from("write to FILE"))).unwrap();
+
+    let CLIParameter::Argument(argument) = parameter else {
+        panic!("expected an argument");
+    };
+    assert_eq!(argument.key, "--output");
+    assert_eq!(argument.aliases, vec![String::from("--out")]);
+    assert!(argument.has_key("--out"));
+}
+
+#[test]
+fn test_find_description_column() {
+    let lines = [
+        "  -o,  --output <FILE>  Write to FILE",
+        "  -r,  --raw            <html> is kept as is",
+        "       --very-long-option-name <VALUE>",
+        "                        Wrapped description",
+    ];
+
+    assert_eq!(find_description_column(lines), Some(24));
+    assert_eq!(split_option_row(lines[0], Some(24)), (String::from("-o, --output <FILE>"), Some(String::from("Write to FILE"))));
+    assert_eq!(split_option_row(lines[2], Some(24)), (String::from("--very-long-option-name <VALUE>"), None));
+}
+
+#[test]
+fn test_parse_clap_option_explanation_aligned_columns() {
+    let option_string = "Options:
+  -o,  --output <FILE>  Write to FILE
+  -r,  --raw            <html> is kept as is
+  -h,  --help           Print help
+";
+
+    let parameters = parse_clap_option_explanation(option_string).unwrap();
+
+    let CLIParameter::Argument(output) = &parameters[0] else {
+        panic!("expected an argument");
+    };
+    assert_eq!((output.key.as_str(), output.short_key.as_deref(), output.name.as_str()), ("--output", Some("-o"), "FILE"));
+    let CLIParameter::Flag(raw) = &parameters[1] else {
+        panic!("expected a flag");
+    };
+    assert_eq!(raw.description.as_deref(), Some("<html> is kept as is"));
+}
+
+#[test]
+fn parse_clap_help_heading_groups() {
+    let help_string = "Usage: fetch [OPTIONS] <URL>
+
+Arguments:
+  <URL>  Address to fetch
+
+Options:
+  -o, --output <FILE>  Write to FILE
+  -h, --help           Print help
+
+Network Options:
+      --proxy <HOST>   Proxy to connect through
+      --insecure       Skip the certificate check
+
+Output Options:
+  -q, --quiet          Print nothing
+";
+
+    let parameters = parse_clap_help_string(help_string).unwrap();
+
+    let option_groups: Vec<(&str, Option<&str>)> = parameters.options.iter().map(|option| (option.key.as_str(), option.group.as_deref())).collect();
+    assert_eq!(option_groups, [("--output", None), ("--proxy", Some("Network Options"))]);
+    let flag_groups: Vec<(&str, Option<&str>)> = parameters.flags.iter().map(|flag| (flag.key.as_str(), flag.group.as_deref())).collect();
+    assert_eq!(flag_groups, [("--help", None), ("--insecure", Some("Network Options")), ("--quiet", Some("Output Options"))]);
+}
+
+#[test]
+fn test_split_clap_metadata_nested_brackets() {
+    assert_eq!(
+        split_clap_metadata("Ranges [default: [1, 2]] [possible values: [1, 2], [3, 4]] [env: RANGES=]"),
+        ClapOptionMetadata {
+            description: Some(String::from("Ranges")),
+            default_value: Some(String::from("[1, 2]")),
+            env_var: Some(String::from("RANGES")),
+            choices: vec![String::from("[1, 2]"), String::from("[3, 4]")],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_merge_short_help() {
+    let mut parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--name"), description: Some(String::from("Name")), ..Default::default() }],
+        flags: vec![CLIFlag { key: String::from("--verbose"), ..Default::default() }],
+        ..Default::default()
+    };
+    let short_parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--name"), description: Some(String::from("Name to greet")), ..Default::default() }],
+        flags: vec![CLIFlag { key: String::from("--quiet"), ..Default::default() }],
+        ..Default::default()
+    };
+
+    parameters.merge_short_help(short_parameters);
+
+    assert_eq!(parameters.options[0].description.as_deref(), Some("Name to greet"));
+    assert_eq!(parameters.flags.len(), 2);
+    assert_eq!(
+        parameters.probes,
+        BTreeMap::from([
+            (String::from("--name"), HelpProbe::Both),
+            (String::from("--quiet"), HelpProbe::Short),
+            (String::from("--verbose"), HelpProbe::Long),
+        ]),
+    );
+}
+
+#[test]
+fn test_merge_short_help_summary() {
+    let mut parameters = CLIParameters {
+        options: vec![CLIArgument {
+            key: String::from("--jobs"),
+            description: Some(String::from("Run up to N jobs at once, defaults to the number of CPUs. Use 1 to build in order")),
+            ..Default::default()
+        }],
+        flags: vec![CLIFlag { key: String::from("--help"), description: Some(String::from("Print help (see a summary with '-h')")), ..Default::default() }],
+        ..Default::default()
+    };
+    let short_parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--jobs"), description: Some(String::from("Number of parallel jobs")), ..Default::default() }],
+        flags: vec![CLIFlag { key: String::from("--help"), description: Some(String::from("Print help")), ..Default::default() }],
+        ..Default::default()
+    };
+
+    parameters.merge_short_help(short_parameters);
+
+    assert_eq!(
+        parameters.options[0].description.as_deref(),
+        Some("Number of parallel jobs\nRun up to N jobs at once, defaults to the number of CPUs. Use 1 to build in order"),
+    );
+    assert_eq!(parameters.flags[0].description.as_deref(), Some("Print help (see a summary with '-h')"));
+}
+
+#[test]
+fn test_find_guesses() {
+    let help_string = "Usage: tool [OPTIONS] --input <FILE>
+
+Options:
+  -i, --input <FILE>     File to read
+  -c, --color <WHEN>     When to color [possible values: true, false]
+  -j, --jobs <N>         Number of jobs
+  -h, --help             Print help
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(
+        parameters.find_guesses(help_string),
+        vec![String::from("Type of --input guessed as Path from FILE"), String::from("Type of --jobs guessed as Number from N")],
+    );
+
+    let gnu_help = "Usage: tool [OPTION]... FILE
+
+  -o, --output=FILE   write to FILE
+  -q, --quiet         print nothing
+  -x, --extra [MODE]  something the parser skips
+";
+    let parameters = parse_help_string_with(gnu_help, &[CLILib::Gnu]).unwrap();
+
+    assert!(parameters.find_guesses(gnu_help).contains(&String::from("Required options are unknown, the Gnu parser lists every option as optional")));
+}
+
+#[test]
+fn test_all_guesses_of_subcommands() {
+    let parameters = CLIParameters {
+        guesses: vec![String::from("Unparsed line: -x")],
+        subcommands: vec![CLISubcommand {
+            name: String::from("build"),
+            parameters: Some(CLIParameters {
+                command_path: vec![String::from("build")],
+                guesses: vec![String::from("Type of --jobs guessed as Number from N")],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    assert_eq!(parameters.all_guesses(), vec![String::from("Unparsed line: -x"), String::from("build: Type of --jobs guessed as Number from N")]);
+}
+
+#[test]
+fn test_infer_value_type() {
+    assert_eq!(ValueType::infer("CONFIG_FILE", None), ValueType::Path);
+    assert_eq!(ValueType::infer("port", None), ValueType::Number);
+    assert_eq!(ValueType::infer("TIMEOUT", None), ValueType::Duration);
+    assert_eq!(ValueType::infer("LEVEL", Some("3")), ValueType::Number);
+    assert_eq!(ValueType::infer("MODE", Some("inf")), ValueType::String);
+    assert_eq!(ValueType::infer("SCALE", Some("1e6")), ValueType::String);
+    assert_eq!(ValueType::infer("COLOR", Some("true")), ValueType::Bool);
+    assert_eq!(ValueType::infer("NAME", Some("World")), ValueType::String);
+}
+
+#[test]
+fn test_infer_boolean_value_types() {
+    assert_eq!(ValueType::infer("TRUE|FALSE", None), ValueType::Bool);
+    assert_eq!(ValueType::infer("{yes,no}", None), ValueType::Bool);
+    assert_eq!(ValueType::infer("ENABLED", None), ValueType::String);
+
+    let mut parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--color"), name: String::from("WHEN"), choices: vec![String::from("true"), String::from("false")], ..Default::default() }],
+        ..Default::default()
+    };
+    parameters.infer_value_types();
+
+    assert_eq!(parameters.options[0].value_type, ValueType::Bool);
+}
+
+#[test]
+fn test_next_bool_value() {
+    let mut argument = CLIArgument { key: String::from("--enabled"), value_type: ValueType::Bool, ..Default::default() };
+
+    argument.values = argument.next_bool_value();
+    assert_eq!(argument.values, vec![String::from("true")]);
+    argument.values = argument.next_bool_value();
+    assert_eq!(argument.values, vec![String::from("false")]);
+    assert_eq!(argument.next_bool_value(), Vec::<String>::new());
+
+    argument.required = true;
+    assert_eq!(argument.next_bool_value(), vec![String::from("true")]);
+}
+
+#[test]
+fn test_parse_version() {
+    assert_eq!(parse_version("cargo 1.78.0 (54d8815d0 2024-03-26)\n").as_deref(), Some("1.78.0"));
+    assert_eq!(parse_version("\ngit version 2.43.0.windows.1").as_deref(), Some("2.43.0"));
+    assert_eq!(parse_version("tool v0.3.1-beta.2").as_deref(), Some("0.3.1-beta.2"));
+    assert_eq!(parse_version("nightly build").as_deref(), Some("nightly build"));
+    assert_eq!(parse_version("  \n"), None);
+}
+
+#[test]
+fn test_fill_derived_values() {
+    let mut parameters = CLIParameters {
+        options: vec![
+            CLIArgument { key: String::from("--name"), short_key: Some(String::from("-n")), values: vec![String::from("nightly")], ..Default::default() },
+        ],
+        positionals: vec![CLIPositional { name: String::from("TARGET"), values: vec![String::from("x86")], ..Default::default() }],
+        ..Default::default()
+    };
+
+    parameters.add_derived("--output", "{name}-{target}-{date}.log");
+    parameters.fill_derived_values(&|name| Some(name).filter(|name| *name == "date").map(|_| String::from("2024-05-01")));
+
+    assert_eq!(parameters.options[1].key, "--output");
+    assert_eq!(parameters.options[1].values, vec![String::from("nightly-x86-2024-05-01.log")]);
+    assert_eq!(parameters.options[1].description.as_deref(), Some("Computed from {name}-{target}-{date}.log"));
+}
+
+#[test]
+fn test_fill_derived_values_of_subcommands() {
+    let build = CLIParameters {
+        options: vec![
+            CLIArgument { key: String::from("--target"), values: vec![String::from("arm")], ..Default::default() },
+            CLIArgument { key: String::from("--output"), ..Default::default() },
+        ],
+        ..Default::default()
+    };
+    let mut parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--target"), values: vec![String::from("x86")], ..Default::default() }],
+        subcommands: vec![CLISubcommand { name: String::from("build"), parameters: Some(build), ..Default::default() }],
+        ..Default::default()
+    };
+
+    parameters.add_derived("--output", "{target}.bin");
+    parameters.fill_derived_values(&|_| None);
+
+    assert_eq!(parameters.options.len(), 1);
+    let build = parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(build.options[1].values, vec![String::from("arm.bin")]);
+}
+
+#[test]
+fn test_add_derived_to_listed_option() {
+    let mut parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--output"), short_key: Some(String::from("-o")), ..Default::default() }],
+        ..Default::default()
+    };
+
+    parameters.add_derived("-o", "{missing}.log");
+    parameters.fill_derived_values(&|_| None);
+
+    assert_eq!(parameters.options.len(), 1);
+    assert_eq!(parameters.options[0].values, vec![String::from(".log")]);
+}
+
+#[test]
+fn test_fill_env_values() {
+    let mut parameters = CLIParameters {
+        options: vec![
+            CLIArgument { key: String::from("--token"), env_var: Some(String::from("API_TOKEN")), ..Default::default() },
+            CLIArgument { key: String::from("--user"), env_var: Some(String::from("API_USER")), values: vec![String::from("me")], ..Default::default() },
+        ],
+        ..Default::default()
+    };
+
+    parameters.fill_env_values(&|name| (name == "API_TOKEN").then(|| String::from("secret")));
+
+    assert_eq!(parameters.options[0].values, vec![String::from("secret")]);
+    assert_eq!(parameters.options[1].values, vec![String::from("me")]);
+}
+
+#[test]
+fn test_parse_clap_option_line_only_short_key() {
+    let option_line = "-n <NAME>    Name of the person to greet";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("NAME"),
+            key: String::from("-n"),
+            short_key: Some(String::from("-n")),
+            description: Some(String::from("Name of the person to greet")),
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_only_long_key() {
+    let option_line = "--name <NAME>    Name of the person to greet";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("NAME"),
+            key: String::from("--name"),
+            long_key: Some(String::from("--name")),
+            description: Some(String::from("Name of the person to greet")),
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_line_multiple_values() {
+    let option_line = "-f, --file <FILE>...    Files to read";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("FILE"),
+            key: String::from("--file"),
+            short_key: Some(String::from("-f")),
+            long_key: Some(String::from("--file")),
+            description: Some(String::from("Files to read")),
+            multiple: true,
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_missing_keys() {
+    let option_line = "<NAME>    Name of the person to greet";
+
+    let argument = parse_clap_option_line(option_line);
+
+    assert_eq!(
+        argument,
         None,
     )
 }
 
 #[test]
-fn test_parse_clap_option_without_description() {
-    let option_line = "  --name <NAME>";
+fn test_parse_clap_option_without_description() {
+    let option_line = "  --name <NAME>";
+
+    let argument = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        argument,
+        CLIParameter::Argument(CLIArgument {
+            name: String::from("NAME"),
+            key: String::from("--name"),
+            long_key: Some(String::from("--name")),
+            description: None,
+            ..Default::default()
+        }),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_flag() {
+    let option_line = "  -h, --help           Print help";
+
+    let parameter = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Flag(CLIFlag {
+            key: String::from("--help"),
+            short_key: Some(String::from("-h")),
+            long_key: Some(String::from("--help")),
+            description: Some(String::from("Print help")),
+            set: false,
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_clap_option_flag_without_description() {
+    let option_line = "  -h, --help";
+
+    let parameter = parse_clap_option_line(option_line).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Flag(CLIFlag {
+            key: String::from("--help"),
+            short_key: Some(String::from("-h")),
+            long_key: Some(String::from("--help")),
+            description: None,
+            set: false,
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_clap_option_explanation() {
+    let arguments = parse_clap_option_explanation(&get_test_clap_option_explanation()).unwrap();
+
+    assert_eq!(
+        arguments,
+        vec![
+            CLIParameter::Argument(CLIArgument {
+                name: String::from("FIRST_NAME"),
+                key: String::from("--first-name"),
+                short_key: Some(String::from("-f")),
+                long_key: Some(String::from("--first-name")),
+                description: Some(String::from("First name of the person to greet")),
+                ..Default::default()
+            }),
+            CLIParameter::Argument(CLIArgument {
+                name: String::from("LAST_NAME"),
+                key: String::from("--last-name"),
+                short_key: Some(String::from("-l")),
+                long_key: Some(String::from("--last-name")),
+                description: Some(String::from("Last name of the person to greet")),
+                ..Default::default()
+            }),
+            CLIParameter::Flag(CLIFlag {
+                key: String::from("--caps"),
+                long_key: Some(String::from("--caps")),
+                description: Some(String::from("Greet in caps")),
+                set: false,
+                ..Default::default()
+            }),
+            CLIParameter::Flag(CLIFlag {
+                key: String::from("--german"),
+                long_key: Some(String::from("--german")),
+                description: Some(String::from("Greet in german")),
+                set: false,
+                ..Default::default()
+            }),
+            CLIParameter::Argument(CLIArgument {
+                name: String::from("COUNT"),
+                key: String::from("--count"),
+                short_key: Some(String::from("-c")),
+                long_key: Some(String::from("--count")),
+                description: Some(String::from("Number of times to greet")),
+                values: vec![String::from("1")],
+                ..Default::default()
+            }),
+            CLIParameter::Flag(CLIFlag {
+                key: String::from("--help"),
+                short_key: Some(String::from("-h")),
+                long_key: Some(String::from("--help")),
+                description: Some(String::from("Print help")),
+                set: false,
+                ..Default::default()
+            }),
+            CLIParameter::Flag(CLIFlag {
+                key: String::from("--version"),
+                short_key: Some(String::from("-V")),
+                long_key: Some(String::from("--version")),
+                description: Some(String::from("Print version")),
+                set: false,
+                ..Default::default()
+            }),
+        ]
+    )
+}
+
+#[test]
+fn test_parse_usage_explanation() {
+    let usage_string = String::from("Usage: greeter.exe [OPTIONS] --name <NAME>");
+    
+    let argument_keys = parse_clap_usage_explanation(&usage_string);
+
+    assert_eq!(
+        argument_keys,
+        Some((String::from("greeter.exe"), UsageGroups { required_keys: vec![String::from("--name")], ..Default::default() })),
+    )
+}
+
+#[test]
+fn test_retrieve_clap_usage_explanation_variants() {
+    let help_string = "Manage items
+
+Usage: items [OPTIONS] --store <STORE>
+             <ITEM>...
+       items --store <STORE> --list
+       items add <ITEM>
+
+Options:
+  -s, --store <STORE>  Store of the items
+  -l, --list           List the items";
+
+    let usage_explanation = retrieve_clap_usage_explanation(help_string).unwrap();
+
+    assert_eq!(usage_explanation, "Usage: items [OPTIONS] --store <STORE> <ITEM>...\n       items --store <STORE> --list\n       items add <ITEM>");
+    let (cli_name, usage_groups) = parse_clap_usage_explanation(&usage_explanation).unwrap();
+    assert_eq!(cli_name, "items");
+    assert_eq!(usage_groups.required_keys, [String::from("--store")]);
+    assert_eq!(usage_groups.required_names, Vec::<String>::new());
+    let parameters = parse_clap_help_string(help_string).unwrap();
+    assert_eq!(parameters.arguments[0].key, "--store");
+    assert_eq!(parameters.positionals[0].name, "ITEM");
+    assert!(!parameters.positionals[0].required);
+}
+
+#[test]
+fn test_parse_clap_help_string_without_options() {
+    let help_string = "Count the lines of files
+
+Usage: lines <FILE>...
+
+Arguments:
+  <FILE>...  Files to count
+";
+
+    let parameters = parse_clap_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_name, "lines");
+    assert!(parameters.options.is_empty() && parameters.flags.is_empty());
+    assert_eq!(parameters.positionals[0].name, "FILE");
+    assert_eq!(parameters.positionals[0].description.as_deref(), Some("Files to count"));
+    assert_eq!(parse_clap_help_string("Usage: tool --output=<file> [--level=<n>]"), Err(0));
+    let cobra_help = "Usage:\n  tool [command]\n\nAvailable Commands:\n  serve  Start the server\n";
+    assert_eq!(parse_clap_help_string(cobra_help), Err(0));
+    assert!(CLILib::Clap.confidence(cobra_help) < CLILib::Cobra.confidence(cobra_help));
+}
+
+#[test]
+fn test_parse_clap_help_string_uppercase_headings() {
+    let help_string = "greeter 1.0
+
+USAGE:
+    greeter [OPTIONS] <NAME>
+
+ARGS:
+    <NAME>    Name of the person to greet
+
+OPTIONS:
+    -c, --count <COUNT>    Number of times to greet
+    -h, --help             Print help information
+
+SUBCOMMANDS:
+    wave    Wave instead of greeting
+";
+
+    let parameters = parse_clap_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_name, "greeter");
+    assert_eq!(parameters.options[0].key, "--count");
+    assert_eq!(parameters.options[0].group, None);
+    assert_eq!(parameters.positionals[0].description.as_deref(), Some("Name of the person to greet"));
+    assert_eq!(parameters.subcommands[0].name, "wave");
+}
+
+#[test]
+fn test_parse_usage_explanation_multiple_words_in_key() {
+    let usage_string = String::from("Usage: greeter.exe [OPTIONS] --first-name <FIRST_NAME>");
+    
+    let argument_keys = parse_clap_usage_explanation(&usage_string);
+
+    assert_eq!(
+        argument_keys,
+        Some((String::from("greeter.exe"), UsageGroups { required_keys: vec![String::from("--first-name")], ..Default::default() })),
+    )
+}
+
+#[test]
+fn test_parse_usage_explanation_short_key() {
+    let usage_string = String::from("Usage: greeter.exe [OPTIONS] -n <FIRST_NAME>");
+    
+    let argument_keys = parse_clap_usage_explanation(&usage_string);
+
+    assert_eq!(
+        argument_keys,
+        Some((String::from("greeter.exe"), UsageGroups { required_keys: vec![String::from("-n")], ..Default::default() })),
+    )
+}
+
+#[test]
+fn test_parse_clap_option_explanation_wrapped_description() {
+    let option_explanation = "Options:
+  -n, --name <NAME>  Name of the person to greet, which is
+                     wrapped onto a second line [default: World]
+  -h, --help         Print help
+
+Examples:
+  greeter --name Ferris";
+
+    let parameters = parse_clap_option_explanation(option_explanation).unwrap();
+
+    assert_eq!(
+        parameters,
+        vec![
+            CLIParameter::Argument(CLIArgument {
+                key: String::from("--name"),
+                short_key: Some(String::from("-n")),
+                long_key: Some(String::from("--name")),
+                name: String::from("NAME"),
+                description: Some(String::from("Name of the person to greet, which is wrapped onto a second line")),
+                values: vec![String::from("World")],
+                ..Default::default()
+            }),
+            CLIParameter::Flag(CLIFlag {
+                key: String::from("--help"),
+                short_key: Some(String::from("-h")),
+                long_key: Some(String::from("--help")),
+                description: Some(String::from("Print help")),
+                ..Default::default()
+            }),
+        ],
+    )
+}
+
+#[test]
+fn test_parse_clap_option_explanation_multiple_keys() {
+    let usage_string = String::from("Usage: greeter.exe [OPTIONS] --first-name <NAME> --count <COUNT>");
+    
+    let argument_keys = parse_clap_usage_explanation(&usage_string);
+
+    assert_eq!(
+        argument_keys,
+        Some((String::from("greeter.exe"), UsageGroups { required_keys: vec![String::from("--first-name"), String::from("--count")], ..Default::default() })),
+    )
+}
+
+#[test]
+fn test_parse_usage_multiplicity() {
+    let usage_string = String::from("Usage: tool [OPTIONS]... [-v...] --file <FILE>... <PATH>...");
+
+    let multiplicity = parse_clap_usage_multiplicity(&usage_string);
+
+    assert_eq!(
+        multiplicity,
+        UsageMultiplicity {
+            keys: vec![String::from("-v"), String::from("--file")],
+            names: vec![String::from("FILE"), String::from("PATH")],
+            all_options: true,
+        },
+    )
+}
+
+#[test]
+fn test_parse_usage_multiplicity_bracket_group() {
+    let usage_string = String::from("Usage: tool [--include <PATTERN>]... --name <NAME>");
+
+    let multiplicity = parse_clap_usage_multiplicity(&usage_string);
+
+    assert_eq!(
+        multiplicity,
+        UsageMultiplicity {
+            keys: vec![String::from("--include")],
+            names: vec![String::from("PATTERN")],
+            all_options: false,
+        },
+    )
+}
+
+#[test]
+fn test_parse_usage_multiplicity_without_ellipsis() {
+    let multiplicity = parse_clap_usage_multiplicity(&get_test_clap_usage_explanation());
+
+    assert_eq!(
+        multiplicity,
+        UsageMultiplicity::default(),
+    )
+}
+
+#[test]
+fn parse_clap_multiplicity() {
+    let help_string = String::from("Usage: tool [--verbose...] --file <FILE>...
+
+Options:
+        --file <FILE>   File to process
+        --out <OUT>     Output directory
+        --verbose       More output");
+
+    let cli_arguments = parse_help_string(&help_string).unwrap();
+
+    assert!(cli_arguments.arguments[0].multiple);
+    assert!(!cli_arguments.options[0].multiple);
+    assert!(cli_arguments.flags[0].multiple);
+}
+
+#[test]
+fn test_parse_usage_groups() {
+    let usage_string = "[OPTIONS] --name <NAME> [--count <COUNT>] [-a | -b] <--json|--yaml> <FILE> [MODE]";
+
+    let usage_groups = parse_usage_groups(usage_string);
+
+    assert_eq!(
+        usage_groups,
+        UsageGroups {
+            required_keys: vec![String::from("--name")],
+            required_names: vec![String::from("FILE")],
+            exclusive_groups: vec![
+                vec![String::from("-a"), String::from("-b")],
+                vec![String::from("--json"), String::from("--yaml")],
+            ],
+        },
+    )
+}
+
+#[test]
+fn test_parse_usage_groups_nested() {
+    let usage_string = "(--input FILE [--format FORMAT] | --stdin) --out-dir DIR source [target ...]";
+
+    let usage_groups = parse_usage_groups(usage_string);
+
+    assert_eq!(
+        usage_groups,
+        UsageGroups {
+            required_keys: vec![String::from("--out-dir")],
+            required_names: vec![String::from("source")],
+            exclusive_groups: vec![vec![String::from("--input"), String::from("--stdin")]],
+        },
+    )
+}
+
+#[test]
+fn test_parse_usage_groups_unbalanced() {
+    let usage_string = "--name <NAME> [--count <COUNT>";
+
+    let usage_groups = parse_usage_groups(usage_string);
+
+    assert_eq!(usage_groups.required_keys, vec![String::from("--name")]);
+}
+
+#[test]
+fn parse_clap_usage_groups() {
+    let help_string = String::from("Usage: tool [OPTIONS] <--json|--yaml> --out <OUT> [--level <LEVEL>]
+
+Options:
+        --json           Output as json
+        --yaml           Output as yaml
+        --out <OUT>      Output file
+        --level <LEVEL>  Compression level
+    -h, --help           Print help");
+
+    let cli_arguments = parse_help_string(&help_string).unwrap();
+
+    let argument_keys: Vec<&str> = cli_arguments.arguments.iter().map(|argument| argument.key.as_str()).collect();
+    let option_keys: Vec<&str> = cli_arguments.options.iter().map(|option| option.key.as_str()).collect();
+    assert_eq!(argument_keys, vec!["--out"]);
+    assert!(cli_arguments.arguments[0].required);
+    assert_eq!(option_keys, vec!["--level"]);
+    assert_eq!(
+        cli_arguments.exclusive_groups,
+        vec![vec![String::from("--json"), String::from("--yaml")]],
+    );
+}
+
+#[test]
+fn parse_clap_with_positionals() {
+    let help_string = String::from("Usage: tool [OPTIONS] --out <OUT> <FILE>... [MODE] [COMMAND]
+
+Commands:
+  init  Create a new project
+
+Arguments:
+  <FILE>...  Files to process
+  [MODE]     Processing mode
+
+Options:
+      --out <OUT>  Output directory
+  -h, --help       Print help");
+
+    let cli_arguments = parse_help_string(&help_string).unwrap();
+
+    assert_eq!(
+        cli_arguments.positionals,
+        vec![
+            CLIPositional {
+                name: String::from("FILE"),
+                description: Some(String::from("Files to process")),
+                multiple: true,
+                required: true,
+                ..Default::default()
+            },
+            CLIPositional {
+                name: String::from("MODE"),
+                description: Some(String::from("Processing mode")),
+                ..Default::default()
+            },
+        ],
+    );
+    assert_eq!(cli_arguments.arguments[0].key, "--out");
+}
+
+#[test]
+fn parse_clap_long_help_arguments_section() {
+    let help_string = String::from("Usage: tool [OPTIONS] <FILE>
+
+Options:
+  -v, --verbose
+          Print more output
+
+  -h, --help
+          Print help (see a summary with '-h')
+
+Arguments:
+  <FILE>
+          File to process, which may be
+          on a network share
+
+  [EXTRA]
+          Extra input");
+
+    let cli_arguments = parse_help_string(&help_string).unwrap();
+
+    assert_eq!(
+        cli_arguments.positionals,
+        vec![
+            CLIPositional {
+                name: String::from("FILE"),
+                description: Some(String::from("File to process, which may be on a network share")),
+                required: true,
+                ..Default::default()
+            },
+            CLIPositional {
+                name: String::from("EXTRA"),
+                description: Some(String::from("Extra input")),
+                ..Default::default()
+            },
+        ],
+    );
+    assert_eq!(cli_arguments.flags.len(), 2);
+    assert_eq!(cli_arguments.flags[0].description.as_deref(), Some("Print more output"));
+}
+
+#[test]
+fn parse_clap() {
+    let help_string = get_test_clap_help_string();
+    let cli_arguments = parse_help_string(&help_string);
+
+    let expected_cli_arguments = Ok(CLIParameters {
+        cli_name: String::from("greeter.exe"),
+        arguments: vec![
+            CLIArgument {
+                name: String::from("FIRST_NAME"),
+                key: String::from("--first-name"),
+                short_key: Some(String::from("-f")),
+                long_key: Some(String::from("--first-name")),
+                description: Some(String::from("First name of the person to greet")),
+                required: true,
+                ..Default::default()
+            },
+            CLIArgument {
+                name: String::from("LAST_NAME"),
+                key: String::from("--last-name"),
+                short_key: Some(String::from("-l")),
+                long_key: Some(String::from("--last-name")),
+                description: Some(String::from("Last name of the person to greet")),
+                required: true,
+                ..Default::default()
+            },
+        ],
+        options: vec![
+            CLIArgument {
+                name: String::from("COUNT"),
+                key: String::from("--count"),
+                short_key: Some(String::from("-c")),
+                long_key: Some(String::from("--count")),
+                description: Some(String::from("Number of times to greet")),
+                values: vec![String::from("1")],
+                default_value: Some(String::from("1")),
+                value_type: ValueType::Number,
+                ..Default::default()
+            }
+        ],
+        flags: vec![
+            CLIFlag {
+                key: String::from("--caps"),
+                long_key: Some(String::from("--caps")),
+                description: Some(String::from("Greet in caps")),
+                set: false,
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--german"),
+                long_key: Some(String::from("--german")),
+                description: Some(String::from("Greet in german")),
+                set: false,
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--help"),
+                short_key: Some(String::from("-h")),
+                long_key: Some(String::from("--help")),
+                description: Some(String::from("Print help")),
+                set: false,
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--version"),
+                short_key: Some(String::from("-V")),
+                long_key: Some(String::from("--version")),
+                description: Some(String::from("Print version")),
+                set: false,
+                ..Default::default()
+            },
+        ],
+        cli_lib: CLILib::Clap,
+        ..Default::default()
+    });
+    assert_eq!(cli_arguments, expected_cli_arguments);
+}
+
+#[test]
+fn test_convert_to_cli() {
+    let parameters = CLIParameters {
+        cli_name: String::from("greeter.exe"),
+        arguments: vec![
+            CLIArgument {
+                name: String::from("FIRST NAME"),
+                key: String::from("--first-name"),
+                description: Some(String::from("First name of the person to greet")),
+                values: vec![String::from("Ferris")],
+                ..Default::default()
+            },
+            CLIArgument {
+                name: String::from("LAST NAME"),
+                key: String::from("--last-name"),
+                description: Some(String::from("Last name of the person to greet")),
+                values: vec![String::from("the Crab")],
+                ..Default::default()
+            },
+        ],
+        options: vec![
+            CLIArgument {
+                name: String::from("COUNT"),
+                key: String::from("--count"),
+                description: Some(String::from("Number of times to greet")),
+                values: vec![String::from("5")],
+                ..Default::default()
+            }
+        ],
+        flags: vec![
+            CLIFlag {
+                key: String::from("--caps"),
+                description: Some(String::from("Greet in caps")),
+                set: true,
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--german"),
+                description: Some(String::from("Greet in german")),
+                set: false,
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--help"),
+                description: Some(String::from("Print help")),
+                set: false,
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--version"),
+                description: Some(String::from("Print version")),
+                set: false,
+                ..Default::default()
+            },
+        ],
+        cli_lib: CLILib::Clap,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("greeter.exe");
+    expected_cli_command.args(["--count", "5", "--caps", "--first-name", "Ferris", "--last-name", "the Crab"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[allow(dead_code)]
+fn get_test_argparse_help_string() -> String {
+    String::from("usage: greeter.py [-h] [-c COUNT] [--caps] [--include INCLUDE [INCLUDE ...]]
+                  -n NAME
+                  file [file ...]
+
+Simple program to greet a person
+
+positional arguments:
+  file                  Files with greetings
+
+options:
+  -h, --help            show this help message and exit
+  -c COUNT, --count COUNT
+                        Number of times to greet, can be a very long text
+                        that is wrapped (default: 1)
+  --caps                Greet in caps
+  --include INCLUDE [INCLUDE ...]
+                        Extra names
+  -n NAME, --name NAME  Name of the person to greet")
+}
+
+#[test]
+fn test_retrieve_argparse_usage_explanation() {
+    let usage_explanation = retrieve_argparse_usage_explanation(&get_test_argparse_help_string()).unwrap();
+
+    assert_eq!(
+        usage_explanation,
+        "usage: greeter.py [-h] [-c COUNT] [--caps] [--include INCLUDE [INCLUDE ...]] -n NAME file [file ...]",
+    )
+}
+
+#[test]
+fn test_parse_argparse_usage_explanation() {
+    let usage_string = "usage: greeter.py [-h] [-c COUNT] --name NAME file [file ...]";
+
+    let usage = parse_argparse_usage_explanation(usage_string).unwrap();
+
+    assert_eq!(
+        usage,
+        (
+            String::from("greeter.py"),
+            UsageGroups {
+                required_keys: vec![String::from("--name")],
+                required_names: vec![String::from("file")],
+                ..Default::default()
+            },
+            vec![String::from("file")],
+        ),
+    )
+}
+
+#[test]
+fn test_parse_argparse_option() {
+    let parameter = parse_argparse_option("-c COUNT, --count COUNT", Some(String::from("Number of times (default: 1)"))).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Argument(CLIArgument {
+            key: String::from("--count"),
+            short_key: Some(String::from("-c")),
+            long_key: Some(String::from("--count")),
+            name: String::from("COUNT"),
+            description: Some(String::from("Number of times")),
+            values: vec![String::from("1")],
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_argparse_option_default_none() {
+    let parameter = parse_argparse_option("--name NAME", Some(String::from("Name (default: None)"))).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Argument(CLIArgument {
+            key: String::from("--name"),
+            long_key: Some(String::from("--name")),
+            name: String::from("NAME"),
+            description: Some(String::from("Name")),
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_argparse_flag() {
+    let parameter = parse_argparse_option("-h, --help", Some(String::from("show this help message and exit"))).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Flag(CLIFlag {
+            key: String::from("--help"),
+            short_key: Some(String::from("-h")),
+            long_key: Some(String::from("--help")),
+            description: Some(String::from("show this help message and exit")),
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn parse_argparse() {
+    let cli_arguments = parse_help_string(&get_test_argparse_help_string());
+
+    let expected_cli_arguments = Ok(CLIParameters {
+        cli_name: String::from("greeter.py"),
+        arguments: vec![
+            CLIArgument {
+                key: String::from("--name"),
+                short_key: Some(String::from("-n")),
+                long_key: Some(String::from("--name")),
+                name: String::from("NAME"),
+                description: Some(String::from("Name of the person to greet")),
+                required: true,
+                ..Default::default()
+            },
+        ],
+        positionals: vec![
+            CLIPositional {
+                name: String::from("file"),
+                description: Some(String::from("Files with greetings")),
+                multiple: true,
+                required: true,
+                ..Default::default()
+            },
+        ],
+        options: vec![
+            CLIArgument {
+                key: String::from("--count"),
+                short_key: Some(String::from("-c")),
+                long_key: Some(String::from("--count")),
+                name: String::from("COUNT"),
+                description: Some(String::from("Number of times to greet, can be a very long text that is wrapped")),
+                values: vec![String::from("1")],
+                default_value: Some(String::from("1")),
+                value_type: ValueType::Number,
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--include"),
+                long_key: Some(String::from("--include")),
+                name: String::from("INCLUDE"),
+                description: Some(String::from("Extra names")),
+                multiple: true,
+                ..Default::default()
+            },
+        ],
+        flags: vec![
+            CLIFlag {
+                key: String::from("--help"),
+                short_key: Some(String::from("-h")),
+                long_key: Some(String::from("--help")),
+                description: Some(String::from("show this help message and exit")),
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--caps"),
+                long_key: Some(String::from("--caps")),
+                description: Some(String::from("Greet in caps")),
+                ..Default::default()
+            },
+        ],
+        cli_lib: CLILib::Argparse,
+        ..Default::default()
+    });
+    assert_eq!(cli_arguments, expected_cli_arguments);
+}
+
+#[test]
+fn test_convert_to_cli_positional() {
+    let parameters = CLIParameters {
+        cli_name: String::from("greeter.py"),
+        positionals: vec![
+            CLIPositional {
+                name: String::from("file"),
+                values: vec![String::from("names.txt")],
+                ..Default::default()
+            },
+        ],
+        cli_lib: CLILib::Argparse,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("greeter.py");
+    expected_cli_command.arg("names.txt");
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_convert_to_cli_expands_globs() {
+    let directory = std::env::temp_dir().join(format!("clitui-convert-glob-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    for name in ["b.log", "a.log", "notes.txt"] {
+        std::fs::write(directory.join(name), "").unwrap();
+    }
+    let root = directory.to_string_lossy();
+    let pattern = format!("{root}/*.log");
+    let parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        options: vec![
+            CLIArgument { key: String::from("--input"), values: vec![pattern.clone()], multiple: true, glob: true, ..Default::default() },
+            CLIArgument { key: String::from("--exclude"), values: vec![pattern.clone()], ..Default::default() },
+        ],
+        positionals: vec![
+            CLIPositional { name: String::from("FILES"), values: vec![pattern.clone(), format!("{root}/*.csv")], glob: true, ..Default::default() },
+        ],
+        cli_lib: CLILib::Clap,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let (a, b) = (format!("{root}/a.log"), format!("{root}/b.log"));
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.args(["--input", &a, "--input", &b, "--exclude", &pattern, &format!("{a} {b} {root}/*.csv")]);
+    assert_eq!(format!("{:?}", cli_command), format!("{:?}", expected_cli_command));
+}
+
+#[test]
+fn parse_argparse_exclusive_group() {
+    let help_string = String::from("usage: tool.py [-h] [-q | -v] --out OUT
+
+options:
+  -h, --help       show this help message and exit
+  -q, --quiet      Less output
+  -v, --verbose    More output
+  -o OUT, --out OUT
+                   Output file");
+
+    let cli_arguments = parse_help_string(&help_string).unwrap();
+
+    assert_eq!(
+        cli_arguments.exclusive_groups,
+        vec![vec![String::from("--quiet"), String::from("--verbose")]],
+    );
+    assert!(cli_arguments.arguments[0].required);
+}
+
+#[test]
+fn test_convert_to_cli_skips_empty_optional_argument() {
+    let parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        arguments: vec![
+            CLIArgument {
+                key: String::from("--json-path"),
+                name: String::from("PATH"),
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--out"),
+                name: String::from("OUT"),
+                required: true,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.args(["--out", ""]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_convert_to_cli_repeats_multiple_values() {
+    let parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        options: vec![
+            CLIArgument {
+                key: String::from("--file"),
+                name: String::from("FILE"),
+                values: vec![String::from("a.txt"), String::new(), String::from("b.txt")],
+                multiple: true,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.args(["--file", "a.txt", "--file", "b.txt"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_convert_to_dry_run_cli_keeps_flag() {
+    let mut parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        flags: vec![CLIFlag { key: String::from("--dry-run"), ..Default::default() }],
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_dry_run_cli(&mut parameters).unwrap();
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.arg("--dry-run");
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    );
+    assert!(!parameters.flags[0].set);
+}
+
+#[allow(dead_code)]
+fn get_test_cobra_help_string() -> String {
+    String::from("Hugo is a fast and flexible static site generator
+
+Usage:
+  hugo [flags]
+  hugo [command]
+
+Available Commands:
+  completion  Generate the autocompletion script for the specified shell
+  server      A high performance webserver
+
+Flags:
+  -D, --buildDrafts            include content marked as draft
+      --cacheDir string        filesystem path to cache directory
+  -h, --help                   help for hugo
+  -p, --port int               port on which the server will listen (default 1313)
+      --tags strings           tags to build
+
+Global Flags:
+      --log-level string   log level (default \"info\")
+
+Use \"hugo [command] --help\" for more information about a command.")
+}
+
+#[test]
+fn test_parse_cobra_flag_line() {
+    let parameter = parse_cobra_flag_line("  -p, --port int               port on which the server will listen (default 1313)").unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Argument(CLIArgument {
+            key: String::from("--port"),
+            short_key: Some(String::from("-p")),
+            long_key: Some(String::from("--port")),
+            name: String::from("PORT"),
+            description: Some(String::from("port on which the server will listen")),
+            values: vec![String::from("1313")],
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_cobra_flag_line_flag() {
+    let parameter = parse_cobra_flag_line("  -D, --buildDrafts            include content marked as draft").unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Flag(CLIFlag {
+            key: String::from("--buildDrafts"),
+            short_key: Some(String::from("-D")),
+            long_key: Some(String::from("--buildDrafts")),
+            description: Some(String::from("include content marked as draft")),
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_cobra_flag_line_without_description() {
+    let parameter = parse_cobra_flag_line("      --cacheDir string").unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Argument(CLIArgument {
+            key: String::from("--cacheDir"),
+            long_key: Some(String::from("--cacheDir")),
+            name: String::from("CACHEDIR"),
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn parse_cobra() {
+    let cli_arguments = parse_help_string(&get_test_cobra_help_string());
+
+    let expected_cli_arguments = Ok(CLIParameters {
+        cli_name: String::from("hugo"),
+        arguments: Vec::new(),
+        options: vec![
+            CLIArgument {
+                key: String::from("--cacheDir"),
+                long_key: Some(String::from("--cacheDir")),
+                name: String::from("CACHEDIR"),
+                description: Some(String::from("filesystem path to cache directory")),
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--port"),
+                short_key: Some(String::from("-p")),
+                long_key: Some(String::from("--port")),
+                name: String::from("PORT"),
+                description: Some(String::from("port on which the server will listen")),
+                values: vec![String::from("1313")],
+                default_value: Some(String::from("1313")),
+                value_type: ValueType::Number,
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--tags"),
+                long_key: Some(String::from("--tags")),
+                name: String::from("TAGS"),
+                description: Some(String::from("tags to build")),
+                multiple: true,
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--log-level"),
+                long_key: Some(String::from("--log-level")),
+                name: String::from("LOG_LEVEL"),
+                description: Some(String::from("log level")),
+                values: vec![String::from("info")],
+                default_value: Some(String::from("info")),
+                ..Default::default()
+            },
+        ],
+        flags: vec![
+            CLIFlag {
+                key: String::from("--buildDrafts"),
+                short_key: Some(String::from("-D")),
+                long_key: Some(String::from("--buildDrafts")),
+                description: Some(String::from("include content marked as draft")),
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("--help"),
+                short_key: Some(String::from("-h")),
+                long_key: Some(String::from("--help")),
+                description: Some(String::from("help for hugo")),
+                ..Default::default()
+            },
+        ],
+        subcommands: vec![
+            CLISubcommand {
+                name: String::from("completion"),
+                description: Some(String::from("Generate the autocompletion script for the specified shell")),
+                parameters: None,
+            },
+            CLISubcommand {
+                name: String::from("server"),
+                description: Some(String::from("A high performance webserver")),
+                parameters: None,
+            },
+        ],
+        cli_lib: CLILib::Cobra,
+        ..Default::default()
+    });
+    assert_eq!(cli_arguments, expected_cli_arguments);
+}
+
+#[test]
+fn test_sort_parameters() {
+    let mut parameters = CLIParameters {
+        arguments: vec![
+            CLIArgument { name: String::from("ZIEL"), ..Default::default() },
+            CLIArgument { name: String::from("ANFANG"), ..Default::default() },
+        ],
+        options: vec![
+            CLIArgument { name: String::from("ZAHL"), ..Default::default() },
+            CLIArgument { name: String::from("ÜBER"), ..Default::default() },
+            CLIArgument { name: String::from("ANZAHL"), ..Default::default() },
+        ],
+        flags: vec![
+            CLIFlag { key: String::from("--verbose"), ..Default::default() },
+            CLIFlag { key: String::from("--écrire"), ..Default::default() },
+        ],
+        ..Default::default()
+    };
+
+    parameters.sort();
+
+    let argument_names: Vec<&str> = parameters.arguments.iter().map(|argument| argument.name.as_str()).collect();
+    let option_names: Vec<&str> = parameters.options.iter().map(|option| option.name.as_str()).collect();
+    let flag_keys: Vec<&str> = parameters.flags.iter().map(|flag| flag.key.as_str()).collect();
+    assert_eq!(argument_names, vec!["ZIEL", "ANFANG"]);
+    assert_eq!(option_names, vec!["ANZAHL", "ÜBER", "ZAHL"]);
+    assert_eq!(flag_keys, vec!["--écrire", "--verbose"]);
+}
+
+#[test]
+fn test_sort_parameters_within_groups() {
+    let network = Some(String::from("Network Options"));
+    let mut parameters = CLIParameters {
+        options: vec![
+            CLIArgument { name: String::from("OUTPUT"), ..Default::default() },
+            CLIArgument { name: String::from("TIMEOUT"), group: network.clone(), ..Default::default() },
+            CLIArgument { name: String::from("CONFIG"), ..Default::default() },
+            CLIArgument { name: String::from("PROXY"), group: network.clone(), ..Default::default() },
+        ],
+        ..Default::default()
+    };
+
+    parameters.sort();
+
+    let option_names: Vec<&str> = parameters.options.iter().map(|option| option.name.as_str()).collect();
+    assert_eq!(option_names, vec!["CONFIG", "OUTPUT", "PROXY", "TIMEOUT"]);
+    assert_eq!(parameters.options[2].group, network);
+}
+
+#[allow(dead_code)]
+fn get_test_docopt_help_string() -> String {
+    String::from("Naval Fate.
+
+Usage:
+  naval_fate ship new <name>...
+  naval_fate ship <name> move <x> <y> [--speed=<kn>]
+  naval_fate mine (set | remove) <x> <y> [--moored | --drifting]
+  naval_fate -h | --help
+  naval_fate --version")
+}
+
+#[test]
+fn test_retrieve_docopt_usage_patterns() {
+    let patterns = retrieve_docopt_usage_patterns("usage: tool [-v] FILE\n        tool --version\n\nOther text");
+
+    assert_eq!(
+        patterns,
+        vec![String::from("tool [-v] FILE"), String::from("tool --version")],
+    )
+}
+
+#[test]
+fn test_retrieve_docopt_usage_patterns_stops_at_option_rows() {
+    let patterns = retrieve_docopt_usage_patterns("Usage: curl [options...] <url>\n -d, --data <data>  HTTP POST data\n -f, --fail         Fail fast");
+
+    assert_eq!(patterns, vec![String::from("curl [options...] <url>")])
+}
+
+#[test]
+fn test_parse_docopt_pattern() {
+    let elements = parse_docopt_pattern("naval_fate mine (set | remove) <x>... [--speed=<kn>] (--moored | --drifting) FILE");
+
+    assert_eq!(
+        elements,
+        vec![
+            DocoptElement::Positional { name: String::from("mine"), description: Some(String::from("Command")), multiple: false },
+            DocoptElement::Positional { name: String::from("set|remove"), description: Some(String::from("One of: set, remove")), multiple: false },
+            DocoptElement::Positional { name: String::from("x"), description: None, multiple: true },
+            DocoptElement::ValueOption { key: String::from("--speed"), name: String::from("kn"), required: false, multiple: false },
+            DocoptElement::Flag { key: String::from("--moored"), multiple: false },
+            DocoptElement::Flag { key: String::from("--drifting"), multiple: false },
+            DocoptElement::Positional { name: String::from("FILE"), description: None, multiple: false },
+        ],
+    )
+}
+
+#[test]
+fn test_parse_docopt_required_value_option() {
+    let cli_arguments = parse_help_string("Usage: tool --output=<file> [--level=<n>]").unwrap();
+
+    assert_eq!(cli_arguments.arguments[0].key, "--output");
+    assert_eq!(cli_arguments.options[0].key, "--level");
+}
+
+#[test]
+fn parse_docopt() {
+    let cli_arguments = parse_help_string(&get_test_docopt_help_string()).unwrap();
+
+    let positional_names: Vec<&str> = cli_arguments.positionals.iter().map(|positional| positional.name.as_str()).collect();
+    let option_keys: Vec<&str> = cli_arguments.options.iter().map(|option| option.key.as_str()).collect();
+    let flag_keys: Vec<&str> = cli_arguments.flags.iter().map(|flag| flag.key.as_str()).collect();
+    assert_eq!(cli_arguments.cli_name, "naval_fate");
+    assert_eq!(cli_arguments.cli_lib, CLILib::Docopt);
+    assert_eq!(positional_names, vec!["ship", "new", "name", "move", "x", "y", "mine", "set|remove"]);
+    assert!(cli_arguments.positionals[2].multiple);
+    assert_eq!(option_keys, vec!["--speed"]);
+    assert_eq!(flag_keys, vec!["--moored", "--drifting", "-h", "--help", "--version"]);
+    assert_eq!(
+        cli_arguments.exclusive_groups,
+        vec![vec![String::from("--moored"), String::from("--drifting")]],
+    );
+}
+
+#[allow(dead_code)]
+fn get_test_gnu_help_string() -> String {
+    String::from("Usage: ls [OPTION]... [FILE]...
+List information about the FILEs (the current directory by default).
+
+Mandatory arguments to long options are mandatory for short options too.
+  -a, --all                  do not ignore entries starting with .
+      --block-size=SIZE      with -l, scale sizes by SIZE when printing them;
+                               e.g., '--block-size=M'
+  -C                         list entries by columns
+      --color[=WHEN]
+                             color the output WHEN
+  -w, --width=COLS           set output width to COLS
+  -a, --all                  listed twice
+
+Exit status:
+ 0  if OK,")
+}
+
+#[test]
+fn test_parse_gnu_invocation_optional_value() {
+    let parameter = parse_gnu_invocation("--color[=WHEN]", Some(String::from("color the output WHEN"))).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Argument(CLIArgument {
+            key: String::from("--color"),
+            long_key: Some(String::from("--color")),
+            name: String::from("WHEN"),
+            description: Some(String::from("color the output WHEN")),
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn test_parse_gnu_invocation_short_value() {
+    let parameter = parse_gnu_invocation("-o FILE", None).unwrap();
+
+    assert_eq!(
+        parameter,
+        CLIParameter::Argument(CLIArgument {
+            key: String::from("-o"),
+            short_key: Some(String::from("-o")),
+            name: String::from("FILE"),
+            ..Default::default()
+        })
+    )
+}
+
+#[test]
+fn parse_gnu() {
+    let cli_arguments = parse_help_string(&get_test_gnu_help_string());
+
+    let expected_cli_arguments = Ok(CLIParameters {
+        cli_name: String::from("ls"),
+        positionals: vec![
+            CLIPositional {
+                name: String::from("FILE"),
+                multiple: true,
+                ..Default::default()
+            },
+        ],
+        options: vec![
+            CLIArgument {
+                key: String::from("--block-size"),
+                long_key: Some(String::from("--block-size")),
+                name: String::from("SIZE"),
+                description: Some(String::from("with -l, scale sizes by SIZE when printing them; e.g., '--block-size=M'")),
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--color"),
+                long_key: Some(String::from("--color")),
+                name: String::from("WHEN"),
+                description: Some(String::from("color the output WHEN")),
+                ..Default::default()
+            },
+            CLIArgument {
+                key: String::from("--width"),
+                short_key: Some(String::from("-w")),
+                long_key: Some(String::from("--width")),
+                name: String::from("COLS"),
+                description: Some(String::from("set output width to COLS")),
+                ..Default::default()
+            },
+        ],
+        flags: vec![
+            CLIFlag {
+                key: String::from("--all"),
+                short_key: Some(String::from("-a")),
+                long_key: Some(String::from("--all")),
+                description: Some(String::from("do not ignore entries starting with .")),
+                ..Default::default()
+            },
+            CLIFlag {
+                key: String::from("-C"),
+                short_key: Some(String::from("-C")),
+                description: Some(String::from("list entries by columns")),
+                ..Default::default()
+            },
+        ],
+        cli_lib: CLILib::Gnu,
+        ..Default::default()
+    });
+    assert_eq!(cli_arguments, expected_cli_arguments);
+}
+
+#[test]
+fn parse_gnu_curl_style() {
+    let cli_arguments = parse_help_string("Usage: curl [options...] <url>
+ -d, --data <data>          HTTP POST data
+ -f, --fail                 Fail fast with no output on HTTP errors
+ -o, --output <file>        Write to file instead of stdout
+ -u, --user <user:password> Server user and password
+ -v, --verbose              Make the operation more talkative
+
+This is not the full help, this menu is stripped into categories.").unwrap();
+
+    let options: Vec<(&str, &str)> = cli_arguments.options.iter().map(|option| (option.key.as_str(), option.name.as_str())).collect();
+    let flag_keys: Vec<&str> = cli_arguments.flags.iter().map(|flag| flag.key.as_str()).collect();
+    assert_eq!(cli_arguments.cli_lib, CLILib::Gnu);
+    assert_eq!(cli_arguments.cli_name, "curl");
+    assert_eq!(options, vec![("--data", "<data>"), ("--output", "<file>"), ("--user", "<user:password>")]);
+    assert_eq!(flag_keys, vec!["--fail", "--verbose"]);
+    assert!(cli_arguments.positionals.is_empty());
+}
+
+#[test]
+fn test_convert_to_cli_runs_invoked_program() {
+    let mut parameters = CLIParameters {
+        cli_name: String::from("greeter.exe"),
+        command_path: vec![String::from("hello")],
+        flags: vec![CLIFlag { key: String::from("--caps"), set: true, ..Default::default() }],
+        ..Default::default()
+    };
+    parameters.set_program(&[String::from("python"), String::from("greeter.py")]);
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("python");
+    expected_cli_command.args(["greeter.py", "hello", "--caps"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_convert_to_cli_joined_values() {
+    let mut parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        options: vec![
+            CLIArgument { key: String::from("--color"), values: vec![String::from("never")], joined: true, ..Default::default() },
+            CLIArgument { key: String::from("--offset"), values: vec![String::from("-5")], ..Default::default() },
+            CLIArgument { key: String::from("--name"), short_key: Some(String::from("-n")), values: vec![String::from("-x")], ..Default::default() },
+        ],
+        ..Default::default()
+    };
+    parameters.set_joined(&[String::from("-n")]);
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.args(["--color=never", "--offset=-5", "--name=-x"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_meta_flags_skipped_in_bulk() {
+    let mut parameters = CLIParameters {
+        flags: vec![
+            CLIFlag { key: String::from("--help"), short_key: Some(String::from("-h")), ..Default::default() },
+            CLIFlag { key: String::from("--version"), short_key: Some(String::from("-V")), ..Default::default() },
+            CLIFlag { key: String::from("--human-readable"), short_key: Some(String::from("-h")), ..Default::default() },
+        ],
+        ..Default::default()
+    };
+
+    let skipped = |parameters: &CLIParameters| parameters.flags.iter().map(CLIFlag::is_skipped_in_bulk).collect::<Vec<bool>>();
+    assert_eq!(skipped(&parameters), [true, true, false]);
+    parameters.set_passed_meta_flags(&[String::from("-V")]);
+    assert_eq!(skipped(&parameters), [true, false, false]);
+}
+
+#[test]
+fn test_fingerprint() {
+    let parameters = CLIParameters {
+        command_path: vec![String::from("build")],
+        options: vec![
+            CLIArgument { key: String::from("--target"), values: vec![String::from("wasm32-unknown-unknown")], ..Default::default() },
+            CLIArgument { key: String::from("--jobs"), values: vec![String::new()], ..Default::default() },
+        ],
+        flags: vec![
+            CLIFlag { key: String::from("--release"), set: true, ..Default::default() },
+            CLIFlag { key: String::from("--color"), negated_key: Some(String::from("--no-color")), set: true, negated: true, ..Default::default() },
+            CLIFlag { key: String::from("--help"), set: true, ..Default::default() },
+        ],
+        positionals: vec![CLIPositional { name: String::from("SPEC"), values: vec![String::from("clitui")], ..Default::default() }],
+        ..Default::default()
+    };
+
+    assert_eq!(parameters.fingerprint(), "build --help --no-color --release --target SPEC");
+}
+
+#[test]
+fn test_parse_clap_joined_option_line() {
+    let Some(CLIParameter::Argument(argument)) = parse_clap_option_line("      --color=<WHEN>  When to use colors") else {
+        panic!("--color=<WHEN> is not an argument");
+    };
+
+    assert_eq!(argument.key, "--color");
+    assert_eq!(argument.name, "WHEN");
+    assert!(argument.joined);
+}
+
+#[test]
+fn test_convert_to_gnu_cli() {
+    let parameters = CLIParameters {
+        cli_name: String::from("ls"),
+        positionals: vec![
+            CLIPositional { name: String::from("FILE"), values: vec![String::from("src")], ..Default::default() },
+        ],
+        options: vec![
+            CLIArgument { key: String::from("--color"), values: vec![String::from("never")], ..Default::default() },
+            CLIArgument { key: String::from("-o"), values: vec![String::from("out")], ..Default::default() },
+        ],
+        flags: vec![
+            CLIFlag { key: String::from("--all"), set: true, ..Default::default() },
+        ],
+        cli_lib: CLILib::Gnu,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("ls");
+    expected_cli_command.args(["--color=never", "-o", "out", "--all", "src"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_convert_to_gnu_cli_short_keys() {
+    let parameters = CLIParameters {
+        cli_name: String::from("ls"),
+        options: vec![
+            CLIArgument { key: String::from("--width"), short_key: Some(String::from("-w")), values: vec![String::from("80")], ..Default::default() },
+            CLIArgument { key: String::from("--color"), values: vec![String::from("never")], ..Default::default() },
+        ],
+        flags: vec![
+            CLIFlag { key: String::from("--all"), short_key: Some(String::from("-a")), set: true, ..Default::default() },
+        ],
+        cli_lib: CLILib::Gnu,
+        key_style: KeyStyle::Short,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("ls");
+    expected_cli_command.args(["-w", "80", "--color=never", "-a"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn parse_windows_xcopy() {
+    let help_string = "Copies files and directory trees.
+
+XCOPY source [destination] [/A | /M] [/D[:date]] [/P] [/S [/E]]
+
+  source       Specifies the file(s) to copy.
+  destination  Specifies the location and/or name of new files.
+  /A           Copies only files with the archive attribute set,
+               doesn't change the attribute.
+  /M           Copies only files with the archive attribute set,
+               turns off the archive attribute.
+  /D:m-d-y     Copies files changed on or after the specified date.
+  /P           Prompts you before creating each destination file.
+  /S           Copies directories and subdirectories except empty ones.
+  /E           Copies directories and subdirectories, including empty ones.
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_lib, CLILib::WindowsSlash);
+    assert_eq!(parameters.cli_name, "XCOPY");
+    assert_eq!(parameters.positionals, vec![
+        CLIPositional { name: String::from("source"), description: Some(String::from("Specifies the file(s) to copy.")), required: true, ..Default::default() },
+        CLIPositional { name: String::from("destination"), description: Some(String::from("Specifies the location and/or name of new files.")), ..Default::default() },
+    ]);
+    assert_eq!(parameters.flags[0].key, "/A");
+    assert_eq!(parameters.flags[0].description.as_deref(), Some("Copies only files with the archive attribute set, doesn't change the attribute."));
+    assert_eq!(parameters.options, vec![CLIArgument {
+        key: String::from("/D"),
+        name: String::from("M-D-Y"),
+        description: Some(String::from("Copies files changed on or after the specified date.")),
+        joined: true,
+        ..Default::default()
+    }]);
+    assert_eq!(parameters.flags.len(), 5);
+}
+
+#[test]
+fn parse_windows_robocopy() {
+    let help_string = "-------------------------------------------------------------------------------
+   ROBOCOPY     ::     Robust File Copy for Windows
+-------------------------------------------------------------------------------
+
+              Usage :: ROBOCOPY source destination [file [file]...] [options]
+
+             source :: Source Directory (drive:\\path or \\\\server\\share\\path).
+        destination :: Destination Dir  (drive:\\path or \\\\server\\share\\path).
+               file :: File(s) to copy  (names/wildcards: default is \"*.*\").
+
+::
+:: Copy options :
+::
+                 /S :: copy Subdirectories, but not empty ones.
+               /MIR :: MIRror a directory tree (equivalent to /E plus /PURGE).
+             /LEV:n :: only copy the top n LEVels of the source directory tree.
+           /LOG:file :: output status to LOG file (overwrite existing log).
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_lib, CLILib::WindowsSlash);
+    assert_eq!(parameters.cli_name, "ROBOCOPY");
+    assert_eq!(parameters.positionals.iter().map(|positional| positional.name.as_str()).collect::<Vec<&str>>(), ["source", "destination", "file"]);
+    assert!(parameters.positionals[2].multiple);
+    assert!(!parameters.positionals[2].required);
+    assert_eq!(parameters.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["/S", "/MIR"]);
+    assert_eq!(parameters.options[0].value_type, ValueType::Number);
+    assert_eq!(parameters.options[1].key, "/LOG");
+    assert_eq!(parameters.options[1].value_type, ValueType::Path);
+}
+
+#[test]
+fn test_convert_to_windows_cli() {
+    let parameters = CLIParameters {
+        cli_name: String::from("robocopy"),
+        positionals: vec![
+            CLIPositional { name: String::from("source"), values: vec![String::from("src")], ..Default::default() },
+            CLIPositional { name: String::from("destination"), values: vec![String::from("dst")], ..Default::default() },
+        ],
+        options: vec![
+            CLIArgument { key: String::from("/LOG"), values: vec![String::from("copy.log")], ..Default::default() },
+        ],
+        flags: vec![
+            CLIFlag { key: String::from("/MIR"), set: true, ..Default::default() },
+        ],
+        cli_lib: CLILib::WindowsSlash,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("robocopy");
+    expected_cli_command.args(["src", "dst", "/LOG:copy.log", "/MIR"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn parse_clap_usage_with_short_key() {
+    let help_string = "Usage: greeter.exe [OPTIONS] -n <NAME>
+
+Options:
+  -n, --name <NAME>    Name of the person to greet
+  -h, --help           Print help
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.arguments[0].key, "--name");
+    assert!(parameters.arguments[0].required);
+}
+
+#[test]
+fn parse_clap_described_conflicts() {
+    let help_string = "Usage: report [OPTIONS]
+
+Options:
+      --json           Print JSON, cannot be used with --yaml or --csv
+      --yaml           Print YAML
+      --csv            Print CSV
+  -o, --output <FILE>  Write to a file, conflicts with: -s
+  -s, --stdout         Write to stdout
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.exclusive_groups, vec![
+        vec![String::from("--output"), String::from("--stdout")],
+        vec![String::from("--json"), String::from("--yaml")],
+        vec![String::from("--json"), String::from("--csv")],
+    ]);
+    assert_eq!(parameters.conflicting_keys("--json"), vec!["--yaml", "--csv"]);
+}
+
+#[test]
+fn parse_clap_counted_flags() {
+    let help_string = "Usage: tool [OPTIONS]
+
+Options:
+  -v, --verbose...  Increase logging verbosity
+  -q, --quiet       Decrease verbosity, can be used multiple times
+  -h, --help        Print help
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    let multiple: Vec<bool> = parameters.flags.iter().map(|flag| flag.multiple).collect();
+    assert_eq!(multiple, [true, true, false]);
+    assert_eq!(parameters.flags[0].description.as_deref(), Some("Increase logging verbosity"));
+}
+
+#[test]
+fn parse_gnu_negatable_flags() {
+    let help_string = "Usage: tool [OPTION]...
+
+Options:
+      --color       colorize the output
+  -q, --quiet       print nothing
+      --no-color    do not colorize the output
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    let keys: Vec<&str> = parameters.flags.iter().map(|flag| flag.key.as_str()).collect();
+    assert_eq!(keys, ["--color", "--quiet"]);
+    assert_eq!(parameters.flags[0].negated_key.as_deref(), Some("--no-color"));
+    assert_eq!(parameters.flags[0].description.as_deref(), Some("colorize the output"));
+}
+
+#[test]
+fn parse_argparse_negatable_flag_in_one_row() {
+    let help_string = "usage: tool [-h] [--color | --no-color]
+
+options:
+  -h, --help           show this help message and exit
+  --color, --no-color  colorize the output
+";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.flags[1].key, "--color");
+    assert_eq!(parameters.flags[1].negated_key.as_deref(), Some("--no-color"));
+    assert!(parameters.flags[1].aliases.is_empty());
+}
+
+#[test]
+fn test_convert_to_cli_negated_flag() {
+    let parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        flags: vec![CLIFlag {
+            key: String::from("--color"),
+            negated_key: Some(String::from("--no-color")),
+            set: true,
+            negated: true,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.arg("--no-color");
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[allow(dead_code)]
+struct KeyValueParser;
+
+impl HelpParser for KeyValueParser {
+    fn name(&self) -> &'static str {
+        "key-value"
+    }
+
+    fn confidence(&self, help_string: &str) -> u8 {
+        if help_string.starts_with("keys:") { 100 } else { 0 }
+    }
+
+    fn parse(&self, help_string: &str) -> Result<CLIParameters, ParseError> {
+        let keys = help_string.strip_prefix("keys:").ok_or(ParseError { parser: self.name(), message: String::from("no keys: prefix"), ..Default::default() })?;
+        Ok(CLIParameters {
+            cli_name: String::from("tool"),
+            flags: keys.split_whitespace().map(|key| CLIFlag { key: key.to_string(), ..Default::default() }).collect(),
+            ..Default::default()
+        })
+    }
+}
+
+#[test]
+fn test_parse_help_string_with_plugged_in_parser() {
+    let parsers: [&dyn HelpParser; 2] = [&CLILib::Gnu, &KeyValueParser];
+
+    let parameters = parse_help_string_with_parsers("keys: --color --no-color", &parsers).unwrap();
+
+    assert_eq!(parameters.flags.len(), 1);
+    assert_eq!(parameters.flags[0].negated_key.as_deref(), Some("--no-color"));
+}
+
+#[test]
+fn test_parse_help_string_with_parsers_errors() {
+    let parsers: [&dyn HelpParser; 2] = [&CLILib::Gnu, &KeyValueParser];
+
+    let errors = parse_help_string_with_parsers("nothing to see", &parsers).unwrap_err();
+
+    assert_eq!(
+        errors.iter().map(ToString::to_string).collect::<Vec<String>>(),
+        ["gnu: no Usage: line, line 1 is \"nothing to see\"", "key-value: no keys: prefix"],
+    );
+    assert_eq!(errors[0].line, Some((1, String::from("nothing to see"))));
+    assert!(errors[0].suggestion.is_some());
+}
+
+#[test]
+fn test_find_unparsed_lines() {
+    let help_string = "Usage: ls [OPTION]...\n  -a, --all       do not ignore entries\n      --color[=WHEN]  color the output\n  -Z, --context   print security context\n";
+    let parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--color"), ..Default::default() }],
+        flags: vec![CLIFlag { key: String::from("--all"), short_key: Some(String::from("-a")), ..Default::default() }],
+        ..Default::default()
+    };
+
+    assert_eq!(find_unparsed_lines(help_string, &parameters), [String::from("-Z, --context   print security context")]);
+}
+
+#[test]
+fn test_parse_spec() {
+    let spec = r#"
+name = "mytool"
+style = "gnu"
+
+[[arguments]]
+key = "--input"
+short_key = "-i"
+name = "FILE"
+
+[[options]]
+key = "--count"
+default = "1"
+description = "Number of runs"
+joined = true
+
+[[positionals]]
+name = "TARGET"
+multiple = true
+
+[[flags]]
+short_key = "-v"
+multiple = true
+"#;
+
+    let parameters = parse_spec(spec).unwrap();
+
+    assert_eq!(parameters.cli_name, "mytool");
+    assert_eq!(parameters.cli_lib, CLILib::Gnu);
+    assert_eq!(parameters.arguments[0].key, "--input");
+    assert_eq!(parameters.arguments[0].short_key.as_deref(), Some("-i"));
+    assert!(parameters.arguments[0].required);
+    assert_eq!(parameters.arguments[0].value_type, ValueType::Path);
+    assert_eq!(parameters.options[0].name, "COUNT");
+    assert_eq!(parameters.options[0].default_value.as_deref(), Some("1"));
+    assert!(parameters.options[0].joined);
+    assert_eq!(parameters.positionals[0].name, "TARGET");
+    assert_eq!(parameters.flags[0].key, "-v");
+    assert!(parameters.flags[0].multiple);
+}
+
+#[test]
+fn test_parse_spec_key_kinds() {
+    let spec = "name = \"robocopy\"\n[[flags]]\nkey = \"-v\"\n[[flags]]\nkey = \"/MIR\"\n[[options]]\nkey = \"/LEV\"\n";
+
+    let parameters = parse_spec(spec).unwrap();
+
+    assert_eq!(parameters.flags[0].key, "-v");
+    assert_eq!(parameters.flags[0].short_key.as_deref(), Some("-v"));
+    assert_eq!(parameters.flags[0].long_key, None);
+    assert_eq!(parameters.flags[1].key, "/MIR");
+    assert_eq!((parameters.flags[1].short_key.as_deref(), parameters.flags[1].long_key.as_deref()), (None, None));
+    assert_eq!(parameters.options[0].name, "LEV");
+}
+
+#[test]
+fn test_parse_invalid_spec() {
+    assert_eq!(parse_spec("name = \"mytool\"\n[[flags]]\ndescription = \"Verbose\""), Err(String::from("Invalid spec: parameter without key or short_key")));
+    assert!(parse_spec("name = \"mytool\"\ncolour = true").unwrap_err().starts_with("Invalid spec:"));
+}
+
+#[test]
+fn test_parse_error_points_at_failing_line() {
+    let help_string = "Build things\n\nUsage:\n  tool [flags]\n\nFlags:\n  unknown row\n";
+
+    let error = CLILib::Cobra.parse(help_string).unwrap_err();
+    assert_eq!(error.line, Some((6, String::from("Flags:"))));
+    let error = CLILib::Gnu.parse("Build things\nusage: tool [OPTION]...\n").unwrap_err();
+    assert_eq!(error.line, Some((2, String::from("usage: tool [OPTION]..."))));
+    let error = CLILib::Argparse.parse("\nNothing to see here\n").unwrap_err();
+    assert_eq!(error.line, Some((2, String::from("Nothing to see here"))));
+}
+
+#[test]
+fn test_parse_json_help() {
+    let dump = r#"{
+  "name": "tool",
+  "args": [
+    {"id": "out", "short": "o", "long": "out", "value_names": ["DIR"], "required": true, "action": "Set", "value_hint": "DirPath"},
+    {"id": "format", "long": "format", "action": "Set", "default_values": ["json"], "possible_values": ["json", "yaml"], "help_heading": "Output"},
+    {"id": "verbose", "short": "v", "action": "Count", "help": "More output"},
+    {"id": "files", "value_names": ["FILE"], "num_args": "1..", "action": "Append"},
+    {"id": "json", "long": "json", "action": "SetTrue"},
+    {"id": "yaml", "long": "yaml", "action": "SetTrue"}
+  ],
+  "groups": [{"id": "style", "args": ["json", "yaml"], "multiple": false}],
+  "subcommands": [{"name": "build", "about": "Build it", "args": [{"id": "release", "long": "release", "action": "SetTrue"}]}]
+}"#;
+
+    let parameters = parse_json_help(dump).unwrap();
+
+    assert_eq!(parameters.cli_name, "tool");
+    assert_eq!(parameters.arguments[0].key, "--out");
+    assert_eq!(parameters.arguments[0].value_type, ValueType::Path);
+    assert_eq!(parameters.options[0].default_value.as_deref(), Some("json"));
+    assert_eq!(parameters.options[0].choices, vec![String::from("json"), String::from("yaml")]);
+    assert_eq!(parameters.options[0].group.as_deref(), Some("Output"));
+    assert_eq!(parameters.flags[0].key, "-v");
+    assert!(parameters.flags[0].multiple);
+    assert_eq!(parameters.positionals[0].name, "FILE");
+    assert!(parameters.positionals[0].multiple);
+    assert_eq!(parameters.exclusive_groups, vec![vec![String::from("--json"), String::from("--yaml")]]);
+    let build = parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(build.command_path, vec![String::from("build")]);
+    assert_eq!(build.flags[0].key, "--release");
+}
+
+#[test]
+fn test_parse_json_help_negated_flag() {
+    let dump = r#"{"name": "tool", "args": [
+    {"id": "color", "long": "color", "action": "SetTrue", "help": "Colored output \ud83c\udfa8"},
+    {"id": "no_color", "long": "no-color", "action": "SetFalse"},
+    {"id": "no_cache", "long": "no-cache", "action": "SetFalse", "help": "Skip the cache"}
+]}"#;
+
+    let parameters = parse_json_help(dump).unwrap();
+
+    assert_eq!(parameters.flags.len(), 2);
+    assert_eq!(parameters.flags[0].key, "--color");
+    assert_eq!(parameters.flags[0].negated_key.as_deref(), Some("--no-color"));
+    assert_eq!(parameters.flags[0].description.as_deref(), Some("Colored output \u{1f3a8}"));
+    assert_eq!(parameters.flags[1].key, "--no-cache");
+    assert_eq!(parameters.flags[1].negated_key, None);
+}
+
+#[test]
+fn test_parse_invalid_json_help() {
+    assert!(parse_json_help("{\"name\": \"tool\"").unwrap_err().starts_with("Invalid command dump:"));
+    assert_eq!(parse_json_help("{\"args\": []}"), Err(String::from("Invalid command dump: missing name")));
+}
+
+#[test]
+fn test_convert_to_cli_counted_flag() {
+    let mut verbose = CLIFlag { key: String::from("--verbose"), short_key: Some(String::from("-v")), multiple: true, ..Default::default() };
+    verbose.set_count(3);
+    let parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        flags: vec![verbose],
+        key_style: KeyStyle::Short,
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.args(["-v", "-v", "-v"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+#[test]
+fn test_parse_command_section() {
+    let help_string = "Rust's package manager
+
+Usage: cargo [OPTIONS] [COMMAND]
+
+Commands:
+    build, b    Compile the current package
+    new         Create a new cargo
+                package
+    help        Print this message or the help of the given subcommand(s)
+
+Options:
+  -h, --help  Print help";
+
+    let subcommands = parse_command_section(help_string);
+
+    assert_eq!(
+        subcommands,
+        vec![
+            CLISubcommand {
+                name: String::from("build"),
+                description: Some(String::from("Compile the current package")),
+                parameters: None,
+            },
+            CLISubcommand {
+                name: String::from("new"),
+                description: Some(String::from("Create a new cargo package")),
+                parameters: None,
+            },
+        ],
+    )
+}
+
+#[test]
+fn parse_argparse_subparsers() {
+    let help_string = "usage: tool.py [-h] {build,test,clean} ...
+
+positional arguments:
+  {build,test,clean}
+    build             Build the project
+    test              Run the tests
+                      of the project
+
+options:
+  -h, --help          show this help message and exit";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_lib, CLILib::Argparse);
+    assert_eq!(parameters.positionals, Vec::new());
+    assert_eq!(
+        parameters.subcommands,
+        vec![
+            CLISubcommand {
+                name: String::from("build"),
+                description: Some(String::from("Build the project")),
+                parameters: None,
+            },
+            CLISubcommand {
+                name: String::from("test"),
+                description: Some(String::from("Run the tests of the project")),
+                parameters: None,
+            },
+            CLISubcommand {
+                name: String::from("clean"),
+                ..Default::default()
+            },
+        ],
+    )
+}
+
+#[test]
+fn test_convert_to_cli_subcommand() {
+    let parameters = CLIParameters {
+        cli_name: String::from("cargo"),
+        command_path: vec![String::from("build")],
+        flags: vec![
+            CLIFlag { key: String::from("--release"), set: true, ..Default::default() },
+        ],
+        ..Default::default()
+    };
+
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("cargo");
+    expected_cli_command.args(["build", "--release"]);
+    assert_eq!(
+        format!("{:?}", cli_command),
+        format!("{:?}", expected_cli_command),
+    )
+}
+
+/// Benchmark with a synthetic clap help of 2000 options, like the help of ffmpeg, run with
+/// cargo test --release -- --ignored bench_parse_large_clap_help
+#[test]
+#[ignore]
+fn bench_parse_large_clap_help() {
+    use std::time::{Duration, Instant};
 
-    let argument = parse_clap_option_line(&option_line).unwrap();
+    let options: String = (0..2000)
+        .map(|index| format!("      --option-{index} <VALUE_{index}>  Synthetic option number {index} [default: {index}]\n"))
+        .collect();
+    let help_string = format!("Synthetic tool\n\nUsage: synthetic [OPTIONS]\n\nOptions:\n{options}  -h, --help  Print help\n");
 
-    assert_eq!(
-        argument,
-        CLIParameter::Argument(CLIArgument {
-            name: String::from("NAME"),
-            key: String::from("--name"),
-            description: None,
-            value: String::new(),
-        }),
-    )
+    let runs = 10;
+    let start = Instant::now();
+    for _ in 0..runs {
+        assert_eq!(parse_clap_help_string(&help_string).unwrap().options.len(), 2000);
+    }
+    let parse_time = start.elapsed() / runs;
+
+    println!("Average parse time: {parse_time:?}");
+    assert!(parse_time < Duration::from_millis(50));
 }
 
 #[test]
-fn test_parse_clap_option_flag() {
-    let option_line = "  -h, --help           Print help";
+fn test_mark_globals() {
+    let verbose = || CLIFlag { key: String::from("--verbose"), ..Default::default() };
+    let help = || CLIFlag { key: String::from("--help"), ..Default::default() };
+    let test = CLIParameters {
+        flags: vec![verbose(), help(), CLIFlag { key: String::from("--release"), ..Default::default() }],
+        ..Default::default()
+    };
+    let build = CLIParameters { flags: vec![verbose(), help()], ..Default::default() };
+    let mut parameters = CLIParameters {
+        flags: vec![verbose(), help()],
+        subcommands: vec![
+            CLISubcommand { name: String::from("build"), parameters: Some(build), ..Default::default() },
+            CLISubcommand {
+                name: String::from("test"),
+                parameters: Some(CLIParameters {
+                    subcommands: vec![CLISubcommand { name: String::from("unit"), parameters: Some(test), ..Default::default() }],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
 
-    let parameter = parse_clap_option_line(&option_line).unwrap();
+    parameters.mark_globals();
 
-    assert_eq!(
-        parameter,
-        CLIParameter::Flag(CLIFlag {
-            key: String::from("--help"),
-            description: Some(String::from("Print help")),
-            set: false,
-        })
-    )
+    assert!(parameters.flags[0].global);
+    assert!(!parameters.flags[1].global);
+    let build = parameters.subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(build.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["--help"]);
+    let unit = parameters.subcommands[1].parameters.as_ref().unwrap().subcommands[0].parameters.as_ref().unwrap();
+    assert_eq!(unit.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["--help", "--release"]);
 }
 
 #[test]
-fn test_parse_clap_option_flag_without_description() {
-    let option_line = "  -h, --help";
+fn test_mark_globals_keeps_different_options_with_same_key() {
+    let output = |name: &str, description: &str| CLIArgument {
+        key: String::from("--output"),
+        long_key: Some(String::from("--output")),
+        name: String::from(name),
+        description: Some(String::from(description)),
+        ..Default::default()
+    };
+    let build = CLIParameters { options: vec![output("FILE", "Path of the binary")], ..Default::default() };
+    let test = CLIParameters { options: vec![output("FORMAT", "Format of the report")], ..Default::default() };
+    let mut parameters = CLIParameters {
+        options: vec![output("FORMAT", "Format of the report")],
+        subcommands: vec![
+            CLISubcommand { name: String::from("build"), parameters: Some(build), ..Default::default() },
+            CLISubcommand { name: String::from("test"), parameters: Some(test), ..Default::default() },
+        ],
+        ..Default::default()
+    };
 
-    let parameter = parse_clap_option_line(&option_line).unwrap();
+    parameters.mark_globals();
 
-    assert_eq!(
-        parameter,
-        CLIParameter::Flag(CLIFlag {
-            key: String::from("--help"),
-            description: None,
-            set: false,
-        })
-    )
+    assert!(parameters.options[0].global);
+    assert_eq!(parameters.subcommands[0].parameters.as_ref().unwrap().options, [output("FILE", "Path of the binary")]);
+    assert!(parameters.subcommands[1].parameters.as_ref().unwrap().options.is_empty());
 }
 
 #[test]
-fn test_parse_clap_option_explanation() {
-    let arguments = parse_clap_option_explanation(&get_test_clap_option_explanation()).unwrap();
+fn test_convert_to_cli_inherited_globals() {
+    let parameters = CLIParameters {
+        cli_name: String::from("tool"),
+        command_path: vec![String::from("remote"), String::from("add")],
+        options: vec![CLIArgument {
+            key: String::from("--config"),
+            values: vec![String::from("ci.toml")],
+            inherited: Some(1),
+            ..Default::default()
+        }],
+        flags: vec![
+            CLIFlag { key: String::from("--force"), set: true, ..Default::default() },
+            CLIFlag { key: String::from("--verbose"), set: true, inherited: Some(0), ..Default::default() },
+        ],
+        ..Default::default()
+    };
 
-    assert_eq!(
-        arguments,
-        vec![
-            CLIParameter::Argument(CLIArgument {
-                name: String::from("FIRST_NAME"),
-                key: String::from("--first-name"),
-                description: Some(String::from("First name of the person to greet")),
-                value: String::new(),
-            }),
-            CLIParameter::Argument(CLIArgument {
-                name: String::from("LAST_NAME"),
-                key: String::from("--last-name"),
-                description: Some(String::from("Last name of the person to greet")),
-                value: String::new(),
-            }),
-            CLIParameter::Flag(CLIFlag {
-                key: String::from("--caps"),
-                description: Some(String::from("Greet in caps")),
-                set: false,
-            }),
-            CLIParameter::Flag(CLIFlag {
-                key: String::from("--german"),
-                description: Some(String::from("Greet in german")),
-                set: false,
-            }),
-            CLIParameter::Argument(CLIArgument {
-                name: String::from("COUNT"),
-                key: String::from("--count"),
-                description: Some(String::from("Number of times to greet")),
-                value: String::from("1"),
-            }),
-            CLIParameter::Flag(CLIFlag {
-                key: String::from("--help"),
-                description: Some(String::from("Print help")),
-                set: false,
-            }),
-            CLIParameter::Flag(CLIFlag {
-                key: String::from("--version"),
-                description: Some(String::from("Print version")),
-                set: false,
-            }),
-        ]
-    )
+    let cli_command = convert_to_cli(&parameters);
+
+    let mut expected_cli_command = Command::new("tool");
+    expected_cli_command.args(["--verbose", "remote", "--config", "ci.toml", "add", "--force"]);
+    assert_eq!(format!("{:?}", cli_command), format!("{:?}", expected_cli_command));
 }
 
 #[test]
-fn test_parse_usage_explanation() {
-    let usage_string = String::from("Usage: greeter.exe [OPTIONS] --name <NAME>");
-    
-    let argument_keys = parse_clap_usage_explanation(&usage_string);
+fn test_normalize_translated_headings() {
+    let help_string = "Begrüßt eine Person\n\nVerwendung: greeter [OPTIONEN] --name <NAME>\n\nOPTIONEN:\n  -n, --name <NAME>\n\nutilisation : tool.py [-h]\n";
 
     assert_eq!(
-        argument_keys,
-        (String::from("greeter.exe"), vec![String::from("--name")]),
-    )
+        normalize_headings(help_string, false),
+        "Begrüßt eine Person\n\nUsage: greeter [OPTIONS] --name <NAME>\n\nOPTIONS:\n  -n, --name <NAME>\n\nusage: tool.py [-h]\n"
+    );
 }
 
 #[test]
-fn test_parse_usage_explanation_multiple_words_in_key() {
-    let usage_string = String::from("Usage: greeter.exe [OPTIONS] --first-name <FIRST_NAME>");
-    
-    let argument_keys = parse_clap_usage_explanation(&usage_string);
+fn test_normalize_guessed_headings() {
+    let help_string = "Penggunaan: greeter [OPSI]\n\nOpsi:\n  -n, --name <NAME>\n";
 
-    assert_eq!(
-        argument_keys,
-        (String::from("greeter.exe"), vec![String::from("--first-name")]),
-    )
+    assert_eq!(normalize_headings(help_string, false), help_string);
+    assert_eq!(normalize_headings(help_string, true), "Usage: greeter [OPSI]\n\nOptions:\n  -n, --name <NAME>\n");
 }
 
 #[test]
-fn test_parse_usage_explanation_short_key() {
-    let usage_string = String::from("Usage: greeter.exe [OPTIONS] -n <FIRST_NAME>");
-    
-    let argument_keys = parse_clap_usage_explanation(&usage_string);
+fn parse_german_clap() {
+    let help_string = "Begrüßt eine Person
 
-    assert_eq!(
-        argument_keys,
-        (String::from("greeter.exe"), vec![String::from("-n")]),
-    )
+Verwendung: greeter [OPTIONEN] --name <NAME> [DATEI]
+
+Argumente:
+  [DATEI]  Datei mit weiteren Namen
+
+Optionen:
+  -n, --name <NAME>    Name der Person
+  -c, --count <COUNT>  Anzahl der Grüße [default: 1]
+      --caps           In Großbuchstaben grüßen
+  -h, --help           Hilfe anzeigen";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_lib, CLILib::Clap);
+    assert_eq!(parameters.cli_name, "greeter");
+    assert_eq!(parameters.arguments[0].key, "--name");
+    assert_eq!(parameters.positionals[0].name, "DATEI");
+    assert_eq!(parameters.options[0].key, "--count");
+    assert_eq!(parameters.flags[0].key, "--caps");
 }
 
 #[test]
-fn test_parse_clap_option_explanation_multiple_keys() {
-    let usage_string = String::from("Usage: greeter.exe [OPTIONS] --first-name <NAME> --count <COUNT>");
-    
-    let argument_keys = parse_clap_usage_explanation(&usage_string);
+fn parse_unknown_language_headings() {
+    let help_string = "Penggunaan: greeter [OPSI] --name <NAME>
 
-    assert_eq!(
-        argument_keys,
-        (String::from("greeter.exe"), vec![String::from("--first-name"), String::from("--count")]),
-    )
+Opsi:
+  -n, --name <NAME>  Nama orang
+      --caps         Sapa dengan huruf besar
+  -h, --help         Cetak bantuan";
+
+    let parameters = parse_help_string(help_string).unwrap();
+
+    assert_eq!(parameters.cli_name, "greeter");
+    assert_eq!(parameters.arguments[0].key, "--name");
+    assert_eq!(parameters.flags[0].key, "--caps");
 }
 
 #[test]
-fn parse_clap() {
-    let help_string = get_test_clap_help_string();
-    let cli_arguments = parse_help_string(&help_string);
+fn test_split_shell_words() {
+    let words = split_shell_words("'(-c --count)'{-c+,--count=}'[Number of times]:count:' \\\n  \"it's\" 'don'\\''t'");
 
-    let expected_cli_arguments = Some(CLIParameters {
-        cli_name: String::from("greeter.exe"),
-        arguments: vec![
-            CLIArgument {
-                name: String::from("FIRST_NAME"),
-                key: String::from("--first-name"),
-                description: Some(String::from("First name of the person to greet")),
-                value: String::new(),
-            },
-            CLIArgument {
-                name: String::from("LAST_NAME"),
-                key: String::from("--last-name"),
-                description: Some(String::from("Last name of the person to greet")),
-                value: String::new(),
-            },
-        ],
-        options: vec![
-            CLIArgument {
-                name: String::from("COUNT"),
-                key: String::from("--count"),
-                description: Some(String::from("Number of times to greet")),
-                value: String::from("1")
-            }
-        ],
-        flags: vec![
-            CLIFlag {
-                key: String::from("--caps"),
-                description: Some(String::from("Greet in caps")),
-                set: false,
-            },
-            CLIFlag {
-                key: String::from("--german"),
-                description: Some(String::from("Greet in german")),
-                set: false,
-            },
-            CLIFlag {
-                key: String::from("--help"),
-                description: Some(String::from("Print help")),
-                set: false,
-            },
-            CLIFlag {
-                key: String::from("--version"),
-                description: Some(String::from("Print version")),
-                set: false,
-            },
-        ],
-        cli_lib: CLILib::Clap,
-    });
-    assert_eq!(cli_arguments, expected_cli_arguments);
+    assert_eq!(words, ["(-c --count)-c+[Number of times]:count:", "(-c --count)--count=[Number of times]:count:", "it's", "don't"]);
 }
 
 #[test]
-fn test_convert_to_cli() {
-    let parameters = CLIParameters {
-        cli_name: String::from("greeter.exe"),
-        arguments: vec![
-            CLIArgument {
-                name: String::from("FIRST NAME"),
-                key: String::from("--first-name"),
-                description: Some(String::from("First name of the person to greet")),
-                value: String::from("Ferris"),
-            },
-            CLIArgument {
-                name: String::from("LAST NAME"),
-                key: String::from("--last-name"),
-                description: Some(String::from("Last name of the person to greet")),
-                value: String::from("the Crab"),
-            },
-        ],
-        options: vec![
-            CLIArgument {
-                name: String::from("COUNT"),
-                key: String::from("--count"),
-                description: Some(String::from("Number of times to greet")),
-                value: String::from("5")
-            }
-        ],
-        flags: vec![
-            CLIFlag {
-                key: String::from("--caps"),
-                description: Some(String::from("Greet in caps")),
-                set: true,
-            },
-            CLIFlag {
-                key: String::from("--german"),
-                description: Some(String::from("Greet in german")),
-                set: false,
-            },
-            CLIFlag {
-                key: String::from("--help"),
-                description: Some(String::from("Print help")),
-                set: false,
-            },
-            CLIFlag {
-                key: String::from("--version"),
-                description: Some(String::from("Print version")),
-                set: false,
-            },
-        ],
-        cli_lib: CLILib::Clap,
-    };
+fn parse_zsh_completion_of_clap() {
+    let script = r#"#compdef greeter
 
-    let cli_command = convert_to_cli(&parameters);
+_greeter() {
+    typeset -A opt_args
+    typeset -a _arguments_options
+    local ret=1
 
-    let mut expected_cli_command = Command::new("greeter.exe");
-    expected_cli_command.args(["--count", "5", "--caps", "--first-name", "Ferris", "--last-name", "the Crab"]);
-    assert_eq!(
-        format!("{:?}", cli_command),
-        format!("{:?}", expected_cli_command),
+    if is-at-least 5.2; then
+        _arguments_options=(-s -S -C)
+    else
+        _arguments_options=(-s -C)
+    fi
+
+    local context curcontext="$curcontext" state line
+    _arguments "${_arguments_options[@]}" : \
+'-c+[Number of times to greet]:COUNT:_default' \
+'--count=[Number of times to greet]:COUNT:_default' \
+'--color=[When to color the greeting]:WHEN:(auto always never)' \
+'-o+[File to write the greeting to]:OUTPUT:_files' \
+'--output=[File to write the greeting to]:OUTPUT:_files' \
+'--caps[Greet in caps]' \
+'*-v[More output per occurrence]' \
+'*--verbose[More output per occurrence]' \
+'-h[Print help]' \
+'--help[Print help]' \
+':name -- Name of the person to greet:_default' \
+'*::friends -- Friends to greet as well:_default' \
+&& ret=0
+}
+
+if [ "$funcstack[1]" = "_greeter" ]; then
+    _greeter "$@"
+else
+    compdef _greeter greeter
+fi"#;
+
+    let parameters = parse_completion_script(script, "greeter").unwrap();
+
+    assert_eq!(parameters.cli_name, "greeter");
+    assert_eq!(parameters.options.len(), 3);
+    assert_eq!(parameters.options[0].key, "--count");
+    assert_eq!(parameters.options[0].short_key.as_deref(), Some("-c"));
+    assert_eq!(parameters.options[0].value_type, ValueType::Number);
+    assert_eq!(parameters.options[1].choices, ["auto", "always", "never"]);
+    assert_eq!(parameters.options[2].value_type, ValueType::Path);
+    assert_eq!(parameters.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["--caps", "--verbose", "--help"]);
+    assert!(parameters.flags[1].multiple);
+    assert_eq!(parameters.positionals, vec![
+        CLIPositional { name: String::from("NAME"), description: Some(String::from("Name of the person to greet")), required: true, ..Default::default() },
+        CLIPositional { name: String::from("FRIENDS"), description: Some(String::from("Friends to greet as well")), multiple: true, ..Default::default() },
+    ]);
+}
+
+#[test]
+fn parse_zsh_completion_with_exclusions() {
+    let script = "#compdef report
+_arguments -s \\
+  '(-j --json --yaml)'{-j,--json}'[Print JSON]' \\
+  '(-j --json)--yaml[Print YAML]' \\
+  '--level=-[Log level]:level:((debug\\:\"Everything\" info\\:\"Less\"))' \\
+  '1:input file:_files'";
+
+    let parameters = parse_completion_script(script, "report").unwrap();
+
+    assert_eq!(parameters.flags[0].key, "--json");
+    assert_eq!(parameters.flags[0].short_key.as_deref(), Some("-j"));
+    assert_eq!(parameters.exclusive_groups, vec![vec![String::from("--json"), String::from("--yaml")]]);
+    assert_eq!(parameters.options[0].choices, ["debug", "info"]);
+    assert!(parameters.options[0].joined);
+    assert_eq!(parameters.positionals[0].name, "INPUT_FILE");
+}
+
+#[test]
+fn parse_bash_completion_of_clap() {
+    let script = r#"_greeter() {
+    local i cur prev opts cmd
+    COMPREPLY=()
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+    cmd=""
+    opts=""
+
+    case "${cmd}" in
+        greeter)
+            opts="-c -h --count --color --caps --help <NAME> [FRIENDS]... build help"
+            if [[ ${cur} == -* || ${COMP_CWORD} -eq 1 ]] ; then
+                COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
+                return 0
+            fi
+            case "${prev}" in
+                --count|-c)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --color)
+                    COMPREPLY=($(compgen -W "auto always never" -- "${cur}"))
+                    return 0
+                    ;;
+                *)
+                    COMPREPLY=()
+                    ;;
+            esac
+            ;;
+        greeter__build)
+            opts="-r -h --release --help"
+            case "${prev}" in
+                --target)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+            esac
+            ;;
+    esac
+}
+
+complete -F _greeter -o nosort -o bashdefault -o default greeter"#;
+
+    let parameters = parse_completion_script(script, "greeter").unwrap();
+
+    assert_eq!(parameters.cli_lib, CLILib::Clap);
+    assert_eq!(parameters.options.len(), 2);
+    assert_eq!(parameters.options[0].key, "--count");
+    assert_eq!(parameters.options[0].short_key.as_deref(), Some("-c"));
+    assert_eq!(parameters.options[1].choices, ["auto", "always", "never"]);
+    assert_eq!(parameters.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["-h", "--caps", "--help"]);
+    assert_eq!(parameters.positionals.iter().map(|positional| (positional.name.as_str(), positional.required, positional.multiple)).collect::<Vec<_>>(), [("NAME", true, false), ("FRIENDS", false, true)]);
+}
+
+#[test]
+fn parse_bash_completion_word_list() {
+    let parameters = parse_completion_script("complete -W \"--all --color= --quiet\" mytool", "mytool").unwrap();
+
+    assert_eq!(parameters.cli_lib, CLILib::Gnu);
+    assert_eq!(parameters.options[0].key, "--color");
+    assert!(parameters.options[0].joined);
+    assert_eq!(parameters.flags.len(), 2);
+    assert_eq!(parse_completion_script("complete -F _other other", "mytool"), None);
+}
+
+#[test]
+fn parse_fish_completion_file() {
+    let script = r#"
+complete -c greeter -f
+complete -c greeter -s c -l count -x -a '1 2 3' -d 'Number of times to greet'
+complete -c greeter -l color -xa 'auto always never' -d 'When to color the greeting'
+complete -c greeter -s o -l output -r -F -d 'File to write to'
+complete -c greeter -l caps -d 'Greet in caps'
+complete -c greeter -n __fish_use_subcommand -a 'build test' -d 'Run a subcommand'
+complete -c greeter -n '__fish_seen_subcommand_from build' -l release -d 'Build in release mode'
+complete -c other -l ignored
+"#;
+
+    let parameters = parse_completion_script(script, "greeter").unwrap();
+
+    let options: Vec<(&str, Option<&str>)> = parameters.options.iter().map(|option| (option.key.as_str(), option.short_key.as_deref())).collect();
+    assert_eq!(options, [("--count", Some("-c")), ("--color", None), ("--output", Some("-o"))]);
+    assert_eq!(parameters.options[1].choices, ["auto", "always", "never"]);
+    assert_eq!(parameters.options[2].value_type, ValueType::Path);
+    assert_eq!(parameters.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["--caps"]);
+    assert_eq!(parameters.subcommands.iter().map(|subcommand| subcommand.name.as_str()).collect::<Vec<&str>>(), ["build", "test"]);
+}
+
+#[test]
+fn test_merge_completion() {
+    let mut parameters = CLIParameters {
+        options: vec![CLIArgument { key: String::from("--color"), description: Some(String::from("Color")), ..Default::default() }],
+        flags: vec![CLIFlag { key: String::from("--caps"), ..Default::default() }],
+        ..Default::default()
+    };
+    let completion = parse_completion_script(
+        "complete -c greeter -l color -xa 'auto never' -d 'When to color'\ncomplete -c greeter -s C -l caps\ncomplete -c greeter -l json -d 'Print JSON'",
+        "greeter",
     )
+    .unwrap();
+
+    parameters.merge_completion(completion);
+
+    assert_eq!(parameters.options[0].description.as_deref(), Some("When to color"));
+    assert_eq!(parameters.options[0].choices, ["auto", "never"]);
+    assert_eq!(parameters.flags[0].short_key.as_deref(), Some("-C"));
+    assert_eq!(parameters.flags.iter().map(|flag| flag.key.as_str()).collect::<Vec<&str>>(), ["--caps", "--json"]);
 }
+
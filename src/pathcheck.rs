@@ -0,0 +1,133 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crate::parsing::PathCheck;
+
+/// What is at the path a value names
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PathState {
+    Exists,
+    /// Nothing is there, but the directory it would be created in exists
+    WillBeCreated,
+    /// Neither the path nor its directory exist
+    Missing,
+}
+
+impl PathState {
+    #[allow(clippy::needless_return)]
+    pub fn of(path: &str) -> PathState {
+        let path = Path::new(path);
+        if path.exists() {
+            return PathState::Exists;
+        }
+        // a relative file name is created in the working directory
+        match path.parent() {
+            Some(parent) if parent.as_os_str().is_empty() || parent.is_dir() => return PathState::WillBeCreated,
+            _ => return PathState::Missing,
+        }
+    }
+
+    /// Whether the state breaks the check of the parameter, which refuses to run the command
+    #[allow(clippy::needless_return)]
+    pub fn violates(&self, check: PathCheck) -> bool {
+        match check {
+            PathCheck::Any => return false,
+            PathCheck::MustExist => return *self != PathState::Exists,
+            PathCheck::MustNotExist => return *self == PathState::Exists,
+        }
+    }
+
+    /// Shown after the value in the form
+    #[allow(clippy::needless_return)]
+    pub fn label(&self) -> &'static str {
+        match self {
+            PathState::Exists => return "exists",
+            PathState::WillBeCreated => return "will be created",
+            PathState::Missing => return "missing",
+        }
+    }
+}
+
+/// Checks the paths on a thread of its own, a slow network mount does not block the form
+#[derive(Debug)]
+pub struct PathChecker {
+    requests: Sender<String>,
+    results: Receiver<(String, PathState)>,
+    /// Paths sent to the thread, each is checked once until `forget`
+    requested: BTreeSet<String>,
+}
+
+impl PathChecker {
+    pub fn spawn() -> PathChecker {
+        let (requests, path_receiver) = mpsc::channel::<String>();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            for path in path_receiver {
+                let state = PathState::of(&path);
+                if result_sender.send((path, state)).is_err() {
+                    return;
+                }
+            }
+        });
+        PathChecker { requests, results, requested: BTreeSet::new() }
+    }
+
+    /// Check the paths that were not requested before
+    pub fn request<'a>(&mut self, paths: impl Iterator<Item = &'a String>) {
+        for path in paths {
+            if self.requested.insert(path.clone()) {
+                let _ = self.requests.send(path.clone());
+            }
+        }
+    }
+
+    /// Move the finished checks into the states
+    pub fn poll(&self, states: &mut BTreeMap<String, PathState>) {
+        states.extend(self.results.try_iter());
+    }
+
+    /// Check every path again, e.g. after a command may have created or removed files
+    pub fn forget(&mut self, states: &mut BTreeMap<String, PathState>) {
+        self.poll(states);
+        self.requested.clear();
+        states.clear();
+    }
+}
+
+// Unit tests
+
+#[test]
+fn test_path_state_of() {
+    let directory = std::env::temp_dir().join(format!("clitui-pathcheck-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = |name: &str| directory.join(name).to_string_lossy().to_string();
+
+    assert_eq!(PathState::of(&directory.to_string_lossy()), PathState::Exists);
+    assert_eq!(PathState::of(&path("out.log")), PathState::WillBeCreated);
+    assert_eq!(PathState::of(&path("logs/out.log")), PathState::Missing);
+    assert_eq!(PathState::of("out.log"), PathState::WillBeCreated);
+    assert!(PathState::Missing.violates(PathCheck::MustExist));
+    assert!(PathState::Exists.violates(PathCheck::MustNotExist));
+    assert!(!PathState::Missing.violates(PathCheck::Any));
+}
+
+#[test]
+fn test_path_checker() {
+    let mut checker = PathChecker::spawn();
+    let mut states = BTreeMap::new();
+    let path = std::env::temp_dir().to_string_lossy().to_string();
+
+    checker.request([path.clone(), path.clone()].iter());
+    let start = std::time::Instant::now();
+    while states.is_empty() && start.elapsed() < std::time::Duration::from_secs(5) {
+        checker.poll(&mut states);
+    }
+
+    assert_eq!(states, BTreeMap::from([(path.clone(), PathState::Exists)]));
+    checker.forget(&mut states);
+    assert!(states.is_empty());
+}
@@ -0,0 +1,113 @@
+use std::{env, ffi::OsString, path::PathBuf};
+
+use directories::ProjectDirs;
+
+/// Kind of file clitui keeps, each has its own base directory per platform
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PathKind {
+    /// `config.toml`
+    Config,
+    /// History and saved documents
+    Data,
+    /// Parsed help, safe to delete
+    Cache,
+}
+
+impl PathKind {
+    /// Variable overriding the directory, e.g. to keep tests away from the real files
+    #[allow(clippy::needless_return)]
+    fn override_variable(&self) -> &'static str {
+        match self {
+            PathKind::Config => return "CLITUI_CONFIG_DIR",
+            PathKind::Data => return "CLITUI_DATA_DIR",
+            PathKind::Cache => return "CLITUI_CACHE_DIR",
+        }
+    }
+}
+
+/// Directory set by the override variable of the kind
+fn override_directory(kind: PathKind, lookup: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    lookup(kind.override_variable()).map(PathBuf::from)
+}
+
+/// XDG base directory of the kind, e.g. `$XDG_DATA_HOME` or `~/.local/share`, also read by other tools on macOS
+#[allow(clippy::needless_return)]
+fn xdg_base_directory(kind: PathKind, lookup: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let home = || lookup("HOME").map(PathBuf::from);
+    match kind {
+        PathKind::Config => return lookup("XDG_CONFIG_HOME").map(PathBuf::from).or_else(|| home().map(|home| home.join(".config"))),
+        PathKind::Data => return lookup("XDG_DATA_HOME").map(PathBuf::from).or_else(|| home().map(|home| home.join(".local").join("share"))),
+        PathKind::Cache => return lookup("XDG_CACHE_HOME").map(PathBuf::from).or_else(|| home().map(|home| home.join(".cache"))),
+    }
+}
+
+/// XDG base directory of the kind in this environment, where tools like bash-completion look for files of the user
+pub fn xdg_directory(kind: PathKind) -> Option<PathBuf> {
+    xdg_base_directory(kind, &|name| env::var_os(name))
+}
+
+/// Directory of clitui for the kind before the platform directories were used, the XDG directories on every Unix system
+/// macOS keeps reading it while the directory in `~/Library` does not exist
+fn legacy_directory(kind: PathKind, lookup: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    xdg_base_directory(kind, lookup).map(|base| base.join("clitui"))
+}
+
+/// Directory of clitui for the kind on this platform, None if the environment has no home directory
+/// XDG directories on Linux and other Unix systems, `~/Library` on macOS and `%APPDATA%` or `%LOCALAPPDATA%` on Windows, see `ProjectDirs`
+pub fn directory(kind: PathKind) -> Option<PathBuf> {
+    if let Some(directory) = override_directory(kind, &|name| env::var_os(name)) {
+        return Some(directory);
+    }
+    let project = ProjectDirs::from("", "", "clitui")?;
+    let directory = match kind {
+        PathKind::Config => project.config_dir(),
+        PathKind::Data => project.data_dir(),
+        PathKind::Cache => project.cache_dir(),
+    };
+    if cfg!(target_os = "macos") && !directory.exists() {
+        if let Some(legacy) = legacy_directory(kind, &|name| env::var_os(name)).filter(|legacy| legacy.is_dir()) {
+            return Some(legacy);
+        }
+    }
+    Some(directory.to_path_buf())
+}
+
+/// `config.toml` in the config directory
+pub fn config_file() -> Option<PathBuf> {
+    directory(PathKind::Config).map(|directory| directory.join("config.toml"))
+}
+
+/// Directory of the history and saved documents, the temporary directory if there is no home directory
+pub fn data_directory() -> PathBuf {
+    directory(PathKind::Data).unwrap_or_else(|| env::temp_dir().join("clitui"))
+}
+
+/// Directory of the parsed help, the temporary directory if there is no home directory
+pub fn cache_directory() -> PathBuf {
+    directory(PathKind::Cache).unwrap_or_else(|| env::temp_dir().join("clitui"))
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_lookup(variables: Vec<(&'static str, &'static str)>) -> impl Fn(&str) -> Option<OsString> {
+    move |name| variables.iter().find(|(variable, _)| *variable == name).map(|(_, value)| OsString::from(value))
+}
+
+#[test]
+fn test_legacy_directory() {
+    let lookup = create_test_lookup(vec![("HOME", "/Users/ferris"), ("XDG_CACHE_HOME", "/tmp/cache")]);
+
+    assert_eq!(legacy_directory(PathKind::Config, &lookup), Some(PathBuf::from("/Users/ferris/.config/clitui")));
+    assert_eq!(legacy_directory(PathKind::Data, &lookup), Some(PathBuf::from("/Users/ferris/.local/share/clitui")));
+    assert_eq!(legacy_directory(PathKind::Cache, &lookup), Some(PathBuf::from("/tmp/cache/clitui")));
+    assert_eq!(legacy_directory(PathKind::Config, &create_test_lookup(vec![])), None);
+}
+
+#[test]
+fn test_override_directory() {
+    let lookup = create_test_lookup(vec![("HOME", "/home/ferris"), ("CLITUI_DATA_DIR", "/tmp/clitui-test")]);
+
+    assert_eq!(override_directory(PathKind::Data, &lookup), Some(PathBuf::from("/tmp/clitui-test")));
+    assert_eq!(override_directory(PathKind::Config, &lookup), None);
+}
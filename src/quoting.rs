@@ -0,0 +1,207 @@
+use std::process::Command;
+
+/// Shell whose quoting rules are used when a command is displayed or exported
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Shell {
+    Posix,
+    Powershell,
+    Cmd,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        if cfg!(windows) {
+            Shell::Powershell
+        } else {
+            Shell::Posix
+        }
+    }
+}
+
+impl Shell {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<Shell> {
+        match name.to_lowercase().as_str() {
+            "posix" | "sh" | "bash" | "zsh" => return Some(Shell::Posix),
+            "powershell" | "pwsh" => return Some(Shell::Powershell),
+            "cmd" => return Some(Shell::Cmd),
+            _ => return None,
+        }
+    }
+}
+
+/// Preferred quote character, cmd always uses double quotes
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum QuoteStyle {
+    #[default]
+    Single,
+    Double,
+}
+
+impl QuoteStyle {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<QuoteStyle> {
+        match name.to_lowercase().as_str() {
+            "single" => return Some(QuoteStyle::Single),
+            "double" => return Some(QuoteStyle::Double),
+            _ => return None,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct QuoteOptions {
+    pub shell: Shell,
+    pub style: QuoteStyle,
+}
+
+/// Program and arguments exactly as the command receives them, without any quoting
+pub fn command_argv(command: &Command) -> Vec<String> {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Characters that never need quoting in any supported shell
+fn is_safe_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || "_-+=.,/:@%".contains(ch)
+}
+
+fn needs_quoting(value: &str) -> bool {
+    value.is_empty() || !value.chars().all(is_safe_char)
+}
+
+/// Quote a single value so the target shell passes it as one argument
+#[allow(clippy::needless_return)]
+pub fn quote(value: &str, options: QuoteOptions) -> String {
+    if !needs_quoting(value) {
+        return value.to_string();
+    }
+    match (options.shell, options.style) {
+        (Shell::Posix, QuoteStyle::Single) => return format!("'{}'", value.replace('\'', r"'\''")),
+        (Shell::Posix, QuoteStyle::Double) => {
+            let escaped: String = value
+                .chars()
+                .map(|ch| match ch {
+                    '"' | '\\' | '$' | '`' => format!("\\{ch}"),
+                    _ => ch.to_string(),
+                })
+                .collect();
+            return format!("\"{escaped}\"");
+        },
+        (Shell::Powershell, QuoteStyle::Single) => return format!("'{}'", value.replace('\'', "''")),
+        (Shell::Powershell, QuoteStyle::Double) => {
+            let escaped: String = value
+                .chars()
+                .map(|ch| match ch {
+                    '"' | '`' | '$' => format!("`{ch}"),
+                    _ => ch.to_string(),
+                })
+                .collect();
+            return format!("\"{escaped}\"");
+        },
+        (Shell::Cmd, _) => return format!("\"{}\"", value.replace('"', "\"\"")),
+    }
+}
+
+/// Format a command as a single line that can be pasted into the target shell
+pub fn format_command(command: &Command, options: QuoteOptions) -> String {
+    let program = command.get_program().to_string_lossy();
+    let mut parts = vec![quote(&program, options)];
+    parts.extend(command.get_args().map(|arg| quote(&arg.to_string_lossy(), options)));
+    parts.join(" ")
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn options(shell: Shell, style: QuoteStyle) -> QuoteOptions {
+    QuoteOptions { shell, style }
+}
+
+#[test]
+fn test_quote_plain_value() {
+    assert_eq!(
+        quote("--first-name", options(Shell::Posix, QuoteStyle::Single)),
+        "--first-name",
+    )
+}
+
+#[test]
+fn test_quote_empty_value() {
+    assert_eq!(
+        quote("", options(Shell::Posix, QuoteStyle::Single)),
+        "''",
+    )
+}
+
+#[test]
+fn test_quote_posix_single() {
+    assert_eq!(
+        quote("the Crab's", options(Shell::Posix, QuoteStyle::Single)),
+        r"'the Crab'\''s'",
+    )
+}
+
+#[test]
+fn test_quote_posix_double() {
+    assert_eq!(
+        quote(r#"say "hi" $USER \o/"#, options(Shell::Posix, QuoteStyle::Double)),
+        r#""say \"hi\" \$USER \\o/""#,
+    )
+}
+
+#[test]
+fn test_quote_posix_metacharacters() {
+    assert_eq!(
+        quote("a;b|c", options(Shell::Posix, QuoteStyle::Single)),
+        "'a;b|c'",
+    )
+}
+
+#[test]
+fn test_quote_powershell_single() {
+    assert_eq!(
+        quote("the Crab's", options(Shell::Powershell, QuoteStyle::Single)),
+        "'the Crab''s'",
+    )
+}
+
+#[test]
+fn test_quote_powershell_double() {
+    assert_eq!(
+        quote(r#"say "hi" $env"#, options(Shell::Powershell, QuoteStyle::Double)),
+        r#""say `"hi`" `$env""#,
+    )
+}
+
+#[test]
+fn test_quote_cmd_ignores_style() {
+    assert_eq!(
+        quote(r#"say "hi""#, options(Shell::Cmd, QuoteStyle::Single)),
+        r#""say ""hi""""#,
+    )
+}
+
+#[test]
+fn test_format_command() {
+    let mut command = Command::new("greeter.exe");
+    command.args(["--first-name", "Ferris", "--last-name", "the Crab"]);
+
+    assert_eq!(
+        format_command(&command, options(Shell::Posix, QuoteStyle::Single)),
+        "greeter.exe --first-name Ferris --last-name 'the Crab'",
+    )
+}
+
+#[test]
+fn test_command_argv_keeps_arguments_unquoted() {
+    let mut command = Command::new("greeter.exe");
+    command.args(["--name", "Ferris 'the crab'", ""]);
+
+    assert_eq!(
+        command_argv(&command),
+        vec!["greeter.exe", "--name", "Ferris 'the crab'", ""],
+    );
+}
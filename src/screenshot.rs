@@ -0,0 +1,204 @@
+use std::fmt::Write;
+
+use ratatui::{buffer::Buffer, style::{Color, Modifier}};
+
+/// File format of a screenshot of the form
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum ScreenshotFormat {
+    /// Text with ANSI escape codes, shown by `cat` or `less -R`
+    #[default]
+    Ansi,
+    /// Preformatted HTML page, e.g. to attach to a bug report
+    Html,
+}
+
+impl ScreenshotFormat {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<ScreenshotFormat> {
+        match name {
+            "ansi" => return Some(ScreenshotFormat::Ansi),
+            "html" => return Some(ScreenshotFormat::Html),
+            _ => return None,
+        }
+    }
+
+    #[allow(clippy::needless_return)]
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScreenshotFormat::Ansi => return "ans",
+            ScreenshotFormat::Html => return "html",
+        }
+    }
+
+    #[allow(clippy::needless_return)]
+    pub fn export(&self, buffer: &Buffer) -> String {
+        match self {
+            ScreenshotFormat::Ansi => return to_ansi(buffer),
+            ScreenshotFormat::Html => return to_html(buffer),
+        }
+    }
+}
+
+/// SGR code of a color, None for the default color of the terminal
+fn ansi_color(color: Color, background: bool) -> Option<String> {
+    let offset = if background { 10 } else { 0 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Rgb(red, green, blue) => return Some(format!("{};2;{red};{green};{blue}", 38 + offset)),
+        Color::Indexed(index) => return Some(format!("{};5;{index}", 38 + offset)),
+    };
+    Some((code + offset).to_string())
+}
+
+/// Every row of the buffer, the style is only written where it changes
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut text = String::new();
+    for y in buffer.area.top()..buffer.area.bottom() {
+        let mut last_style = None;
+        for x in buffer.area.left()..buffer.area.right() {
+            let cell = buffer.get(x, y);
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if last_style != Some(style) {
+                let mut codes = vec![String::from("0")];
+                codes.extend(ansi_color(cell.fg, false));
+                codes.extend(ansi_color(cell.bg, true));
+                for (modifier, code) in [(Modifier::BOLD, "1"), (Modifier::DIM, "2"), (Modifier::ITALIC, "3"), (Modifier::UNDERLINED, "4"), (Modifier::REVERSED, "7"), (Modifier::CROSSED_OUT, "9")] {
+                    if cell.modifier.contains(modifier) {
+                        codes.push(String::from(code));
+                    }
+                }
+                let _ = write!(text, "\x1b[{}m", codes.join(";"));
+                last_style = Some(style);
+            }
+            text.push_str(cell.symbol());
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+/// CSS color of the xterm palette, None for the default color of the page
+fn css_color(color: Color) -> Option<String> {
+    let hex = match color {
+        Color::Reset => return None,
+        Color::Black => "#000000",
+        Color::Red => "#cd0000",
+        Color::Green => "#00cd00",
+        Color::Yellow => "#cdcd00",
+        Color::Blue => "#0000ee",
+        Color::Magenta => "#cd00cd",
+        Color::Cyan => "#00cdcd",
+        Color::Gray => "#e5e5e5",
+        Color::DarkGray => "#7f7f7f",
+        Color::LightRed => "#ff0000",
+        Color::LightGreen => "#00ff00",
+        Color::LightYellow => "#ffff00",
+        Color::LightBlue => "#5c5cff",
+        Color::LightMagenta => "#ff00ff",
+        Color::LightCyan => "#00ffff",
+        Color::White => "#ffffff",
+        Color::Rgb(red, green, blue) => return Some(format!("#{red:02x}{green:02x}{blue:02x}")),
+        Color::Indexed(index) => return css_color(indexed_color(index)),
+    };
+    Some(String::from(hex))
+}
+
+/// Color of the xterm 256 color palette: the 16 system colors, a 6x6x6 cube and a ramp of 24 grays
+#[allow(clippy::needless_return)]
+fn indexed_color(index: u8) -> Color {
+    const SYSTEM_COLORS: [Color; 16] = [
+        Color::Black, Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan, Color::Gray,
+        Color::DarkGray, Color::LightRed, Color::LightGreen, Color::LightYellow, Color::LightBlue, Color::LightMagenta, Color::LightCyan, Color::White,
+    ];
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => return SYSTEM_COLORS[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            return Color::Rgb(CUBE_LEVELS[(cube / 36) as usize], CUBE_LEVELS[(cube / 6 % 6) as usize], CUBE_LEVELS[(cube % 6) as usize]);
+        },
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            return Color::Rgb(gray, gray, gray);
+        },
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Page with one span per cell whose style differs from the default
+pub fn to_html(buffer: &Buffer) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html>\n<body style=\"background: #000000; color: #e5e5e5\">\n<pre>\n");
+    for y in buffer.area.top()..buffer.area.bottom() {
+        for x in buffer.area.left()..buffer.area.right() {
+            let cell = buffer.get(x, y);
+            let (mut fg, mut bg) = (css_color(cell.fg), css_color(cell.bg));
+            if cell.modifier.contains(Modifier::REVERSED) {
+                (fg, bg) = (bg.or(Some(String::from("#000000"))), fg.or(Some(String::from("#e5e5e5"))));
+            }
+            let mut css = Vec::new();
+            css.extend(fg.map(|color| format!("color: {color}")));
+            css.extend(bg.map(|color| format!("background: {color}")));
+            if cell.modifier.contains(Modifier::BOLD) {
+                css.push(String::from("font-weight: bold"));
+            }
+            if cell.modifier.contains(Modifier::CROSSED_OUT) {
+                css.push(String::from("text-decoration: line-through"));
+            }
+            if css.is_empty() {
+                html.push_str(&escape_html(cell.symbol()));
+            } else {
+                let _ = write!(html, "<span style=\"{}\">{}</span>", css.join("; "), escape_html(cell.symbol()));
+            }
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_buffer() -> Buffer {
+    use ratatui::style::Stylize;
+    use ratatui::text::Line;
+    Buffer::with_lines([Line::from(vec!["[x] ".into(), "<A>".green().bold()])])
+}
+
+#[test]
+fn test_to_ansi() {
+    assert_eq!(to_ansi(&create_test_buffer()), "\x1b[0m[x] \x1b[0;32;1m<A>\x1b[0m\n");
+}
+
+#[test]
+fn test_to_html() {
+    let html = to_html(&create_test_buffer());
+
+    assert!(html.contains("\n[x] <span style=\"color: #00cd00; font-weight: bold\">&lt;</span>"));
+}
+
+#[test]
+fn test_css_color_indexed() {
+    assert_eq!(css_color(Color::Indexed(1)), Some(String::from("#cd0000")));
+    assert_eq!(css_color(Color::Indexed(208)), Some(String::from("#ff8700")));
+    assert_eq!(css_color(Color::Indexed(244)), Some(String::from("#808080")));
+}
@@ -0,0 +1,342 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use crate::{
+    model::{Model, ParameterValue},
+    parsing::{CLIArgument, CLIFlag, CLIParameters, CLIPositional, CLISubcommand},
+};
+
+/// Version of the share code format, the first field of every code
+const FORMAT_VERSION: &str = "1";
+
+/// Separates the fields of a share code, it cannot appear in command line arguments
+const SEPARATOR: char = '\0';
+
+/// Single filled in parameter of a shared form
+#[derive(Debug, PartialEq, Eq)]
+pub enum SharedValue {
+    /// Single value of an argument or an option, repeated for multiple values
+    Argument { key: String, value: String },
+    Positional { name: String, value: String },
+    Flag { key: String },
+    /// Argument or option whose default value was cleared, the imported form would fill it in again otherwise
+    Cleared { key: String },
+}
+
+/// Filled in form that can be sent as a single URL-safe string, independent of any files
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SharedForm {
+    pub command_path: Vec<String>,
+    pub values: Vec<SharedValue>,
+}
+
+impl SharedForm {
+    /// Collect the values that differ from an empty form
+    pub fn from_parameters(parameters: &CLIParameters) -> SharedForm {
+        let arguments = parameters.arguments
+            .iter()
+            .chain(&parameters.options)
+            .flat_map(|argument| {
+                let cleared = argument.default_value.is_some() && argument.filled_values().next().is_none();
+                let cleared = cleared.then(|| SharedValue::Cleared { key: argument.key.clone() });
+                argument.filled_values().map(|value| SharedValue::Argument { key: argument.key.clone(), value: value.clone() }).chain(cleared)
+            });
+        let positionals = parameters.positionals
+            .iter()
+            .flat_map(|positional| {
+                positional.filled_values().map(|value| SharedValue::Positional { name: positional.name.clone(), value: value.clone() })
+            });
+        // repeatable flags are shared once per time they are passed
+        let flags = parameters.flags
+            .iter()
+            .flat_map(|flag| {
+                let key = if flag.negated { flag.negated_key.as_ref().unwrap_or(&flag.key) } else { &flag.key };
+                (0..flag.times()).map(|_| SharedValue::Flag { key: key.clone() })
+            });
+        SharedForm {
+            command_path: parameters.command_path.clone(),
+            values: arguments.chain(positionals).chain(flags).collect(),
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut fields = vec![FORMAT_VERSION];
+        for subcommand in &self.command_path {
+            fields.extend(["c", subcommand]);
+        }
+        for value in &self.values {
+            match value {
+                SharedValue::Argument { key, value } => fields.extend(["a", key, value]),
+                SharedValue::Positional { name, value } => fields.extend(["p", name, value]),
+                SharedValue::Flag { key } => fields.extend(["f", key]),
+                SharedValue::Cleared { key } => fields.extend(["x", key]),
+            }
+        }
+        URL_SAFE_NO_PAD.encode(fields.join(&SEPARATOR.to_string()))
+    }
+
+    pub fn decode(code: &str) -> Result<SharedForm, String> {
+        let bytes = URL_SAFE_NO_PAD.decode(code.trim()).map_err(|_| String::from("Invalid share code"))?;
+        let content = String::from_utf8(bytes).map_err(|_| String::from("Invalid share code"))?;
+        let mut fields = content.split(SEPARATOR).map(str::to_string);
+        if fields.next().as_deref() != Some(FORMAT_VERSION) {
+            return Err(String::from("Unsupported share code version"));
+        }
+        let mut form = SharedForm::default();
+        while let Some(kind) = fields.next() {
+            let mut next_field = || fields.next().ok_or(String::from("Truncated share code"));
+            match kind.as_str() {
+                "c" => form.command_path.push(next_field()?),
+                "a" => form.values.push(SharedValue::Argument { key: next_field()?, value: next_field()? }),
+                "p" => form.values.push(SharedValue::Positional { name: next_field()?, value: next_field()? }),
+                "f" => form.values.push(SharedValue::Flag { key: next_field()? }),
+                "x" => form.values.push(SharedValue::Cleared { key: next_field()? }),
+                _ => return Err(format!("Unknown entry in share code: {kind}")),
+            }
+        }
+        Ok(form)
+    }
+
+    /// Enter the subcommands of the form and fill in its values
+    pub fn apply(&self, model: &mut Model) -> Result<(), String> {
+        for name in &self.command_path {
+            let index = model.parameters.subcommands
+                .iter()
+                .position(|subcommand| subcommand.name == *name)
+                .ok_or(format!("Unknown subcommand in share code: {name}"))?;
+            if !model.enter_subcommand(index) {
+                return Err(format!("Subcommand could not be probed: {name}"));
+            }
+        }
+        model.selecting_subcommand = false;
+        // the first shared value of a parameter replaces its prefilled values
+        let mut filled: Vec<(&str, Vec<String>)> = Vec::new();
+        for value in &self.values {
+            let (SharedValue::Argument { key, value } | SharedValue::Positional { name: key, value }) = value else {
+                continue;
+            };
+            if model.get(key).is_none() {
+                return Err(format!("Unknown parameter in share code: {key}"));
+            }
+            match filled.iter_mut().find(|(filled_key, _)| filled_key == key) {
+                Some((_, values)) => values.push(value.clone()),
+                None => filled.push((key, vec![value.clone()])),
+            }
+        }
+        for (key, values) in filled {
+            model.set_values(key, values)?;
+        }
+        for value in &self.values {
+            let SharedValue::Cleared { key } = value else {
+                continue;
+            };
+            if model.get(key).is_none() {
+                return Err(format!("Unknown parameter in share code: {key}"));
+            }
+            model.set_values(key, Vec::new())?;
+        }
+        // repeatable flags are shared once per time they are passed, help and version flags are only set by hand
+        for value in &self.values {
+            let SharedValue::Flag { key } = value else {
+                continue;
+            };
+            if model.parameters.flags.iter().any(|flag| flag.has_key(key) && flag.is_skipped_in_bulk()) {
+                continue;
+            }
+            let Some(ParameterValue::Flag { times, negated }) = model.get(key) else {
+                return Err(format!("Unknown parameter in share code: {key}"));
+            };
+            let passed_negated = model.parameters.flags.iter().any(|flag| flag.negated_key.as_ref() == Some(key));
+            let times = if negated == passed_negated { times } else { 0 };
+            model.set_value(key, &(times + 1).to_string())?;
+        }
+        model.select_first_section();
+        Ok(())
+    }
+}
+
+/// Fill the form from the arguments of a command line, e.g. `apply -f manifests/ --context prod` of an alias
+/// Leading subcommands are entered, keys are found in any of their forms and the other words fill the positionals in order
+pub fn apply_command_line(model: &mut Model, words: &[String]) -> Result<(), String> {
+    let mut words = words.iter().peekable();
+    while let Some(word) = words.peek() {
+        let Some(index) = model.parameters.subcommands.iter().position(|subcommand| subcommand.name == **word) else {
+            break;
+        };
+        if !model.enter_subcommand(index) {
+            return Err(format!("Subcommand could not be probed: {word}"));
+        }
+        words.next();
+    }
+    let parameters = &model.parameters;
+    let mut positionals = parameters.positionals.iter().peekable();
+    let mut values = Vec::new();
+    let mut only_positionals = false;
+    while let Some(word) = words.next() {
+        if word == "--" && !only_positionals {
+            only_positionals = true;
+            continue;
+        }
+        if !only_positionals && word.len() > 1 && word.starts_with('-') {
+            let (key, joined_value) = match word.split_once('=') {
+                Some((key, value)) => (key, Some(value.to_string())),
+                None => (word.as_str(), None),
+            };
+            if let Some(argument) = parameters.arguments.iter().chain(&parameters.options).find(|argument| argument.has_key(key)) {
+                let value = match joined_value {
+                    Some(value) => value,
+                    None => words.next().ok_or(format!("Missing value for {key} in command line"))?.clone(),
+                };
+                values.push(SharedValue::Argument { key: argument.key.clone(), value });
+                continue;
+            }
+            if let Some(flag) = parameters.flags.iter().find(|flag| flag.has_key(word) || flag.negated_key.as_ref() == Some(word)) {
+                let key = if flag.negated_key.as_ref() == Some(word) { word } else { &flag.key };
+                values.push(SharedValue::Flag { key: key.clone() });
+                continue;
+            }
+            return Err(format!("Unknown parameter in command line: {word}"));
+        }
+        // a repeatable positional takes every value left
+        let positional = positionals.peek().ok_or(format!("Unexpected value in command line: {word}"))?;
+        values.push(SharedValue::Positional { name: positional.name.clone(), value: word.clone() });
+        if !positional.multiple {
+            positionals.next();
+        }
+    }
+    SharedForm { values, ..Default::default() }.apply(model)
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_parameters() -> CLIParameters {
+    let build_parameters = CLIParameters {
+        cli_name: String::from("cargo"),
+        command_path: vec![String::from("build")],
+        options: vec![CLIArgument { key: String::from("--target"), name: String::from("TRIPLE"), ..Default::default() }],
+        positionals: vec![CLIPositional { name: String::from("SPEC"), ..Default::default() }],
+        flags: vec![CLIFlag { key: String::from("--release"), ..Default::default() }],
+        ..Default::default()
+    };
+    CLIParameters {
+        cli_name: String::from("cargo"),
+        flags: vec![CLIFlag { key: String::from("--version"), ..Default::default() }],
+        subcommands: vec![CLISubcommand {
+            name: String::from("build"),
+            parameters: Some(build_parameters),
+            ..Default::default()
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_encode_decode_roundtrip() {
+    let form = SharedForm {
+        command_path: vec![String::from("build")],
+        values: vec![
+            SharedValue::Argument { key: String::from("--target"), value: String::from("x86_64 \"linux\"\n") },
+            SharedValue::Positional { name: String::from("SPEC"), value: String::new() },
+            SharedValue::Flag { key: String::from("--release") },
+        ],
+    };
+
+    let code = form.encode();
+
+    assert!(code.chars().all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_'));
+    assert_eq!(SharedForm::decode(&code), Ok(form));
+}
+
+#[test]
+fn test_decode_invalid_code() {
+    assert_eq!(SharedForm::decode("not base64!"), Err(String::from("Invalid share code")));
+}
+
+#[test]
+fn test_decode_truncated_code() {
+    let code = URL_SAFE_NO_PAD.encode("1\0a\0--name");
+
+    assert_eq!(SharedForm::decode(&code), Err(String::from("Truncated share code")));
+}
+
+#[test]
+fn test_apply_reproduces_form() {
+    let mut model = Model::new(create_test_parameters());
+    model.enter_subcommand(0);
+    model.set_value("--target", "wasm32-unknown-unknown").unwrap();
+    model.set_values("SPEC", vec![String::from("clitui"), String::from("clitui-extras")]).unwrap();
+    model.toggle_flag("--release").unwrap();
+    let code = SharedForm::from_parameters(&model.parameters).encode();
+
+    let mut imported_model = Model::new(create_test_parameters());
+    SharedForm::decode(&code).unwrap().apply(&mut imported_model).unwrap();
+
+    assert!(!imported_model.selecting_subcommand);
+    assert_eq!(imported_model.parameters, model.parameters);
+}
+
+#[test]
+fn test_apply_keeps_cleared_default() {
+    let create_parameters = || {
+        let mut parameters = create_test_parameters();
+        let build_parameters = parameters.subcommands[0].parameters.as_mut().unwrap();
+        build_parameters.options[0].values = vec![String::from("x86_64-unknown-linux-gnu")];
+        build_parameters.options[0].default_value = Some(String::from("x86_64-unknown-linux-gnu"));
+        parameters
+    };
+    let mut model = Model::new(create_parameters());
+    model.enter_subcommand(0);
+    model.set_values("--target", Vec::new()).unwrap();
+    let form = SharedForm::from_parameters(&model.parameters);
+
+    let mut imported_model = Model::new(create_parameters());
+    SharedForm::decode(&form.encode()).unwrap().apply(&mut imported_model).unwrap();
+
+    assert_eq!(form.values, [SharedValue::Cleared { key: String::from("--target") }]);
+    assert!(imported_model.parameters.options[0].values.is_empty());
+}
+
+#[test]
+fn test_apply_command_line() {
+    let mut model = Model::new(create_test_parameters());
+    let words = ["build", "--target=wasm32-unknown-unknown", "--release", "clitui"].map(String::from);
+
+    apply_command_line(&mut model, &words).unwrap();
+
+    assert_eq!(model.parameters.command_path, [String::from("build")]);
+    assert_eq!(model.parameters.options[0].values, [String::from("wasm32-unknown-unknown")]);
+    assert_eq!(model.parameters.positionals[0].values, [String::from("clitui")]);
+    assert!(model.parameters.flags[0].set);
+    assert_eq!(
+        apply_command_line(&mut Model::new(create_test_parameters()), &["build", "--jobs", "4"].map(String::from)),
+        Err(String::from("Unknown parameter in command line: --jobs")),
+    );
+}
+
+#[test]
+fn test_apply_unknown_parameter() {
+    let form = SharedForm {
+        values: vec![SharedValue::Flag { key: String::from("--unknown") }],
+        ..Default::default()
+    };
+    let mut model = Model::new(create_test_parameters());
+
+    assert_eq!(
+        form.apply(&mut model),
+        Err(String::from("Unknown parameter in share code: --unknown")),
+    );
+}
+
+#[test]
+fn test_apply_leaves_meta_flags_unset() {
+    let words = [String::from("--version")];
+    let mut model = Model::new(create_test_parameters());
+    apply_command_line(&mut model, &words).unwrap();
+    assert!(!model.parameters.flags[0].set);
+
+    let mut passing_parameters = create_test_parameters();
+    passing_parameters.set_passed_meta_flags(&[String::from("--version")]);
+    let mut passing_model = Model::new(passing_parameters);
+    apply_command_line(&mut passing_model, &words).unwrap();
+    assert!(passing_model.parameters.flags[0].set);
+}
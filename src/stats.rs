@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use crate::storage::HistoryEntry;
+
+/// Number of most used flags kept per tool
+const TOP_FLAGS: usize = 5;
+
+/// Number of the latest runs with the same fingerprint an estimate is based on
+const ESTIMATE_RUNS: usize = 10;
+
+/// Usage of one wrapped tool, computed from the local history only
+#[derive(Debug, Default, PartialEq)]
+pub struct ToolStats {
+    pub tool: String,
+    pub runs: usize,
+    /// Runs that exited with code 0
+    pub successes: usize,
+    pub average_duration_ms: u64,
+    /// Most used keys with the number of runs that passed them, most used first
+    pub flags: Vec<(String, usize)>,
+}
+
+impl ToolStats {
+    /// Share of successful runs in percent
+    pub fn success_rate(&self) -> usize {
+        (self.successes * 100).checked_div(self.runs).unwrap_or_default()
+    }
+}
+
+/// Keys passed in a command line, `--jobs=4` counts as `--jobs` and a key passed twice counts once
+fn command_line_keys(command_line: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for word in command_line.split_whitespace().skip(1) {
+        let word = word.trim_matches(['\'', '"']);
+        if word == "--" {
+            break;
+        }
+        let key = word.split('=').next().unwrap_or_default();
+        if key.len() > 1 && key.starts_with('-') && !keys.iter().any(|seen| seen == key) {
+            keys.push(key.to_string());
+        }
+    }
+    keys
+}
+
+/// Stats of every tool in the history, the most run tool first
+pub fn tool_stats(history: &[HistoryEntry]) -> Vec<ToolStats> {
+    let mut stats: Vec<(ToolStats, u64)> = Vec::new();
+    for entry in history {
+        let index = match stats.iter().position(|(tool_stats, _)| tool_stats.tool == entry.tool) {
+            Some(index) => index,
+            None => {
+                stats.push((ToolStats { tool: entry.tool.clone(), ..Default::default() }, 0));
+                stats.len() - 1
+            },
+        };
+        let (tool_stats, total_duration_ms) = &mut stats[index];
+        tool_stats.runs += 1;
+        tool_stats.successes += usize::from(entry.exit_code == Some(0));
+        *total_duration_ms += entry.duration_ms;
+        for key in command_line_keys(&entry.command_line) {
+            match tool_stats.flags.iter_mut().find(|(flag, _)| *flag == key) {
+                Some((_, count)) => *count += 1,
+                None => tool_stats.flags.push((key, 1)),
+            }
+        }
+    }
+    let mut stats: Vec<ToolStats> = stats
+        .into_iter()
+        .map(|(mut tool_stats, total_duration_ms)| {
+            tool_stats.average_duration_ms = total_duration_ms / tool_stats.runs as u64;
+            // stable sort, flags used equally often keep the order they were first used in
+            tool_stats.flags.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+            tool_stats.flags.truncate(TOP_FLAGS);
+            tool_stats
+        })
+        .collect();
+    stats.sort_by_key(|tool_stats| std::cmp::Reverse(tool_stats.runs));
+    stats
+}
+
+/// Usual duration of a run of the tool with the fingerprint, the median of its latest successful runs
+/// None if it never succeeded before
+pub fn estimate_duration(history: &[HistoryEntry], tool: &str, fingerprint: &str) -> Option<Duration> {
+    let mut durations: Vec<u64> = history
+        .iter()
+        .rev()
+        .filter(|entry| entry.tool == tool && entry.exit_code == Some(0) && entry.fingerprint.as_deref() == Some(fingerprint))
+        .take(ESTIMATE_RUNS)
+        .map(|entry| entry.duration_ms)
+        .collect();
+    durations.sort_unstable();
+    durations.get(durations.len() / 2).map(|duration_ms| Duration::from_millis(*duration_ms))
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_entry(tool: &str, command_line: &str, duration_ms: u64, exit_code: Option<i32>) -> HistoryEntry {
+    HistoryEntry { tool: tool.to_string(), command_line: command_line.to_string(), started: 0, duration_ms, exit_code, fingerprint: None }
+}
+
+#[test]
+fn test_tool_stats() {
+    let history = vec![
+        create_test_entry("rg", "rg -i foo", 100, Some(0)),
+        create_test_entry("cargo", "cargo build --release --jobs=4", 4000, Some(0)),
+        create_test_entry("cargo", "cargo build --release", 2000, Some(101)),
+        create_test_entry("cargo", "cargo test -- --nocapture", 3000, None),
+    ];
+
+    let stats = tool_stats(&history);
+
+    assert_eq!(stats[0], ToolStats {
+        tool: String::from("cargo"),
+        runs: 3,
+        successes: 1,
+        average_duration_ms: 3000,
+        flags: vec![(String::from("--release"), 2), (String::from("--jobs"), 1)],
+    });
+    assert_eq!(stats[0].success_rate(), 33);
+    assert_eq!(stats[1].tool, "rg");
+    assert_eq!(stats[1].success_rate(), 100);
+}
+
+#[test]
+fn test_tool_stats_without_history() {
+    assert_eq!(tool_stats(&[]), Vec::new());
+}
+
+#[test]
+fn test_estimate_duration() {
+    let entry = |duration_ms: u64, exit_code: Option<i32>, fingerprint: &str| HistoryEntry {
+        fingerprint: Some(fingerprint.to_string()),
+        ..create_test_entry("cargo", "cargo build --release", duration_ms, exit_code)
+    };
+    let history = vec![
+        entry(9000, Some(0), "build --release"),
+        entry(2000, Some(0), "build --release"),
+        entry(1000, Some(101), "build --release"),
+        entry(100, Some(0), "build"),
+        entry(3000, Some(0), "build --release"),
+    ];
+
+    assert_eq!(estimate_duration(&history, "cargo", "build --release"), Some(Duration::from_secs(3)));
+    assert_eq!(estimate_duration(&history, "cargo", "build"), Some(Duration::from_millis(100)));
+    assert_eq!(estimate_duration(&history, "cargo", "test"), None);
+    assert_eq!(estimate_duration(&history, "rg", "build"), None);
+}
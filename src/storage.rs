@@ -0,0 +1,357 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Run of a command from the output pane
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// File name of the wrapped program, e.g. `cargo`
+    pub tool: String,
+    /// Command line as shown to the user
+    pub command_line: String,
+    /// Seconds since the Unix epoch when the command was started
+    pub started: u64,
+    pub duration_ms: u64,
+    /// Missing if the command was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Parameters of a run from the output pane, see `CLIParameters::fingerprint`, the durations of earlier runs estimate the next one
+    /// Missing for test runs and runs in the terminal
+    #[serde(default)]
+    pub fingerprint: Option<String>,
+}
+
+/// Where the history and saved documents like profiles are kept, so backends can be swapped
+pub trait Storage {
+    fn append_history(&mut self, entry: &HistoryEntry) -> io::Result<()>;
+    /// Oldest entry first
+    fn history(&self) -> io::Result<Vec<HistoryEntry>>;
+    /// Oldest run of the tool first, backends with an index look them up without reading every entry
+    fn tool_history(&self, tool: &str) -> io::Result<Vec<HistoryEntry>> {
+        Ok(self.history()?.into_iter().filter(|entry| entry.tool == tool).collect())
+    }
+    fn document_names(&self) -> io::Result<Vec<String>>;
+    /// None if there is no document with this name
+    fn read_document(&self, name: &str) -> io::Result<Option<String>>;
+    fn write_document(&mut self, name: &str, content: &str) -> io::Result<()>;
+}
+
+/// Backend of the storage, chosen with `storage` in the config
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum StorageBackend {
+    /// `history.toml` and `documents/`, see `FileStorage`
+    #[default]
+    Files,
+    /// `clitui.sqlite`, only if clitui is built with the `sqlite` feature
+    Sqlite,
+}
+
+impl StorageBackend {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<StorageBackend> {
+        match name {
+            "files" => return Some(StorageBackend::Files),
+            "sqlite" => return Some(StorageBackend::Sqlite),
+            _ => return None,
+        }
+    }
+}
+
+/// Storage of the backend in the data directory
+#[allow(clippy::needless_return)]
+pub fn open(backend: StorageBackend, directory: &Path) -> io::Result<Box<dyn Storage>> {
+    match backend {
+        StorageBackend::Files => return Ok(Box::new(FileStorage { directory: directory.to_path_buf() })),
+        #[cfg(feature = "sqlite")]
+        StorageBackend::Sqlite => return Ok(Box::new(SqliteStorage::open(&directory.join(SQLITE_FILE_NAME))?)),
+        #[cfg(not(feature = "sqlite"))]
+        StorageBackend::Sqlite => return Err(io::Error::new(io::ErrorKind::Unsupported, "clitui was built without the sqlite feature")),
+    }
+}
+
+/// Storage at the path, a directory of the file backend or a database of the SQLite backend, e.g. to migrate from it
+pub fn open_path(path: &Path) -> io::Result<Box<dyn Storage>> {
+    if path.is_file() {
+        #[cfg(feature = "sqlite")]
+        return Ok(Box::new(SqliteStorage::open(path)?));
+        #[cfg(not(feature = "sqlite"))]
+        return Err(io::Error::new(io::ErrorKind::Unsupported, format!("{} is no storage directory and clitui was built without the sqlite feature", path.display())));
+    }
+    Ok(Box::new(FileStorage { directory: path.to_path_buf() }))
+}
+
+/// The history is appended to `history.toml`, documents are files in `documents/`
+#[derive(Debug, PartialEq)]
+pub struct FileStorage {
+    pub directory: PathBuf,
+}
+
+/// `history.toml` is a list of `[[history]]` tables, so new entries are appended without reading the file
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct HistoryFile {
+    history: Vec<HistoryEntry>,
+}
+
+impl FileStorage {
+    fn history_path(&self) -> PathBuf {
+        self.directory.join("history.toml")
+    }
+
+    fn documents_directory(&self) -> PathBuf {
+        self.directory.join("documents")
+    }
+}
+
+fn invalid_data(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+impl Storage for FileStorage {
+    fn append_history(&mut self, entry: &HistoryEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        let table = toml::to_string(&HistoryFile { history: vec![entry.clone()] }).map_err(invalid_data)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(self.history_path())?;
+        writeln!(file, "{table}")
+    }
+
+    #[allow(clippy::needless_return)]
+    fn history(&self) -> io::Result<Vec<HistoryEntry>> {
+        match fs::read_to_string(self.history_path()) {
+            Ok(content) => return toml::from_str::<HistoryFile>(&content).map(|file| file.history).map_err(invalid_data),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        }
+    }
+
+    fn document_names(&self) -> io::Result<Vec<String>> {
+        let entries = match fs::read_dir(self.documents_directory()) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+        let mut names = Vec::new();
+        for entry in entries {
+            if let Some(name) = entry?.file_name().to_str() {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    #[allow(clippy::needless_return)]
+    fn read_document(&self, name: &str) -> io::Result<Option<String>> {
+        match fs::read_to_string(self.documents_directory().join(name)) {
+            Ok(content) => return Ok(Some(content)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        }
+    }
+
+    fn write_document(&mut self, name: &str, content: &str) -> io::Result<()> {
+        fs::create_dir_all(self.documents_directory())?;
+        fs::write(self.documents_directory().join(name), content)
+    }
+}
+
+/// File name of the database in the data directory
+#[cfg(feature = "sqlite")]
+const SQLITE_FILE_NAME: &str = "clitui.sqlite";
+
+/// The history and the documents are tables of an SQLite database, runs are indexed by tool
+#[cfg(feature = "sqlite")]
+pub struct SqliteStorage {
+    connection: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite_error(error: rusqlite::Error) -> io::Error {
+    io::Error::other(error.to_string())
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteStorage {
+    /// Open the database, creating it and its tables if they are missing
+    pub fn open(path: &Path) -> io::Result<SqliteStorage> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let connection = rusqlite::Connection::open(path).map_err(sqlite_error)?;
+        connection
+            .execute_batch(
+                "CREATE TABLE IF NOT EXISTS history (
+                    id INTEGER PRIMARY KEY,
+                    tool TEXT NOT NULL,
+                    command_line TEXT NOT NULL,
+                    started INTEGER NOT NULL,
+                    duration_ms INTEGER NOT NULL,
+                    exit_code INTEGER,
+                    fingerprint TEXT
+                );
+                CREATE INDEX IF NOT EXISTS history_tool ON history (tool);
+                CREATE TABLE IF NOT EXISTS documents (name TEXT PRIMARY KEY, content TEXT NOT NULL);",
+            )
+            .map_err(sqlite_error)?;
+        Ok(SqliteStorage { connection })
+    }
+
+    /// Entries matching the condition, oldest first
+    fn query_history(&self, condition: &str, params: impl rusqlite::Params) -> io::Result<Vec<HistoryEntry>> {
+        let sql = format!("SELECT tool, command_line, started, duration_ms, exit_code, fingerprint FROM history {condition} ORDER BY id");
+        let mut statement = self.connection.prepare(&sql).map_err(sqlite_error)?;
+        let rows = statement
+            .query_map(params, |row| {
+                Ok(HistoryEntry {
+                    tool: row.get(0)?,
+                    command_line: row.get(1)?,
+                    started: row.get::<_, i64>(2)? as u64,
+                    duration_ms: row.get::<_, i64>(3)? as u64,
+                    exit_code: row.get(4)?,
+                    fingerprint: row.get(5)?,
+                })
+            })
+            .map_err(sqlite_error)?;
+        rows.collect::<Result<Vec<HistoryEntry>, rusqlite::Error>>().map_err(sqlite_error)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Storage for SqliteStorage {
+    fn append_history(&mut self, entry: &HistoryEntry) -> io::Result<()> {
+        self.connection
+            .execute(
+                "INSERT INTO history (tool, command_line, started, duration_ms, exit_code, fingerprint) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                rusqlite::params![entry.tool, entry.command_line, entry.started as i64, entry.duration_ms as i64, entry.exit_code, entry.fingerprint],
+            )
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+
+    fn history(&self) -> io::Result<Vec<HistoryEntry>> {
+        self.query_history("", [])
+    }
+
+    fn tool_history(&self, tool: &str) -> io::Result<Vec<HistoryEntry>> {
+        self.query_history("WHERE tool = ?1", [tool])
+    }
+
+    fn document_names(&self) -> io::Result<Vec<String>> {
+        let mut statement = self.connection.prepare("SELECT name FROM documents ORDER BY name").map_err(sqlite_error)?;
+        let names = statement.query_map([], |row| row.get(0)).map_err(sqlite_error)?;
+        names.collect::<Result<Vec<String>, rusqlite::Error>>().map_err(sqlite_error)
+    }
+
+    fn read_document(&self, name: &str) -> io::Result<Option<String>> {
+        let mut statement = self.connection.prepare("SELECT content FROM documents WHERE name = ?1").map_err(sqlite_error)?;
+        let mut contents = statement.query_map([name], |row| row.get(0)).map_err(sqlite_error)?;
+        contents.next().transpose().map_err(sqlite_error)
+    }
+
+    fn write_document(&mut self, name: &str, content: &str) -> io::Result<()> {
+        self.connection
+            .execute("INSERT OR REPLACE INTO documents (name, content) VALUES (?1, ?2)", [name, content])
+            .map_err(sqlite_error)?;
+        Ok(())
+    }
+}
+
+/// Copy the history and the documents of one storage into another, e.g. when switching backends
+/// Documents with the same name are overwritten, returns the number of copied history entries
+pub fn migrate(from: &dyn Storage, to: &mut dyn Storage) -> io::Result<usize> {
+    let history = from.history()?;
+    for entry in &history {
+        to.append_history(entry)?;
+    }
+    for name in from.document_names()? {
+        if let Some(content) = from.read_document(&name)? {
+            to.write_document(&name, &content)?;
+        }
+    }
+    Ok(history.len())
+}
+
+// Unit tests
+
+#[allow(dead_code)]
+fn create_test_storage(name: &str) -> FileStorage {
+    let directory = env::temp_dir().join(format!("clitui-storage-{name}"));
+    let _ = fs::remove_dir_all(&directory);
+    FileStorage { directory }
+}
+
+#[allow(dead_code)]
+fn create_test_entry(exit_code: Option<i32>) -> HistoryEntry {
+    HistoryEntry {
+        tool: String::from("cargo"),
+        command_line: String::from("cargo build --release"),
+        started: 1_700_000_000,
+        duration_ms: 4200,
+        exit_code,
+        fingerprint: Some(String::from("build --release")),
+    }
+}
+
+#[test]
+fn test_file_storage_appends_history() {
+    let mut storage = create_test_storage("history");
+
+    storage.append_history(&create_test_entry(Some(0))).unwrap();
+    storage.append_history(&create_test_entry(None)).unwrap();
+
+    assert_eq!(storage.history().unwrap(), vec![create_test_entry(Some(0)), create_test_entry(None)]);
+}
+
+#[test]
+fn test_file_storage_without_files() {
+    let storage = create_test_storage("empty");
+
+    assert_eq!(storage.history().unwrap(), Vec::new());
+    assert_eq!(storage.document_names().unwrap(), Vec::<String>::new());
+    assert_eq!(storage.read_document("release").unwrap(), None);
+}
+
+#[test]
+fn test_migrate() {
+    let mut from = create_test_storage("migrate-from");
+    from.append_history(&create_test_entry(Some(1))).unwrap();
+    from.write_document("release.toml", "keys = [\"--release\"]").unwrap();
+    let mut to = create_test_storage("migrate-to");
+
+    assert_eq!(migrate(&from, &mut to).unwrap(), 1);
+
+    assert_eq!(to.history().unwrap(), vec![create_test_entry(Some(1))]);
+    assert_eq!(to.read_document("release.toml").unwrap().as_deref(), Some("keys = [\"--release\"]"));
+}
+
+#[test]
+fn test_tool_history() {
+    let mut storage = create_test_storage("tool-history");
+    storage.append_history(&create_test_entry(Some(0))).unwrap();
+    storage.append_history(&HistoryEntry { tool: String::from("git"), ..create_test_entry(Some(0)) }).unwrap();
+
+    assert_eq!(storage.tool_history("cargo").unwrap(), vec![create_test_entry(Some(0))]);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn test_migrate_to_sqlite() {
+    let mut from = create_test_storage("migrate-sqlite-from");
+    from.append_history(&create_test_entry(Some(1))).unwrap();
+    from.append_history(&HistoryEntry { tool: String::from("git"), ..create_test_entry(None) }).unwrap();
+    from.write_document("release.toml", "keys = [\"--release\"]").unwrap();
+    let directory = create_test_storage("migrate-sqlite-to").directory;
+    let mut to = open(StorageBackend::Sqlite, &directory).unwrap();
+
+    assert_eq!(migrate(&from, to.as_mut()).unwrap(), 2);
+
+    assert_eq!(to.history().unwrap(), from.history().unwrap());
+    assert_eq!(to.tool_history("cargo").unwrap(), vec![create_test_entry(Some(1))]);
+    assert_eq!(to.document_names().unwrap(), vec![String::from("release.toml")]);
+    assert_eq!(to.read_document("missing").unwrap(), None);
+    assert_eq!(open_path(&directory.join(SQLITE_FILE_NAME)).unwrap().history().unwrap().len(), 2);
+}
@@ -1,49 +1,338 @@
-use std::io::{self, stdout, Stdout};
+use std::{
+    collections::BTreeMap,
+    io::{self, stdout, Stdout},
+    ops::Range,
+    time::Duration,
+};
 
 use ratatui::{
     backend::CrosstermBackend, crossterm::{
+        event::{DisableMouseCapture, EnableMouseCapture},
         execute,
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    }, layout::{Alignment, Rect}, style::Stylize, text::Line, widgets::{block::{Position, Title}, Block, Borders, Paragraph, List, ListState}, Frame, Terminal,
+    }, buffer::Buffer, layout::{Alignment, Rect}, style::{Color, Stylize}, text::{Line, Span}, widgets::{block::{Position, Title}, Block, Borders, Paragraph, List, ListState, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap, Clear}, Frame, Terminal,
     style::{Style, Modifier}
 };
 
 use crate::{
-    model::{Model, Section},
+    config::ListStyle,
+    diff::{self, DiffSpan},
+    editor::ValueEditor,
+    filepicker::FilePicker,
+    finder::Finder,
+    glob,
+    keymap::Action,
+    model::{Dropdown, ListRows, Mode, Model, Section},
+    output::{OutputLine, OutputPane},
+    pager::Pager,
+    quoting,
+    stats::ToolStats,
     parsing::{
+        convert_to_cli,
         CLIArgument,
         CLIFlag,
-    }
+        CLIPositional,
+        HelpProbe,
+        PathCheck,
+        ValueType,
+    },
+    pathcheck::PathState,
 };
 
 mod layout;
+mod theme;
 
 use layout::UILayout;
+pub use theme::Theme;
+
+/// How often the screen is refreshed to show changes nobody typed, like new output, set by `animation` in the config
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Animation {
+    /// Reduced over SSH and on dumb terminals
+    #[default]
+    Auto,
+    Full,
+    /// Fewer redraws, which saves bandwidth and flicker on slow links
+    Reduced,
+}
+
+impl Animation {
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<Animation> {
+        match name {
+            "auto" => return Some(Animation::Auto),
+            "full" => return Some(Animation::Full),
+            "reduced" => return Some(Animation::Reduced),
+            _ => return None,
+        }
+    }
+
+    /// Whether the animation is reduced, with the environment given by the lookup
+    #[allow(clippy::needless_return)]
+    pub fn is_reduced(&self, lookup: &impl Fn(&str) -> Option<String>) -> bool {
+        match self {
+            Animation::Auto => {
+                let over_ssh = lookup("SSH_CONNECTION").is_some() || lookup("SSH_TTY").is_some();
+                return over_ssh || lookup("TERM").is_some_and(|term| term == "dumb");
+            },
+            Animation::Full => return false,
+            Animation::Reduced => return true,
+        }
+    }
+}
 
 /// Trait for items being displayed in the gui
 pub trait GUIDisplay {
     fn display_list(&self) -> String;
+    /// Name and value shown in the two columns of `ListStyle::Columns`
+    fn display_columns(&self) -> (String, String);
     fn display_description(&self) -> Option<String>;
+    /// Heading the help lists the parameter under, shown in the title of its section while it is selected
+    fn display_group(&self) -> Option<&str> {
+        None
+    }
+    /// Check and values of a parameter taking paths, their state is shown after the item
+    fn display_paths(&self) -> Option<(PathCheck, &[String])> {
+        None
+    }
+    /// Labels and values of what is known about the parameter, listed in the detail panel
+    fn display_details(&self) -> Vec<(&'static str, String)>;
+    /// Text following the values in the list item, e.g. ` (glob)`, the text cursor is placed before it
+    fn display_value_suffix(&self) -> &'static str {
+        ""
+    }
+
+    /// List item in the given style, the name column is `name_width` wide and the value ends at `width`
+    #[allow(clippy::needless_return)]
+    fn display_item(&self, style: ListStyle, name_width: usize, width: usize) -> String {
+        match style {
+            ListStyle::Inline => return self.display_list(),
+            ListStyle::Columns => {
+                let (name, value) = self.display_columns();
+                let value_width = width.saturating_sub(name_width + 1).max(value.chars().count());
+                return format!("{name:name_width$} {value:>value_width$}");
+            },
+        }
+    }
+}
+
+/// Checkbox of a boolean argument, `[-]` like a negated flag when it is passed as false
+#[allow(clippy::needless_return)]
+fn bool_checkbox(values: &[String]) -> String {
+    match values.first().map(String::as_str) {
+        None | Some("") => return String::from("[ ]"),
+        Some(value @ ("false" | "no" | "off")) => return format!("[-] {value}"),
+        Some(value) => return format!("[x] {value}"),
+    }
+}
+
+/// Short and long key of a parameter, e.g. `-n, --name`
+#[allow(clippy::needless_return)]
+fn keys_detail(short_key: Option<&String>, key: &String) -> String {
+    match short_key.filter(|short_key| *short_key != key) {
+        Some(short_key) => return format!("{short_key}, {key}"),
+        None => return key.clone(),
+    }
+}
+
+/// Required parameters are marked with a `*` after their name
+fn required_marker(required: bool) -> &'static str {
+    if required { "*" } else { "" }
+}
+
+/// State of the first path breaking the check, or of the first path if none does
+/// Nothing until the path checker has reached the values
+/// Without colors a `!` marks a state breaking the check
+fn path_marker(paths: Option<(PathCheck, &[String])>, states: &BTreeMap<String, PathState>, theme: &Theme, monochrome: bool) -> Option<Span<'static>> {
+    let (check, values) = paths?;
+    let states: Vec<PathState> = values.iter().filter_map(|value| states.get(value).copied()).collect();
+    let state = states.iter().find(|state| state.violates(check)).or(states.first())?;
+    if state.violates(check) {
+        let warning = if monochrome { "!" } else { "" };
+        return Some(Span::styled(format!(" ({}{warning})", state.label()), theme.error));
+    }
+    Some(Span::styled(format!(" ({})", state.label()), theme.muted))
+}
+
+/// Values as listed in one row, line breaks are shown as `⏎`
+fn display_values(values: &[String]) -> String {
+    values.iter().map(|value| value.replace('\n', "⏎")).collect::<Vec<String>>().join(", ")
+}
+
+/// Values expanded as globs are marked after the values
+fn glob_marker(glob: bool) -> &'static str {
+    if glob { " (glob)" } else { "" }
 }
 
 impl GUIDisplay for CLIArgument {
     fn display_list(&self) -> String {
-        format!("{}: {}", self.name, self.value)
+        if self.template.is_some() {
+            return format!("{} = {}", self.name, display_values(&self.values));
+        }
+        if self.value_type == ValueType::Bool {
+            return format!("{}{}: {}", self.name, required_marker(self.required), bool_checkbox(&self.values));
+        }
+        format!("{}{}: {}{}", self.name, required_marker(self.required), display_values(&self.values), glob_marker(self.glob))
+    }
+
+    fn display_columns(&self) -> (String, String) {
+        let marker = if self.template.is_some() { " =" } else { required_marker(self.required) };
+        (format!("{}{marker}", self.name), format!("{}{}", display_values(&self.values), glob_marker(self.glob)))
+    }
+
+    fn display_description(&self) -> Option<String> {
+        let mut description = format!("{}: {}", self.name, self.description.as_deref()?);
+        if !self.aliases.is_empty() {
+            description.push_str(&format!(" [aliases: {}]", self.aliases.join(", ")));
+        }
+        if let Some(env_var) = &self.env_var {
+            description.push_str(&format!(" [env: {env_var}]"));
+        }
+        if let Some(hint) = self.value_type.hint() {
+            description.push_str(&format!(" ({hint})"));
+        }
+        Some(description)
+    }
+
+    fn display_group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn display_value_suffix(&self) -> &'static str {
+        if self.template.is_some() || self.value_type == ValueType::Bool {
+            return "";
+        }
+        glob_marker(self.glob)
+    }
+
+    /// Globs name several files, the preview below the description shows them instead
+    fn display_paths(&self) -> Option<(PathCheck, &[String])> {
+        (self.takes_files() && !self.glob).then_some((self.path_check, &self.values))
+    }
+
+    fn display_details(&self) -> Vec<(&'static str, String)> {
+        let mut notes = Vec::new();
+        if let Some(template) = &self.template {
+            notes.push(format!("computed as {template}"));
+        }
+        if self.joined {
+            notes.push(format!("passed as {}=value", self.key));
+        }
+        if self.glob {
+            notes.push(String::from("globs are expanded"));
+        }
+        if self.global {
+            notes.push(String::from("accepted by the subcommands as well"));
+        }
+        let value_type = self.value_type.hint().unwrap_or("text");
+        [
+            ("Description", self.description.clone()),
+            ("Keys", Some(keys_detail(self.short_key.as_ref(), &self.key))),
+            ("Aliases", (!self.aliases.is_empty()).then(|| self.aliases.join(", "))),
+            ("Type", Some(if self.multiple { format!("{value_type}, repeatable") } else { value_type.to_string() })),
+            ("Default", self.default_value.clone()),
+            ("Env", self.env_var.clone()),
+            ("Choices", (!self.choices.is_empty()).then(|| self.choices.join(", "))),
+            ("Group", self.group.clone()),
+            ("Notes", (!notes.is_empty()).then(|| notes.join(", "))),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
+    }
+}
+
+impl GUIDisplay for CLIPositional {
+    fn display_list(&self) -> String {
+        format!("{}{}: {}{}", self.name, required_marker(self.required), display_values(&self.values), glob_marker(self.glob))
+    }
+
+    fn display_columns(&self) -> (String, String) {
+        (format!("{}{}", self.name, required_marker(self.required)), format!("{}{}", display_values(&self.values), glob_marker(self.glob)))
     }
 
     fn display_description(&self) -> Option<String> {
         Some(format!("{}: {}", self.name, self.description.as_deref()?))
     }
+
+    fn display_paths(&self) -> Option<(PathCheck, &[String])> {
+        (self.takes_files() && !self.glob).then_some((self.path_check, &self.values))
+    }
+
+    fn display_value_suffix(&self) -> &'static str {
+        glob_marker(self.glob)
+    }
+
+    fn display_details(&self) -> Vec<(&'static str, String)> {
+        let value_type = if self.takes_files() { "path" } else { "text" };
+        [
+            ("Description", self.description.clone()),
+            ("Type", Some(if self.multiple { format!("{value_type}, repeatable") } else { value_type.to_string() })),
+            ("Notes", self.glob.then(|| String::from("globs are expanded"))),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
+    }
 }
 
 impl GUIDisplay for CLIFlag {
     fn display_list(&self) -> String {
-        let checkbox = if self.set {String::from("[x]")} else {String::from("[ ]")};
+        if let Some(negated_key) = self.negated_key.as_ref().filter(|_| self.set && self.negated) {
+            return format!("[-] {}", negated_key.trim_start_matches('-').to_uppercase());
+        }
+        let checkbox = match self.times() {
+            0 => String::from("[ ]"),
+            1 if !self.multiple => String::from("[x]"),
+            times => format!("[{times}]"),
+        };
         format!("{checkbox} {}", self.name())
     }
 
+    /// The checkbox moves to the value column, a negated flag shows its negated key there
+    fn display_columns(&self) -> (String, String) {
+        if let Some(negated_key) = self.negated_key.as_ref().filter(|_| self.set && self.negated) {
+            return (self.name(), format!("[-] {}", negated_key.trim_start_matches('-').to_uppercase()));
+        }
+        let value = match self.times() {
+            0 => String::from("[ ]"),
+            1 if !self.multiple => String::from("[x]"),
+            times => format!("[{times}]"),
+        };
+        (self.name(), value)
+    }
+
     fn display_description(&self) -> Option<String> {
-        Some(format!("{}: {}", self.name(), self.description.as_deref()?))
+        let mut description = format!("{}: {}", self.name(), self.description.as_deref()?);
+        if !self.aliases.is_empty() {
+            description.push_str(&format!(" [aliases: {}]", self.aliases.join(", ")));
+        }
+        Some(description)
+    }
+
+    fn display_group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn display_details(&self) -> Vec<(&'static str, String)> {
+        let mut notes = Vec::new();
+        if self.multiple {
+            notes.push(String::from("repeatable, e.g. -vvv"));
+        }
+        if self.global {
+            notes.push(String::from("accepted by the subcommands as well"));
+        }
+        [
+            ("Description", self.description.clone()),
+            ("Keys", Some(keys_detail(self.short_key.as_ref(), &self.key))),
+            ("Aliases", (!self.aliases.is_empty()).then(|| self.aliases.join(", "))),
+            ("Turned off by", self.negated_key.clone()),
+            ("Group", self.group.clone()),
+            ("Notes", (!notes.is_empty()).then(|| notes.join(", "))),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| Some((label, value?)))
+        .collect()
     }
 }
 
@@ -52,72 +341,1417 @@ pub type Tui = Terminal<CrosstermBackend<Stdout>>;
 
 /// Initialize the terminal
 pub fn init() -> io::Result<Tui> {
-    execute!(stdout(), EnterAlternateScreen)?;
+    execute!(stdout(), EnterAlternateScreen, EnableMouseCapture)?;
     enable_raw_mode()?;
     Terminal::new(CrosstermBackend::new(stdout()))
 }
 
 /// Restore the terminal to its original state
 pub fn restore() -> io::Result<()> {
-    execute!(stdout(), LeaveAlternateScreen)?;
+    execute!(stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
     disable_raw_mode()?;
     Ok(())
 }
 
 /// Render main border
-fn render_main_border(frame: &mut Frame, title: &str) {
-    let title = Title::from(title.bold());
-    let instructions = Title::from(Line::from(vec![
-        " Run ".into(),
-        "<Enter>".blue().into(),
-        " Toggle ".into(),
-        "<Space>".blue().into(),
-        " Quit ".into(),
-        "<Ctrl + Q> ".blue().into(),
-    ]));
+fn render_main_border(frame: &mut Frame, model: &Model) {
+    // only the command itself knows its version, subcommands show the version of the command
+    let root_parameters = model.parent_parameters.first().map_or(&model.parameters, |(parameters, _)| parameters);
+    let mut title = match (&root_parameters.version, &model.output) {
+        (_, Some(output)) if model.mode() == Mode::Pager => Line::from(format!("$ {}", output.command_line).bold()),
+        (Some(version), _) => Line::from(vec![model.parameters.display_name().bold(), Span::styled(format!(" {version}"), model.theme.muted)]),
+        (None, _) => Line::from(model.parameters.display_name().bold()),
+    };
+    if let Some(alias) = model.alias.as_ref().filter(|_| model.mode() != Mode::Pager) {
+        title.spans.insert(0, Span::styled(format!("{alias}: "), model.theme.accent));
+    }
+    let title = Title::from(title);
+    // the pager is only offered when the output does not fit below the title of the pane
+    let output_fits = model.output.as_ref().is_none_or(|output| {
+        output.shown_lines().len() <= layout::UILayout::build(frame.size(), model).output_section.height.saturating_sub(1) as usize
+    });
+    let instructions = Title::from(instructions(model, frame.size().width.saturating_sub(4) as usize, output_fits));
     let block = Block::bordered()
         .title(title)
         .title(instructions.alignment(Alignment::Center).position(Position::Bottom));
     frame.render_widget(block, frame.size());
 }
 
+/// Whether the action does something right now, beyond being available in the mode
+#[allow(clippy::needless_return)]
+fn is_offered(action: Action, model: &Model, output_fits: bool) -> bool {
+    let running = model.output.as_ref().is_some_and(OutputPane::is_running);
+    match action {
+        Action::Run | Action::RunInTerminal | Action::ExternalPager => return !running,
+        Action::TestRun => return !running && model.parameters.flags.iter().any(CLIFlag::is_dry_run),
+        Action::Pager => return !running && !output_fits,
+        Action::Top | Action::Bottom => return model.pager.is_some(),
+        Action::ToggleGlob => return model.get_selected_glob().is_some(),
+        Action::PickFile => return model.selected_takes_files(),
+        Action::Expand => {
+            let selected_bool = model.get_selected_argument().is_some_and(|argument| argument.value_type == ValueType::Bool);
+            return model.selected_field().is_some_and(|field| field.section != Section::Flags && model.choices(&field).is_none()) && !selected_bool;
+        },
+        Action::Back => return !matches!(model.mode(), Mode::Form | Mode::Subcommands | Mode::Output) || model.can_go_back(),
+        _ => return true,
+    }
+}
+
+/// Labels and keys of the actions offered in the current mode, e.g. ` Run <Enter> Quit <Ctrl + Q> `
+/// The actions with the highest priority that fit into the width are shown in the order of `Action::ALL`,
+/// the others are left to the keybinding help, which is then offered as `More`
+fn instructions(model: &Model, width: usize, output_fits: bool) -> Line<'static> {
+    let mode = model.mode();
+    let offered: Vec<(Action, &str, String)> = Action::ALL
+        .into_iter()
+        .filter(|action| action.is_available(mode) && is_offered(*action, model, output_fits))
+        .filter_map(|action| Some((action, action.label(mode)?, model.keymap.display_keys(action))))
+        .filter(|(_, _, keys)| !keys.is_empty())
+        .collect();
+    let mut by_priority: Vec<&(Action, &str, String)> = offered.iter().collect();
+    by_priority.sort_by_key(|(action, _, _)| action.priority());
+    let mut shown = Vec::new();
+    let mut used_width = 1;
+    for (action, label, keys) in by_priority {
+        let entry_width = label.chars().count() + keys.chars().count() + 2;
+        if used_width + entry_width <= width {
+            used_width += entry_width;
+            shown.push(*action);
+        }
+    }
+    let overflow = shown.len() < offered.len();
+    let mut spans: Vec<Span> = Vec::new();
+    for (action, label, keys) in offered.into_iter().filter(|(action, _, _)| shown.contains(action)) {
+        let label = if overflow && action == Action::Help { "More" } else { label };
+        // Space opens the dropdown of a parameter with possible values
+        let choosing = action == Action::Toggle && model.selected_field().is_some_and(|field| model.choices(&field).is_some());
+        let label = if choosing { "Choose" } else { label };
+        spans.extend([format!(" {label} ").into(), Span::styled(keys, model.theme.key)]);
+    }
+    spans.push(" ".into());
+    Line::from(spans)
+}
+
 /// Render additional layout lines
 fn render_layout(frame: &mut Frame, layout: &UILayout) {
     let vertical_line = Block::default()
         .borders(Borders::RIGHT);
     frame.render_widget(vertical_line.clone(), layout.left_third);
+    if !layout.detail_section.is_empty() {
+        frame.render_widget(vertical_line.clone(), layout.right_third);
+    }
     frame.render_widget(vertical_line, layout.middle_third);
 }
 
-fn render_parameters_section<T: GUIDisplay>(frame: &mut Frame, parameters: &Vec<T>, selected_index: Option<usize>, title: &str, area: Rect) {
-    let mut state = ListState::default().with_selected(selected_index);
-    let items: Vec<String> = parameters
+/// Range of items that fit into the given height with the selected item in the middle,
+/// so the items around it are visible in both directions, except at the start and the end of the list
+fn visible_window(len: usize, selected_index: Option<usize>, height: usize) -> Range<usize> {
+    let height = height.max(1);
+    let start = selected_index
+        .map(|index| index.saturating_sub(height / 2))
+        .unwrap_or(0)
+        .min(len.saturating_sub(height));
+    start..(start + height).min(len)
+}
+
+/// Scrollbar at the right edge of the list, only while the window does not show every item
+fn render_scrollbar(frame: &mut Frame, area: Rect, len: usize, window: &Range<usize>) {
+    if window.len() >= len {
+        return;
+    }
+    // the thumb reaches the bottom once the last item is visible, not once it is at the top
+    let mut state = ScrollbarState::new(len - window.len() + 1).position(window.start).viewport_content_length(window.len());
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight).begin_symbol(None).end_symbol(None);
+    frame.render_stateful_widget(scrollbar, area, &mut state);
+}
+
+/// Only the visible window of the parameters is turned into list items,
+/// so huge parameter lists render as fast as small ones
+/// The name column is as wide as the longest name of the whole section, so it does not move while scrolling or filtering,
+/// the width is only computed the first time the section is shown
+/// While the filter is typed only the matches are listed and the first match, which Enter selects, is highlighted
+fn render_parameters_section<T: GUIDisplay>(frame: &mut Frame, model: &Model, parameters: &[T], section: Section, area: Rect) {
+    let shown = model.shown_indices(section);
+    let selected_index = match &model.filter {
+        Some(_) => model.first_filter_match().filter(|(first_section, _)| *first_section == section).map(|(_, index)| index),
+        None => model.get_selected_index(section),
+    };
+    let selected_position = selected_index.and_then(|index| shown.iter().position(|shown_index| *shown_index == index));
+    let title = match selected_index.and_then(|index| parameters.get(index)?.display_group()) {
+        Some(group) => format!("{} - {group}", section_title(section)),
+        None => section_title(section).to_string(),
+    };
+    let block = Block::default().title(title).title_alignment(Alignment::Center);
+    let window = visible_window(shown.len(), selected_position, block.inner(area).height as usize);
+    if model.get_selected_index(section).is_some() {
+        model.section_page_height.set(block.inner(area).height as usize);
+    }
+    let mut state = ListState::default().with_selected(selected_position.map(|position| position - window.start));
+    let name_width = match model.list_style {
+        ListStyle::Inline => 0,
+        ListStyle::Columns => *model.name_widths.borrow_mut().entry((model.parameters.command_path.clone(), section)).or_insert_with(|| {
+            parameters.iter().map(|parameter| parameter.display_columns().0.chars().count()).max().unwrap_or_default()
+        }),
+    };
+    // the highlight symbol `>>` takes the first two columns of every item, the scrollbar the last one
+    let scrollable = window.len() < shown.len();
+    let width = block.inner(area).width.saturating_sub(2 + u16::from(scrollable)) as usize;
+    let item = |parameter: &T| {
+        let marker = path_marker(parameter.display_paths(), &model.path_states, &model.theme, model.monochrome);
+        let marker_width = marker.as_ref().map_or(0, |marker| marker.content.chars().count());
+        (parameter.display_item(model.list_style, name_width, width.saturating_sub(marker_width)), marker)
+    };
+    let items: Vec<Line> = shown[window.clone()]
         .iter()
-        .map(|argument| argument.display_list())
+        .map(|index| {
+            let (item, marker) = item(&parameters[*index]);
+            Line::from(vec![Span::from(item)].into_iter().chain(marker).collect::<Vec<Span>>())
+        })
         .collect();
+    let list_area = block.inner(area);
+    // the terminal cursor shows where typing inserts into the edited value
+    let editing = model.text_cursor.as_ref().filter(|cursor| cursor.field.section == section && model.mode() == Mode::Form && model.filter.is_none() && model.finder.is_none());
+    if let (Some(cursor), Some(position)) = (editing, selected_position) {
+        let parameter = &parameters[shown[position]];
+        let value_width = model.last_value(&cursor.field).map_or(0, |value| value.chars().count());
+        let after_cursor = value_width - model.cursor_position(&cursor.field) + parameter.display_value_suffix().chars().count();
+        let column = (2 + item(parameter).0.chars().count()).saturating_sub(after_cursor) as u16;
+        frame.set_cursor(list_area.x + column.min(list_area.width.saturating_sub(1)), list_area.y + (position - window.start) as u16);
+    }
+    model.list_rows.borrow_mut().push(ListRows { section: Some(section), area: list_area, indices: shown[window.clone()].to_vec() });
     let list = List::new(items)
-        .block(Block::default().title(title).title_alignment(Alignment::Center))
-        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+        .block(block)
+        .highlight_style(model.theme.selected)
         .highlight_symbol(">>")
         .repeat_highlight_symbol(true);
 
     frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, list_area, shown.len(), &window);
+}
+
+/// Line showing how the value of the argument differs from its default, None while it is the default
+fn default_diff_line(argument: &CLIArgument, theme: &Theme) -> Option<Line<'static>> {
+    let default_value = argument.default_value.as_deref()?;
+    let value = argument.values.join(", ");
+    if value == default_value {
+        return None;
+    }
+    let mut spans = vec![Span::styled("Default changed: ", theme.muted)];
+    spans.extend(diff::diff_chars(default_value, &value).into_iter().map(|span| match span {
+        DiffSpan::Same(text) => Span::from(text),
+        DiffSpan::Added(text) => Span::styled(text, theme.success).bold(),
+        DiffSpan::Removed(text) => Span::styled(text, theme.error).crossed_out(),
+    }));
+    Some(Line::from(spans))
+}
+
+/// Number of matched files listed in the preview, the others are only counted
+const GLOB_PREVIEW_FILES: usize = 5;
+
+/// Line previewing the files the globs among the values match, None if no value is a glob
+fn glob_preview_line(values: &[String], theme: &Theme) -> Option<Line<'static>> {
+    let patterns: Vec<&String> = values.iter().filter(|value| glob::is_pattern(value)).collect();
+    if patterns.is_empty() {
+        return None;
+    }
+    let matches: Vec<String> = patterns.iter().flat_map(|pattern| glob::expand(pattern)).collect();
+    if matches.is_empty() {
+        let patterns: Vec<&str> = patterns.iter().map(|pattern| pattern.as_str()).collect();
+        return Some(Line::styled(format!("No files match {}, passed as typed", patterns.join(", ")), theme.warning));
+    }
+    let mut preview = matches.iter().take(GLOB_PREVIEW_FILES).cloned().collect::<Vec<String>>().join(", ");
+    if matches.len() > GLOB_PREVIEW_FILES {
+        preview.push_str(&format!(" and {} more", matches.len() - GLOB_PREVIEW_FILES));
+    }
+    Some(Line::from(vec![Span::styled(format!("Matches {} files: ", matches.len()), theme.muted), Span::from(preview)]))
+}
+
+/// Filter as it is typed, followed by the number of matches
+fn filter_line(model: &Model, filter: &str) -> Line<'static> {
+    let count: usize = Section::ALL
+        .into_iter()
+        .filter(|section| model.section_is_available(*section))
+        .map(|section| model.shown_indices(section).len())
+        .sum();
+    let matches = match count {
+        0 => Span::styled("  no parameter matches", model.theme.error),
+        1 => Span::styled("  1 parameter matches, Enter selects it", model.theme.muted),
+        count => Span::styled(format!("  {count} parameters match, Enter selects the first"), model.theme.muted),
+    };
+    Line::from(vec![Span::from(format!("/{filter}")), matches])
+}
+
+/// Render the filter or the latest message, false if there is neither
+fn render_status(frame: &mut Frame, model: &Model, area: Rect) -> bool {
+    if let Some(filter) = &model.filter {
+        frame.render_widget(Paragraph::new(filter_line(model, filter)), area);
+        return true;
+    }
+    if let Some(error) = &model.error {
+        let prefix = if model.monochrome { "Error: " } else { "" };
+        frame.render_widget(Paragraph::new(format!("{prefix}{error}")).style(model.theme.error), area);
+        return true;
+    }
+    if let Some(notice) = &model.notice {
+        frame.render_widget(Paragraph::new(notice.as_str()).style(model.theme.success), area);
+        return true;
+    }
+    false
+}
+
+/// Whether the selected parameter can be passed, otherwise the reason why the command would not run
+fn validation_line(model: &Model) -> Line<'static> {
+    let selected = (model.current_section, model.current_key_index);
+    let label = Span::styled("Status: ", model.theme.muted);
+    if let Some((_, _, name)) = model.find_missing_required().filter(|(section, index, _)| (*section, *index) == selected) {
+        return Line::from(vec![label, Span::styled(format!("{name} is required"), model.theme.error)]);
+    }
+    // the render only reads what the path checker found, a slow mount must not block the frame
+    if let Some((_, _, error)) = model.find_checked_path_violation().filter(|(section, index, _)| (*section, *index) == selected) {
+        return Line::from(vec![label, Span::styled(error, model.theme.error)]);
+    }
+    if model.is_selected_path_unchecked() {
+        return Line::from(vec![label, Span::styled("checking…", model.theme.muted)]);
+    }
+    Line::from(vec![label, Span::from("valid")])
+}
+
+/// Command line the form runs on Enter, quoted like it is printed and colored like the arguments view,
+/// cut off with `…` if it is wider than the area
+fn preview_line(model: &Model, width: usize) -> Line<'static> {
+    let argv = quoting::command_argv(&convert_to_cli(&model.parameters));
+    let mut spans = vec![Span::styled("$ ", model.theme.muted)];
+    let mut used_width = 2;
+    for (index, arg) in argv.iter().enumerate() {
+        // the preview is a single line, line breaks inside the quotes are shown as `⏎`
+        let quoted = quoting::quote(arg, model.quote_options).replace('\n', "⏎");
+        let text = if index == 0 { quoted } else { format!(" {quoted}") };
+        let style = match arg.as_str() {
+            _ if index == 0 => Style::new().bold(),
+            _ if arg.starts_with('-') => model.theme.key,
+            _ => model.theme.value,
+        };
+        let text_width = text.chars().count();
+        if used_width + text_width > width {
+            let fitting: String = text.chars().take(width.saturating_sub(used_width + 1)).collect();
+            spans.extend([Span::styled(fitting, style), Span::styled("…", model.theme.muted)]);
+            break;
+        }
+        used_width += text_width;
+        spans.push(Span::styled(text, style));
+    }
+    Line::from(spans)
+}
+
+/// Render what is known about the selected parameter in the panel on the right of the form
+fn render_details(frame: &mut Frame, model: &Model, area: Rect) {
+    let index = model.current_key_index;
+    let details = match model.current_section {
+        Section::Arguments => model.parameters.arguments.get(index).map(GUIDisplay::display_details),
+        Section::Positionals => model.parameters.positionals.get(index).map(GUIDisplay::display_details),
+        Section::Flags => model.parameters.flags.get(index).map(GUIDisplay::display_details),
+        Section::Options => model.parameters.options.get(index).map(GUIDisplay::display_details),
+    };
+    let block = Block::default().title("Details").title_alignment(Alignment::Center);
+    let Some(details) = details.filter(|_| model.section_is_available(model.current_section)) else {
+        frame.render_widget(block, area);
+        return;
+    };
+    let mut lines: Vec<Line> = details
+        .into_iter()
+        .map(|(label, value)| Line::from(vec![Span::styled(format!("{label}: "), model.theme.muted), Span::from(value)]))
+        .collect();
+    let key = model.selected_field().map(|field| field.key).unwrap_or_default();
+    lines.extend(model.parameters.probes.get(&key).and_then(HelpProbe::note).map(|note| Line::from(vec![Span::styled("Help: ", model.theme.muted), Span::from(note)])));
+    lines.push(validation_line(model));
+    lines.extend(model.get_selected_argument().and_then(|argument| default_diff_line(argument, &model.theme)));
+    if let Some((true, values)) = model.get_selected_glob() {
+        lines.extend(glob_preview_line(values, &model.theme));
+    }
+    frame.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: false }), area);
 }
 
 fn render_description(frame: &mut Frame, model: &Model, area: Rect) {
-    let description = model.get_selected_description();
-    if let Some(description) = description {
-        frame.render_widget(Paragraph::new(description), area);
+    if render_status(frame, model, area) {
+        return;
+    }
+    // descriptions merged from `-h` and `--help` show the summary above the detail
+    let description = model.get_selected_description().unwrap_or_default();
+    let mut lines: Vec<Line> = description.lines().map(|line| Line::from(line.to_string())).collect();
+    lines.extend(model.get_selected_argument().and_then(|argument| default_diff_line(argument, &model.theme)));
+    if let Some((true, values)) = model.get_selected_glob() {
+        lines.extend(glob_preview_line(values, &model.theme));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// Render the subcommands to choose from, the first entry is the command itself
+fn render_subcommands(frame: &mut Frame, model: &Model, area: Rect) {
+    let entries: Vec<(String, &str)> = std::iter::once((model.parameters.display_name(), "Use without subcommand"))
+        .chain(model.parameters.subcommands.iter().map(|subcommand| {
+            (subcommand.name.clone(), subcommand.description.as_deref().unwrap_or_default())
+        }))
+        .collect();
+    let width = entries.iter().map(|(name, _)| name.chars().count()).max().unwrap_or_default();
+    let title = if model.discovering { "Subcommands (discovering…)" } else { "Subcommands" };
+    let block = Block::default().title(title).title_alignment(Alignment::Center);
+    let window = visible_window(entries.len(), Some(model.subcommand_index), block.inner(area).height as usize);
+    let mut state = ListState::default().with_selected(Some(model.subcommand_index - window.start));
+    let items: Vec<String> = entries[window.clone()]
+        .iter()
+        .map(|(name, description)| format!("{name:width$}  {description}"))
+        .collect();
+    let list_area = block.inner(area);
+    model.list_rows.borrow_mut().push(ListRows { section: None, area: list_area, indices: window.clone().collect() });
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(model.theme.selected)
+        .highlight_symbol(">>")
+        .repeat_highlight_symbol(true);
+
+    frame.render_stateful_widget(list, area, &mut state);
+    render_scrollbar(frame, list_area, entries.len(), &window);
+}
+
+/// Lines of the keybinding help, generated from the active keymap
+fn help_lines(model: &Model) -> Vec<String> {
+    let entries: Vec<(String, &str)> = Action::ALL
+        .iter()
+        .map(|action| (model.keymap.display_keys(*action), action.description()))
+        .chain(std::iter::once((String::from("<Any character>"), "Edit the selected value")))
+        .collect();
+    let width = entries.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or_default();
+    entries
+        .iter()
+        .map(|(keys, description)| format!("{keys:width$}  {description}"))
+        .collect()
+}
+
+/// Render every argument the command receives on its own line, without shell quoting,
+/// the program in bold, keys and values in the colors of the theme
+fn render_argv(frame: &mut Frame, model: &Model, area: Rect) {
+    let block = Block::default().title("Arguments as received by the command").title_alignment(Alignment::Center);
+    let argv = quoting::command_argv(&convert_to_cli(&model.parameters));
+    let width = argv.len().to_string().len();
+    let lines: Vec<Line> = argv
+        .iter()
+        .enumerate()
+        .skip(model.argv_scroll)
+        .map(|(index, arg)| {
+            let value = match arg.as_str() {
+                "" => Span::styled("(empty)", model.theme.muted),
+                _ if index == 0 => arg.as_str().bold(),
+                _ if arg.starts_with('-') => Span::styled(arg.as_str(), model.theme.key),
+                _ => Span::styled(arg.as_str(), model.theme.value),
+            };
+            Line::from(vec![Span::styled(format!("{index:>width$}  "), model.theme.muted), value])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Render the usage statistics of every tool in the history, starting at the first visible tool
+fn render_stats(frame: &mut Frame, stats: &[ToolStats], scroll: usize, theme: &Theme, area: Rect) {
+    let block = Block::default().title("Usage statistics from the history").title_alignment(Alignment::Center);
+    let mut lines: Vec<Line> = Vec::new();
+    if stats.is_empty() {
+        lines.push(Line::styled("No commands were run inside clitui yet", theme.muted));
+    }
+    for tool_stats in stats.iter().skip(scroll) {
+        lines.push(Line::from(tool_stats.tool.as_str().bold()));
+        lines.push(Line::from(format!(
+            "  {} runs, {}% successful, {:.1}s on average",
+            tool_stats.runs,
+            tool_stats.success_rate(),
+            tool_stats.average_duration_ms as f64 / 1000.0,
+        )));
+        if !tool_stats.flags.is_empty() {
+            let mut spans = vec![Span::from("  most used: ")];
+            for (index, (flag, count)) in tool_stats.flags.iter().enumerate() {
+                if index > 0 {
+                    spans.push(", ".into());
+                }
+                spans.extend([Span::styled(flag.as_str(), theme.key), Span::styled(format!(" ({count})"), theme.muted)]);
+            }
+            lines.push(Line::from(spans));
+        }
+        lines.push(Line::default());
+    }
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Render the keybinding help, scrolled to the first visible line
+fn render_help(frame: &mut Frame, model: &Model, area: Rect) {
+    let block = Block::default().title("Keybindings").title_alignment(Alignment::Center);
+    let lines: Vec<Line> = help_lines(model)
+        .into_iter()
+        .skip(model.help_scroll)
+        .map(Line::from)
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Marks the lines of the error output when colors cannot tell them apart
+const MONOCHROME_STDERR_MARKER: &str = "! ";
+
+/// Width of the progress bar of a command estimated from the history
+const PROGRESS_WIDTH: usize = 20;
+
+/// Duration rounded to seconds, e.g. `45s`, `2m 10s` or `1h 5m`
+#[allow(clippy::needless_return)]
+fn format_duration(duration: Duration) -> String {
+    let seconds = (duration.as_millis() + 500) / 1000;
+    match seconds {
+        0..60 => return format!("{seconds}s"),
+        60..3600 => return format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => return format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+/// Progress of the running command towards its usual duration, e.g. `━━━━━━━━━━────────── usually takes ~2m 10s, ~1m 5s left`
+/// With reduced animation only the usual duration is shown, it does not change on every redraw
+/// None without an estimate and once the command has finished
+fn estimate_line(output: &OutputPane, theme: &Theme, reduced_animation: bool) -> Option<Line<'static>> {
+    let estimate = output.estimate.filter(|_| output.is_running())?;
+    if reduced_animation {
+        return Some(Line::from(Span::styled(format!("usually takes ~{}", format_duration(estimate)), theme.muted)));
+    }
+    let elapsed = output.started.elapsed().unwrap_or_default();
+    let done = (elapsed.as_secs_f64() / estimate.as_secs_f64().max(f64::EPSILON)).min(1.0);
+    let filled = (done * PROGRESS_WIDTH as f64) as usize;
+    let text = match estimate.checked_sub(elapsed) {
+        Some(left) => format!(" usually takes ~{}, ~{} left", format_duration(estimate), format_duration(left)),
+        None => format!(" takes longer than the usual ~{}", format_duration(estimate)),
+    };
+    Some(Line::from(vec![
+        Span::styled("━".repeat(filled), theme.accent),
+        Span::styled("─".repeat(PROGRESS_WIDTH - filled), theme.muted),
+        Span::styled(text, theme.muted),
+    ]))
+}
+
+/// Render the output of the command, the top right corner shows whether it is followed
+/// and the bottom right corner how far it is compared to earlier runs
+fn render_output(frame: &mut Frame, output: &OutputPane, theme: &Theme, monochrome: bool, reduced_animation: bool, area: Rect) {
+    let follow_state = if output.follow {
+        Span::styled("following", theme.success)
+    } else {
+        Span::styled("paused", theme.warning)
+    };
+    let status = match output.status {
+        None => "running".into(),
+        Some(status) if status.success() => Span::styled("finished", theme.success),
+        Some(status) => match status.code() {
+            Some(code) => Span::styled(format!("failed with exit code {code}"), theme.error),
+            None => Span::styled("terminated", theme.error),
+        },
+    };
+    let mut block = Block::default()
+        .title(Title::from(format!("$ {}", output.command_line)).alignment(Alignment::Left))
+        .title(Title::from(Line::from(vec![status, " | ".into(), follow_state])).alignment(Alignment::Right));
+    if output.filtering || !output.filter.is_empty() {
+        block = block.title(Title::from(output_filter_line(output, theme)).position(Position::Bottom));
+    }
+    if let Some(estimate) = estimate_line(output, theme, reduced_animation) {
+        block = block.title(Title::from(estimate).position(Position::Bottom).alignment(Alignment::Right));
+    }
+    let window = output.visible_lines(block.inner(area).height as usize);
+    let lines: Vec<Line> = output.shown_lines()[window]
+        .iter()
+        .map(|index| match &output.lines[*index] {
+            OutputLine::Stdout(text) => Line::from(text.as_str()),
+            OutputLine::Stderr(text) if monochrome => Line::from(format!("{MONOCHROME_STDERR_MARKER}{text}")),
+            OutputLine::Stderr(text) => Line::styled(text.as_str(), theme.error),
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Filter of the output followed by the number of lines it keeps, or why it is invalid
+fn output_filter_line(output: &OutputPane, theme: &Theme) -> Line<'static> {
+    let status = match output.filter_error() {
+        Some(error) => Span::styled(format!("  {error}"), theme.error),
+        None => Span::styled(format!("  {} of {} lines match", output.shown_lines().len(), output.lines.len()), theme.muted),
+    };
+    Line::from(vec![Span::from(format!("/{}", output.filter)), status])
+}
+
+/// Split the line into spans with every match of the query highlighted by patching the match style onto the style of the line
+fn highlight_matches<'a>(text: &'a str, query: &str, style: Style, match_style: Style) -> Vec<Span<'a>> {
+    if query.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for (index, matched) in text.match_indices(query) {
+        spans.push(Span::styled(&text[start..index], style));
+        spans.push(Span::styled(matched, style.patch(match_style)));
+        start = index + matched.len();
+    }
+    spans.push(Span::styled(&text[start..], style));
+    spans
+}
+
+/// Render the output of a finished command with line numbers over the whole screen,
+/// the last line shows the search query or the visible range
+fn render_pager(frame: &mut Frame, pager: &Pager, output: &OutputPane, theme: &Theme, monochrome: bool, area: Rect) {
+    let page_area = Rect { height: area.height.saturating_sub(1), ..area };
+    let prompt_area = Rect { y: area.y + page_area.height, height: area.height.min(1), ..area };
+    pager.page_height.set(page_area.height as usize);
+
+    let len = output.lines.len();
+    let width = len.to_string().len();
+    let lines: Vec<Line> = output.lines
+        .iter()
+        .enumerate()
+        .skip(pager.top)
+        .take(page_area.height as usize)
+        .map(|(index, line)| {
+            let style = match line {
+                OutputLine::Stdout(_) => Style::new(),
+                OutputLine::Stderr(_) => theme.error,
+            };
+            let mut spans = vec![Span::styled(format!("{:>width$} ", index + 1), theme.muted)];
+            if monochrome && matches!(line, OutputLine::Stderr(_)) {
+                spans.push(Span::from(MONOCHROME_STDERR_MARKER));
+            }
+            spans.extend(highlight_matches(line.text(), &pager.query, style, theme.selected));
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), page_area);
+
+    let prompt = if pager.searching {
+        Line::from(format!("/{}", pager.query))
+    } else if !pager.query.is_empty() {
+        match pager.match_count(&output.lines) {
+            0 => Line::styled(format!("No line contains {}", pager.query), theme.error),
+            count => Line::from(format!("{count} lines contain {}", pager.query)),
+        }
+    } else {
+        let end = (pager.top + page_area.height as usize).min(len);
+        Line::styled(format!("Lines {}-{end} of {len}", (pager.top + 1).min(end)), theme.muted)
+    };
+    frame.render_widget(Paragraph::new(prompt), prompt_area);
+}
+
+/// Drop the colors of the rendered screen, reverse video of selections and matches becomes bold and underlined
+/// State is still conveyed by the `>>` of the selection, checkboxes, `*` of required parameters and text markers
+fn remove_colors(buffer: &mut Buffer) {
+    for cell in buffer.content.iter_mut() {
+        cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        if cell.modifier.contains(Modifier::REVERSED) {
+            cell.modifier.remove(Modifier::REVERSED);
+            cell.modifier.insert(Modifier::BOLD | Modifier::UNDERLINED);
+        }
+    }
+}
+
+/// Title of the section in the form, also naming the section of the matches of the finder
+#[allow(clippy::needless_return)]
+fn section_title(section: Section) -> &'static str {
+    match section {
+        Section::Arguments => return "Arguments",
+        Section::Positionals => return "Positionals",
+        Section::Flags => return "Flags",
+        Section::Options => return "Options",
+    }
+}
+
+/// Render the finder in the middle of the form, the query above the matches and the section of each match after it
+fn render_finder(frame: &mut Frame, model: &Model, finder: &Finder, area: Rect) {
+    let width = (area.width * 3 / 5).max(area.width.min(40));
+    let height = (area.height * 3 / 5).max(area.height.min(8));
+    let area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let block = Block::bordered().title("Jump to parameter").title_alignment(Alignment::Center);
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let matches = finder.matches(model);
+    let count = match matches.len() {
+        0 => Span::styled("  no parameter matches", model.theme.error),
+        1 => Span::styled("  1 match", model.theme.muted),
+        count => Span::styled(format!("  {count} matches"), model.theme.muted),
+    };
+    let prompt_area = Rect { height: inner.height.min(1), ..inner };
+    frame.render_widget(Paragraph::new(Line::from(vec![Span::from(format!("> {}", finder.query)), count])), prompt_area);
+
+    let list_area = Rect { y: inner.y + prompt_area.height, height: inner.height - prompt_area.height, ..inner };
+    let selected = Some(finder.selected).filter(|_| !matches.is_empty());
+    let window = visible_window(matches.len(), selected, list_area.height as usize);
+    let mut state = ListState::default().with_selected(selected.map(|selected| selected - window.start));
+    let items: Vec<Line> = matches[window.clone()]
+        .iter()
+        .map(|found| Line::from(vec![Span::from(found.label.clone()), Span::styled(format!("  {}", section_title(found.section)), model.theme.muted)]))
+        .collect();
+    let list = List::new(items)
+        .highlight_style(model.theme.selected)
+        .highlight_symbol(">>")
+        .repeat_highlight_symbol(true);
+    frame.render_stateful_widget(list, list_area, &mut state);
+    render_scrollbar(frame, list_area, matches.len(), &window);
+}
+
+/// Popup editing a value over several lines, scrolled so the cursor stays visible
+/// The bottom border shows why the value was refused, or how to save and discard it
+fn render_value_editor(frame: &mut Frame, model: &Model, editor: &ValueEditor, area: Rect) {
+    let width = (area.width * 4 / 5).max(area.width.min(40));
+    let height = (area.height * 3 / 5).max(area.height.min(8));
+    let area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let hint = match &model.error {
+        Some(error) => Line::styled(error.clone(), model.theme.error),
+        None => Line::from(vec![
+            Span::styled(model.keymap.display_keys(Action::Expand), model.theme.key),
+            Span::from(" Save  "),
+            Span::styled(model.keymap.display_keys(Action::Back), model.theme.key),
+            Span::from(" Discard"),
+        ]),
+    };
+    let block = Block::bordered()
+        .title(format!("Edit {}", editor.field.key))
+        .title_alignment(Alignment::Center)
+        .title(Title::from(hint).position(Position::Bottom));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let top = editor.row.saturating_sub(inner.height.saturating_sub(1) as usize);
+    let left = editor.column.saturating_sub(inner.width.saturating_sub(1) as usize);
+    let lines: Vec<Line> = editor.lines.iter().map(|line| Line::from(line.as_str())).collect();
+    frame.render_widget(Paragraph::new(lines).scroll((top as u16, left as u16)), inner);
+    if !inner.is_empty() {
+        frame.set_cursor(inner.x + (editor.column - left) as u16, inner.y + (editor.row - top) as u16);
     }
 }
 
-/// Render a frame on the terminal
+/// Popup listing the possible values of a parameter, the current value is marked
+fn render_dropdown(frame: &mut Frame, model: &Model, dropdown: &Dropdown, area: Rect) {
+    let choices = model.choices(&dropdown.field).unwrap_or_default();
+    let current = model.last_value(&dropdown.field);
+    let width = (choices.iter().map(|choice| choice.chars().count()).max().unwrap_or(0) + 14).max(dropdown.field.key.chars().count() + 12);
+    let width = (width as u16).min(area.width);
+    let height = (choices.len() as u16 + 2).min(area.height);
+    let area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let block = Block::bordered().title(format!("Choose {}", dropdown.field.key)).title_alignment(Alignment::Center);
+    let list_area = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let window = visible_window(choices.len(), Some(dropdown.selected), list_area.height as usize);
+    let mut state = ListState::default().with_selected(Some(dropdown.selected.saturating_sub(window.start)));
+    let items: Vec<Line> = choices[window.clone()]
+        .iter()
+        .map(|choice| match current.filter(|current| *current == choice) {
+            Some(_) => Line::from(vec![Span::from(choice.clone()), Span::styled(" (current)", model.theme.muted)]),
+            None => Line::from(choice.clone()),
+        })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(model.theme.selected)
+        .highlight_symbol(">>")
+        .repeat_highlight_symbol(true);
+    frame.render_stateful_widget(list, list_area, &mut state);
+    render_scrollbar(frame, list_area, choices.len(), &window);
+}
+
+/// Popup listing the entries of the directory the file picker shows, directories end with `/`
+fn render_file_picker(frame: &mut Frame, model: &Model, picker: &FilePicker, area: Rect) {
+    let width = (area.width * 3 / 5).max(area.width.min(40));
+    let height = (area.height * 4 / 5).max(area.height.min(8));
+    let area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let hint = match &model.error {
+        Some(error) => Line::styled(error.clone(), model.theme.error),
+        None => Line::from(vec![
+            Span::styled("<Enter>", model.theme.key),
+            Span::from(" Open/Pick  "),
+            Span::styled("<Backspace>", model.theme.key),
+            Span::from(" Up  "),
+            Span::styled(model.keymap.display_keys(Action::Back), model.theme.key),
+            Span::from(" Cancel"),
+        ]),
+    };
+    let block = Block::bordered()
+        .title(picker.directory.to_string_lossy().to_string())
+        .title_alignment(Alignment::Center)
+        .title(Title::from(hint).position(Position::Bottom));
+    let list_area = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let window = visible_window(picker.entries.len(), Some(picker.selected), list_area.height as usize);
+    let mut state = ListState::default().with_selected(Some(picker.selected - window.start));
+    let items: Vec<Line> = picker.entries[window.clone()]
+        .iter()
+        .map(|entry| if entry.is_dir { Line::styled(format!("{}/", entry.name), model.theme.key) } else { Line::from(entry.name.clone()) })
+        .collect();
+    let list = List::new(items)
+        .highlight_style(model.theme.selected)
+        .highlight_symbol(">>")
+        .repeat_highlight_symbol(true);
+    frame.render_stateful_widget(list, list_area, &mut state);
+    render_scrollbar(frame, list_area, picker.entries.len(), &window);
+}
+
+/// Render the share code in full, broken over as many lines as it needs to be copied from the terminal
+fn render_share_code(frame: &mut Frame, model: &Model, share_code: &str, area: Rect) {
+    let width = (area.width * 3 / 5).max(area.width.min(40));
+    let code_width = width.saturating_sub(2).max(1) as usize;
+    let code_height = share_code.chars().count().div_ceil(code_width) as u16;
+    let height = (code_height + 2).min(area.height);
+    let area = Rect::new(area.x + (area.width - width) / 2, area.y + (area.height - height) / 2, width, height);
+    let hint = Line::from(vec![
+        Span::from("Printed again on quit  "),
+        Span::styled(model.keymap.display_keys(Action::Back), model.theme.key),
+        Span::from(" Close"),
+    ]);
+    let block = Block::bordered()
+        .title("Share code")
+        .title_alignment(Alignment::Center)
+        .title(Title::from(hint).position(Position::Bottom));
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(share_code).style(model.theme.success).block(block).wrap(Wrap { trim: false }), area);
+}
+
+/// Render a frame on the terminal, without colors in monochrome mode
 pub fn render_frame(frame: &mut Frame, model: &Model) {
+    // views without lists leave nothing to click
+    model.list_rows.borrow_mut().clear();
+    render_screen(frame, model);
+    if model.monochrome {
+        remove_colors(frame.buffer_mut());
+    }
+}
+
+fn render_screen(frame: &mut Frame, model: &Model) {
+    if model.showing_help {
+        let layout = layout::UILayout::build(frame.size(), model);
+        render_help(frame, model, layout.help_section);
+        render_main_border(frame, model);
+        return;
+    }
+    if model.showing_argv {
+        let layout = layout::UILayout::build(frame.size(), model);
+        render_argv(frame, model, layout.argv_section);
+        render_main_border(frame, model);
+        return;
+    }
+    if let Some(stats) = &model.stats {
+        let layout = layout::UILayout::build(frame.size(), model);
+        render_stats(frame, stats, model.stats_scroll, &model.theme, layout.stats_section);
+        render_main_border(frame, model);
+        return;
+    }
+    if let (Some(pager), Some(output)) = (&model.pager, &model.output) {
+        let layout = layout::UILayout::build(frame.size(), model);
+        render_pager(frame, pager, output, &model.theme, model.monochrome, layout.pager_section);
+        render_main_border(frame, model);
+        return;
+    }
+    if let Some(output) = &model.output {
+        let layout = layout::UILayout::build(frame.size(), model);
+        render_output(frame, output, &model.theme, model.monochrome, model.reduced_animation, layout.output_section);
+        render_main_border(frame, model);
+        return;
+    }
+    if model.selecting_subcommand {
+        let layout = layout::UILayout::build(frame.size(), model);
+        render_subcommands(frame, model, layout.subcommand_section);
+        render_main_border(frame, model);
+        return;
+    }
     let layout = layout::UILayout::build(frame.size(), model);
     render_layout(frame, &layout);
-    render_parameters_section(frame, &model.parameters.arguments, model.get_selected_index(Section::Arguments), "Arguments", layout.argument_section);
-    render_parameters_section(frame, &model.parameters.flags, model.get_selected_index(Section::Flags), "Flags", layout.flag_section);
-    render_parameters_section(frame, &model.parameters.options, model.get_selected_index(Section::Options), "Options", layout.option_section);
-    render_description(frame, model, layout.description_section);
-    render_main_border(frame, &model.parameters.cli_name);
-}
\ No newline at end of file
+    if !model.hidden_sections.contains(&Section::Arguments) {
+        render_parameters_section(frame, model, &model.parameters.arguments, Section::Arguments, layout.argument_section);
+    }
+    if !model.hidden_sections.contains(&Section::Positionals) {
+        render_parameters_section(frame, model, &model.parameters.positionals, Section::Positionals, layout.positional_section);
+    }
+    if !model.hidden_sections.contains(&Section::Flags) {
+        render_parameters_section(frame, model, &model.parameters.flags, Section::Flags, layout.flag_section);
+    }
+    if !model.hidden_sections.contains(&Section::Options) {
+        render_parameters_section(frame, model, &model.parameters.options, Section::Options, layout.option_section);
+    }
+    // the preview is wider than a section and covers the lines between them
+    frame.render_widget(Clear, layout.preview_section);
+    frame.render_widget(Paragraph::new(preview_line(model, layout.preview_section.width as usize)), layout.preview_section);
+    if layout.detail_section.is_empty() {
+        render_description(frame, model, layout.description_section);
+    } else {
+        render_status(frame, model, layout.description_section);
+        render_details(frame, model, layout.detail_section);
+    }
+    if let Some(finder) = &model.finder {
+        render_finder(frame, model, finder, layout.subcommand_section);
+    }
+    if let Some(editor) = &model.value_editor {
+        render_value_editor(frame, model, editor, layout.subcommand_section);
+    }
+    if let Some(picker) = &model.file_picker {
+        render_file_picker(frame, model, picker, layout.subcommand_section);
+    }
+    if let Some(share_code) = &model.share_code {
+        render_share_code(frame, model, share_code, layout.subcommand_section);
+    }
+    if let Some(dropdown) = &model.dropdown {
+        render_dropdown(frame, model, dropdown, layout.subcommand_section);
+    }
+    render_main_border(frame, model);
+}
+
+// Unit tests
+
+#[test]
+fn test_visible_window_without_selection() {
+    assert_eq!(visible_window(100, None, 10), 0..10);
+}
+
+#[test]
+fn test_visible_window_follows_selection() {
+    assert_eq!(visible_window(100, Some(25), 10), 20..30);
+    assert_eq!(visible_window(100, Some(3), 10), 0..10);
+    assert_eq!(visible_window(100, Some(98), 10), 90..100);
+}
+
+#[test]
+fn test_visible_window_shorter_than_height() {
+    assert_eq!(visible_window(3, Some(2), 10), 0..3);
+}
+
+#[test]
+fn test_visible_window_empty() {
+    assert_eq!(visible_window(0, None, 10), 0..0);
+}
+
+#[test]
+fn test_render_scrollbar_follows_window() {
+    use ratatui::backend::TestBackend;
+
+    let mut terminal = Terminal::new(TestBackend::new(10, 4)).unwrap();
+    let thumb_rows = |terminal: &Terminal<TestBackend>| -> Vec<u16> {
+        (0..4).filter(|row| terminal.backend().buffer().get(9, *row).symbol() == "█").collect()
+    };
+
+    terminal.draw(|frame| render_scrollbar(frame, frame.size(), 16, &(0..4))).unwrap();
+    assert_eq!(thumb_rows(&terminal), [0]);
+    terminal.draw(|frame| render_scrollbar(frame, frame.size(), 16, &(12..16))).unwrap();
+    assert_eq!(thumb_rows(&terminal), [3]);
+    terminal.draw(|frame| render_scrollbar(frame, frame.size(), 4, &(0..4))).unwrap();
+    assert_eq!(thumb_rows(&terminal), Vec::<u16>::new());
+}
+
+/// Benchmark with a synthetic 5000 parameter model, run with
+/// cargo test --release -- --ignored bench_render_frame_large_model
+#[test]
+#[ignore]
+fn bench_render_frame_large_model() {
+    use std::time::{Duration, Instant};
+    use ratatui::backend::TestBackend;
+    use crate::parsing::{CLIArgument, CLIParameters};
+
+    let options = (0..5000)
+        .map(|index| CLIArgument {
+            key: format!("--option-{index}"),
+            name: format!("OPTION_{index}"),
+            description: Some(format!("Synthetic option number {index}")),
+            ..Default::default()
+        })
+        .collect();
+    let parameters = CLIParameters {
+        cli_name: String::from("synthetic"),
+        options,
+        ..Default::default()
+    };
+    let mut model = Model::new(parameters);
+    model.current_section = Section::Options;
+    let mut terminal = Terminal::new(TestBackend::new(200, 60)).unwrap();
+
+    let frames = 200;
+    let start = Instant::now();
+    for index in 0..frames {
+        model.current_key_index = index * 25;
+        terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+    }
+    let frame_time = start.elapsed() / frames as u32;
+
+    println!("Average frame time: {frame_time:?}");
+    assert!(frame_time < Duration::from_millis(5));
+}
+
+#[test]
+fn test_display_list_marks_required() {
+    let argument = CLIArgument {
+        name: String::from("NAME"),
+        values: vec![String::from("Ferris")],
+        required: true,
+        ..Default::default()
+    };
+
+    assert_eq!(argument.display_list(), "NAME*: Ferris");
+}
+
+#[test]
+fn test_display_description_aliases() {
+    let flag = CLIFlag {
+        key: String::from("--version"),
+        aliases: vec![String::from("--ver"), String::from("-v")],
+        description: Some(String::from("Print version")),
+        ..Default::default()
+    };
+
+    assert_eq!(flag.display_description().as_deref(), Some("VERSION: Print version [aliases: --ver, -v]"));
+}
+
+#[test]
+fn test_display_item_columns() {
+    let argument = CLIArgument {
+        name: String::from("NAME"),
+        values: vec![String::from("Ferris")],
+        required: true,
+        ..Default::default()
+    };
+    let flag = CLIFlag { key: String::from("--verbose"), set: true, ..Default::default() };
+
+    assert_eq!(argument.display_item(ListStyle::Columns, 8, 20), "NAME*         Ferris");
+    assert_eq!(flag.display_item(ListStyle::Columns, 8, 20), "VERBOSE          [x]");
+    assert_eq!(flag.display_item(ListStyle::Inline, 8, 20), "[x] VERBOSE");
+    let negated_flag = CLIFlag { key: String::from("--color"), negated_key: Some(String::from("--no-color")), set: true, negated: true, ..Default::default() };
+    assert_eq!(negated_flag.display_item(ListStyle::Columns, 8, 20), "COLOR    [-] NO-COLOR");
+}
+
+#[test]
+fn test_default_diff_line() {
+    let mut argument = CLIArgument {
+        values: vec![String::from("tool.toml")],
+        default_value: Some(String::from("tool.toml")),
+        ..Default::default()
+    };
+    assert_eq!(default_diff_line(&argument, &Theme::default()), None);
+
+    argument.values = vec![String::from("tool.local.toml")];
+    let line = default_diff_line(&argument, &Theme::default()).unwrap();
+
+    let texts: Vec<&str> = line.spans.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(texts, ["Default changed: ", "tool.", "local.", "toml"]);
+}
+
+#[test]
+fn test_glob_preview_line() {
+    let directory = std::env::temp_dir().join(format!("clitui-glob-preview-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    std::fs::create_dir_all(&directory).unwrap();
+    for index in 0..7 {
+        std::fs::write(directory.join(format!("{index}.log")), "").unwrap();
+    }
+    let root = directory.to_string_lossy();
+
+    let line = glob_preview_line(&[format!("{root}/*.log")], &Theme::default()).unwrap();
+
+    assert_eq!(line.spans[0].content, "Matches 7 files: ");
+    assert!(line.spans[1].content.ends_with("4.log and 2 more"));
+    assert_eq!(glob_preview_line(&[String::from("plain.log")], &Theme::default()), None);
+    assert_eq!(glob_preview_line(&[format!("{root}/*.csv")], &Theme::default()).unwrap().spans[0].content, format!("No files match {root}/*.csv, passed as typed"));
+}
+
+#[test]
+fn test_animation_is_reduced() {
+    let ssh = |name: &str| (name == "SSH_CONNECTION").then(|| String::from("10.0.0.2 51234 10.0.0.1 22"));
+    let local = |name: &str| (name == "TERM").then(|| String::from("xterm-256color"));
+
+    assert!(Animation::Auto.is_reduced(&ssh));
+    assert!(!Animation::Auto.is_reduced(&local));
+    assert!(!Animation::Full.is_reduced(&ssh));
+    assert!(Animation::Reduced.is_reduced(&local));
+    assert!(Animation::Auto.is_reduced(&|name: &str| (name == "TERM").then(|| String::from("dumb"))));
+}
+
+#[test]
+fn test_path_marker() {
+    let values = [String::from("in.txt"), String::from("out.txt")];
+    let states = BTreeMap::from([(String::from("in.txt"), PathState::Exists), (String::from("out.txt"), PathState::Missing)]);
+
+    assert_eq!(path_marker(Some((PathCheck::Any, &values)), &states, &Theme::default(), false), Some(Span::from(" (exists)").dark_gray()));
+    assert_eq!(path_marker(Some((PathCheck::MustExist, &values)), &states, &Theme::default(), false), Some(Span::from(" (missing)").red()));
+    assert_eq!(path_marker(Some((PathCheck::MustExist, &values)), &states, &Theme::default(), true), Some(Span::from(" (missing!)").red()));
+    assert_eq!(path_marker(Some((PathCheck::Any, &values)), &BTreeMap::new(), &Theme::default(), false), None);
+    assert_eq!(path_marker(None, &states, &Theme::default(), false), None);
+}
+
+#[test]
+fn test_render_frame_monochrome() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let mut model = Model::new(CLIParameters { flags: vec![CLIFlag { key: String::from("--verbose"), ..Default::default() }], ..Default::default() });
+    model.error = Some(String::from("Missing value"));
+    model.monochrome = true;
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    assert!(buffer.content.iter().all(|cell| cell.fg == Color::Reset && cell.bg == Color::Reset && !cell.modifier.contains(Modifier::REVERSED)));
+    let text: String = buffer.content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Error: Missing value"));
+    assert!(text.contains(">>[ ] VERBOSE"));
+}
+
+#[test]
+fn test_render_frame_filters_output() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let mut model = Model::new(CLIParameters::default());
+    let mut output = OutputPane::new(String::from("cargo build"));
+    for line in ["Compiling clitui", "warning: unused import", "error: mismatched types"] {
+        output.push(OutputLine::Stdout(String::from(line)));
+    }
+    output.set_filter(String::from("!warning"));
+    model.output = Some(output);
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("error: mismatched types"));
+    assert!(!text.contains("unused import"));
+    assert!(text.contains("/!warning  2 of 3 lines match"));
+}
+
+#[test]
+fn test_render_frame_wraps_share_code() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::{CLIFlag, CLIParameters};
+
+    let mut model = Model::new(CLIParameters { flags: vec![CLIFlag { key: String::from("--verbose"), ..Default::default() }], ..Default::default() });
+    let share_code = format!("{}END", "a".repeat(150));
+    model.share_code = Some(share_code);
+    let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("aaaEND"));
+}
+
+#[test]
+fn test_render_frame_filters_parameters() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let flags = ["--verbose", "--no-default-features", "--all-features"]
+        .map(|key| CLIFlag { key: String::from(key), ..Default::default() })
+        .to_vec();
+    let mut model = Model::new(CLIParameters { flags, ..Default::default() });
+    model.filter = Some(String::from("FEATURES"));
+    let mut terminal = Terminal::new(TestBackend::new(160, 20)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains(">>[ ] NO-DEFAULT-FEATURES"));
+    assert!(text.contains("[ ] ALL-FEATURES"));
+    assert!(!text.contains("VERBOSE"));
+    assert!(text.contains("/FEATURES  2 parameters match"));
+}
+
+#[test]
+fn test_display_details_of_argument() {
+    let argument = CLIArgument {
+        key: String::from("--format"),
+        short_key: Some(String::from("-f")),
+        name: String::from("FORMAT"),
+        description: Some(String::from("Output format")),
+        default_value: Some(String::from("json")),
+        choices: vec![String::from("json"), String::from("yaml")],
+        joined: true,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        argument.display_details(),
+        [
+            ("Description", String::from("Output format")),
+            ("Keys", String::from("-f, --format")),
+            ("Type", String::from("text")),
+            ("Default", String::from("json")),
+            ("Choices", String::from("json, yaml")),
+            ("Notes", String::from("passed as --format=value")),
+        ],
+    );
+}
+
+#[test]
+fn test_render_frame_detail_panel() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let arguments = vec![CLIArgument {
+        key: String::from("--name"),
+        name: String::from("NAME"),
+        description: Some(String::from("Name to greet")),
+        env_var: Some(String::from("GREETER_NAME")),
+        required: true,
+        ..Default::default()
+    }];
+    let model = Model::new(CLIParameters { arguments, ..Default::default() });
+    let screen_text = |width: u16| {
+        let mut terminal = Terminal::new(TestBackend::new(width, 20)).unwrap();
+        terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+        terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+
+    let wide = screen_text(layout::DETAIL_PANEL_MIN_WIDTH);
+    assert!(wide.contains("Details"));
+    assert!(wide.contains("Env: GREETER_NAME"));
+    assert!(wide.contains("Status: NAME is required"));
+    let narrow = screen_text(layout::DETAIL_PANEL_MIN_WIDTH - 1);
+    assert!(!narrow.contains("Details"));
+    assert!(narrow.contains("NAME: Name to greet [env: GREETER_NAME]"));
+}
+
+#[test]
+fn test_render_frame_path_status_from_checker() {
+    use ratatui::backend::TestBackend;
+    use crate::{parsing::{CLIParameters, CLIPositional, PathCheck}, pathcheck::PathState};
+
+    let positionals = vec![CLIPositional {
+        name: String::from("FILE"),
+        values: vec![String::from("/nonexistent/names.txt")],
+        path_check: PathCheck::MustExist,
+        ..Default::default()
+    }];
+    let mut model = Model::new(CLIParameters { positionals, ..Default::default() });
+    model.select_first_section();
+    let screen_text = |model: &Model| {
+        let mut terminal = Terminal::new(TestBackend::new(layout::DETAIL_PANEL_MIN_WIDTH, 20)).unwrap();
+        terminal.draw(|frame| render_frame(frame, model)).unwrap();
+        terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect::<String>()
+    };
+
+    assert!(screen_text(&model).contains("Status: checking…"));
+    model.path_states.insert(String::from("/nonexistent/names.txt"), PathState::Missing);
+    assert!(screen_text(&model).contains("Status: FILE does not exist"));
+}
+
+#[test]
+fn test_render_frame_finder() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let flags = ["--verbose", "--no-default-features", "--all-features"]
+        .map(|key| CLIFlag { key: String::from(key), ..Default::default() })
+        .to_vec();
+    let mut model = Model::new(CLIParameters { flags, ..Default::default() });
+    model.finder = Some(Finder { query: String::from("feat"), selected: 1 });
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Jump to parameter"));
+    assert!(text.contains("> feat  2 matches"));
+    assert!(text.contains("  --no-default-features  Flags"));
+    assert!(text.contains(">>--all-features  Flags"));
+}
+
+#[test]
+fn test_render_frame_theme() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let flags = vec![CLIFlag { key: String::from("--verbose"), ..Default::default() }];
+    let mut model = Model::new(CLIParameters { flags, ..Default::default() });
+    model.theme = Theme::from_name("high-contrast").unwrap();
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let selected = buffer.content.iter().find(|cell| cell.symbol() == ">").unwrap();
+    assert_eq!((selected.fg, selected.bg), (Color::Black, Color::LightYellow));
+    assert!(!buffer.content.iter().any(|cell| cell.modifier.contains(Modifier::REVERSED)));
+}
+
+#[test]
+fn test_render_frame_records_list_rows() {
+    use ratatui::backend::TestBackend;
+    use crate::parsing::CLIParameters;
+
+    let flags = ["--verbose", "--quiet"].map(|key| CLIFlag { key: String::from(key), ..Default::default() }).to_vec();
+    let model = Model::new(CLIParameters { flags, ..Default::default() });
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    // the highlight symbol starts the row of the selected flag
+    let position = buffer.content.windows(2).position(|cells| cells[0].symbol() == ">" && cells[1].symbol() == ">").unwrap() as u16;
+    let (column, row) = (position % buffer.area.width, position / buffer.area.width);
+    assert_eq!(model.list_item_at(column, row), Some((Some(Section::Flags), 0)));
+    assert_eq!(model.list_item_at(column + 4, row + 1), Some((Some(Section::Flags), 1)));
+    assert_eq!(model.list_item_at(column, row + 2), None);
+}
+
+#[test]
+fn test_render_frame_text_cursor() {
+    use ratatui::backend::TestBackend;
+    use crate::{model::TextCursor, parsing::CLIParameters};
+
+    let arguments = vec![CLIArgument { key: String::from("--name"), name: String::from("NAME"), values: vec![String::from("Ferris")], ..Default::default() }];
+    let mut model = Model::new(CLIParameters { arguments, ..Default::default() });
+    model.text_cursor = Some(TextCursor { field: model.selected_field().unwrap(), position: 2 });
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let (column, row) = terminal.get_cursor().unwrap();
+    let buffer = terminal.backend().buffer();
+    let cell = |column: u16| buffer.content[(row * buffer.area.width + column) as usize].symbol().to_string();
+    assert_eq!((cell(column - 2), cell(column - 1), cell(column)), (String::from("F"), String::from("e"), String::from("r")));
+}
+
+#[test]
+fn test_render_frame_value_editor() {
+    use ratatui::backend::TestBackend;
+    use crate::{editor::ValueEditor, parsing::CLIParameters};
+
+    let values = vec![String::from("Fix\n\nDetails")];
+    let arguments = vec![CLIArgument { key: String::from("--message"), name: String::from("MESSAGE"), values, ..Default::default() }];
+    let mut model = Model::new(CLIParameters { arguments, ..Default::default() });
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("MESSAGE: Fix⏎⏎Details"));
+    assert!(text.contains("--message 'Fix⏎⏎Details'"));
+    model.value_editor = Some(ValueEditor::new(model.selected_field().unwrap(), &model.parameters.arguments[0].values[0]));
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Edit --message"));
+    assert!(text.contains("<Ctrl + O> Save  <Esc> Discard"));
+    let (column, row) = terminal.get_cursor().unwrap();
+    let buffer = terminal.backend().buffer();
+    let line: String = (column - 7..column).map(|column| buffer.content[(row * buffer.area.width + column) as usize].symbol().to_string()).collect();
+    assert_eq!(line, "Details");
+}
+
+#[test]
+fn test_render_frame_file_picker() {
+    use ratatui::backend::TestBackend;
+    use crate::{filepicker::FilePicker, parsing::{CLIParameters, CLIPositional}};
+
+    let directory = std::env::temp_dir().join(format!("clitui-ui-picker-{}", std::process::id()));
+    std::fs::create_dir_all(directory.join("logs")).unwrap();
+    std::fs::write(directory.join("names.txt"), "Ferris").unwrap();
+    let positionals = vec![CLIPositional { name: String::from("FILE"), ..Default::default() }];
+    let mut model = Model::new(CLIParameters { positionals, ..Default::default() });
+    model.current_section = Section::Positionals;
+    model.file_picker = Some(FilePicker::open(model.selected_field().unwrap(), "", &directory).unwrap());
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains(">>./"));
+    assert!(text.contains("logs/"));
+    assert!(text.contains("names.txt"));
+    assert!(text.contains("<Enter> Open/Pick  <Backspace> Up  <Esc> Cancel"));
+}
+
+#[test]
+fn test_render_frame_dropdown() {
+    use ratatui::backend::TestBackend;
+    use crate::{model::Dropdown, parsing::CLIParameters};
+
+    let choices = vec![String::from("json"), String::from("yaml")];
+    let arguments = vec![CLIArgument { key: String::from("--format"), name: String::from("FORMAT"), values: vec![String::from("yaml")], choices, ..Default::default() }];
+    let mut model = Model::new(CLIParameters { arguments, ..Default::default() });
+    model.dropdown = Some(Dropdown { field: model.selected_field().unwrap(), selected: 0 });
+    let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+
+    terminal.draw(|frame| render_frame(frame, &model)).unwrap();
+
+    let text: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("Choose --format"));
+    assert!(text.contains(">>json"));
+    assert!(text.contains("yaml (current)"));
+    assert!(text.contains(" Choose <Space> "));
+}
+
+#[test]
+fn test_estimate_line() {
+    let mut output = OutputPane::new(String::from("cargo build --release"));
+    output.started = std::time::SystemTime::now() - Duration::from_secs(65);
+
+    assert_eq!(estimate_line(&output, &Theme::default(), false), None);
+    output.estimate = Some(Duration::from_secs(130));
+    let line = estimate_line(&output, &Theme::default(), false).unwrap();
+    assert_eq!(line_text(&line), format!("{}{} usually takes ~2m 10s, ~1m 5s left", "━".repeat(10), "─".repeat(10)));
+    output.estimate = Some(Duration::from_secs(30));
+    let line = estimate_line(&output, &Theme::default(), false).unwrap();
+    assert_eq!(line_text(&line), format!("{} takes longer than the usual ~30s", "━".repeat(20)));
+    let line = estimate_line(&output, &Theme::default(), true).unwrap();
+    assert_eq!(line_text(&line), "usually takes ~30s");
+}
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(Duration::from_millis(44_600)), "45s");
+    assert_eq!(format_duration(Duration::from_secs(130)), "2m 10s");
+    assert_eq!(format_duration(Duration::from_secs(3900)), "1h 5m");
+}
+
+#[test]
+fn test_highlight_matches() {
+    let spans = highlight_matches("error: bad error", "error", Style::new(), Theme::default().selected);
+
+    let texts: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(texts, ["", "error", ": bad ", "error", ""]);
+}
+
+#[allow(dead_code)]
+fn line_text(line: &Line) -> String {
+    line.spans.iter().map(|span| span.content.as_ref()).collect()
+}
+
+#[test]
+fn test_instructions_follow_keymap() {
+    use crate::parsing::CLIParameters;
+
+    let mut model = Model::new(CLIParameters { flags: vec![CLIFlag { key: String::from("--dry-run"), ..Default::default() }], ..Default::default() });
+    model.keymap = crate::keymap::Keymap::from_config(&[(String::from("quit"), vec![String::from("Ctrl+X")])].into()).unwrap();
+
+    assert_eq!(
+        line_text(&instructions(&model, 200, true)),
+        " Run <Enter> Test run <Ctrl + T> Run in terminal <Ctrl + R> Toggle <Space> Add value <Ctrl + N> Share <Ctrl + E> Arguments <F2> Stats <F3> Help <F1>/<?> Quit <Ctrl + X> "
+    );
+}
+
+#[test]
+fn test_instructions_overflow_into_help() {
+    use crate::parsing::CLIParameters;
+
+    let mut model = Model::new(CLIParameters::default());
+
+    assert_eq!(line_text(&instructions(&model, 80, true)), " Run <Enter> Toggle <Space> Share <Ctrl + E> More <F1>/<?> Quit <Ctrl + Q> ");
+
+    model.showing_help = true;
+    assert_eq!(line_text(&instructions(&model, 60, true)), " Close <Esc> Quit <Ctrl + Q> ");
+}
+
+#[test]
+fn test_preview_line() {
+    use crate::parsing::CLIParameters;
+
+    let flags = vec![CLIFlag { key: String::from("--caps"), set: true, ..Default::default() }];
+    let arguments = vec![CLIArgument { key: String::from("--first-name"), name: String::from("FIRST_NAME"), values: vec![String::from("Ferris Crab")], ..Default::default() }];
+    let model = Model::new(CLIParameters { cli_name: String::from("greeter.exe"), flags, arguments, ..Default::default() });
+
+    assert_eq!(line_text(&preview_line(&model, 80)), "$ greeter.exe --caps --first-name 'Ferris Crab'");
+    assert_eq!(line_text(&preview_line(&model, 24)), "$ greeter.exe --caps --…");
+    assert_eq!(preview_line(&model, 80).spans[2].style, model.theme.key);
+}
@@ -2,19 +2,47 @@ use ratatui::layout::{
         Constraint, Direction, Layout, Margin, Rect
     };
 
-use crate::model::Model;
+use crate::model::{Model, Section};
+
+/// Terminals at least this wide show the details of the selected parameter in a panel on the right
+pub const DETAIL_PANEL_MIN_WIDTH: u16 = 160;
 
 pub struct UILayout {
     pub left_third: Rect,
-    pub middle_third: Rect, 
+    pub middle_third: Rect,
+    /// Separated from the detail panel, if there is one
+    pub right_third: Rect,
     pub argument_section: Rect,
+    /// Below the arguments, empty if there are no positionals or they are hidden
+    pub positional_section: Rect,
     pub flag_section: Rect,
     pub option_section: Rect,
+    /// Bottom row, only the status messages while the detail panel is shown
     pub description_section: Rect,
+    /// Row above the description with the command line the form builds
+    pub preview_section: Rect,
+    /// Right of the options, empty on terminals narrower than `DETAIL_PANEL_MIN_WIDTH`
+    pub detail_section: Rect,
+    pub subcommand_section: Rect,
+    pub help_section: Rect,
+    pub output_section: Rect,
+    pub argv_section: Rect,
+    pub stats_section: Rect,
+    /// Everything inside the main border
+    pub pager_section: Rect,
 }
 
 impl UILayout {
-    pub fn build(area: Rect, _model: &Model) -> UILayout {
+    pub fn build(area: Rect, model: &Model) -> UILayout {
+        let (form_area, detail_area) = if area.width >= DETAIL_PANEL_MIN_WIDTH {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Ratio(3, 4), Constraint::Ratio(1, 4)])
+                .split(area);
+            (columns[0], columns[1])
+        } else {
+            (area, Rect::default())
+        };
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
@@ -22,27 +50,49 @@ impl UILayout {
                 Constraint::Ratio(1, 3),
                 Constraint::Ratio(1, 3)
             ])
-            .split(area);
+            .split(form_area);
 
         let margin = Margin {
             vertical: 3,
             horizontal: 5,
         };
 
-        let argument_section = chunks[0].inner(margin);
+        let mut argument_section = chunks[0].inner(margin);
+        let mut positional_section = Rect::default();
+        if model.section_is_available(Section::Positionals) {
+            let left_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                .split(argument_section);
+            argument_section = left_chunks[0];
+            positional_section = left_chunks[1];
+        }
         let flag_section = chunks[1].inner(margin);
         let option_section = chunks[2].inner(margin);
+        let subcommand_section = area.inner(margin);
+        let detail_section = if detail_area.is_empty() { detail_area } else { detail_area.inner(Margin { vertical: 3, horizontal: 2 }) };
 
         // Use bottom for description section
         let description_section = Rect::new(area.x, area.height - 2, area.width, 2).inner(Margin {horizontal: 2, vertical: 0});
+        let preview_section = Rect::new(area.x, area.height.saturating_sub(3), area.width, 1).inner(Margin { horizontal: 2, vertical: 0 });
         
         UILayout {
             left_third: chunks[0],
             middle_third: chunks[1],
+            right_third: chunks[2],
             argument_section,
+            positional_section,
             flag_section,
             option_section,
             description_section,
+            preview_section,
+            detail_section,
+            subcommand_section,
+            help_section: subcommand_section,
+            output_section: subcommand_section,
+            argv_section: subcommand_section,
+            stats_section: subcommand_section,
+            pager_section: area.inner(Margin { vertical: 1, horizontal: 1 }),
         }
     }
 }
\ No newline at end of file
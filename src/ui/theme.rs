@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style, Stylize};
+
+/// Styles of the roles text plays on the screen, every render function takes its colors from here
+#[derive(Debug, PartialEq, Clone)]
+pub struct Theme {
+    /// Keys of actions and parameters, e.g. `Ctrl + R` in the instruction bar
+    pub key: Style,
+    /// Values passed to the command, e.g. in the arguments as received by the command
+    pub value: Style,
+    /// Highlighted item of a list and matches of a search
+    pub selected: Style,
+    /// Labels and hints around the content, e.g. `Default changed:`
+    pub muted: Style,
+    /// Errors, required parameters that are missing and the error output of the command
+    pub error: Style,
+    /// Confirmations like a copied share code or a finished command
+    pub success: Style,
+    /// Things that may not do what is expected, e.g. a glob matching no files
+    pub warning: Style,
+    /// Name of the alias the form was opened with
+    pub accent: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            key: Style::new().blue(),
+            value: Style::new().green(),
+            selected: Style::new().add_modifier(Modifier::REVERSED),
+            muted: Style::new().dark_gray(),
+            error: Style::new().red(),
+            success: Style::new().green(),
+            warning: Style::new().yellow(),
+            accent: Style::new().cyan(),
+        }
+    }
+}
+
+impl Theme {
+    /// Built-in theme, `dark`, `light` or `high-contrast`
+    #[allow(clippy::needless_return)]
+    pub fn from_name(name: &str) -> Option<Theme> {
+        match name {
+            "dark" => return Some(Theme::default()),
+            // yellow and cyan are hard to read on a light background
+            "light" => return Some(Theme { warning: Style::new().magenta(), accent: Style::new().blue(), ..Theme::default() }),
+            // bright and bold colors only, the selection is black on yellow instead of the reversed colors of the terminal
+            "high-contrast" => return Some(Theme {
+                key: Style::new().light_cyan().bold(),
+                value: Style::new().light_green().bold(),
+                selected: Style::new().black().on_light_yellow().bold(),
+                muted: Style::new().white(),
+                error: Style::new().light_red().bold(),
+                success: Style::new().light_green().bold(),
+                warning: Style::new().light_yellow().bold(),
+                accent: Style::new().light_magenta().bold(),
+            }),
+            _ => return None,
+        }
+    }
+
+    /// Replace the style of a role with a color from the config, e.g. `selected = "bold black on yellow"`
+    pub fn set_color(&mut self, role: &str, color: &str) -> Result<(), String> {
+        let style = parse_style(color).ok_or(format!("Unknown color in config: {color}"))?;
+        let target = match role {
+            "key" => &mut self.key,
+            "value" => &mut self.value,
+            "selected" => &mut self.selected,
+            "muted" => &mut self.muted,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "warning" => &mut self.warning,
+            "accent" => &mut self.accent,
+            _ => return Err(format!("Unknown color role in config: {role}")),
+        };
+        *target = style;
+        Ok(())
+    }
+}
+
+/// Style from modifiers, a foreground color and a background color after `on`, each optional,
+/// e.g. `red`, `#ff8800`, `bold white on blue` or `reversed`
+/// Colors are named like `light-red`, given as `#rrggbb` or as index of the 256 color palette
+pub fn parse_style(text: &str) -> Option<Style> {
+    let mut style = Style::new();
+    let mut words = text.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word {
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            "italic" => style.add_modifier(Modifier::ITALIC),
+            "underlined" => style.add_modifier(Modifier::UNDERLINED),
+            "reversed" => style.add_modifier(Modifier::REVERSED),
+            "on" => style.bg(Color::from_str(words.next()?).ok()?),
+            _ => style.fg(Color::from_str(word).ok()?),
+        };
+    }
+    (style != Style::new()).then_some(style)
+}
+
+// Unit tests
+
+#[test]
+fn test_parse_style() {
+    assert_eq!(parse_style("red"), Some(Style::new().red()));
+    assert_eq!(parse_style("bold white on blue"), Some(Style::new().white().on_blue().bold()));
+    assert_eq!(parse_style("#ff8800"), Some(Style::new().fg(Color::Rgb(255, 136, 0))));
+    assert_eq!(parse_style("reversed"), Some(Style::new().add_modifier(Modifier::REVERSED)));
+    assert_eq!(parse_style("on"), None);
+    assert_eq!(parse_style("ultraviolet"), None);
+    assert_eq!(parse_style(""), None);
+}
+
+#[test]
+fn test_theme_set_color() {
+    let mut theme = Theme::from_name("light").unwrap();
+
+    theme.set_color("key", "magenta").unwrap();
+
+    assert_eq!(theme.key, Style::new().magenta());
+    assert_eq!(theme.warning, Style::new().magenta());
+    assert_eq!(theme.set_color("title", "red"), Err(String::from("Unknown color role in config: title")));
+    assert_eq!(theme.set_color("key", "ultraviolet"), Err(String::from("Unknown color in config: ultraviolet")));
+    assert_eq!(Theme::from_name("solarized"), None);
+}